@@ -1,5 +1,9 @@
-use crate::models::config::ServerProperties;
-use crate::services::config_parser;
+use crate::models::config::{
+    ConfigEntry, ConfigRevision, PropertyValidationError, ServerProperties,
+};
+use crate::services::{
+    config_document, config_history, config_parser, global, server_properties_schema,
+};
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -35,6 +39,16 @@ fn validate_path_within_server(server_path: &str, file_path: &str) -> Result<(),
     Ok(())
 }
 
+/// 根据服务器目录找到已登记服务器的 MC 版本，找不到时按最新版本处理
+fn find_mc_version(server_path: &str) -> Option<String> {
+    let target = std::fs::canonicalize(server_path).ok()?;
+    global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| std::fs::canonicalize(&s.path).is_ok_and(|p| p == target))
+        .map(|s| s.mc_version)
+}
+
 #[tauri::command]
 pub fn read_config(server_path: String, path: String) -> Result<HashMap<String, String>, String> {
     validate_config_path(&path)?;
@@ -50,15 +64,61 @@ pub fn write_config(
 ) -> Result<(), String> {
     validate_config_path(&path)?;
     validate_path_within_server(&server_path, &path)?;
+    config_history::snapshot(
+        Path::new(&server_path),
+        Path::new(&path),
+        config_history::ORIGIN_CONFIG_EDITOR,
+    )?;
     config_parser::write_properties(&path, &values)
 }
 
+/// 以结构化方式读取 YAML / TOML / JSON5 配置
+#[tauri::command]
+pub fn read_config_document(
+    server_path: String,
+    path: String,
+) -> Result<serde_json::Value, String> {
+    validate_config_path(&path)?;
+    validate_path_within_server(&server_path, &path)?;
+    Ok(config_document::ConfigDocument::load(Path::new(&path))?.to_value())
+}
+
+#[tauri::command]
+pub fn get_config_value(
+    server_path: String,
+    path: String,
+    key: String,
+) -> Result<Option<serde_json::Value>, String> {
+    validate_config_path(&path)?;
+    validate_path_within_server(&server_path, &path)?;
+    config_document::ConfigDocument::load(Path::new(&path))?.get(&key)
+}
+
+/// 按点分路径修改配置，保留注释与格式；任意一项校验失败时不写入
+#[tauri::command]
+pub fn set_config_values(
+    server_path: String,
+    path: String,
+    values: HashMap<String, serde_json::Value>,
+) -> Result<(), String> {
+    validate_config_path(&path)?;
+    validate_path_within_server(&server_path, &path)?;
+    let mut values = values.into_iter().collect::<Vec<_>>();
+    values.sort_by(|a, b| a.0.cmp(&b.0));
+    config_history::snapshot(
+        Path::new(&server_path),
+        Path::new(&path),
+        config_history::ORIGIN_CONFIG_EDITOR,
+    )?;
+    config_document::write_values(Path::new(&path), &values)
+}
+
 #[tauri::command]
 pub fn read_server_properties(server_path: String) -> Result<ServerProperties, String> {
     validate_config_path(&server_path)?;
     let props_path = format!("{}/server.properties", server_path);
     validate_path_within_server(&server_path, &props_path)?;
-    config_parser::parse_server_properties(&props_path)
+    config_parser::parse_server_properties(&props_path, find_mc_version(&server_path).as_deref())
 }
 
 #[tauri::command]
@@ -69,5 +129,51 @@ pub fn write_server_properties(
     validate_config_path(&server_path)?;
     let props_path = format!("{}/server.properties", server_path);
     validate_path_within_server(&server_path, &props_path)?;
-    config_parser::write_properties(&props_path, &values)
+    config_history::snapshot(
+        Path::new(&server_path),
+        Path::new(&props_path),
+        config_history::ORIGIN_SERVER_PROPERTIES,
+    )?;
+    config_parser::write_server_properties(
+        &props_path,
+        &values,
+        find_mc_version(&server_path).as_deref(),
+    )
+}
+
+/// 当前服务器版本可用的全部配置项（值为默认值）
+#[tauri::command]
+pub fn get_server_properties_schema(server_path: String) -> Vec<ConfigEntry> {
+    server_properties_schema::schema_entries(find_mc_version(&server_path).as_deref())
+}
+
+#[tauri::command]
+pub fn validate_server_properties(
+    server_path: String,
+    values: HashMap<String, String>,
+) -> Vec<PropertyValidationError> {
+    server_properties_schema::validate_values(&values, find_mc_version(&server_path).as_deref())
+}
+
+#[tauri::command]
+pub fn list_config_revisions(
+    server_id: String,
+    file_path: Option<String>,
+) -> Result<Vec<ConfigRevision>, String> {
+    config_history::list_revisions(&server_id, file_path.as_deref())
+}
+
+/// 两个修订之间的统一 diff，`to_id` 为空时与当前文件比较
+#[tauri::command]
+pub fn diff_config_revisions(
+    server_id: String,
+    from_id: String,
+    to_id: Option<String>,
+) -> Result<String, String> {
+    config_history::diff_revisions(&server_id, &from_id, to_id.as_deref())
+}
+
+#[tauri::command]
+pub fn restore_config_revision(server_id: String, revision_id: String) -> Result<(), String> {
+    config_history::restore_revision(&server_id, &revision_id)
 }
@@ -7,12 +7,13 @@ use uuid::Uuid;
 #[tauri::command]
 pub async fn download_file(
     url: String,
-    save_path: String,           // 对应前端 savePath
-    thread_count: Option<usize>, // 对应前端 threadCount
+    save_path: String,             // 对应前端 savePath
+    thread_count: Option<usize>,   // 对应前端 threadCount
+    expected_hash: Option<String>, // 对应前端 expectedHash，例如 sha256:<hex>
     manager: State<'_, DownloadManager>,
 ) -> Result<String, String> {
     let id = manager
-        .create_task(&url, &save_path, thread_count.unwrap_or(8))
+        .create_task_with_hash(&url, &save_path, thread_count.unwrap_or(8), expected_hash)
         .await;
     Ok(id.to_string())
 }
@@ -48,3 +49,33 @@ pub async fn remove_download_task(
     manager.remove_task(id).await;
     Ok(())
 }
+
+/// 暂停下载，已下载的部分保留在磁盘上
+#[tauri::command]
+pub async fn pause_download_task(
+    id_str: String,
+    manager: State<'_, DownloadManager>,
+) -> Result<(), String> {
+    let id = Uuid::parse_str(&id_str).map_err(|e| e.to_string())?;
+    manager.pause_task(id).await
+}
+
+/// 继续已暂停或失败的下载
+#[tauri::command]
+pub async fn resume_download_task(
+    id_str: String,
+    manager: State<'_, DownloadManager>,
+) -> Result<(), String> {
+    let id = Uuid::parse_str(&id_str).map_err(|e| e.to_string())?;
+    manager.resume_task(id).await
+}
+
+/// 取消下载并删除未完成的文件
+#[tauri::command]
+pub async fn cancel_download_task(
+    id_str: String,
+    manager: State<'_, DownloadManager>,
+) -> Result<(), String> {
+    let id = Uuid::parse_str(&id_str).map_err(|e| e.to_string())?;
+    manager.cancel_task(id).await
+}
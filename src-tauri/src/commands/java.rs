@@ -1,4 +1,6 @@
-use crate::services::java_detector;
+use crate::models::java_catalog::{JavaDistribution, JavaPackage};
+use crate::models::settings::PartialSettings;
+use crate::services::{global, java_detector, java_inventory, java_requirements};
 use once_cell::sync::Lazy;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
@@ -9,9 +11,15 @@ static JAVA_INSTALL_CANCEL_FLAG: Lazy<Mutex<Option<Arc<AtomicBool>>>> =
 
 #[tauri::command]
 pub async fn detect_java() -> Result<Vec<java_detector::JavaInfo>, String> {
-    tauri::async_runtime::spawn_blocking(java_detector::detect_java_installations)
+    let javas = tauri::async_runtime::spawn_blocking(java_detector::detect_java_installations)
         .await
-        .map_err(|e| format!("Java 检测任务失败: {}", e))
+        .map_err(|e| format!("Java 检测任务失败: {}", e))?;
+    // 保存检测结果，下次检测时路径与修改时间未变的 Java 不再重新检查
+    global::settings_manager().update_partial(PartialSettings {
+        cached_java_list: Some(javas.clone()),
+        ..Default::default()
+    })?;
+    Ok(javas)
 }
 
 #[tauri::command]
@@ -21,12 +29,44 @@ pub async fn validate_java_path(path: String) -> Result<java_detector::JavaInfo,
         .map_err(|e| format!("Java 路径验证任务失败: {}", e))?
 }
 
+/// 按 Minecraft 版本与核心类型推荐已安装的 Java，没有兼容的 Java 时给出需要安装的版本
+#[tauri::command]
+pub async fn recommend_java(
+    mc_version: String,
+    core_type: String,
+) -> Result<java_requirements::JavaRecommendation, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        java_requirements::recommend(&mc_version, &core_type)
+    })
+    .await
+    .map_err(|e| format!("Java 推荐任务失败: {}", e))
+}
+
+/// 可安装的 Java 发行版与主版本，离线时使用缓存
+#[tauri::command]
+pub async fn list_java_distributions() -> Result<Vec<JavaDistribution>, String> {
+    Ok(global::java_catalog().distributions().await)
+}
+
+/// 当前系统上可下载的 Java 包，第一个为推荐的包
+#[tauri::command]
+pub async fn list_java_packages(
+    distribution: String,
+    major_version: u32,
+    image_type: Option<String>,
+) -> Result<Vec<JavaPackage>, String> {
+    global::java_catalog()
+        .packages(&distribution, major_version, image_type.as_deref().unwrap_or("jdk"))
+        .await
+}
+
 #[tauri::command]
 pub async fn install_java<R: tauri::Runtime>(
     _app: tauri::AppHandle<R>,
     window: tauri::Window<R>,
     url: String,
     version_name: String,
+    checksum: Option<String>,
 ) -> Result<String, String> {
     use crate::services::java_installer;
 
@@ -41,7 +81,8 @@ pub async fn install_java<R: tauri::Runtime>(
     }
 
     let result =
-        java_installer::download_and_install_java(url, version_name, window, cancel_flag).await;
+        java_installer::download_and_install_java(url, version_name, checksum, window, cancel_flag)
+            .await;
 
     // Clear flag after done
     if let Ok(mut lock) = JAVA_INSTALL_CANCEL_FLAG.lock() {
@@ -63,3 +104,40 @@ pub async fn cancel_java_install() -> Result<(), String> {
 
     Ok(())
 }
+
+/// `runtimes/` 下的运行时，包含占用空间与使用它们的服务器
+#[tauri::command]
+pub async fn list_java_runtimes() -> Result<Vec<java_inventory::RuntimeUsage>, String> {
+    tauri::async_runtime::spawn_blocking(java_inventory::list_runtimes)
+        .await
+        .map_err(|e| format!("运行时列表任务失败: {}", e))
+}
+
+/// 卸载没有服务器使用的运行时
+#[tauri::command]
+pub async fn uninstall_java_runtime(name: String) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || java_inventory::uninstall_runtime(&name))
+        .await
+        .map_err(|e| format!("卸载运行时任务失败: {}", e))?
+}
+
+/// 把使用某个 Java 的服务器全部切换到另一个 Java，返回切换的服务器 ID
+#[tauri::command]
+pub async fn repoint_servers_java(
+    from_java_path: String,
+    to_java_path: String,
+) -> Result<Vec<String>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        java_inventory::repoint_servers(&from_java_path, &to_java_path)
+    })
+    .await
+    .map_err(|e| format!("切换 Java 任务失败: {}", e))?
+}
+
+/// Java 路径已不存在的服务器与推荐的替代
+#[tauri::command]
+pub async fn find_missing_java() -> Result<Vec<java_inventory::MissingJava>, String> {
+    tauri::async_runtime::spawn_blocking(java_inventory::missing_java)
+        .await
+        .map_err(|e| format!("Java 检查任务失败: {}", e))
+}
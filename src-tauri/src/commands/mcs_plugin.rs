@@ -1,5 +1,13 @@
 use crate::models::mcs_plugin::*;
+use crate::models::plugin_repository::{
+    PluginInstallRecord, PluginSearchPage, PluginSearchQuery, PluginUpdate, PluginVersion,
+};
+use crate::models::server::ServerInstance;
+use crate::services::download_manager::DownloadManager;
 use crate::services::global;
+use crate::services::plugin_repository::{self, PluginTarget};
+use std::path::Path;
+use tauri::State;
 
 fn m_manager() -> &'static crate::services::mcs_plugin_manager::m_PluginManager {
     global::m_plugin_manager()
@@ -7,17 +15,36 @@ fn m_manager() -> &'static crate::services::mcs_plugin_manager::m_PluginManager
 
 #[tauri::command]
 pub async fn m_get_plugins(server_id: String) -> Result<Vec<m_PluginInfo>, String> {
-    let server_path = {
+    let (server_path, core_type) = {
         let server_manager = global::server_manager();
         let servers = server_manager.servers.lock().unwrap();
         let server = servers
             .iter()
             .find(|s| s.id == server_id)
             .ok_or("Server not found")?;
-        server.path.clone()
+        (server.path.clone(), server.core_type.clone())
     };
 
-    m_manager().m_get_plugins(&server_path).await
+    m_manager().m_get_plugins(&server_path, &core_type).await
+}
+
+#[tauri::command]
+pub async fn m_check_plugin_dependencies(
+    server_id: String,
+) -> Result<m_PluginDependencyReport, String> {
+    let server = {
+        let server_manager = global::server_manager();
+        let servers = server_manager.servers.lock().unwrap();
+        servers
+            .iter()
+            .find(|s| s.id == server_id)
+            .cloned()
+            .ok_or("Server not found")?
+    };
+
+    m_manager()
+        .m_check_plugin_dependencies(&server.path, &server.core_type, &server.mc_version)
+        .await
 }
 
 #[tauri::command]
@@ -80,3 +107,84 @@ pub async fn m_install_plugin(
         .m_install_plugin(&server_path, file_data, &file_name)
         .await
 }
+
+fn m_find_server(server_id: &str) -> Result<ServerInstance, String> {
+    let server_manager = global::server_manager();
+    let servers = server_manager.servers.lock().unwrap();
+    servers
+        .iter()
+        .find(|s| s.id == server_id)
+        .cloned()
+        .ok_or_else(|| "Server not found".to_string())
+}
+
+fn m_target(server: &ServerInstance) -> PluginTarget<'_> {
+    PluginTarget {
+        core_type: &server.core_type,
+        mc_version: &server.mc_version,
+    }
+}
+
+/// 在 Hangar / Spiget 中搜索适用于该服务器的插件
+#[tauri::command]
+pub async fn m_search_repository_plugins(
+    server_id: String,
+    query: PluginSearchQuery,
+) -> Result<PluginSearchPage, String> {
+    let server = m_find_server(&server_id)?;
+    global::plugin_repository()
+        .search(m_target(&server), &query)
+        .await
+}
+
+#[tauri::command]
+pub async fn m_get_repository_plugin_versions(
+    server_id: String,
+    source: String,
+    project_id: String,
+) -> Result<Vec<PluginVersion>, String> {
+    let server = m_find_server(&server_id)?;
+    global::plugin_repository()
+        .get_versions(m_target(&server), &source, &project_id)
+        .await
+}
+
+/// 从插件仓库安装或更新插件，未指定版本时安装最新的兼容版本
+#[tauri::command]
+pub async fn m_install_repository_plugin(
+    server_id: String,
+    source: String,
+    project_id: String,
+    version_id: Option<String>,
+    downloads: State<'_, DownloadManager>,
+) -> Result<PluginInstallRecord, String> {
+    let server = m_find_server(&server_id)?;
+    global::plugin_repository()
+        .install(
+            &downloads,
+            Path::new(&server.path),
+            m_target(&server),
+            &source,
+            &project_id,
+            version_id.as_deref(),
+        )
+        .await
+}
+
+#[tauri::command]
+pub fn m_list_repository_plugin_records(
+    server_id: String,
+) -> Result<Vec<PluginInstallRecord>, String> {
+    let server = m_find_server(&server_id)?;
+    Ok(plugin_repository::load_records(Path::new(&server.path)))
+}
+
+#[tauri::command]
+pub async fn m_check_repository_plugin_updates(
+    server_id: String,
+) -> Result<Vec<PluginUpdate>, String> {
+    let server = m_find_server(&server_id)?;
+    Ok(global::plugin_repository()
+        .check_updates(Path::new(&server.path), m_target(&server))
+        .await)
+}
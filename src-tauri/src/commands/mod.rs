@@ -3,12 +3,15 @@ pub mod downloader;
 pub mod java;
 pub mod logging;
 pub mod mcs_plugin;
+pub mod mods;
 pub mod player;
 pub mod plugin;
+pub mod resource_pack;
 pub mod server;
 pub mod settings;
 pub mod system;
 pub mod update;
+pub mod world;
 
 // 更新功能子模块
 mod update_arch;
@@ -1,39 +1,126 @@
+use crate::models::server::ServerInstance;
+use crate::models::server_mod::{
+    ContentUpdate, ContentUpdateRequest, InstalledMod, ModInstallRecord, ModPreflightReport,
+    ModSearchPage, ModSearchQuery, ModVersion,
+};
+use crate::services::download_manager::DownloadManager;
 use crate::services::global;
-use crate::services::mod_manager::ModInfo;
+use crate::services::update_rollback::{self, PendingRollback};
+use crate::services::{content_updates, mod_manager, mod_metadata, mod_preflight};
 use std::path::PathBuf;
-use tauri::command;
+use tauri::{command, State};
 
+fn find_server(server_id: &str) -> Result<ServerInstance, String> {
+    global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| format!("未找到服务器: {}", server_id))
+}
+
+fn server_path(server_id: &str) -> Result<PathBuf, String> {
+    find_server(server_id).map(|s| PathBuf::from(s.path))
+}
+
+#[command]
+pub async fn search_mods(query: ModSearchQuery) -> Result<ModSearchPage, String> {
+    global::mod_manager().search(&query).await
+}
+
+/// 项目中适用于该服务器的版本
 #[command]
-pub async fn search_mods(
-    query: String,
-    game_version: String,
-    loader: String,
-) -> Result<Vec<ModInfo>, String> {
-    let mod_manager = global::mod_manager();
-    mod_manager
-        .search_modrinth(&query, &game_version, &loader)
+pub async fn get_mod_versions(
+    server_id: String,
+    project_id: String,
+) -> Result<Vec<ModVersion>, String> {
+    let server = find_server(&server_id)?;
+    let loaders = mod_preflight::server_loaders(&server.core_type, &server.mc_version);
+    global::mod_manager()
+        .get_project_versions(&project_id, Some(&server.mc_version), &loaders)
         .await
 }
 
+/// 安装模组及其必需前置，未指定版本时安装兼容的最新版本
 #[command]
 pub async fn install_mod(
     server_id: String,
-    download_url: String,
-    file_name: String,
-) -> Result<(), String> {
-    let server_path = {
-        let server_manager = global::server_manager();
-        let servers = server_manager.servers.lock().unwrap();
-        let server = servers
-            .iter()
-            .find(|s| s.id == server_id)
-            .ok_or("Server not found")?;
-        server.path.clone()
-    }; // MutexGuard 在这里被释放
-
-    let mods_dir = PathBuf::from(&server_path).join("mods");
-    let target_path = mods_dir.join(file_name);
-
-    let mod_manager = global::mod_manager();
-    mod_manager.download_mod(&download_url, &target_path).await
+    project_id: String,
+    version_id: Option<String>,
+    downloads: State<'_, DownloadManager>,
+) -> Result<Vec<ModInstallRecord>, String> {
+    let server = find_server(&server_id)?;
+    let loaders = mod_preflight::server_loaders(&server.core_type, &server.mc_version);
+    global::mod_manager()
+        .install(
+            &downloads,
+            &PathBuf::from(&server.path),
+            &server.mc_version,
+            &loaders,
+            &project_id,
+            version_id.as_deref(),
+        )
+        .await
+}
+
+#[command]
+pub fn list_mod_install_records(server_id: String) -> Result<Vec<ModInstallRecord>, String> {
+    Ok(mod_manager::load_records(&server_path(&server_id)?))
+}
+
+#[command]
+pub fn remove_mod(server_id: String, file_name: String) -> Result<(), String> {
+    mod_manager::remove_mod(&server_path(&server_id)?, &file_name)
+}
+
+#[command]
+pub async fn list_installed_mods(server_id: String) -> Result<Vec<InstalledMod>, String> {
+    let server_path = server_path(&server_id)?;
+    tauri::async_runtime::spawn_blocking(move || mod_metadata::scan_mods(&server_path))
+        .await
+        .map_err(|e| format!("扫描模组任务失败: {}", e))?
+}
+
+#[command]
+pub fn toggle_mod(server_id: String, file_name: String, enabled: bool) -> Result<(), String> {
+    let server_path = server_path(&server_id)?;
+    mod_metadata::set_mod_enabled(&server_path, &file_name, enabled)
+}
+
+#[command]
+pub async fn check_mod_preflight(server_id: String) -> Result<ModPreflightReport, String> {
+    let server = find_server(&server_id)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        mod_preflight::check(&PathBuf::from(&server.path), &server.core_type, &server.mc_version)
+    })
+    .await
+    .map_err(|e| format!("模组检查任务失败: {}", e))?
+}
+
+/// 按文件摘要检查 mods 与 plugins 目录的更新
+#[command]
+pub async fn check_content_updates(server_id: String) -> Result<Vec<ContentUpdate>, String> {
+    let server = find_server(&server_id)?;
+    content_updates::check_updates(global::mod_manager(), &server).await
+}
+
+/// 应用更新，下一次启动失败时自动回滚
+#[command]
+pub async fn apply_content_updates(
+    server_id: String,
+    updates: Vec<ContentUpdateRequest>,
+    downloads: State<'_, DownloadManager>,
+) -> Result<PendingRollback, String> {
+    let server = find_server(&server_id)?;
+    content_updates::apply_updates(global::mod_manager(), &downloads, &server, &updates).await
+}
+
+#[command]
+pub fn get_pending_rollback(server_id: String) -> Result<Option<PendingRollback>, String> {
+    Ok(update_rollback::pending(&server_path(&server_id)?))
+}
+
+/// 手动撤销尚未通过试启动的更新
+#[command]
+pub fn rollback_pending_update(server_id: String) -> Result<bool, String> {
+    update_rollback::rollback(&server_path(&server_id)?)
 }
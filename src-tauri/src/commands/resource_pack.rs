@@ -0,0 +1,27 @@
+use crate::models::resource_pack::HostedResourcePack;
+use crate::services::global;
+
+/// 托管资源包并写入 server.properties，require 默认为 true
+#[tauri::command]
+pub async fn host_resource_pack(
+    server_id: String,
+    file_path: String,
+    require: Option<bool>,
+) -> Result<HostedResourcePack, String> {
+    global::resource_pack_host()
+        .host(&server_id, &file_path, require.unwrap_or(true))
+        .await
+}
+
+#[tauri::command]
+pub fn stop_resource_pack_hosting(
+    server_id: String,
+    clear_properties: Option<bool>,
+) -> Result<(), String> {
+    global::resource_pack_host().unhost(&server_id, clear_properties.unwrap_or(true))
+}
+
+#[tauri::command]
+pub fn get_hosted_resource_pack(server_id: String) -> Option<HostedResourcePack> {
+    global::resource_pack_host().get(&server_id)
+}
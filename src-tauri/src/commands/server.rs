@@ -1,6 +1,12 @@
+use crate::models::core_catalog::{CoreBuild, CoreUpgradeResult, CoreVersion};
+use crate::models::crash::CrashAnalysis;
 use crate::models::server::*;
-use crate::services::global;
-use std::path::Path;
+use crate::services::download_manager::DownloadManager;
+use crate::services::{
+    core_catalog, core_upgrade, global, loader_installer, modpack_import, server_export,
+};
+use std::path::{Path, PathBuf};
+use tauri::State;
 
 fn manager() -> &'static crate::services::server_manager::ServerManager {
     global::server_manager()
@@ -87,9 +93,11 @@ pub fn add_existing_server(
     manager().add_existing_server(req)
 }
 
+/// 便携包按导出时的信息恢复，Modrinth 与 CurseForge 整合包按清单下载并安装加载器，
+/// 其余来源直接解压或复制
 #[tauri::command]
 #[allow(clippy::too_many_arguments)]
-pub fn import_modpack(
+pub async fn import_modpack(
     name: String,
     modpack_path: String,
     java_path: String,
@@ -103,6 +111,7 @@ pub fn import_modpack(
     startup_file_path: Option<String>,
     core_type: Option<String>,
     mc_version: Option<String>,
+    downloads: State<'_, DownloadManager>,
 ) -> Result<ServerInstance, String> {
     let req = ImportModpackRequest {
         name,
@@ -119,7 +128,144 @@ pub fn import_modpack(
         core_type,
         mc_version,
     };
-    manager().import_modpack(req)
+    let archive = PathBuf::from(&req.modpack_path);
+    let bundle_path = archive.clone();
+    let bundle =
+        tauri::async_runtime::spawn_blocking(move || server_export::read_bundle(&bundle_path))
+            .await
+            .map_err(|e| format!("读取便携包任务失败: {}", e))??;
+    if let Some(bundle) = bundle {
+        return tauri::async_runtime::spawn_blocking(move || {
+            server_export::import_bundle(req, bundle)
+        })
+        .await
+        .map_err(|e| format!("导入便携包任务失败: {}", e))?;
+    }
+
+    let pack = tauri::async_runtime::spawn_blocking(move || modpack_import::read_pack(&archive))
+        .await
+        .map_err(|e| format!("读取整合包任务失败: {}", e))??;
+    if let Some(pack) = pack {
+        return modpack_import::import(&downloads, req, pack).await;
+    }
+    tauri::async_runtime::spawn_blocking(move || manager().import_modpack(req))
+        .await
+        .map_err(|e| format!("导入整合包任务失败: {}", e))?
+}
+
+/// 可以自动下载的核心类型
+#[tauri::command]
+pub fn list_downloadable_core_types() -> Vec<String> {
+    core_catalog::SUPPORTED_CORE_TYPES
+        .iter()
+        .chain(loader_installer::LOADER_CORE_TYPES.iter())
+        .map(|key| key.to_string())
+        .collect()
+}
+
+#[tauri::command]
+pub async fn list_core_versions(core_type: String) -> Result<Vec<CoreVersion>, String> {
+    global::core_catalog().list_versions(&core_type).await
+}
+
+#[tauri::command]
+pub async fn list_core_builds(
+    core_type: String,
+    mc_version: String,
+) -> Result<Vec<CoreBuild>, String> {
+    global::core_catalog()
+        .list_builds(&core_type, &mc_version)
+        .await
+}
+
+/// 下载核心并创建服务器，未指定构建时使用最新的稳定构建
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_server_from_core(
+    name: String,
+    core_type: String,
+    mc_version: String,
+    build: Option<String>,
+    java_path: String,
+    max_memory: u32,
+    min_memory: u32,
+    port: u16,
+    online_mode: bool,
+    run_path: String,
+    downloads: State<'_, DownloadManager>,
+) -> Result<ServerInstance, String> {
+    let req = ImportModpackRequest {
+        name,
+        modpack_path: String::new(),
+        java_path,
+        max_memory,
+        min_memory,
+        port,
+        startup_mode: "jar".to_string(),
+        online_mode,
+        custom_command: None,
+        run_path,
+        startup_file_path: None,
+        core_type: None,
+        mc_version: None,
+    };
+    global::core_catalog()
+        .create_server(&downloads, req, &core_type, &mc_version, build.as_deref())
+        .await
+}
+
+/// 原地升级核心并试启动，启动失败时自动回滚
+#[tauri::command]
+pub async fn upgrade_server_core(
+    server_id: String,
+    mc_version: Option<String>,
+    build: Option<String>,
+    keep_running: bool,
+    downloads: State<'_, DownloadManager>,
+) -> Result<CoreUpgradeResult, String> {
+    core_upgrade::upgrade(
+        &downloads,
+        &server_id,
+        mc_version.as_deref(),
+        build.as_deref(),
+        keep_running,
+    )
+    .await
+}
+
+/// 在服务器目录中运行 Forge/NeoForge/Fabric/Quilt 安装器，之后直接启动生成的文件
+#[tauri::command]
+pub async fn install_server_loader(
+    server_id: String,
+    loader_version: Option<String>,
+) -> Result<ServerInstance, String> {
+    core_upgrade::install_loader(&server_id, loader_version.as_deref()).await
+}
+
+/// 读取 Modrinth 或 CurseForge 整合包的清单，其他压缩包返回空
+#[tauri::command]
+pub async fn inspect_modpack(modpack_path: String) -> Result<Option<ModpackInfo>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        modpack_import::read_pack(Path::new(&modpack_path)).map(|pack| pack.map(|p| p.info))
+    })
+    .await
+    .map_err(|e| format!("读取整合包任务失败: {}", e))?
+}
+
+/// 导出为便携包（bundle）、.mrpack（mrpack）或客户端包（client）
+#[tauri::command]
+pub async fn export_server(
+    server_id: String,
+    format: String,
+    output_path: String,
+    include: Vec<String>,
+) -> Result<ServerExportResult, String> {
+    let server = manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| format!("未找到服务器: {}", server_id))?;
+    server_export::export(&server, &format, Path::new(&output_path), &include).await
 }
 
 #[tauri::command]
@@ -187,6 +333,50 @@ fn scan_startup_candidates_blocking(
         .collect::<Vec<String>>();
 
     if source_kind == "archive" {
+        // 便携包与 Modrinth/CurseForge 整合包的核心与版本取自清单，启动文件在导入时确定
+        let manifest = match server_export::read_bundle(source)? {
+            Some(bundle) => Some((
+                "bundle",
+                bundle.server.core_type,
+                bundle.server.core_version,
+                bundle.server.mc_version,
+            )),
+            None => modpack_import::read_pack(source)?.map(|pack| {
+                let info = pack.info;
+                ("modpack-loader", info.core_type, info.loader_version, info.mc_version)
+            }),
+        };
+        if let Some((id, core_type, core_version, mc_version)) = manifest {
+            candidates.push(StartupCandidateItem {
+                id: id.to_string(),
+                mode: "jar".to_string(),
+                label: core_type.clone(),
+                detail: [core_version.as_str(), mc_version.as_str()]
+                    .into_iter()
+                    .filter(|value| !value.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(" · "),
+                path: String::new(),
+                recommended: 1,
+            });
+            return Ok(StartupScanResult {
+                detected_core_type_key:
+                    crate::services::server_installer::CoreType::normalize_to_api_core_key(
+                        &core_type,
+                    ),
+                parsed_core: ParsedServerCoreInfo {
+                    core_type,
+                    main_class: None,
+                    jar_path: None,
+                },
+                candidates,
+                core_type_options,
+                mc_version_options,
+                detected_mc_version: Some(mc_version),
+                mc_version_detection_failed: false,
+            });
+        }
+
         // 检查是否是 jar 文件，直接作为启动项
         if source.is_file() {
             let extension = source
@@ -518,9 +708,16 @@ fn copy_directory_recursive(source: &Path, target: &Path) -> Result<(), std::io:
     Ok(())
 }
 
+/// 启动前会扫描模组，在后台线程执行；allow_client_only 为 true 时仅客户端模组不阻止启动。
+/// starter 方式的加载器服务器首次启动时先安装加载器
 #[tauri::command]
-pub fn start_server(id: String) -> Result<(), String> {
-    manager().start_server(&id)
+pub async fn start_server(id: String, allow_client_only: Option<bool>) -> Result<(), String> {
+    core_upgrade::prepare_starter(&id).await?;
+    tauri::async_runtime::spawn_blocking(move || {
+        manager().start_server_with(&id, allow_client_only.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("启动服务器任务失败: {}", e))?
 }
 
 #[tauri::command]
@@ -553,6 +750,16 @@ pub fn get_server_logs(id: String, since: usize, max_lines: Option<usize>) -> Ve
     crate::services::server_log_pipeline::get_logs(&id, since, max_lines)
 }
 
+/// 分析最近的日志与崩溃报告，区块加载失败时会自动校验区域文件
+#[tauri::command]
+pub async fn analyze_server_crash(id: String) -> Result<CrashAnalysis, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::services::crash_analyzer::analyze_server(&id)
+    })
+    .await
+    .map_err(|e| format!("崩溃分析任务失败: {}", e))?
+}
+
 #[tauri::command]
 pub fn update_server_name(id: String, name: String) -> Result<(), String> {
     manager().update_server_name(&id, &name)
@@ -0,0 +1,170 @@
+use crate::models::server::{ServerInstance, ServerStatus};
+use crate::models::world::*;
+use crate::services::datapack_manager;
+use crate::services::global;
+use crate::services::region_checker;
+use crate::services::world_pruner;
+use std::path::{Path, PathBuf};
+
+fn manager() -> &'static crate::services::server_manager::ServerManager {
+    global::server_manager()
+}
+
+fn find_server(server_id: &str) -> Result<ServerInstance, String> {
+    manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| "未找到服务器".to_string())
+}
+
+fn find_server_path(server_id: &str) -> Result<PathBuf, String> {
+    find_server(server_id).map(|s| PathBuf::from(s.path))
+}
+
+fn is_server_running(server_id: &str) -> bool {
+    manager().get_server_status(server_id).status == ServerStatus::Running
+}
+
+fn send_commands(server_id: &str, commands: &[String]) -> Result<(), String> {
+    for command in commands {
+        manager().send_command(server_id, command)?;
+    }
+    Ok(())
+}
+
+fn ensure_server_stopped(server_id: &str) -> Result<(), String> {
+    if manager().get_server_status(server_id).status != ServerStatus::Stopped {
+        return Err("请先停止服务器再修改世界文件".to_string());
+    }
+    Ok(())
+}
+
+/// 裁剪低 InhabitedTime 区块，dry_run 默认开启
+#[tauri::command]
+pub async fn prune_world_chunks(
+    server_id: String,
+    world_name: Option<String>,
+    min_inhabited_ticks: i64,
+    protected_radius: u32,
+    dry_run: Option<bool>,
+) -> Result<WorldPruneReport, String> {
+    let req = WorldPruneRequest {
+        server_id,
+        world_name,
+        min_inhabited_ticks,
+        protected_radius,
+        dry_run: dry_run.unwrap_or(true),
+    };
+
+    let server_path = find_server_path(&req.server_id)?;
+    if !req.dry_run {
+        ensure_server_stopped(&req.server_id)?;
+    }
+
+    tauri::async_runtime::spawn_blocking(move || world_pruner::prune_world(&server_path, &req))
+        .await
+        .map_err(|e| format!("区块裁剪任务失败: {}", e))?
+}
+
+/// 校验世界内所有区域文件的完整性
+#[tauri::command]
+pub async fn scan_world_regions(
+    server_id: String,
+    world_name: Option<String>,
+) -> Result<RegionScanReport, String> {
+    let server_path = find_server_path(&server_id)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        region_checker::scan_world(&server_path, world_name.as_deref())
+    })
+    .await
+    .map_err(|e| format!("区域校验任务失败: {}", e))?
+}
+
+/// 将校验出的损坏区块移入隔离区，服务器下次加载时会重新生成
+#[tauri::command]
+pub async fn quarantine_region_chunks(
+    server_id: String,
+    world_name: Option<String>,
+    issues: Vec<RegionIssue>,
+) -> Result<RegionRepairReport, String> {
+    let server_path = find_server_path(&server_id)?;
+    ensure_server_stopped(&server_id)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        region_checker::quarantine_chunks(&server_path, world_name.as_deref(), &issues)
+    })
+    .await
+    .map_err(|e| format!("区块隔离任务失败: {}", e))?
+}
+
+#[tauri::command]
+pub fn list_datapacks(
+    server_id: String,
+    world_name: Option<String>,
+) -> Result<Vec<DatapackInfo>, String> {
+    let server = find_server(&server_id)?;
+    datapack_manager::list_datapacks(
+        Path::new(&server.path),
+        world_name.as_deref(),
+        &server.mc_version,
+    )
+}
+
+/// 安装本地 zip 或文件夹数据包，服务器运行时立即启用
+#[tauri::command]
+pub fn install_datapack(
+    server_id: String,
+    world_name: Option<String>,
+    source_path: String,
+) -> Result<DatapackInfo, String> {
+    let server = find_server(&server_id)?;
+    let info = datapack_manager::install_datapack(
+        Path::new(&server.path),
+        world_name.as_deref(),
+        &source_path,
+        &server.mc_version,
+    )?;
+
+    if is_server_running(&server_id) {
+        let commands = datapack_manager::set_datapack_enabled(
+            Path::new(&server.path),
+            world_name.as_deref(),
+            &info.file_name,
+            true,
+            true,
+        )?;
+        send_commands(&server_id, &commands)?;
+    }
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn set_datapack_enabled(
+    server_id: String,
+    world_name: Option<String>,
+    file_name: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let server = find_server(&server_id)?;
+    let commands = datapack_manager::set_datapack_enabled(
+        Path::new(&server.path),
+        world_name.as_deref(),
+        &file_name,
+        enabled,
+        is_server_running(&server_id),
+    )?;
+    send_commands(&server_id, &commands)
+}
+
+#[tauri::command]
+pub fn remove_datapack(
+    server_id: String,
+    world_name: Option<String>,
+    file_name: String,
+) -> Result<(), String> {
+    let server = find_server(&server_id)?;
+    if is_server_running(&server_id) {
+        manager().send_command(&server_id, &datapack_manager::disable_command(&file_name))?;
+    }
+    datapack_manager::remove_datapack(Path::new(&server.path), world_name.as_deref(), &file_name)
+}
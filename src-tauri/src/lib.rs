@@ -9,12 +9,15 @@ use commands::downloader as download_commands;
 use commands::java as java_commands;
 use commands::logging as logging_commands;
 use commands::mcs_plugin as mcs_plugin_commands;
+use commands::mods as mod_commands;
 use commands::player as player_commands;
 use commands::plugin as plugin_commands;
+use commands::resource_pack as resource_pack_commands;
 use commands::server as server_commands;
 use commands::settings as settings_commands;
 use commands::system as system_commands;
 use commands::update as update_commands;
+use commands::world as world_commands;
 
 use crate::services::download_manager::DownloadManager;
 use plugins::manager::PluginManager;
@@ -99,6 +102,14 @@ pub fn run() {
             server_commands::import_server,
             server_commands::add_existing_server,
             server_commands::import_modpack,
+            server_commands::inspect_modpack,
+            server_commands::list_downloadable_core_types,
+            server_commands::list_core_versions,
+            server_commands::list_core_builds,
+            server_commands::create_server_from_core,
+            server_commands::upgrade_server_core,
+            server_commands::install_server_loader,
+            server_commands::export_server,
             server_commands::parse_server_core_type,
             server_commands::scan_startup_candidates,
             server_commands::collect_copy_conflicts,
@@ -111,14 +122,42 @@ pub fn run() {
             server_commands::delete_server,
             server_commands::get_server_logs,
             server_commands::update_server_name,
+            server_commands::analyze_server_crash,
             java_commands::detect_java,
             java_commands::validate_java_path,
+            java_commands::recommend_java,
             java_commands::install_java,
             java_commands::cancel_java_install,
+            java_commands::list_java_distributions,
+            java_commands::list_java_packages,
+            java_commands::list_java_runtimes,
+            java_commands::uninstall_java_runtime,
+            java_commands::repoint_servers_java,
+            java_commands::find_missing_java,
             config_commands::read_config,
             config_commands::write_config,
+            config_commands::read_config_document,
+            config_commands::get_config_value,
+            config_commands::set_config_values,
             config_commands::read_server_properties,
             config_commands::write_server_properties,
+            config_commands::get_server_properties_schema,
+            config_commands::validate_server_properties,
+            config_commands::list_config_revisions,
+            config_commands::diff_config_revisions,
+            config_commands::restore_config_revision,
+            mod_commands::search_mods,
+            mod_commands::get_mod_versions,
+            mod_commands::install_mod,
+            mod_commands::list_mod_install_records,
+            mod_commands::remove_mod,
+            mod_commands::list_installed_mods,
+            mod_commands::toggle_mod,
+            mod_commands::check_mod_preflight,
+            mod_commands::check_content_updates,
+            mod_commands::apply_content_updates,
+            mod_commands::get_pending_rollback,
+            mod_commands::rollback_pending_update,
             system_commands::get_system_info,
             system_commands::pick_jar_file,
             system_commands::pick_archive_file,
@@ -165,6 +204,9 @@ pub fn run() {
             download_commands::poll_task,
             download_commands::poll_all_downloads,
             download_commands::remove_download_task,
+            download_commands::pause_download_task,
+            download_commands::resume_download_task,
+            download_commands::cancel_download_task,
             plugin_commands::list_plugins,
             plugin_commands::scan_plugins,
             plugin_commands::enable_plugin,
@@ -200,13 +242,29 @@ pub fn run() {
             plugin_commands::get_permission_list,
             plugin_commands::get_plugin_permissions,
             mcs_plugin_commands::m_get_plugins,
+            mcs_plugin_commands::m_check_plugin_dependencies,
             mcs_plugin_commands::m_toggle_plugin,
             mcs_plugin_commands::m_delete_plugin,
             mcs_plugin_commands::m_install_plugin,
+            mcs_plugin_commands::m_search_repository_plugins,
+            mcs_plugin_commands::m_get_repository_plugin_versions,
+            mcs_plugin_commands::m_install_repository_plugin,
+            mcs_plugin_commands::m_list_repository_plugin_records,
+            mcs_plugin_commands::m_check_repository_plugin_updates,
             mcs_plugin_commands::m_get_plugin_config_files,
             logging_commands::get_logs,
             logging_commands::clear_logs,
-            logging_commands::check_developer_mode
+            logging_commands::check_developer_mode,
+            world_commands::prune_world_chunks,
+            world_commands::scan_world_regions,
+            world_commands::quarantine_region_chunks,
+            world_commands::list_datapacks,
+            world_commands::install_datapack,
+            world_commands::set_datapack_enabled,
+            world_commands::remove_datapack,
+            resource_pack_commands::host_resource_pack,
+            resource_pack_commands::stop_resource_pack_hosting,
+            resource_pack_commands::get_hosted_resource_pack
         ])
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
@@ -507,6 +565,25 @@ pub fn run() {
 
             app.manage(manager.clone());
 
+            // 恢复上次托管的资源包
+            services::global::resource_pack_host().restore();
+
+            // 检查服务器的 Java 是否还存在（例如系统升级后旧 JDK 被移除），提示写入服务器日志
+            std::thread::spawn(|| {
+                for missing in services::java_inventory::missing_java() {
+                    let suggestion = match missing.suggestion {
+                        Some(java) => {
+                            format!("建议改用 Java {}（{}）", java.major_version, java.path)
+                        }
+                        None => "请重新选择 Java".to_string(),
+                    };
+                    let _ = services::server_log_pipeline::append_sealantern_log(
+                        &missing.server_id,
+                        &format!("Java 不存在: {}，{}", missing.java_path, suggestion),
+                    );
+                }
+            });
+
             if let Ok(mut m) = manager.lock() {
                 m.auto_enable_plugins();
             }
@@ -9,6 +9,14 @@ pub struct ConfigEntry {
     pub value_type: String,
     pub default_value: String,
     pub category: String,
+    /// select 类型的可选值
+    #[serde(default)]
+    pub options: Vec<String>,
+    /// number 类型的取值范围
+    #[serde(default)]
+    pub min: Option<i64>,
+    #[serde(default)]
+    pub max: Option<i64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,3 +24,25 @@ pub struct ServerProperties {
     pub entries: Vec<ConfigEntry>,
     pub raw: HashMap<String, String>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PropertyValidationError {
+    pub key: String,
+    pub value: String,
+    pub message: String,
+}
+
+/// 配置文件的一次历史修订（写入前的内容）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigRevision {
+    pub id: String,
+    /// 相对服务器目录的路径，使用 `/` 分隔
+    pub file_path: String,
+    /// 触发写入的来源，例如 config_editor、server_properties、plugin:<id>
+    pub origin: String,
+    pub created_at: u64,
+    pub size: u64,
+    /// 为 false 表示写入前文件不存在
+    pub existed: bool,
+    pub sha256: String,
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// 核心可用的 Minecraft 版本（代理端为其自身版本），最新的在前
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreVersion {
+    pub id: String,
+    /// 正式版为 true，快照、预发布与实验版本为 false
+    pub stable: bool,
+}
+
+/// 某个版本下的一个构建
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreBuild {
+    /// Paper/Leaves/Purpur 为构建号，Fabric 为加载器版本，原版为 Minecraft 版本
+    pub id: String,
+    pub stable: bool,
+}
+
+/// 解析出的核心下载信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreDownload {
+    pub core_type: String,
+    pub mc_version: String,
+    pub build: String,
+    pub url: String,
+    pub file_name: String,
+    pub sha1: Option<String>,
+    pub sha256: Option<String>,
+    /// Purpur 只提供 MD5
+    #[serde(default)]
+    pub md5: Option<String>,
+}
+
+/// 核心升级的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreUpgradeResult {
+    pub previous_core_version: String,
+    pub previous_mc_version: String,
+    pub core_version: String,
+    pub mc_version: String,
+    pub jar_path: String,
+    /// 试启动成功后服务器是否仍在运行
+    pub running: bool,
+    /// 升级前世界与配置的便携包备份
+    pub backup_path: String,
+}
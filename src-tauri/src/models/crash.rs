@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use super::world::{RegionIssue, RegionScanReport};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkLoadError {
+    /// 日志或崩溃报告中的原始行
+    pub line: String,
+    /// 能从日志中识别出坐标时才有值
+    pub chunk_x: Option<i32>,
+    pub chunk_z: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashAnalysis {
+    pub server_id: String,
+    /// 参与分析的崩溃报告文件（crash-reports 目录下最新的一份）
+    pub crash_report: Option<String>,
+    pub chunk_errors: Vec<ChunkLoadError>,
+    /// 发现区块加载错误时自动执行的区域文件校验
+    pub region_scan: Option<RegionScanReport>,
+    /// 区域校验中与日志坐标吻合的问题区块
+    pub matched_issues: Vec<RegionIssue>,
+    pub suggestions: Vec<String>,
+}
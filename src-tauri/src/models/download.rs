@@ -5,7 +5,10 @@ use uuid::Uuid;
 pub enum TaskStatus {
     Pending,
     Downloading,
+    /// 已暂停，可以继续；应用重启后未完成的任务也处于此状态
+    Paused,
     Completed,
+    Cancelled,
     Error(String),
 }
 
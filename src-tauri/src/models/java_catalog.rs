@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+/// 可以自动安装的 Java 发行版
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JavaDistribution {
+    /// temurin / zulu / liberica / graalvm
+    pub id: String,
+    pub name: String,
+    /// 可安装的主版本，最新的在前
+    pub major_versions: Vec<u32>,
+    pub lts_versions: Vec<u32>,
+    /// 是否提供 musl（Alpine 等）版本
+    pub supports_musl: bool,
+}
+
+/// 当前平台上的一个可下载的 Java 包
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JavaPackage {
+    pub distribution: String,
+    pub major_version: u32,
+    /// 完整版本号，例如 `17.0.9+9`
+    pub version: String,
+    /// windows / linux / macos
+    pub os: String,
+    /// x64 / aarch64 / x86
+    pub arch: String,
+    /// Linux 上为 glibc 或 musl，其他系统为空
+    pub libc: String,
+    /// jdk / jre
+    pub image_type: String,
+    pub file_name: String,
+    pub url: String,
+    pub size: u64,
+    /// 发行版提供的摘要，格式见 [`crate::utils::hash::verify_file`]；Liberica 为 `sha1:<hex>`
+    #[serde(alias = "sha256")]
+    pub checksum: Option<String>,
+}
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 #[allow(non_camel_case_types)]
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -14,6 +14,19 @@ pub struct m_PluginInfo {
     pub main_class: String,
     pub has_config_folder: bool,
     pub config_files: Vec<m_PluginConfigFile>,
+    /// 描述文件来源：paper / bukkit / bungee / velocity
+    #[serde(default)]
+    pub platform: String,
+    #[serde(default)]
+    pub depend: Vec<String>,
+    #[serde(default)]
+    pub softdepend: Vec<String>,
+    #[serde(default)]
+    pub loadbefore: Vec<String>,
+    #[serde(default)]
+    pub provides: Vec<String>,
+    #[serde(default)]
+    pub api_version: Option<String>,
 }
 
 #[allow(non_camel_case_types)]
@@ -34,4 +47,74 @@ pub struct m_PluginConfig {
     pub author: Option<String>,
     pub authors: Option<Vec<String>>,
     pub main: Option<String>,
+    /// bungee.yml 使用 `depends`
+    #[serde(default, alias = "depends", deserialize_with = "string_or_list")]
+    pub depend: Vec<String>,
+    /// bungee.yml 使用 `softDepends`
+    #[serde(default, alias = "softDepends", deserialize_with = "string_or_list")]
+    pub softdepend: Vec<String>,
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub loadbefore: Vec<String>,
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub provides: Vec<String>,
+    #[serde(default, rename = "api-version", deserialize_with = "scalar_as_string")]
+    pub api_version: Option<String>,
+}
+
+/// 部分插件把单个依赖直接写成字符串
+fn string_or_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        One(String),
+        Many(Vec<String>),
+        Null(()),
+    }
+    Ok(match Value::deserialize(deserializer)? {
+        Value::One(value) => vec![value],
+        Value::Many(values) => values,
+        Value::Null(()) => Vec::new(),
+    })
+}
+
+/// `api-version: 1.20` 按数字解析会变成 1.2。serde_yaml 按字符串读取标量时保留原文，
+/// 所以这里直接请求字符串，不能经过 untagged 等会先缓存为通用类型的方式
+fn scalar_as_string<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    Option::<String>::deserialize(deserializer)
+}
+
+/// 插件依赖检查发现的问题
+#[allow(non_camel_case_types)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct m_PluginDependencyIssue {
+    /// error / warning
+    pub severity: String,
+    /// missing_dependency / disabled_dependency / dependency_cycle / api_version_too_new
+    pub kind: String,
+    pub file_name: String,
+    pub plugin: String,
+    /// 相关的插件名
+    pub related: Vec<String>,
+    pub message: String,
+}
+
+/// 依赖图中的一条边，`from` 在 `to` 之后加载
+#[allow(non_camel_case_types)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct m_PluginDependencyEdge {
+    pub from: String,
+    pub to: String,
+    /// depend / softdepend / loadbefore
+    pub kind: String,
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct m_PluginDependencyReport {
+    pub plugins: Vec<m_PluginInfo>,
+    pub edges: Vec<m_PluginDependencyEdge>,
+    pub issues: Vec<m_PluginDependencyIssue>,
+    pub has_errors: bool,
 }
@@ -1,7 +1,14 @@
 pub mod config;
+pub mod core_catalog;
+pub mod crash;
+pub mod java_catalog;
 pub mod mcs_plugin;
 pub mod plugin;
+pub mod plugin_repository;
+pub mod resource_pack;
 pub mod server;
+pub mod server_mod;
 pub mod settings;
+pub mod world;
 
 pub mod download;
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+fn default_search_limit() -> u32 {
+    20
+}
+
+/// 插件仓库搜索条件。平台与 Minecraft 版本由服务器决定，不在这里传入
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginSearchQuery {
+    /// hangar / spiget
+    pub source: String,
+    #[serde(default)]
+    pub query: String,
+    #[serde(default)]
+    pub offset: u32,
+    #[serde(default = "default_search_limit")]
+    pub limit: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginSearchHit {
+    pub source: String,
+    /// Hangar 为项目 slug，Spiget 为资源 ID
+    pub project_id: String,
+    pub name: String,
+    pub author: String,
+    pub description: String,
+    pub downloads: u64,
+    pub icon_url: Option<String>,
+    /// 仓库声明支持的 Minecraft 版本
+    pub game_versions: Vec<String>,
+    /// 付费或外链资源无法直接安装
+    pub installable: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginSearchPage {
+    pub hits: Vec<PluginSearchHit>,
+    pub offset: u32,
+    pub limit: u32,
+    /// Spiget 不提供总数时为空
+    pub total: Option<u64>,
+}
+
+/// 插件的一个可下载版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginVersion {
+    pub source: String,
+    pub project_id: String,
+    /// Hangar 为版本名，Spiget 为版本 ID
+    pub version_id: String,
+    pub version_number: String,
+    /// Unix 时间戳（秒）
+    pub published: i64,
+    pub game_versions: Vec<String>,
+    pub file_name: String,
+    pub size: u64,
+    /// 仓库提供的 SHA-256，Spiget 没有
+    pub sha256: Option<String>,
+    pub download_url: String,
+    /// 前置插件名称
+    pub dependencies: Vec<String>,
+}
+
+/// 从插件仓库安装的插件，保存在 `.sealantern/plugins.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginInstallRecord {
+    pub source: String,
+    pub project_id: String,
+    pub version_id: String,
+    pub version_number: String,
+    pub file_name: String,
+    /// 安装后计算的文件 SHA-256
+    pub sha256: String,
+    pub installed_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginUpdate {
+    pub record: PluginInstallRecord,
+    /// 适用于当前平台与 Minecraft 版本的最新版本
+    pub latest_version: Option<PluginVersion>,
+    pub update_available: bool,
+    /// 查询失败时的错误信息，不影响其他插件
+    pub error: Option<String>,
+}
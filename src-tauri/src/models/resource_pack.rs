@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostedResourcePack {
+    pub server_id: String,
+    /// 资源包在本机上的绝对路径
+    pub file_path: String,
+    pub file_name: String,
+    pub sha1: String,
+    pub size: u64,
+    /// 写入 server.properties 的下载地址
+    pub url: String,
+    pub require: bool,
+    pub updated_at: u64,
+}
@@ -110,6 +110,36 @@ pub struct ImportModpackRequest {
     pub mc_version: Option<String>,
 }
 
+/// 从整合包清单读取的信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackInfo {
+    /// `modrinth` 或 `curseforge`
+    pub format: String,
+    pub name: String,
+    pub version: String,
+    pub mc_version: String,
+    /// 核心类型，与 `CoreType::as_str` 一致
+    pub core_type: String,
+    pub loader_version: String,
+    /// 需要下载的服务端文件数
+    pub file_count: usize,
+}
+
+/// 导出服务器的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerExportResult {
+    /// bundle / mrpack / client
+    pub format: String,
+    pub output_path: String,
+    /// 写入压缩包的文件数
+    pub file_count: usize,
+    /// 以摘要引用、导入时从 Modrinth 下载的模组数
+    pub referenced_mods: usize,
+    /// 被排除的仅服务端或仅客户端文件，相对服务器目录
+    pub excluded: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddExistingServerRequest {
     pub name: String,
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModDependency {
+    pub mod_id: String,
+    /// 原样保留的版本范围，Fabric 为 `>=1.20`，Forge 为 `[1.20,1.21)`
+    pub version_range: Option<String>,
+    /// required / optional / incompatible
+    pub kind: String,
+    /// 依赖生效的一侧：both / client / server
+    pub side: String,
+}
+
+/// mods 目录中的一个模组文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledMod {
+    /// 不含 `.disabled` 后缀的文件名
+    pub file_name: String,
+    pub file_size: u64,
+    pub enabled: bool,
+    /// 元数据声明支持的加载器：fabric / quilt / forge / neoforge，无法识别时为空
+    pub loaders: Vec<String>,
+    pub mod_id: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub authors: Vec<String>,
+    pub mc_version_range: Option<String>,
+    pub loader_version_range: Option<String>,
+    pub dependencies: Vec<ModDependency>,
+    /// 运行环境：both / client / server
+    pub environment: String,
+    /// 同一文件中的其他模组 ID，以及 Fabric/Quilt 的 provides
+    pub provides: Vec<String>,
+    /// 文件无法读取时的错误信息
+    pub error: Option<String>,
+}
+
+/// 启动前模组检查发现的一个问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModPreflightIssue {
+    /// error / warning
+    pub severity: String,
+    /// unreadable / wrong_loader / mc_version_mismatch / client_only / duplicate_mod /
+    /// missing_dependency / dependency_version_mismatch / incompatible_mod
+    pub kind: String,
+    pub file_name: String,
+    pub mod_id: String,
+    /// 相关的另一个模组或依赖 ID
+    pub related: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModPreflightReport {
+    /// 服务端可加载的模组类型，非模组服务端为空
+    pub loaders: Vec<String>,
+    pub mc_version: String,
+    /// 参与检查的已启用模组数量
+    pub checked_mods: usize,
+    pub issues: Vec<ModPreflightIssue>,
+    pub has_errors: bool,
+}
+
+fn default_search_limit() -> u32 {
+    20
+}
+
+/// Modrinth 搜索条件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModSearchQuery {
+    #[serde(default)]
+    pub query: String,
+    /// Minecraft 版本，如 `1.20.1`
+    #[serde(default)]
+    pub game_version: Option<String>,
+    /// 加载器，如 `fabric`、`forge`
+    #[serde(default)]
+    pub loader: Option<String>,
+    /// mod / modpack / plugin 等，默认 mod
+    #[serde(default)]
+    pub project_type: Option<String>,
+    /// 排序方式：relevance / downloads / follows / newest / updated
+    #[serde(default)]
+    pub index: Option<String>,
+    #[serde(default)]
+    pub offset: u32,
+    #[serde(default = "default_search_limit")]
+    pub limit: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModSearchHit {
+    pub project_id: String,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub downloads: u64,
+    #[serde(default)]
+    pub icon_url: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// 支持的 Minecraft 版本
+    #[serde(default)]
+    pub versions: Vec<String>,
+    #[serde(default)]
+    pub latest_version: Option<String>,
+    /// required / optional / unsupported
+    #[serde(default)]
+    pub client_side: String,
+    #[serde(default)]
+    pub server_side: String,
+    #[serde(default)]
+    pub date_modified: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModSearchPage {
+    pub hits: Vec<ModSearchHit>,
+    pub offset: u32,
+    pub limit: u32,
+    pub total_hits: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModFileHashes {
+    #[serde(default)]
+    pub sha512: Option<String>,
+    #[serde(default)]
+    pub sha1: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModVersionFile {
+    pub url: String,
+    pub filename: String,
+    #[serde(default)]
+    pub primary: bool,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub hashes: ModFileHashes,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModVersionDependency {
+    #[serde(default)]
+    pub version_id: Option<String>,
+    #[serde(default)]
+    pub project_id: Option<String>,
+    #[serde(default)]
+    pub file_name: Option<String>,
+    /// required / optional / incompatible / embedded
+    pub dependency_type: String,
+}
+
+/// Modrinth 上某个项目的一个版本
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModVersion {
+    pub id: String,
+    pub project_id: String,
+    #[serde(default)]
+    pub name: String,
+    pub version_number: String,
+    /// release / beta / alpha
+    #[serde(default)]
+    pub version_type: String,
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    #[serde(default)]
+    pub loaders: Vec<String>,
+    #[serde(default)]
+    pub date_published: String,
+    pub files: Vec<ModVersionFile>,
+    #[serde(default)]
+    pub dependencies: Vec<ModVersionDependency>,
+}
+
+impl ModVersion {
+    /// 主文件，没有标记时取第一个
+    pub fn primary_file(&self) -> Option<&ModVersionFile> {
+        self.files
+            .iter()
+            .find(|f| f.primary)
+            .or_else(|| self.files.first())
+    }
+}
+
+/// 通过 Sea Lantern 安装的模组记录，用于之后更新或移除
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModInstallRecord {
+    pub project_id: String,
+    pub version_id: String,
+    pub version_number: String,
+    pub file_name: String,
+    #[serde(default)]
+    pub sha1: Option<String>,
+    #[serde(default)]
+    pub sha512: Option<String>,
+    /// 来源平台，目前为 modrinth
+    pub source: String,
+    /// 作为前置被自动安装时，记录需要它的项目 ID
+    #[serde(default)]
+    pub required_by: Option<String>,
+    pub installed_at: u64,
+}
+
+/// mods/plugins 目录中一个文件的更新信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentUpdate {
+    /// mod / plugin
+    pub kind: String,
+    /// 不含 `.disabled` 后缀的文件名
+    pub file_name: String,
+    pub enabled: bool,
+    pub sha1: String,
+    pub sha512: String,
+    /// 未能在 Modrinth 上识别时为空
+    pub project_id: Option<String>,
+    pub current_version: Option<ModVersion>,
+    /// 适用于当前 Minecraft 版本与加载器的最新版本
+    pub latest_version: Option<ModVersion>,
+    /// 比当前版本新的兼容版本，最新的在前
+    pub newer_versions: Vec<ModVersion>,
+    pub update_available: bool,
+}
+
+/// 要应用的一项更新
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentUpdateRequest {
+    pub kind: String,
+    pub file_name: String,
+    pub version_id: String,
+}
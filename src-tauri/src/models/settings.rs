@@ -122,6 +122,54 @@ pub struct AppSettings {
 
     #[serde(default = "default_blocked_commands")]
     pub plugin_blocked_commands: Vec<String>,
+
+    // 资源包托管端口
+    #[serde(default = "default_resource_pack_port")]
+    pub resource_pack_port: u16,
+
+    // 玩家下载资源包使用的地址，留空时自动使用本机局域网 IP
+    #[serde(default)]
+    pub resource_pack_public_host: String,
+
+    // 资源包托管监听的地址，默认所有网卡；只供本机访问时可改为 127.0.0.1
+    #[serde(default = "default_resource_pack_bind_address")]
+    pub resource_pack_bind_address: String,
+
+    // 启动前模组检查发现错误时阻止启动
+    #[serde(default)]
+    pub block_start_on_mod_errors: bool,
+
+    // Modrinth API 地址，留空时使用官方地址
+    #[serde(default)]
+    pub modrinth_api_base_url: String,
+
+    // CurseForge API 地址，留空时使用官方地址；镜像通常不需要密钥
+    #[serde(default)]
+    pub curseforge_api_base_url: String,
+
+    // CurseForge API 密钥，请求时放在 x-api-key 头中
+    #[serde(default)]
+    pub curseforge_api_key: String,
+
+    // Hangar API 地址，留空时使用官方地址
+    #[serde(default)]
+    pub hangar_api_base_url: String,
+
+    // Spiget API 地址，留空时使用官方地址
+    #[serde(default)]
+    pub spiget_api_base_url: String,
+
+    // 下载服务端核心使用的 BMCLAPI 兼容镜像，留空时使用官方地址
+    #[serde(default)]
+    pub core_mirror_base_url: String,
+
+    // Adoptium 兼容的 Java 目录接口地址，留空时使用官方地址
+    #[serde(default)]
+    pub java_catalog_api_base_url: String,
+
+    // Temurin 下载镜像（例如 TUNA 的 Adoptium 目录），留空时从官方地址下载
+    #[serde(default)]
+    pub java_mirror_base_url: String,
 }
 
 fn default_true() -> bool {
@@ -193,6 +241,14 @@ fn default_close_action() -> String {
     "ask".to_string()
 }
 
+fn default_resource_pack_port() -> u16 {
+    25580
+}
+
+fn default_resource_pack_bind_address() -> String {
+    "0.0.0.0".to_string()
+}
+
 impl AppSettings {
     pub fn get_changed_groups(&self, other: &AppSettings) -> Vec<SettingsGroup> {
         let mut changed = Vec::new();
@@ -200,6 +256,18 @@ impl AppSettings {
         if self.close_servers_on_exit != other.close_servers_on_exit
             || self.auto_accept_eula != other.auto_accept_eula
             || self.close_action != other.close_action
+            || self.resource_pack_port != other.resource_pack_port
+            || self.resource_pack_public_host != other.resource_pack_public_host
+            || self.resource_pack_bind_address != other.resource_pack_bind_address
+            || self.block_start_on_mod_errors != other.block_start_on_mod_errors
+            || self.modrinth_api_base_url != other.modrinth_api_base_url
+            || self.curseforge_api_base_url != other.curseforge_api_base_url
+            || self.curseforge_api_key != other.curseforge_api_key
+            || self.hangar_api_base_url != other.hangar_api_base_url
+            || self.spiget_api_base_url != other.spiget_api_base_url
+            || self.core_mirror_base_url != other.core_mirror_base_url
+            || self.java_catalog_api_base_url != other.java_catalog_api_base_url
+            || self.java_mirror_base_url != other.java_mirror_base_url
         {
             changed.push(SettingsGroup::General);
         }
@@ -362,6 +430,42 @@ impl AppSettings {
         if let Some(ref v) = partial.plugin_blocked_commands {
             self.plugin_blocked_commands = v.clone();
         }
+        if let Some(v) = partial.resource_pack_port {
+            self.resource_pack_port = v;
+        }
+        if let Some(ref v) = partial.resource_pack_public_host {
+            self.resource_pack_public_host = v.clone();
+        }
+        if let Some(ref v) = partial.resource_pack_bind_address {
+            self.resource_pack_bind_address = v.clone();
+        }
+        if let Some(v) = partial.block_start_on_mod_errors {
+            self.block_start_on_mod_errors = v;
+        }
+        if let Some(ref v) = partial.modrinth_api_base_url {
+            self.modrinth_api_base_url = v.clone();
+        }
+        if let Some(ref v) = partial.curseforge_api_base_url {
+            self.curseforge_api_base_url = v.clone();
+        }
+        if let Some(ref v) = partial.curseforge_api_key {
+            self.curseforge_api_key = v.clone();
+        }
+        if let Some(ref v) = partial.hangar_api_base_url {
+            self.hangar_api_base_url = v.clone();
+        }
+        if let Some(ref v) = partial.spiget_api_base_url {
+            self.spiget_api_base_url = v.clone();
+        }
+        if let Some(ref v) = partial.core_mirror_base_url {
+            self.core_mirror_base_url = v.clone();
+        }
+        if let Some(ref v) = partial.java_catalog_api_base_url {
+            self.java_catalog_api_base_url = v.clone();
+        }
+        if let Some(ref v) = partial.java_mirror_base_url {
+            self.java_mirror_base_url = v.clone();
+        }
     }
 }
 
@@ -435,6 +539,30 @@ pub struct PartialSettings {
     pub plugin_allowed_commands: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub plugin_blocked_commands: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_pack_port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_pack_public_host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_pack_bind_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_start_on_mod_errors: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modrinth_api_base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curseforge_api_base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curseforge_api_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hangar_api_base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spiget_api_base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub core_mirror_base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub java_catalog_api_base_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub java_mirror_base_url: Option<String>,
 }
 
 impl Default for AppSettings {
@@ -474,6 +602,18 @@ impl Default for AppSettings {
             minimal_mode: false,
             plugin_allowed_commands: default_allowed_commands(),
             plugin_blocked_commands: default_blocked_commands(),
+            resource_pack_port: default_resource_pack_port(),
+            resource_pack_public_host: String::new(),
+            resource_pack_bind_address: default_resource_pack_bind_address(),
+            block_start_on_mod_errors: false,
+            modrinth_api_base_url: String::new(),
+            curseforge_api_base_url: String::new(),
+            curseforge_api_key: String::new(),
+            hangar_api_base_url: String::new(),
+            spiget_api_base_url: String::new(),
+            core_mirror_base_url: String::new(),
+            java_catalog_api_base_url: String::new(),
+            java_mirror_base_url: String::new(),
         }
     }
 }
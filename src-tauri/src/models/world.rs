@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldPruneRequest {
+    pub server_id: String,
+    /// 世界目录名，留空时读取 server.properties 的 level-name
+    #[serde(default)]
+    pub world_name: Option<String>,
+    /// InhabitedTime 低于该值（tick）的区块会被删除，20 tick = 1 秒
+    pub min_inhabited_ticks: i64,
+    /// 出生点周围受保护的区块半径
+    pub protected_radius: u32,
+    /// 为 true 时只统计，不修改任何文件
+    #[serde(default = "default_true")]
+    pub dry_run: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionPruneSummary {
+    pub dimension: String,
+    pub file_name: String,
+    pub chunks_total: u32,
+    pub chunks_pruned: u32,
+    pub chunks_unreadable: u32,
+    /// 区域内最近一次保存的游戏刻（LastUpdate 最大值）
+    pub latest_update_tick: i64,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub file_removed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldPruneReport {
+    pub world_path: String,
+    pub dry_run: bool,
+    pub spawn_chunk_x: i32,
+    pub spawn_chunk_z: i32,
+    pub regions_scanned: u32,
+    pub chunks_scanned: u32,
+    pub chunks_pruned: u32,
+    pub chunks_kept: u32,
+    /// 无法解析的区块一律保留
+    pub chunks_unreadable: u32,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub regions: Vec<RegionPruneSummary>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionIssueKind {
+    /// 文件不足 8 KiB，位置表/时间戳表缺失
+    TruncatedHeader,
+    /// 扇区偏移落在文件头内或超出文件末尾
+    InvalidOffset,
+    /// 与其他区块占用了相同扇区
+    OverlappingSectors,
+    /// 长度字段为 0 或超出已分配扇区
+    InvalidLength,
+    UnknownCompression,
+    MissingExternalFile,
+    DecompressFailed,
+    InvalidNbt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionIssue {
+    /// 相对世界目录的区域文件路径，例如 DIM-1/region/r.0.0.mca
+    pub region_path: String,
+    /// 文件级问题（如文件头损坏）没有区块坐标
+    pub chunk_x: Option<i32>,
+    pub chunk_z: Option<i32>,
+    pub kind: RegionIssueKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionScanReport {
+    pub world_path: String,
+    pub regions_scanned: u32,
+    pub chunks_scanned: u32,
+    /// 使用 LZ4 或自定义压缩、无法校验内容的区块数
+    pub chunks_skipped: u32,
+    pub issues: Vec<RegionIssue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionRepairReport {
+    pub world_path: String,
+    /// 隔离数据的存放目录，可手动放回以撤销修复
+    pub quarantine_path: String,
+    pub created_at: u64,
+    pub quarantined: Vec<RegionIssue>,
+    /// (问题, 失败原因)
+    pub failed: Vec<(RegionIssue, String)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatapackInfo {
+    /// 数据包在 datapacks 目录中的文件名或文件夹名
+    pub file_name: String,
+    /// 服务器内的数据包 ID，例如 file/example.zip
+    pub pack_id: String,
+    pub is_zip: bool,
+    pub enabled: bool,
+    pub pack_format: Option<i64>,
+    pub description: String,
+    pub file_size: u64,
+    /// 当前服务器版本对应的数据包格式，未知版本时为空
+    pub expected_pack_format: Option<i64>,
+    /// 数据包格式与服务器版本不匹配等提示
+    pub warning: Option<String>,
+}
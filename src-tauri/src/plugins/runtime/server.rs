@@ -1,7 +1,9 @@
 use super::helpers::validate_server_path;
 use super::PluginRuntime;
+use crate::services::config_document::{self, ConfigDocument};
+use crate::services::config_history;
 use crate::services::global::{i18n_service, server_manager};
-use mlua::Table;
+use mlua::{LuaSerdeExt, Table};
 use std::collections::HashSet;
 use std::fs;
 use std::path::PathBuf;
@@ -27,6 +29,18 @@ impl PluginRuntime {
             })
     }
 
+    fn resolve_config_path(server_id: &str, relative_path: &str) -> Result<PathBuf, mlua::Error> {
+        let server = Self::find_server(server_id)?;
+        validate_server_path(&PathBuf::from(server.path), relative_path)
+    }
+
+    fn config_error(e: String) -> mlua::Error {
+        mlua::Error::runtime(i18n_service().t_with_options(
+            "server.config_failed",
+            &crate::plugins::runtime::console::i18n_arg("0", &e),
+        ))
+    }
+
     fn map_lua_err(key: &str, e: mlua::Error) -> String {
         format!("{}: {}", i18n_service().t(key), e)
     }
@@ -109,6 +123,7 @@ impl PluginRuntime {
             .map_err(|e| Self::map_lua_err("server.set_read_file_failed", e))?;
 
         let perms = self.permissions.clone();
+        let plugin_id = self.plugin_id.clone();
         let write_file_fn = self
             .lua
             .create_function(
@@ -119,6 +134,14 @@ impl PluginRuntime {
                     let server_dir = PathBuf::from(&server.path);
                     let full_path = validate_server_path(&server_dir, &relative_path)?;
 
+                    config_history::snapshot_for(
+                        &server.id,
+                        &server_dir,
+                        &full_path,
+                        &format!("plugin:{}", plugin_id),
+                    )
+                    .map_err(mlua::Error::runtime)?;
+
                     if let Some(parent) = full_path.parent() {
                         fs::create_dir_all(parent).map_err(|e| {
                             mlua::Error::runtime(i18n_service().t_with_options(
@@ -210,6 +233,82 @@ impl PluginRuntime {
             .set("exists", exists_fn)
             .map_err(|e| Self::map_lua_err("server.set_exists_failed", e))?;
 
+        let config_table = self
+            .lua
+            .create_table()
+            .map_err(|e| Self::map_lua_err("server.create_config_table_failed", e))?;
+
+        let perms = self.permissions.clone();
+        let config_read_fn = self
+            .lua
+            .create_function(move |lua, (server_id, relative_path): (String, String)| {
+                Self::check_server_permission(&perms)?;
+                let full_path = Self::resolve_config_path(&server_id, &relative_path)?;
+                let document = ConfigDocument::load(&full_path).map_err(Self::config_error)?;
+                lua.to_value(&document.to_value())
+            })
+            .map_err(|e| Self::map_lua_err("server.create_config_read_failed", e))?;
+        config_table
+            .set("read", config_read_fn)
+            .map_err(|e| Self::map_lua_err("server.set_config_read_failed", e))?;
+
+        let perms = self.permissions.clone();
+        let config_get_fn = self
+            .lua
+            .create_function(
+                move |lua, (server_id, relative_path, key): (String, String, String)| {
+                    Self::check_server_permission(&perms)?;
+                    let full_path = Self::resolve_config_path(&server_id, &relative_path)?;
+                    let document = ConfigDocument::load(&full_path).map_err(Self::config_error)?;
+                    match document.get(&key).map_err(Self::config_error)? {
+                        Some(value) => lua.to_value(&value),
+                        None => Ok(mlua::Value::Nil),
+                    }
+                },
+            )
+            .map_err(|e| Self::map_lua_err("server.create_config_get_failed", e))?;
+        config_table
+            .set("get", config_get_fn)
+            .map_err(|e| Self::map_lua_err("server.set_config_get_failed", e))?;
+
+        let perms = self.permissions.clone();
+        let plugin_id = self.plugin_id.clone();
+        let config_set_fn = self
+            .lua
+            .create_function(
+                move |lua,
+                      (server_id, relative_path, key, value): (
+                    String,
+                    String,
+                    String,
+                    mlua::Value,
+                )| {
+                    Self::check_server_permission(&perms)?;
+                    let server = Self::find_server(&server_id)?;
+                    let server_dir = PathBuf::from(&server.path);
+                    let full_path = validate_server_path(&server_dir, &relative_path)?;
+                    let value: serde_json::Value = lua.from_value(value)?;
+                    config_history::snapshot_for(
+                        &server.id,
+                        &server_dir,
+                        &full_path,
+                        &format!("plugin:{}", plugin_id),
+                    )
+                    .map_err(mlua::Error::runtime)?;
+                    config_document::write_values(&full_path, &[(key, value)])
+                        .map_err(Self::config_error)?;
+                    Ok(true)
+                },
+            )
+            .map_err(|e| Self::map_lua_err("server.create_config_set_failed", e))?;
+        config_table
+            .set("set", config_set_fn)
+            .map_err(|e| Self::map_lua_err("server.set_config_set_failed", e))?;
+
+        server_table
+            .set("config", config_table)
+            .map_err(|e| Self::map_lua_err("server.set_config_failed", e))?;
+
         let perms = self.permissions.clone();
         let logs_table = self
             .lua
@@ -0,0 +1,343 @@
+//! 保留注释的结构化配置文档：YAML（bukkit.yml、paper-global.yml、插件配置）、
+//! TOML（Forge/NeoForge 的 `*-server.toml`、velocity.toml）与 JSON/JSON5。
+//!
+//! 路径用 `.` 分隔，例如 `settings.connection-throttle`；键本身含有 `.` 时写作 `\.`。
+//! 数组元素用下标访问，例如 `worlds.0`。
+//! 每次设置都会检查类型是否与原值一致，并重新解析修改后的文本，确认读回的值正确后才生效。
+
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+use toml_edit::{DocumentMut, Item, TableLike};
+
+use super::config_document_json5::Json5Document;
+use super::config_document_yaml::YamlDocument;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+    Json5,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "yml" | "yaml" => Some(Self::Yaml),
+            "toml" => Some(Self::Toml),
+            "json" | "json5" => Some(Self::Json5),
+            _ => None,
+        }
+    }
+}
+
+enum Inner {
+    Yaml(YamlDocument),
+    Toml(DocumentMut),
+    Json5(Json5Document),
+}
+
+pub struct ConfigDocument {
+    inner: Inner,
+}
+
+impl ConfigDocument {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let format = ConfigFormat::from_path(path)
+            .ok_or_else(|| format!("不支持的配置文件格式: {}", path.display()))?;
+        let text =
+            fs::read_to_string(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+        Self::parse(format, &text)
+    }
+
+    pub fn parse(format: ConfigFormat, text: &str) -> Result<Self, String> {
+        let inner = match format {
+            ConfigFormat::Yaml => Inner::Yaml(YamlDocument::parse(text)?),
+            ConfigFormat::Toml => Inner::Toml(
+                text.parse::<DocumentMut>()
+                    .map_err(|e| format!("TOML 解析失败: {}", e))?,
+            ),
+            ConfigFormat::Json5 => Inner::Json5(Json5Document::parse(text)?),
+        };
+        Ok(Self { inner })
+    }
+
+    pub fn format(&self) -> ConfigFormat {
+        match self.inner {
+            Inner::Yaml(_) => ConfigFormat::Yaml,
+            Inner::Toml(_) => ConfigFormat::Toml,
+            Inner::Json5(_) => ConfigFormat::Json5,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        match &self.inner {
+            Inner::Yaml(document) => document.render().to_string(),
+            Inner::Toml(document) => document.to_string(),
+            Inner::Json5(document) => document.render().to_string(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.render()).map_err(|e| format!("写入 {} 失败: {}", path.display(), e))
+    }
+
+    /// 整个文档转换为 JSON 值
+    pub fn to_value(&self) -> Value {
+        match &self.inner {
+            Inner::Yaml(document) => document.to_value(),
+            Inner::Toml(document) => table_to_json(document.as_table()),
+            Inner::Json5(document) => document.to_value(),
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Result<Option<Value>, String> {
+        let segments = split_path(path)?;
+        Ok(lookup(&self.to_value(), &segments).cloned())
+    }
+
+    /// 设置单个值。原值存在时新值必须是同一类型（整数与小数视为同一类型，原值为 null 时不限制）
+    pub fn set(&mut self, path: &str, value: Value) -> Result<(), String> {
+        let segments = split_path(path)?;
+        if let Some(current) = lookup(&self.to_value(), &segments) {
+            if !current.is_null() && type_name(current) != type_name(&value) {
+                return Err(format!(
+                    "{} 的类型为 {}，不能设置为 {}",
+                    path,
+                    type_name(current),
+                    type_name(&value)
+                ));
+            }
+        }
+
+        let text = match &self.inner {
+            Inner::Yaml(document) => {
+                let mut document = YamlDocument::parse(document.render())?;
+                document.set(&segments, &value)?;
+                document.render().to_string()
+            }
+            Inner::Toml(document) => {
+                let mut document = document.clone();
+                set_toml(document.as_table_mut(), &segments, &value)?;
+                document.to_string()
+            }
+            Inner::Json5(document) => {
+                let mut document = Json5Document::parse(document.render())?;
+                document.set(&segments, &value)?;
+                document.render().to_string()
+            }
+        };
+
+        let updated = Self::parse(self.format(), &text)
+            .map_err(|e| format!("修改 {} 后文档无法解析: {}", path, e))?;
+        let read_back = lookup(&updated.to_value(), &segments).cloned();
+        if !read_back
+            .as_ref()
+            .is_some_and(|read| same_value(read, &value))
+        {
+            return Err(format!("修改 {} 后读回的值不一致，已放弃修改", path));
+        }
+        *self = updated;
+        Ok(())
+    }
+}
+
+/// 批量修改并写回文件；任意一项失败时不写入，返回每项的错误（每行一条）
+pub fn write_values(path: &Path, values: &[(String, Value)]) -> Result<(), String> {
+    let mut document = ConfigDocument::load(path)?;
+    let errors = values
+        .iter()
+        .filter_map(|(key, value)| document.set(key, value.clone()).err())
+        .collect::<Vec<_>>();
+    if !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    document.save(path)
+}
+
+/// 按 `.` 拆分路径，`\.` 表示键名中的点
+pub fn split_path(path: &str) -> Result<Vec<String>, String> {
+    let mut segments = vec![String::new()];
+    let mut chars = path.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.clone().next() == Some('.') => {
+                chars.next();
+                segments.last_mut().unwrap().push('.');
+            }
+            '.' => segments.push(String::new()),
+            c => segments.last_mut().unwrap().push(c),
+        }
+    }
+    if segments.iter().any(String::is_empty) {
+        return Err(format!("无效的配置路径: {}", path));
+    }
+    Ok(segments)
+}
+
+fn lookup<'a>(value: &'a Value, segments: &[String]) -> Option<&'a Value> {
+    segments
+        .iter()
+        .try_fold(value, |value, segment| match value {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// 数字按数值比较，`1` 与 `1.0` 视为相同
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => x.as_f64() == y.as_f64(),
+        (Value::Array(x), Value::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(x, y)| same_value(x, y))
+        }
+        (Value::Object(x), Value::Object(y)) => {
+            x.len() == y.len()
+                && x.iter()
+                    .all(|(key, x)| y.get(key).is_some_and(|y| same_value(x, y)))
+        }
+        _ => a == b,
+    }
+}
+
+fn table_to_json(table: &dyn TableLike) -> Value {
+    Value::Object(
+        table
+            .iter()
+            .filter_map(|(key, item)| item_to_json(item).map(|value| (key.to_string(), value)))
+            .collect(),
+    )
+}
+
+fn item_to_json(item: &Item) -> Option<Value> {
+    match item {
+        Item::None => None,
+        Item::Value(value) => Some(toml_value_to_json(value)),
+        Item::Table(table) => Some(table_to_json(table)),
+        Item::ArrayOfTables(array) => {
+            Some(Value::Array(array.iter().map(|table| table_to_json(table)).collect()))
+        }
+    }
+}
+
+fn toml_value_to_json(value: &toml_edit::Value) -> Value {
+    match value {
+        toml_edit::Value::String(s) => Value::String(s.value().clone()),
+        toml_edit::Value::Integer(i) => Value::from(*i.value()),
+        toml_edit::Value::Float(f) => serde_json::Number::from_f64(*f.value())
+            .map(Value::Number)
+            .unwrap_or(Value::Null),
+        toml_edit::Value::Boolean(b) => Value::Bool(*b.value()),
+        toml_edit::Value::Datetime(d) => Value::String(d.value().to_string()),
+        toml_edit::Value::Array(array) => {
+            Value::Array(array.iter().map(toml_value_to_json).collect())
+        }
+        toml_edit::Value::InlineTable(table) => table_to_json(table),
+    }
+}
+
+fn json_to_toml(value: &Value) -> Result<toml_edit::Value, String> {
+    Ok(match value {
+        Value::Null => return Err("TOML 不支持 null 值".to_string()),
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().unwrap_or_default().into(),
+        },
+        Value::String(s) => s.as_str().into(),
+        Value::Array(items) => {
+            let mut array = toml_edit::Array::new();
+            for item in items {
+                array.push(json_to_toml(item)?);
+            }
+            array.into()
+        }
+        Value::Object(map) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (key, item) in map {
+                table.insert(key, json_to_toml(item)?);
+            }
+            table.into()
+        }
+    })
+}
+
+fn set_toml(table: &mut dyn TableLike, segments: &[String], value: &Value) -> Result<(), String> {
+    let (key, parents) = segments.split_last().ok_or("配置路径不能为空")?;
+    let mut table = table;
+    for segment in parents {
+        table = table
+            .entry(segment)
+            .or_insert(toml_edit::table())
+            .as_table_like_mut()
+            .ok_or_else(|| format!("{} 不是表，无法设置子项", segment))?;
+    }
+
+    // 已有的普通表保持 `[section]` 形式，逐项写入
+    if let (Value::Object(map), Some(Item::Table(_))) = (value, table.get(key)) {
+        for (child, value) in map {
+            set_toml(table, &[key.clone(), child.clone()], value)?;
+        }
+        return Ok(());
+    }
+
+    let mut new_value = json_to_toml(value)?;
+    // 原地替换以保留键前的注释，并沿用原值的前后空白与行尾注释
+    match table.get_mut(key) {
+        Some(item) => {
+            if let Item::Value(old) = item {
+                *new_value.decor_mut() = old.decor().clone();
+            }
+            *item = Item::Value(new_value);
+        }
+        None => {
+            table.insert(key, Item::Value(new_value));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_set_keeps_comments() {
+        let text = "# Velocity\nbind = \"0.0.0.0:25577\" # 监听地址\n\n[servers]\nlobby = \"127.0.0.1:30066\"\n";
+        let mut document = ConfigDocument::parse(ConfigFormat::Toml, text).unwrap();
+
+        document.set("bind", Value::from("0.0.0.0:25578")).unwrap();
+        document
+            .set("advanced.compression-level", Value::from(6))
+            .unwrap();
+        assert!(document.set("bind", Value::from(1)).is_err());
+
+        let rendered = document.render();
+        assert!(rendered.starts_with("# Velocity\nbind = \"0.0.0.0:25578\" # 监听地址\n"));
+        assert_eq!(document.get("advanced.compression-level").unwrap(), Some(Value::from(6)));
+        assert_eq!(document.get("servers.lobby").unwrap(), Some(Value::from("127.0.0.1:30066")));
+    }
+
+    #[test]
+    fn split_path_handles_escaped_dots() {
+        assert_eq!(
+            split_path(r"servers.play\.example\.com.port").unwrap(),
+            vec!["servers", "play.example.com", "port"]
+        );
+        assert!(split_path("a..b").is_err());
+    }
+}
@@ -0,0 +1,455 @@
+//! JSON / JSON5 配置的保留注释编辑。
+//!
+//! 解析时记录每个值在原文中的位置，修改时只替换对应片段，
+//! 注释、缩进、引号风格和尾随逗号都保持原样。
+
+use serde_json::{Map, Number, Value};
+
+#[derive(Debug)]
+enum NodeKind {
+    Object(Vec<Member>),
+    Array(Vec<Node>),
+    Scalar(Value),
+}
+
+#[derive(Debug)]
+struct Node {
+    start: usize,
+    end: usize,
+    kind: NodeKind,
+}
+
+#[derive(Debug)]
+struct Member {
+    key: String,
+    key_start: usize,
+    quoted: bool,
+    value: Node,
+}
+
+pub struct Json5Document {
+    text: String,
+    root: Node,
+}
+
+impl Json5Document {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parser = Parser { text, pos: 0 };
+        parser.skip_trivia()?;
+        let root = parser.parse_value(0)?;
+        parser.skip_trivia()?;
+        if parser.pos < text.len() {
+            return Err(parser.error("值之后存在多余内容"));
+        }
+        Ok(Self { text: text.to_string(), root })
+    }
+
+    pub fn render(&self) -> &str {
+        &self.text
+    }
+
+    pub fn to_value(&self) -> Value {
+        node_to_value(&self.root)
+    }
+
+    pub fn set(&mut self, path: &[String], value: &Value) -> Result<(), String> {
+        let mut node = &self.root;
+        for (index, segment) in path.iter().enumerate() {
+            match &node.kind {
+                NodeKind::Object(members) => {
+                    match members.iter().rev().find(|member| &member.key == segment) {
+                        Some(member) => node = &member.value,
+                        None => {
+                            let nested = nest_value(&path[index + 1..], value);
+                            let text = insert_member(&self.text, node, segment, &nested);
+                            return self.replace_text(text);
+                        }
+                    }
+                }
+                NodeKind::Array(items) => {
+                    node = segment
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| items.get(i))
+                        .ok_or_else(|| format!("数组中不存在下标 {}", segment))?;
+                }
+                NodeKind::Scalar(_) => {
+                    return Err(format!("{} 不是对象，无法设置子项", path[..index].join(".")));
+                }
+            }
+        }
+
+        let mut text = self.text.clone();
+        text.replace_range(node.start..node.end, &render_value(value));
+        self.replace_text(text)
+    }
+
+    fn replace_text(&mut self, text: String) -> Result<(), String> {
+        *self = Self::parse(&text)?;
+        Ok(())
+    }
+}
+
+/// 把剩余路径包装成嵌套对象，例如 `["a", "b"]` + 1 => `{"a": {"b": 1}}`
+fn nest_value(rest: &[String], value: &Value) -> Value {
+    rest.iter().rev().fold(value.clone(), |inner, key| {
+        let mut map = Map::new();
+        map.insert(key.clone(), inner);
+        Value::Object(map)
+    })
+}
+
+fn render_value(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "null".to_string())
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+fn line_indent(text: &str, pos: usize) -> &str {
+    let line_start = text[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line = &text[line_start..pos];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+fn insert_member(text: &str, object: &Node, key: &str, value: &Value) -> String {
+    let mut output = text.to_string();
+    let NodeKind::Object(members) = &object.kind else {
+        return output;
+    };
+
+    let Some(last) = members.last() else {
+        let close = object.end - 1;
+        let member =
+            format!(" {}: {} ", render_value(&Value::String(key.into())), render_value(value));
+        output.insert_str(close, &member);
+        return output;
+    };
+
+    let key_text = if !last.quoted && is_identifier(key) {
+        key.to_string()
+    } else {
+        render_value(&Value::String(key.to_string()))
+    };
+    let member = format!("{}: {}", key_text, render_value(value));
+    let indent = line_indent(text, last.key_start).to_string();
+    let close = object.end - 1;
+    let after_last = last.value.end;
+    let line_end = text[after_last..]
+        .find('\n')
+        .map(|i| after_last + i)
+        .unwrap_or(text.len());
+
+    if line_end < close {
+        // 闭合括号在后面的行：新成员放在最后一个成员所在行之后，保留该行的行尾注释
+        let has_comma = text[after_last..close].trim_start().starts_with(',');
+        let line_end = if text[..line_end].ends_with('\r') {
+            line_end - 1
+        } else {
+            line_end
+        };
+        let newline = &text[line_end
+            ..line_end
+                + if text[line_end..].starts_with("\r\n") {
+                    2
+                } else {
+                    1
+                }];
+        output.insert_str(line_end, &format!("{}{}{}", newline, indent, member));
+        if !has_comma {
+            output.insert(after_last, ',');
+        }
+    } else {
+        output.insert_str(after_last, &format!(", {}", member));
+    }
+    output
+}
+
+fn node_to_value(node: &Node) -> Value {
+    match &node.kind {
+        NodeKind::Object(members) => Value::Object(
+            members
+                .iter()
+                .map(|member| (member.key.clone(), node_to_value(&member.value)))
+                .collect(),
+        ),
+        NodeKind::Array(items) => Value::Array(items.iter().map(node_to_value).collect()),
+        NodeKind::Scalar(value) => value.clone(),
+    }
+}
+
+/// 嵌套层级上限，防止恶意文件造成栈溢出
+const MAX_DEPTH: usize = 256;
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> String {
+        let line = self.text[..self.pos].matches('\n').count() + 1;
+        format!("JSON5 解析失败（第 {} 行）: {}", line, message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        if self.bump() == Some(expected) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("缺少 '{}'", expected)))
+        }
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), String> {
+        loop {
+            let rest = &self.text[self.pos..];
+            if rest.starts_with("//") {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                let end = comment
+                    .find("*/")
+                    .ok_or_else(|| self.error("块注释未闭合"))?;
+                self.pos += end + 4;
+            } else if self
+                .peek()
+                .is_some_and(|c| c.is_whitespace() || c == '\u{feff}')
+            {
+                self.bump();
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<Node, String> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("嵌套层级过深"));
+        }
+        let start = self.pos;
+        let kind = match self.peek() {
+            Some('{') => self.parse_object(depth)?,
+            Some('[') => self.parse_array(depth)?,
+            Some(quote @ ('"' | '\'')) => {
+                NodeKind::Scalar(Value::String(self.parse_string(quote)?))
+            }
+            Some(c) if c.is_ascii_digit() || matches!(c, '-' | '+' | '.') => {
+                NodeKind::Scalar(self.parse_number()?)
+            }
+            Some(_) => {
+                let word = self.parse_identifier();
+                match word.as_str() {
+                    "true" => NodeKind::Scalar(Value::Bool(true)),
+                    "false" => NodeKind::Scalar(Value::Bool(false)),
+                    // JSON 值无法表示 Infinity / NaN，按 null 读取
+                    "null" | "Infinity" | "NaN" => NodeKind::Scalar(Value::Null),
+                    _ => return Err(self.error("无法识别的值")),
+                }
+            }
+            None => return Err(self.error("内容意外结束")),
+        };
+        Ok(Node { start, end: self.pos, kind })
+    }
+
+    fn parse_object(&mut self, depth: usize) -> Result<NodeKind, String> {
+        self.expect('{')?;
+        let mut members = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some('}') {
+                self.bump();
+                return Ok(NodeKind::Object(members));
+            }
+
+            let key_start = self.pos;
+            let (key, quoted) = match self.peek() {
+                Some(quote @ ('"' | '\'')) => (self.parse_string(quote)?, true),
+                _ => (self.parse_identifier(), false),
+            };
+            if key.is_empty() && !quoted {
+                return Err(self.error("缺少键名"));
+            }
+            self.skip_trivia()?;
+            self.expect(':')?;
+            self.skip_trivia()?;
+            let value = self.parse_value(depth + 1)?;
+            members.push(Member { key, key_start, quoted, value });
+
+            self.skip_trivia()?;
+            match self.bump() {
+                Some(',') => {}
+                Some('}') => return Ok(NodeKind::Object(members)),
+                _ => return Err(self.error("缺少 ',' 或 '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<NodeKind, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip_trivia()?;
+            if self.peek() == Some(']') {
+                self.bump();
+                return Ok(NodeKind::Array(items));
+            }
+            items.push(self.parse_value(depth + 1)?);
+            self.skip_trivia()?;
+            match self.bump() {
+                Some(',') => {}
+                Some(']') => return Ok(NodeKind::Array(items)),
+                _ => return Err(self.error("缺少 ',' 或 ']'")),
+            }
+        }
+    }
+
+    fn parse_identifier(&mut self) -> String {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        {
+            self.bump();
+        }
+        self.text[start..self.pos].to_string()
+    }
+
+    fn parse_number(&mut self) -> Result<Value, String> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-'))
+        {
+            self.bump();
+        }
+        let raw = &self.text[start..self.pos];
+        let (negative, digits) = match raw.as_bytes().first() {
+            Some(b'-') => (true, &raw[1..]),
+            Some(b'+') => (false, &raw[1..]),
+            _ => (false, raw),
+        };
+
+        if matches!(digits, "Infinity" | "NaN") {
+            return Ok(Value::Null);
+        }
+        if let Some(hex) = digits
+            .strip_prefix("0x")
+            .or_else(|| digits.strip_prefix("0X"))
+        {
+            let number =
+                i64::from_str_radix(hex, 16).map_err(|_| self.error("无效的十六进制数"))?;
+            return Ok(Value::from(if negative { -number } else { number }));
+        }
+        if let Ok(number) = digits.parse::<i64>() {
+            return Ok(Value::from(if negative { -number } else { number }));
+        }
+        let number = digits
+            .parse::<f64>()
+            .map_err(|_| self.error("无效的数字"))?;
+        let number = if negative { -number } else { number };
+        Ok(Number::from_f64(number)
+            .map(Value::Number)
+            .unwrap_or(Value::Null))
+    }
+
+    fn parse_string(&mut self, quote: char) -> Result<String, String> {
+        self.expect(quote)?;
+        let mut units: Vec<u16> = Vec::new();
+        let mut buffer = [0u16; 2];
+        loop {
+            let c = self.bump().ok_or_else(|| self.error("字符串未闭合"))?;
+            if c == quote {
+                return Ok(String::from_utf16_lossy(&units));
+            }
+            if c == '\n' {
+                return Err(self.error("字符串中不能直接换行"));
+            }
+            if c != '\\' {
+                units.extend_from_slice(c.encode_utf16(&mut buffer));
+                continue;
+            }
+
+            let escaped = self.bump().ok_or_else(|| self.error("字符串未闭合"))?;
+            let decoded = match escaped {
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'v' => '\u{b}',
+                '0' => '\0',
+                // 反斜杠续行
+                '\n' => continue,
+                '\r' => {
+                    if self.peek() == Some('\n') {
+                        self.bump();
+                    }
+                    continue;
+                }
+                'u' | 'x' => {
+                    let len = if escaped == 'u' { 4 } else { 2 };
+                    let hex = self.text.get(self.pos..self.pos + len).unwrap_or("");
+                    let unit = u16::from_str_radix(hex, 16)
+                        .ok()
+                        .filter(|_| hex.len() == len)
+                        .ok_or_else(|| self.error("无效的转义序列"))?;
+                    self.pos += len;
+                    units.push(unit);
+                    continue;
+                }
+                other => other,
+            };
+            units.extend_from_slice(decoded.encode_utf16(&mut buffer));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(path: &str) -> Vec<String> {
+        path.split('.').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn set_keeps_comments_and_style() {
+        let text = "{\n  // 端口\n  port: 25577, // 注释\n  'motd': 'hi',\n  list: [1, 2,],\n}\n";
+        let mut document = Json5Document::parse(text).unwrap();
+        assert_eq!(document.to_value()["motd"], "hi");
+        assert_eq!(document.to_value()["list"][1], 2);
+
+        document.set(&path("port"), &Value::from(25578)).unwrap();
+        document.set(&path("list.0"), &Value::from(5)).unwrap();
+        document
+            .set(&path("extra.enabled"), &Value::Bool(true))
+            .unwrap();
+        assert_eq!(
+            document.render(),
+            "{\n  // 端口\n  port: 25578, // 注释\n  'motd': 'hi',\n  list: [5, 2,],\n  extra: {\"enabled\":true}\n}\n"
+        );
+        assert_eq!(document.to_value()["extra"]["enabled"], true);
+    }
+
+    #[test]
+    fn insert_into_plain_json() {
+        let mut document = Json5Document::parse("{\n  \"a\": 1\n}").unwrap();
+        document.set(&path("b"), &Value::from("x")).unwrap();
+        assert_eq!(document.render(), "{\n  \"a\": 1,\n  \"b\": \"x\"\n}");
+        assert!(document.set(&path("a.c"), &Value::Null).is_err());
+    }
+}
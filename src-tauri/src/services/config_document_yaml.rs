@@ -0,0 +1,458 @@
+//! YAML 配置的保留注释编辑。
+//!
+//! 读取交给 serde_yaml；写入时逐行定位键所在的位置，只替换值的片段，
+//! 注释、缩进和引号风格保持原样。只有块样式映射里的键可以定位，
+//! 序列元素内部的键需要整体设置其父级。
+
+use serde_json::Value;
+
+pub struct YamlDocument {
+    text: String,
+}
+
+struct KeyLine {
+    path: Vec<String>,
+    line: usize,
+    indent: usize,
+    /// 值在该行中的字节范围（不含行尾注释）
+    value_start: usize,
+    value_end: usize,
+    /// 子级内容结束的行（不含），没有子级时为 `line + 1`
+    block_end: usize,
+}
+
+impl YamlDocument {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        serde_yaml::from_str::<serde_yaml::Value>(text)
+            .map_err(|e| format!("YAML 解析失败: {}", e))?;
+        Ok(Self { text: text.to_string() })
+    }
+
+    pub fn render(&self) -> &str {
+        &self.text
+    }
+
+    pub fn to_value(&self) -> Value {
+        serde_yaml::from_str::<serde_yaml::Value>(&self.text)
+            .map(yaml_to_json)
+            .unwrap_or(Value::Null)
+    }
+
+    pub fn set(&mut self, path: &[String], value: &Value) -> Result<(), String> {
+        let mut lines = self
+            .text
+            .split('\n')
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        let cr = if self.text.contains("\r\n") { "\r" } else { "" };
+        let keys = scan(&lines);
+
+        if let Some(key) = keys.iter().rev().find(|key| key.path == path) {
+            let line = &lines[key.line];
+            let original = &line[key.value_start..key.value_end];
+            let mut rendered = render_value(value, original);
+            if original.is_empty() {
+                if !line[..key.value_start].ends_with(' ') {
+                    rendered.insert(0, ' ');
+                }
+                if !line[key.value_end..].trim().is_empty() {
+                    rendered.push(' ');
+                }
+            }
+            let new_line =
+                format!("{}{}{}", &line[..key.value_start], rendered, &line[key.value_end..]);
+            lines[key.line] = new_line;
+            // 原来的子级（嵌套映射、序列、多行文本）由新的单行值取代
+            lines.drain(key.line + 1..last_content_line(&lines, key));
+            return self.replace_lines(&lines);
+        }
+
+        let step = indent_step(&keys);
+        let parent = (1..path.len())
+            .rev()
+            .find_map(|len| keys.iter().rev().find(|key| key.path == path[..len]));
+
+        let (insert_at, indent, rest) = match parent {
+            Some(parent) => {
+                let line = &lines[parent.line];
+                let inline = &line[parent.value_start..parent.value_end];
+                let first_child = keys
+                    .iter()
+                    .find(|key| key.line > parent.line && key.line < parent.block_end);
+                let has_sequence = lines[parent.line + 1..parent.block_end]
+                    .iter()
+                    .any(|line| is_sequence_item(line.trim()));
+                if !(inline.is_empty() || inline.starts_with('&')) || has_sequence {
+                    return Err(format!("{} 不是映射，无法设置子项", parent.path.join(".")));
+                }
+                let indent = first_child
+                    .map(|child| child.indent)
+                    .unwrap_or(parent.indent + step);
+                (last_content_line(&lines, parent), indent, &path[parent.path.len()..])
+            }
+            None => {
+                let root = serde_yaml::from_str::<serde_yaml::Value>(&self.text)
+                    .map_err(|e| format!("YAML 解析失败: {}", e))?;
+                if !matches!(root, serde_yaml::Value::Mapping(_) | serde_yaml::Value::Null) {
+                    return Err("YAML 根节点不是映射，无法按路径设置".to_string());
+                }
+                let insert_at = lines
+                    .iter()
+                    .rposition(|line| !line.trim().is_empty())
+                    .map(|index| index + 1)
+                    .unwrap_or(0);
+                (insert_at, 0, path)
+            }
+        };
+
+        let new_lines = rest.iter().enumerate().map(|(depth, segment)| {
+            let padding = " ".repeat(indent + depth * step);
+            let key = render_string(segment, false);
+            if depth + 1 == rest.len() {
+                format!("{}{}: {}{}", padding, key, render_value(value, ""), cr)
+            } else {
+                format!("{}{}:{}", padding, key, cr)
+            }
+        });
+        lines.splice(insert_at..insert_at, new_lines);
+        self.replace_lines(&lines)
+    }
+
+    fn replace_lines(&mut self, lines: &[String]) -> Result<(), String> {
+        *self = Self::parse(&lines.join("\n"))?;
+        Ok(())
+    }
+}
+
+fn yaml_to_json(value: serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                n.as_f64()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+                    .unwrap_or(Value::Null)
+            }
+        }
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(items) => {
+            Value::Array(items.into_iter().map(yaml_to_json).collect())
+        }
+        serde_yaml::Value::Mapping(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let key = match key {
+                        serde_yaml::Value::String(s) => s,
+                        other => match yaml_to_json(other) {
+                            Value::String(s) => s,
+                            other => other.to_string(),
+                        },
+                    };
+                    (key, yaml_to_json(value))
+                })
+                .collect(),
+        ),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+fn split_indent(line: &str) -> (usize, &str) {
+    let line = line.trim_end_matches('\r');
+    let content = line.trim_start_matches(' ');
+    (line.len() - content.len(), content)
+}
+
+fn is_sequence_item(content: &str) -> bool {
+    content == "-" || content.starts_with("- ")
+}
+
+fn scan(lines: &[String]) -> Vec<KeyLine> {
+    let mut keys = Vec::new();
+    // (缩进, 键)；序列元素记为 None，其内部的键不可定位
+    let mut stack: Vec<(usize, Option<String>)> = Vec::new();
+    let mut block_scalar_indent: Option<usize> = None;
+
+    for (index, raw) in lines.iter().enumerate() {
+        let (indent, content) = split_indent(raw);
+        if content.is_empty() {
+            continue;
+        }
+        if let Some(parent) = block_scalar_indent {
+            if indent > parent {
+                continue;
+            }
+            block_scalar_indent = None;
+        }
+        if content.starts_with('#') {
+            continue;
+        }
+        if indent == 0 && (content.starts_with("---") || content.starts_with("...")) {
+            stack.clear();
+            continue;
+        }
+        if is_sequence_item(content) {
+            while stack.last().is_some_and(|(i, _)| *i > indent) {
+                stack.pop();
+            }
+            if stack
+                .last()
+                .is_some_and(|(i, key)| *i == indent && key.is_none())
+            {
+                stack.pop();
+            }
+            stack.push((indent, None));
+            continue;
+        }
+
+        while stack.last().is_some_and(|(i, _)| *i >= indent) {
+            stack.pop();
+        }
+        let Some((key, after_colon)) = parse_key(content) else {
+            continue;
+        };
+
+        let line = raw.trim_end_matches('\r');
+        let colon_end = indent + after_colon;
+        let rest = &line[colon_end..];
+        let value_start = colon_end + (rest.len() - rest.trim_start().len());
+        let value_end = value_start + value_len(&line[value_start..]);
+        let inline = &line[value_start..value_end];
+        if inline.starts_with('|') || inline.starts_with('>') {
+            block_scalar_indent = Some(indent);
+        }
+
+        if stack.iter().all(|(_, key)| key.is_some()) {
+            let mut path = stack
+                .iter()
+                .filter_map(|(_, key)| key.clone())
+                .collect::<Vec<_>>();
+            path.push(key.clone());
+            keys.push(KeyLine {
+                path,
+                line: index,
+                indent,
+                value_start,
+                value_end,
+                block_end: index + 1,
+            });
+        }
+        stack.push((indent, Some(key)));
+    }
+
+    for key in &mut keys {
+        for (index, raw) in lines.iter().enumerate().skip(key.line + 1) {
+            let (indent, content) = split_indent(raw);
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+            if indent > key.indent || (indent == key.indent && is_sequence_item(content)) {
+                key.block_end = index + 1;
+            } else {
+                break;
+            }
+        }
+    }
+    keys
+}
+
+/// 子级之后紧跟的空行和注释通常属于下一个键，插入和删除时不跨过它们
+fn last_content_line(lines: &[String], key: &KeyLine) -> usize {
+    (key.line + 1..key.block_end)
+        .rev()
+        .find(|&index| {
+            let content = lines[index].trim();
+            !content.is_empty() && !content.starts_with('#')
+        })
+        .map(|index| index + 1)
+        .unwrap_or(key.line + 1)
+}
+
+fn indent_step(keys: &[KeyLine]) -> usize {
+    keys.iter()
+        .map(|key| key.indent)
+        .filter(|&indent| indent > 0)
+        .min()
+        .unwrap_or(2)
+}
+
+/// 解析行首的键，返回键名和冒号之后的偏移
+fn parse_key(content: &str) -> Option<(String, usize)> {
+    let (key, rest_start) = match content.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = quoted_len(content, quote)?;
+            let key = serde_yaml::from_str::<String>(&content[..end]).ok()?;
+            let rest = &content[end..];
+            (key, end + (rest.len() - rest.trim_start().len()))
+        }
+        '?' | '{' | '[' | '&' | '*' | '!' | '|' | '>' | '%' | '@' | '`' => return None,
+        _ => {
+            let bytes = content.as_bytes();
+            let colon = (0..bytes.len()).find(|&i| {
+                bytes[i] == b':' && matches!(bytes.get(i + 1), None | Some(b' ') | Some(b'\t'))
+            })?;
+            let key = content[..colon].trim_end();
+            if key.is_empty() || key.contains(" #") {
+                return None;
+            }
+            (key.to_string(), colon)
+        }
+    };
+    if !content[rest_start..].starts_with(':') {
+        return None;
+    }
+    Some((key, rest_start + 1))
+}
+
+/// 引号字符串（含引号）的字节长度，未闭合时返回 None
+fn quoted_len(text: &str, quote: char) -> Option<usize> {
+    let mut chars = text.char_indices().skip(1).peekable();
+    while let Some((index, c)) = chars.next() {
+        if quote == '"' && c == '\\' {
+            chars.next();
+        } else if c == quote {
+            // 单引号字符串中 '' 表示一个单引号
+            if quote == '\'' && chars.peek().is_some_and(|&(_, next)| next == '\'') {
+                chars.next();
+                continue;
+            }
+            return Some(index + 1);
+        }
+    }
+    None
+}
+
+fn value_len(text: &str) -> usize {
+    match text.chars().next() {
+        Some(quote @ ('"' | '\'')) => quoted_len(text, quote).unwrap_or(text.len()),
+        _ => {
+            let end = [" #", "\t#"]
+                .iter()
+                .filter_map(|marker| text.find(marker))
+                .min()
+                .unwrap_or(text.len());
+            text[..end].trim_end().len()
+        }
+    }
+}
+
+fn render_value(value: &Value, original: &str) -> String {
+    match value {
+        Value::String(s) => {
+            if original.starts_with('\'') && !s.chars().any(char::is_control) {
+                format!("'{}'", s.replace('\'', "''"))
+            } else if original.starts_with('"') {
+                quote(s)
+            } else {
+                render_string(s, false)
+            }
+        }
+        _ => render_flow(value),
+    }
+}
+
+/// 复合值统一写成流式（`{a: 1}`、`[1, 2]`），不需要计算缩进
+fn render_flow(value: &Value) -> String {
+    match value {
+        Value::String(s) => render_string(s, true),
+        Value::Array(items) => {
+            format!("[{}]", items.iter().map(render_flow).collect::<Vec<_>>().join(", "))
+        }
+        Value::Object(map) => format!(
+            "{{{}}}",
+            map.iter()
+                .map(|(key, value)| format!("{}: {}", render_string(key, true), render_flow(value)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        other => other.to_string(),
+    }
+}
+
+fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+fn render_string(s: &str, flow: bool) -> String {
+    if is_plain_safe(s, flow) {
+        s.to_string()
+    } else {
+        quote(s)
+    }
+}
+
+/// 字符串能否不加引号写出，且读回时仍是同一个字符串
+fn is_plain_safe(s: &str, flow: bool) -> bool {
+    const INDICATORS: &[char] = &[
+        '-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@',
+        '`',
+    ];
+    !s.is_empty()
+        && s.trim() == s
+        && !s.starts_with(INDICATORS)
+        && !s.contains(": ")
+        && !s.contains(" #")
+        && !s.ends_with(':')
+        && !s.chars().any(char::is_control)
+        && !(flow && s.contains([',', '[', ']', '{', '}']))
+        && matches!(
+            serde_yaml::from_str::<serde_yaml::Value>(s),
+            Ok(serde_yaml::Value::String(parsed)) if parsed == s
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(path: &str) -> Vec<String> {
+        path.split('.').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn set_replaces_values_in_place() {
+        let text = "# Bukkit\nsettings:\n  allow-end: true # 末地\n  shutdown-message: 'Server closed'\n  \
+                    connection-throttle: 4000\nworlds:\n  - world\n\nspawn-limits:\n  monsters: 70\n";
+        let mut document = YamlDocument::parse(text).unwrap();
+        assert_eq!(document.to_value()["settings"]["allow-end"], true);
+
+        document
+            .set(&path("settings.allow-end"), &Value::Bool(false))
+            .unwrap();
+        document
+            .set(&path("settings.shutdown-message"), &Value::from("It's over"))
+            .unwrap();
+        document
+            .set(&path("worlds"), &serde_json::json!(["world", "world_nether"]))
+            .unwrap();
+        document
+            .set(&path("settings.motd"), &Value::from("a: b"))
+            .unwrap();
+        document
+            .set(&path("chunk-gc.period"), &Value::from(600))
+            .unwrap();
+
+        assert_eq!(
+            document.render(),
+            "# Bukkit\nsettings:\n  allow-end: false # 末地\n  shutdown-message: 'It''s over'\n  \
+             connection-throttle: 4000\n  motd: \"a: b\"\nworlds: [world, world_nether]\n\nspawn-limits:\n  \
+             monsters: 70\nchunk-gc:\n  period: 600\n"
+        );
+        let value = document.to_value();
+        assert_eq!(value["settings"]["motd"], "a: b");
+        assert_eq!(value["worlds"][1], "world_nether");
+        assert_eq!(value["chunk-gc"]["period"], 600);
+    }
+
+    #[test]
+    fn set_rejects_scalar_parent() {
+        let mut document = YamlDocument::parse("a: 1\n").unwrap();
+        assert!(document.set(&path("a.b"), &Value::Null).is_err());
+        assert_eq!(document.render(), "a: 1\n");
+    }
+}
@@ -0,0 +1,341 @@
+//! 配置文件修改历史。
+//!
+//! 每次通过 Sea Lantern 写入服务器配置前，先把旧内容保存为一个修订，
+//! 存放在 `<应用数据>/config_history/<server_id>/`：`index.json` 记录修订列表，
+//! 每个修订的内容单独保存为 `<revision_id>.bak`。内容与上一次修订相同时不重复保存。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+use similar::TextDiff;
+
+use crate::models::config::ConfigRevision;
+use crate::services::global;
+
+const HISTORY_DIR: &str = "config_history";
+const INDEX_FILE: &str = "index.json";
+/// 每个文件最多保留的修订数，超出时删除最旧的
+const MAX_REVISIONS_PER_FILE: usize = 50;
+
+pub const ORIGIN_CONFIG_EDITOR: &str = "config_editor";
+pub const ORIGIN_SERVER_PROPERTIES: &str = "server_properties";
+pub const ORIGIN_RESOURCE_PACK: &str = "resource_pack";
+pub const ORIGIN_RESTORE: &str = "restore";
+
+/// 读写索引时串行化，避免并发写入互相覆盖
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// 已登记服务器的 (历史目录, 服务器目录)。只接受已知的服务器 ID，避免拼出数据目录外的路径
+fn resolve(server_id: &str) -> Result<(PathBuf, PathBuf), String> {
+    let server_path = server_path(server_id)?;
+    let dir = PathBuf::from(crate::utils::path::get_or_create_app_data_dir())
+        .join(HISTORY_DIR)
+        .join(server_id);
+    Ok((dir, server_path))
+}
+
+fn load_index(dir: &Path) -> Vec<ConfigRevision> {
+    fs::read_to_string(dir.join(INDEX_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(dir: &Path, revisions: &[ConfigRevision]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(revisions)
+        .map_err(|e| format!("序列化配置历史失败: {}", e))?;
+    fs::write(dir.join(INDEX_FILE), content).map_err(|e| format!("保存配置历史失败: {}", e))
+}
+
+fn blob_path(dir: &Path, revision_id: &str) -> PathBuf {
+    dir.join(format!("{}.bak", revision_id))
+}
+
+/// 根据服务器目录找到已登记服务器的 ID
+pub fn server_id_for_path(server_path: &Path) -> Option<String> {
+    let target = fs::canonicalize(server_path).ok()?;
+    global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| fs::canonicalize(&s.path).is_ok_and(|p| p == target))
+        .map(|s| s.id)
+}
+
+/// 文件相对服务器目录的路径，统一使用 `/` 分隔
+fn relative_path(server_path: &Path, file_path: &Path) -> Result<String, String> {
+    let server = fs::canonicalize(server_path).map_err(|e| format!("无效的服务器目录: {}", e))?;
+    let file = match fs::canonicalize(file_path) {
+        Ok(file) => file,
+        Err(_) => {
+            let parent = file_path.parent().unwrap_or(Path::new(""));
+            let name = file_path.file_name().ok_or("无效的配置路径")?;
+            fs::canonicalize(parent)
+                .map_err(|e| format!("无效的配置路径: {}", e))?
+                .join(name)
+        }
+    };
+    let relative = file
+        .strip_prefix(&server)
+        .map_err(|_| "配置路径必须在服务器目录内".to_string())?;
+    Ok(relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/"))
+}
+
+/// 写入前调用：保存文件当前内容。服务器未登记时不记录
+pub fn snapshot(server_path: &Path, file_path: &Path, origin: &str) -> Result<(), String> {
+    let Some(server_id) = server_id_for_path(server_path) else {
+        return Ok(());
+    };
+    snapshot_for(&server_id, server_path, file_path, origin)
+}
+
+pub fn snapshot_for(
+    server_id: &str,
+    server_path: &Path,
+    file_path: &Path,
+    origin: &str,
+) -> Result<(), String> {
+    let (dir, _) = resolve(server_id)?;
+    snapshot_in(&dir, server_path, file_path, origin)
+}
+
+fn snapshot_in(
+    dir: &Path,
+    server_path: &Path,
+    file_path: &Path,
+    origin: &str,
+) -> Result<(), String> {
+    let file = relative_path(server_path, file_path)?;
+    let (existed, content) = match fs::read(file_path) {
+        Ok(content) => (true, content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (false, Vec::new()),
+        Err(e) => return Err(format!("读取 {} 失败: {}", file, e)),
+    };
+    let sha256 = format!("{:x}", Sha256::digest(&content));
+
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut revisions = load_index(dir);
+    let unchanged = revisions
+        .iter()
+        .rev()
+        .find(|revision| revision.file_path == file)
+        .is_some_and(|last| last.existed == existed && last.sha256 == sha256);
+    if unchanged {
+        return Ok(());
+    }
+
+    fs::create_dir_all(dir).map_err(|e| format!("创建配置历史目录失败: {}", e))?;
+    let revision = ConfigRevision {
+        id: uuid::Uuid::new_v4().to_string(),
+        file_path: file.clone(),
+        origin: origin.to_string(),
+        created_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        size: content.len() as u64,
+        existed,
+        sha256,
+    };
+    fs::write(blob_path(dir, &revision.id), &content)
+        .map_err(|e| format!("保存配置快照失败: {}", e))?;
+    revisions.push(revision);
+
+    let same_file = revisions.iter().filter(|r| r.file_path == file).count();
+    if same_file > MAX_REVISIONS_PER_FILE {
+        let mut excess = same_file - MAX_REVISIONS_PER_FILE;
+        revisions.retain(|revision| {
+            if excess > 0 && revision.file_path == file {
+                excess -= 1;
+                let _ = fs::remove_file(blob_path(dir, &revision.id));
+                false
+            } else {
+                true
+            }
+        });
+    }
+    save_index(dir, &revisions)
+}
+
+/// 修订列表，最新的在前；指定文件时只返回该文件的修订
+pub fn list_revisions(
+    server_id: &str,
+    file_path: Option<&str>,
+) -> Result<Vec<ConfigRevision>, String> {
+    let (dir, _) = resolve(server_id)?;
+    Ok(list_in(&dir, file_path))
+}
+
+fn list_in(dir: &Path, file_path: Option<&str>) -> Vec<ConfigRevision> {
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut revisions = load_index(dir);
+    if let Some(file_path) = file_path {
+        let file_path = file_path.replace('\\', "/");
+        revisions.retain(|revision| revision.file_path == file_path);
+    }
+    revisions.reverse();
+    revisions
+}
+
+fn find_revision(dir: &Path, revision_id: &str) -> Result<ConfigRevision, String> {
+    list_in(dir, None)
+        .into_iter()
+        .find(|revision| revision.id == revision_id)
+        .ok_or_else(|| format!("未找到配置修订: {}", revision_id))
+}
+
+fn read_revision(dir: &Path, revision: &ConfigRevision) -> Result<Vec<u8>, String> {
+    fs::read(blob_path(dir, &revision.id)).map_err(|e| format!("读取配置快照失败: {}", e))
+}
+
+fn server_path(server_id: &str) -> Result<PathBuf, String> {
+    global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .map(|s| PathBuf::from(s.path))
+        .ok_or_else(|| format!("未找到服务器: {}", server_id))
+}
+
+fn format_time(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0)
+        .map(|time| {
+            time.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// 两个修订之间的统一 diff；`to_id` 为空时与文件的当前内容比较
+pub fn diff_revisions(
+    server_id: &str,
+    from_id: &str,
+    to_id: Option<&str>,
+) -> Result<String, String> {
+    let (dir, server_path) = resolve(server_id)?;
+    diff_in(&dir, &server_path, from_id, to_id)
+}
+
+fn diff_in(
+    dir: &Path,
+    server_path: &Path,
+    from_id: &str,
+    to_id: Option<&str>,
+) -> Result<String, String> {
+    let from = find_revision(dir, from_id)?;
+    let old = read_revision(dir, &from)?;
+    let old_label = format!("a/{} ({})", from.file_path, format_time(from.created_at));
+
+    let (new, new_label) = match to_id {
+        Some(to_id) => {
+            let to = find_revision(dir, to_id)?;
+            let label = format!("b/{} ({})", to.file_path, format_time(to.created_at));
+            (read_revision(dir, &to)?, label)
+        }
+        None => {
+            let path = server_path.join(&from.file_path);
+            let content = fs::read(&path).unwrap_or_default();
+            (content, format!("b/{} (当前)", from.file_path))
+        }
+    };
+
+    let old = String::from_utf8_lossy(&old);
+    let new = String::from_utf8_lossy(&new);
+    Ok(TextDiff::from_lines(old.as_ref(), new.as_ref())
+        .unified_diff()
+        .context_radius(3)
+        .header(&old_label, &new_label)
+        .to_string())
+}
+
+/// 恢复到指定修订。恢复前同样会保存当前内容，因此恢复本身也可以撤销
+pub fn restore_revision(server_id: &str, revision_id: &str) -> Result<(), String> {
+    let (dir, server_path) = resolve(server_id)?;
+    restore_in(&dir, &server_path, revision_id)
+}
+
+fn restore_in(dir: &Path, server_path: &Path, revision_id: &str) -> Result<(), String> {
+    let revision = find_revision(dir, revision_id)?;
+    let content = read_revision(dir, &revision)?;
+    let path = server_path.join(&revision.file_path);
+
+    snapshot_in(dir, server_path, &path, ORIGIN_RESTORE)?;
+    if revision.existed {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        fs::write(&path, content).map_err(|e| format!("恢复 {} 失败: {}", revision.file_path, e))
+    } else if path.exists() {
+        // 该修订记录的是文件创建之前的状态
+        fs::remove_file(&path).map_err(|e| format!("恢复 {} 失败: {}", revision.file_path, e))
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dirs(name: &str) -> (PathBuf, PathBuf) {
+        let root = std::env::temp_dir().join(format!("sl_config_history_{}", name));
+        let _ = fs::remove_dir_all(&root);
+        let server = root.join("server");
+        fs::create_dir_all(server.join("config")).unwrap();
+        (root.join("history"), server)
+    }
+
+    #[test]
+    fn rejects_unknown_server_ids() {
+        assert!(list_revisions("../../etc", None).is_err());
+        assert!(diff_revisions("../other", "id", None).is_err());
+        assert!(restore_revision("..", "id").is_err());
+    }
+
+    #[test]
+    fn snapshot_list_diff_and_restore() {
+        let (dir, server) = temp_dirs("roundtrip");
+        let file = server.join("config").join("a.yml");
+
+        // 文件尚不存在时也会记录，恢复该修订即删除文件
+        snapshot_in(&dir, &server, &file, ORIGIN_CONFIG_EDITOR).unwrap();
+        fs::write(&file, "a: 1\nb: 2\n").unwrap();
+        snapshot_in(&dir, &server, &file, ORIGIN_CONFIG_EDITOR).unwrap();
+        // 内容未变化时不重复保存
+        snapshot_in(&dir, &server, &file, ORIGIN_CONFIG_EDITOR).unwrap();
+        fs::write(&file, "a: 1\nb: 3\n").unwrap();
+
+        let revisions = list_in(&dir, Some("config\\a.yml"));
+        assert_eq!(revisions.len(), 2);
+        assert_eq!(revisions[0].file_path, "config/a.yml");
+        assert!(revisions[0].existed);
+        assert!(!revisions[1].existed);
+        assert!(list_in(&dir, Some("other.yml")).is_empty());
+
+        let diff = diff_in(&dir, &server, &revisions[0].id, None).unwrap();
+        assert!(diff.contains("-b: 2"));
+        assert!(diff.contains("+b: 3"));
+        let diff = diff_in(&dir, &server, &revisions[1].id, Some(&revisions[0].id)).unwrap();
+        assert!(diff.contains("+a: 1"));
+
+        restore_in(&dir, &server, &revisions[0].id).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a: 1\nb: 2\n");
+        // 恢复前的内容也保存为修订，可以撤销
+        let after = list_in(&dir, None);
+        assert_eq!(after.len(), 3);
+        assert_eq!(after[0].origin, ORIGIN_RESTORE);
+
+        restore_in(&dir, &server, &revisions[1].id).unwrap();
+        assert!(!file.exists());
+        assert!(restore_in(&dir, &server, "missing").is_err());
+
+        let _ = fs::remove_dir_all(dir.parent().unwrap());
+    }
+}
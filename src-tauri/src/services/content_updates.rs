@@ -0,0 +1,388 @@
+//! mods/ 与 plugins/ 中文件的更新检查。
+//!
+//! 按文件的 SHA-512 批量查询 Modrinth 兼容接口的 `version_files`，识别所属项目并找出
+//! 适用于当前 Minecraft 版本与加载器的新版本。应用更新时旧文件进入回滚备份，
+//! 下一次启动失败会自动恢复。
+
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::server::ServerInstance;
+use crate::models::server_mod::{ContentUpdate, ContentUpdateRequest, ModInstallRecord};
+use crate::services::download_manager::DownloadManager;
+use crate::services::mod_manager::{self, ModManager};
+use crate::services::mod_preflight;
+use crate::services::server_installer::CoreType;
+use crate::services::update_rollback::{PendingRollback, RollbackTransaction};
+use crate::utils::hash;
+
+const DISABLED_SUFFIX: &str = ".disabled";
+pub const ROLLBACK_REASON: &str = "content_update";
+
+/// 服务端核心可加载的插件类型（对应 Modrinth 的加载器分类），非插件核心返回空
+pub fn plugin_loaders(core_type: &str) -> Vec<&'static str> {
+    let core_key = CoreType::normalize_to_api_core_key(core_type).unwrap_or_default();
+    match core_key.as_str() {
+        "paper" | "leaves" | "leaf" | "pufferfish" => vec!["paper", "spigot", "bukkit"],
+        "purpur" | "pufferfish_purpur" => vec!["purpur", "paper", "spigot", "bukkit"],
+        "folia" => vec!["folia"],
+        "spigot" => vec!["spigot", "bukkit"],
+        "bukkit" => vec!["bukkit"],
+        "arclight-forge" | "arclight-neoforge" | "arclight-fabric" | "mohist" | "youer"
+        | "catserver" | "banner" => vec!["bukkit", "spigot", "paper"],
+        "spongevanilla" | "spongeforge" => vec!["sponge"],
+        "velocity" => vec!["velocity"],
+        "bungeecord" | "lightfall" | "travertine" => vec!["bungeecord", "waterfall"],
+        _ => Vec::new(),
+    }
+}
+
+/// 目录中的 jar 文件
+struct LocalFile {
+    kind: &'static str,
+    file_name: String,
+    enabled: bool,
+    sha1: String,
+    sha512: String,
+}
+
+fn content_dirs(server: &ServerInstance) -> Vec<(&'static str, &'static str, Vec<&'static str>)> {
+    vec![
+        (
+            "mod",
+            "mods",
+            mod_preflight::server_loaders(&server.core_type, &server.mc_version),
+        ),
+        ("plugin", "plugins", plugin_loaders(&server.core_type)),
+    ]
+}
+
+fn scan_dir(server_path: &Path, kind: &'static str, dir: &'static str) -> Vec<LocalFile> {
+    let Ok(entries) = std::fs::read_dir(server_path.join(dir)) else {
+        return Vec::new();
+    };
+    let mut files = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let lower = name.to_ascii_lowercase();
+        if !path.is_file() || !(lower.ends_with(".jar") || lower.ends_with(".jar.disabled")) {
+            continue;
+        }
+        let (Ok(sha1), Ok(sha512)) = (hash::sha1_file(&path), hash::sha512_file(&path)) else {
+            continue;
+        };
+        files.push(LocalFile {
+            kind,
+            enabled: !name.ends_with(DISABLED_SUFFIX),
+            file_name: name.trim_end_matches(DISABLED_SUFFIX).to_string(),
+            sha1,
+            sha512,
+        });
+    }
+    files.sort_by_key(|f| f.file_name.to_lowercase());
+    files
+}
+
+/// 检查服务器中所有模组与插件的更新
+pub async fn check_updates(
+    manager: &ModManager,
+    server: &ServerInstance,
+) -> Result<Vec<ContentUpdate>, String> {
+    let server_path = PathBuf::from(&server.path);
+    let mut updates = Vec::new();
+
+    for (kind, dir, loaders) in content_dirs(server) {
+        let scan_path = server_path.clone();
+        let files = tauri::async_runtime::spawn_blocking(move || scan_dir(&scan_path, kind, dir))
+            .await
+            .map_err(|e| format!("计算文件摘要任务失败: {}", e))?;
+        if files.is_empty() {
+            continue;
+        }
+
+        let hashes = files.iter().map(|f| f.sha512.clone()).collect::<Vec<_>>();
+        let current = manager.versions_from_hashes(&hashes, "sha512").await?;
+        // 非对应类型的服务端（例如原版服务端的 plugins 目录）只做识别，不查更新
+        let latest = if loaders.is_empty() {
+            Default::default()
+        } else {
+            manager
+                .latest_versions_from_hashes(&hashes, "sha512", &loaders, &server.mc_version)
+                .await?
+        };
+
+        for file in files {
+            let current_version = current.get(&file.sha512).cloned();
+            let latest_version = latest.get(&file.sha512).cloned();
+            let update_available = match (&current_version, &latest_version) {
+                (Some(current), Some(latest)) => {
+                    latest.id != current.id && latest.date_published > current.date_published
+                }
+                _ => false,
+            };
+
+            let mut newer_versions = Vec::new();
+            if let (true, Some(current)) = (update_available, &current_version) {
+                newer_versions = manager
+                    .get_project_versions(&current.project_id, Some(&server.mc_version), &loaders)
+                    .await?
+                    .into_iter()
+                    .take_while(|v| v.id != current.id)
+                    .filter(|v| v.date_published > current.date_published)
+                    .collect();
+            }
+
+            updates.push(ContentUpdate {
+                kind: file.kind.to_string(),
+                project_id: current_version.as_ref().map(|v| v.project_id.clone()),
+                file_name: file.file_name,
+                enabled: file.enabled,
+                sha1: file.sha1,
+                sha512: file.sha512,
+                current_version,
+                latest_version,
+                newer_versions,
+                update_available,
+            });
+        }
+    }
+    Ok(updates)
+}
+
+/// 应用更新。新文件保持旧文件的启用状态，旧文件进入回滚备份直到下一次启动成功
+pub async fn apply_updates(
+    manager: &ModManager,
+    downloads: &DownloadManager,
+    server: &ServerInstance,
+    requests: &[ContentUpdateRequest],
+) -> Result<PendingRollback, String> {
+    let server_path = PathBuf::from(&server.path);
+    let staging = server_path.join(".sealantern").join("staging");
+    std::fs::create_dir_all(&staging).map_err(|e| format!("创建临时目录失败: {}", e))?;
+
+    let mut tx = RollbackTransaction::begin(&server_path, ROLLBACK_REASON)?;
+    let records_path = mod_manager::records_path(&server_path);
+    tx.backup_file(
+        records_path
+            .strip_prefix(&server_path)
+            .unwrap_or(&records_path),
+    )?;
+    let mut records = mod_manager::load_records(&server_path);
+
+    for request in requests {
+        let dir = match request.kind.as_str() {
+            "mod" => "mods",
+            "plugin" => "plugins",
+            other => return Err(format!("未知的内容类型: {}", other)),
+        };
+        let old_name = request.file_name.trim_end_matches(DISABLED_SUFFIX);
+        mod_manager::validate_file_name(old_name)?;
+        let enabled = server_path.join(dir).join(old_name).exists();
+        if !enabled
+            && !server_path
+                .join(dir)
+                .join(format!("{}{}", old_name, DISABLED_SUFFIX))
+                .exists()
+        {
+            return Err(format!("文件不存在: {}", old_name));
+        }
+
+        let version = manager.get_version(&request.version_id).await?;
+        let file = version
+            .primary_file()
+            .ok_or_else(|| format!("{} 没有可下载的文件", version.version_number))?
+            .clone();
+        mod_manager::validate_file_name(&file.filename)?;
+        let staged = staging.join(&file.filename);
+        mod_manager::download_verified(downloads, &file, &staged).await?;
+
+        let suffix = if enabled { "" } else { DISABLED_SUFFIX };
+        let old_path = Path::new(dir).join(format!("{}{}", old_name, suffix));
+        let new_path = Path::new(dir).join(format!("{}{}", file.filename, suffix));
+        tx.replace_file(&old_path, &new_path, &staged)?;
+
+        // 安装记录只覆盖模组
+        if request.kind != "mod" {
+            continue;
+        }
+        let required_by = records
+            .iter()
+            .find(|r| r.file_name == old_name || r.project_id == version.project_id)
+            .and_then(|r| r.required_by.clone());
+        records.retain(|r| r.file_name != old_name && r.project_id != version.project_id);
+        records.push(ModInstallRecord {
+            project_id: version.project_id.clone(),
+            version_id: version.id.clone(),
+            version_number: version.version_number.clone(),
+            file_name: file.filename.clone(),
+            sha1: file.hashes.sha1.clone(),
+            sha512: file.hashes.sha512.clone(),
+            source: "modrinth".to_string(),
+            required_by,
+            installed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        });
+    }
+
+    if requests.iter().any(|r| r.kind == "mod") {
+        mod_manager::save_records(&server_path, &records)?;
+    }
+    let _ = std::fs::remove_dir(&staging);
+    tx.commit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::update_rollback;
+    use crate::utils::test_http::{self, Response};
+
+    fn version_json(id: &str, date: &str, file: &str, sha512: &str, host: &str) -> String {
+        format!(
+            r#"{{"id":"{id}","project_id":"lithium","version_number":"{id}","version_type":"release",
+                "date_published":"{date}","files":[{{"url":"http://{host}/files/{file}",
+                "filename":"{file}","primary":true,"hashes":{{"sha512":"{sha512}"}}}}]}}"#
+        )
+    }
+
+    fn test_server(core_type: &str) -> ServerInstance {
+        let path = std::env::temp_dir().join(format!("sl_content_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(path.join("mods")).unwrap();
+        serde_json::from_value(serde_json::json!({
+            "id": "content-test",
+            "name": "content-test",
+            "core_type": core_type,
+            "core_version": "",
+            "mc_version": "1.20.1",
+            "path": path.to_string_lossy(),
+            "jar_path": "",
+            "java_path": "",
+            "max_memory": 1024,
+            "min_memory": 512,
+            "jvm_args": [],
+            "port": 25565,
+            "created_at": 0,
+            "last_started_at": null,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn plugin_loaders_follow_core_family() {
+        assert_eq!(plugin_loaders("Purpur"), vec!["purpur", "paper", "spigot", "bukkit"]);
+        assert_eq!(plugin_loaders("velocity"), vec!["velocity"]);
+        assert!(plugin_loaders("fabric").is_empty());
+    }
+
+    #[tokio::test]
+    async fn check_updates_lists_newer_versions() {
+        let server = test_server("fabric");
+        let server_path = PathBuf::from(&server.path);
+        std::fs::write(server_path.join("mods/lithium-1.jar"), "v1").unwrap();
+        std::fs::write(server_path.join("mods/unknown.jar.disabled"), "?").unwrap();
+        let sha512 = hash::sha512_file(&server_path.join("mods/lithium-1.jar")).unwrap();
+
+        let (base, requests) = test_http::serve(move |request| {
+            let host = request.header("host").unwrap_or_default();
+            let v1 = version_json("v1", "2024-01-01", "lithium-1.jar", "", host);
+            let v2 = version_json("v2", "2024-02-01", "lithium-2.jar", "", host);
+            let v3 = version_json("v3", "2024-03-01", "lithium-3.jar", "", host);
+            let body = if request.target.starts_with("/version_files/update") {
+                format!(r#"{{"{}":{}}}"#, sha512, v3)
+            } else if request.target.starts_with("/version_files") {
+                format!(r#"{{"{}":{}}}"#, sha512, v1)
+            } else if request.target.starts_with("/project/lithium/version") {
+                format!("[{},{},{}]", v3, v2, v1)
+            } else {
+                return Response::not_found();
+            };
+            Response::ok(body)
+        });
+        let manager = ModManager::with_base_url(&base).unwrap();
+
+        let updates = check_updates(&manager, &server).await.unwrap();
+        assert_eq!(updates.len(), 2);
+        let lithium = &updates[0];
+        assert_eq!(lithium.file_name, "lithium-1.jar");
+        assert!(lithium.update_available);
+        assert_eq!(lithium.latest_version.as_ref().unwrap().id, "v3");
+        let newer = lithium
+            .newer_versions
+            .iter()
+            .map(|v| v.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(newer, vec!["v3", "v2"]);
+
+        let unknown = &updates[1];
+        assert_eq!(unknown.file_name, "unknown.jar");
+        assert!(!unknown.enabled);
+        assert!(!unknown.update_available && unknown.project_id.is_none());
+
+        let update_query = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|r| r.target.starts_with("/version_files/update"))
+            .map(|r| r.body.clone())
+            .unwrap();
+        assert!(update_query.contains(r#""loaders":["fabric"]"#));
+        assert!(update_query.contains(r#""game_versions":["1.20.1"]"#));
+
+        std::fs::remove_dir_all(&server_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn apply_updates_keeps_disabled_state_and_rolls_back() {
+        let server = test_server("fabric");
+        let server_path = PathBuf::from(&server.path);
+        std::fs::write(server_path.join("mods/lithium-1.jar.disabled"), "v1").unwrap();
+        let new_file = server_path.join("lithium-2.jar");
+        std::fs::write(&new_file, "v2").unwrap();
+        let sha512 = hash::sha512_file(&new_file).unwrap();
+        std::fs::remove_file(&new_file).unwrap();
+
+        let (base, _) = test_http::serve(move |request| {
+            let host = request.header("host").unwrap_or_default();
+            match request.target.as_str() {
+                "/version/v2" => {
+                    Response::ok(version_json("v2", "2024-02-01", "lithium-2.jar", &sha512, host))
+                }
+                "/files/lithium-2.jar" => Response::ok("v2"),
+                _ => Response::not_found(),
+            }
+        });
+        let manager = ModManager::with_base_url(&base).unwrap();
+        let downloads = DownloadManager::with_state_dir(server_path.join("tasks"));
+
+        let request = ContentUpdateRequest {
+            kind: "mod".to_string(),
+            file_name: "lithium-1.jar.disabled".to_string(),
+            version_id: "v2".to_string(),
+        };
+        let plan = apply_updates(&manager, &downloads, &server, &[request])
+            .await
+            .unwrap();
+        assert_eq!(plan.reason, ROLLBACK_REASON);
+        assert!(!server_path.join("mods/lithium-1.jar.disabled").exists());
+        assert_eq!(
+            std::fs::read_to_string(server_path.join("mods/lithium-2.jar.disabled")).unwrap(),
+            "v2"
+        );
+        let records = mod_manager::load_records(&server_path);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].version_id, "v2");
+
+        assert!(update_rollback::rollback(&server_path).unwrap());
+        assert_eq!(
+            std::fs::read_to_string(server_path.join("mods/lithium-1.jar.disabled")).unwrap(),
+            "v1"
+        );
+        assert!(!server_path.join("mods/lithium-2.jar.disabled").exists());
+        assert!(mod_manager::load_records(&server_path).is_empty());
+
+        std::fs::remove_dir_all(&server_path).unwrap();
+    }
+}
@@ -0,0 +1,890 @@
+//! 服务端核心目录与下载。
+//!
+//! 按核心类型列出可用版本与构建，并把核心 jar 下载到服务器目录：
+//! 原版来自 Mojang 版本清单（校验 SHA-1），Paper、Folia、Velocity 与 Leaves 使用
+//! PaperMC 风格的构建接口（校验 SHA-256），Purpur 使用其自身的构建接口（校验 MD5），Fabric 使用
+//! 元数据服务生成的服务端启动器。配置了 BMCLAPI 兼容镜像时，Mojang 与 Fabric 的
+//! 请求都改走镜像。没有摘要的来源至少确认下载的文件是完整的 jar。
+//!
+//! Forge、NeoForge 与 Quilt 只列出版本，创建服务器时由加载器安装器在服务器目录中生成
+//! 服务端。
+
+use reqwest::Client;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::models::core_catalog::{CoreBuild, CoreDownload, CoreVersion};
+use crate::models::server::{ImportModpackRequest, ServerInstance};
+use crate::services::download_manager::DownloadManager;
+use crate::services::global;
+use crate::services::java_requirements;
+use crate::services::loader_installer::{self, InstallLog, LoaderSpec};
+use crate::services::server_installer::CoreType;
+use crate::services::server_manager::ModpackStartup;
+
+const MOJANG_META_HOSTS: [&str; 2] =
+    ["https://piston-meta.mojang.com", "https://launchermeta.mojang.com"];
+const MOJANG_DATA_HOSTS: [&str; 2] =
+    ["https://piston-data.mojang.com", "https://launcher.mojang.com"];
+
+/// 可以自动下载的核心（API 核心键）
+pub const SUPPORTED_CORE_TYPES: [&str; 8] = [
+    "paper",
+    "folia",
+    "purpur",
+    "leaves",
+    "fabric",
+    "vanilla",
+    "vanilla-snapshot",
+    "velocity",
+];
+
+/// 各来源的 API 地址
+#[derive(Debug, Clone)]
+pub struct CoreEndpoints {
+    pub mojang_manifest: String,
+    /// PaperMC 风格接口的根地址，项目位于 `{base}/projects/{project}`
+    pub papermc: String,
+    pub leaves: String,
+    pub purpur: String,
+    pub fabric_meta: String,
+    /// Forge 的 `maven-metadata.json` 所在目录
+    pub forge_maven: String,
+    /// NeoForge 的 Maven 版本列表接口
+    pub neoforge_versions: String,
+    pub quilt_meta: String,
+    /// BMCLAPI 兼容镜像，Mojang 的文件地址会替换到这里
+    pub mojang_mirror: Option<String>,
+}
+
+impl CoreEndpoints {
+    pub fn official() -> Self {
+        CoreEndpoints {
+            mojang_manifest: "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json"
+                .to_string(),
+            papermc: "https://api.papermc.io/v2".to_string(),
+            leaves: "https://api.leavesmc.org/v2".to_string(),
+            purpur: "https://api.purpurmc.org/v2/purpur".to_string(),
+            fabric_meta: "https://meta.fabricmc.net/v2".to_string(),
+            forge_maven: "https://files.minecraftforge.net/net/minecraftforge/forge".to_string(),
+            neoforge_versions:
+                "https://maven.neoforged.net/api/maven/versions/releases/net/neoforged/neoforge"
+                    .to_string(),
+            quilt_meta: "https://meta.quiltmc.org/v3".to_string(),
+            mojang_mirror: None,
+        }
+    }
+
+    /// Mojang 与 Fabric 走 BMCLAPI 兼容镜像，其余来源保持官方地址
+    pub fn with_mirror(mirror: &str) -> Self {
+        let mirror = mirror.trim().trim_end_matches('/');
+        CoreEndpoints {
+            mojang_manifest: format!("{}/mc/game/version_manifest_v2.json", mirror),
+            fabric_meta: format!("{}/fabric-meta/v2", mirror),
+            mojang_mirror: Some(mirror.to_string()),
+            ..Self::official()
+        }
+    }
+
+    /// 把 Mojang 的元数据与文件地址改写到镜像
+    fn mojang_url(&self, url: &str) -> String {
+        let Some(mirror) = &self.mojang_mirror else {
+            return url.to_string();
+        };
+        MOJANG_META_HOSTS
+            .iter()
+            .chain(MOJANG_DATA_HOSTS.iter())
+            .find_map(|host| url.strip_prefix(host))
+            .map(|path| format!("{}{}", mirror, path))
+            .unwrap_or_else(|| url.to_string())
+    }
+}
+
+/// 核心对应的来源。Forge、NeoForge 与 Quilt 需要运行安装器，只提供版本列表
+enum CoreSource {
+    Mojang { snapshot: bool },
+    PaperMc { base: String, project: &'static str },
+    Purpur,
+    Fabric,
+    Forge,
+    NeoForge,
+    Quilt,
+}
+
+#[derive(Deserialize)]
+struct MojangManifest {
+    versions: Vec<MojangVersionRef>,
+}
+
+#[derive(Deserialize)]
+struct MojangVersionRef {
+    id: String,
+    #[serde(rename = "type")]
+    kind: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct MojangVersion {
+    downloads: MojangDownloads,
+}
+
+#[derive(Deserialize)]
+struct MojangDownloads {
+    server: Option<MojangDownload>,
+}
+
+#[derive(Deserialize)]
+struct MojangDownload {
+    url: String,
+    sha1: String,
+}
+
+#[derive(Deserialize)]
+struct PaperProject {
+    versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PaperBuilds {
+    builds: Vec<PaperBuild>,
+}
+
+#[derive(Deserialize)]
+struct PaperBuild {
+    build: u64,
+    #[serde(default)]
+    channel: String,
+    downloads: std::collections::HashMap<String, PaperDownload>,
+}
+
+#[derive(Deserialize)]
+struct PaperDownload {
+    name: String,
+    #[serde(default)]
+    sha256: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PurpurProject {
+    versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PurpurVersion {
+    builds: PurpurBuilds,
+}
+
+#[derive(Deserialize)]
+struct PurpurBuilds {
+    all: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PurpurBuild {
+    #[serde(default)]
+    md5: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct FabricVersion {
+    version: String,
+    #[serde(default)]
+    stable: bool,
+}
+
+#[derive(Deserialize)]
+struct FabricLoaderEntry {
+    loader: FabricVersion,
+}
+
+#[derive(Deserialize)]
+struct NeoForgeVersions {
+    versions: Vec<String>,
+}
+
+pub struct CoreCatalog {
+    client: Client,
+    /// 固定的 API 地址，为空时按设置选择官方地址或镜像
+    endpoints: Option<CoreEndpoints>,
+}
+
+impl CoreCatalog {
+    pub fn new() -> Result<Self, String> {
+        Ok(CoreCatalog {
+            client: Client::builder()
+                .user_agent(concat!("SeaLantern/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .map_err(|e| format!("Failed to create HTTP client: {}", e))?,
+            endpoints: None,
+        })
+    }
+
+    /// 使用指定的 API 地址，便于对接本地模拟服务
+    #[cfg(test)]
+    pub fn with_endpoints(endpoints: CoreEndpoints) -> Result<Self, String> {
+        let mut catalog = Self::new()?;
+        catalog.endpoints = Some(endpoints);
+        Ok(catalog)
+    }
+
+    fn endpoints(&self) -> CoreEndpoints {
+        if let Some(endpoints) = &self.endpoints {
+            return endpoints.clone();
+        }
+        let mirror = global::settings_manager().get().core_mirror_base_url;
+        if mirror.trim().is_empty() {
+            CoreEndpoints::official()
+        } else {
+            CoreEndpoints::with_mirror(&mirror)
+        }
+    }
+
+    fn source(&self, core_type: &str) -> Result<(CoreSource, CoreEndpoints), String> {
+        let endpoints = self.endpoints();
+        let core_key = CoreType::normalize_to_api_core_key(core_type).unwrap_or_default();
+        let source = match core_key.as_str() {
+            "vanilla" => CoreSource::Mojang { snapshot: false },
+            "vanilla-snapshot" => CoreSource::Mojang { snapshot: true },
+            "paper" => CoreSource::PaperMc {
+                base: endpoints.papermc.clone(),
+                project: "paper",
+            },
+            "folia" => CoreSource::PaperMc {
+                base: endpoints.papermc.clone(),
+                project: "folia",
+            },
+            "velocity" => CoreSource::PaperMc {
+                base: endpoints.papermc.clone(),
+                project: "velocity",
+            },
+            "leaves" => CoreSource::PaperMc {
+                base: endpoints.leaves.clone(),
+                project: "leaves",
+            },
+            "purpur" => CoreSource::Purpur,
+            "fabric" => CoreSource::Fabric,
+            "forge" => CoreSource::Forge,
+            "neoforge" => CoreSource::NeoForge,
+            "quilt" => CoreSource::Quilt,
+            _ => return Err(format!("暂不支持自动下载 {} 核心", core_type)),
+        };
+        Ok((source, endpoints))
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T, String> {
+        let resp = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("请求 {} 失败: {}", url, e))?;
+        if !resp.status().is_success() {
+            return Err(format!("请求 {} 失败: {}", url, resp.status()));
+        }
+        resp.json()
+            .await
+            .map_err(|e| format!("解析 {} 的响应失败: {}", url, e))
+    }
+
+    async fn mojang_manifest(&self, endpoints: &CoreEndpoints) -> Result<MojangManifest, String> {
+        self.get_json(&endpoints.mojang_manifest).await
+    }
+
+    /// 可用的版本，最新的在前
+    pub async fn list_versions(&self, core_type: &str) -> Result<Vec<CoreVersion>, String> {
+        let (source, endpoints) = self.source(core_type)?;
+        match source {
+            CoreSource::Mojang { snapshot } => {
+                let kind = if snapshot { "snapshot" } else { "release" };
+                Ok(self
+                    .mojang_manifest(&endpoints)
+                    .await?
+                    .versions
+                    .into_iter()
+                    .filter(|v| v.kind == kind)
+                    .map(|v| CoreVersion { id: v.id, stable: !snapshot })
+                    .collect())
+            }
+            CoreSource::PaperMc { base, project } => {
+                let project: PaperProject = self
+                    .get_json(&format!("{}/projects/{}", base, project))
+                    .await?;
+                Ok(newest_first(project.versions))
+            }
+            CoreSource::Purpur => {
+                let project: PurpurProject = self.get_json(&endpoints.purpur).await?;
+                Ok(newest_first(project.versions))
+            }
+            CoreSource::Fabric => {
+                let versions: Vec<FabricVersion> = self
+                    .get_json(&format!("{}/versions/game", endpoints.fabric_meta))
+                    .await?;
+                Ok(versions
+                    .into_iter()
+                    .map(|v| CoreVersion { id: v.version, stable: v.stable })
+                    .collect())
+            }
+            CoreSource::Quilt => {
+                let versions: Vec<FabricVersion> = self
+                    .get_json(&format!("{}/versions/game", endpoints.quilt_meta))
+                    .await?;
+                Ok(versions
+                    .into_iter()
+                    .map(|v| CoreVersion { id: v.version, stable: v.stable })
+                    .collect())
+            }
+            CoreSource::Forge => {
+                let mut versions = self
+                    .forge_metadata(&endpoints)
+                    .await?
+                    .into_keys()
+                    .collect::<Vec<_>>();
+                versions.sort_by_key(|v| std::cmp::Reverse(version_key(v)));
+                Ok(versions
+                    .into_iter()
+                    .map(|id| CoreVersion { stable: !id.contains(['_', '-']), id })
+                    .collect())
+            }
+            CoreSource::NeoForge => {
+                let mut versions = Vec::<String>::new();
+                for version in self.neoforge_versions(&endpoints).await?.iter().rev() {
+                    if let Some(mc) = neoforge_mc_version(version) {
+                        if !versions.contains(&mc) {
+                            versions.push(mc);
+                        }
+                    }
+                }
+                Ok(versions
+                    .into_iter()
+                    .map(|id| CoreVersion { stable: !id.contains('-'), id })
+                    .collect())
+            }
+        }
+    }
+
+    async fn forge_metadata(
+        &self,
+        endpoints: &CoreEndpoints,
+    ) -> Result<std::collections::HashMap<String, Vec<String>>, String> {
+        self.get_json(&format!("{}/maven-metadata.json", endpoints.forge_maven))
+            .await
+    }
+
+    async fn neoforge_versions(&self, endpoints: &CoreEndpoints) -> Result<Vec<String>, String> {
+        let versions: NeoForgeVersions = self.get_json(&endpoints.neoforge_versions).await?;
+        Ok(versions.versions)
+    }
+
+    /// 某个版本下的构建，最新的在前
+    pub async fn list_builds(
+        &self,
+        core_type: &str,
+        mc_version: &str,
+    ) -> Result<Vec<CoreBuild>, String> {
+        let (source, endpoints) = self.source(core_type)?;
+        match source {
+            CoreSource::Mojang { snapshot } => Ok(vec![CoreBuild {
+                id: mc_version.to_string(),
+                stable: !snapshot,
+            }]),
+            CoreSource::PaperMc { base, project } => {
+                let builds: PaperBuilds = self
+                    .get_json(&format!(
+                        "{}/projects/{}/versions/{}/builds",
+                        base, project, mc_version
+                    ))
+                    .await?;
+                Ok(builds
+                    .builds
+                    .into_iter()
+                    .rev()
+                    .map(|b| CoreBuild {
+                        id: b.build.to_string(),
+                        stable: is_stable_channel(&b.channel),
+                    })
+                    .collect())
+            }
+            CoreSource::Purpur => {
+                let version: PurpurVersion = self
+                    .get_json(&format!("{}/{}", endpoints.purpur, mc_version))
+                    .await?;
+                Ok(version
+                    .builds
+                    .all
+                    .into_iter()
+                    .rev()
+                    .map(|id| CoreBuild { id, stable: true })
+                    .collect())
+            }
+            CoreSource::Fabric => {
+                let loaders: Vec<FabricLoaderEntry> = self
+                    .get_json(&format!("{}/versions/loader/{}", endpoints.fabric_meta, mc_version))
+                    .await?;
+                Ok(loaders
+                    .into_iter()
+                    .map(|entry| CoreBuild {
+                        id: entry.loader.version,
+                        stable: entry.loader.stable,
+                    })
+                    .collect())
+            }
+            CoreSource::Quilt => {
+                let loaders: Vec<FabricLoaderEntry> = self
+                    .get_json(&format!("{}/versions/loader/{}", endpoints.quilt_meta, mc_version))
+                    .await?;
+                Ok(loaders
+                    .into_iter()
+                    .map(|entry| CoreBuild {
+                        stable: !entry.loader.version.contains('-'),
+                        id: entry.loader.version,
+                    })
+                    .collect())
+            }
+            CoreSource::Forge => {
+                let prefix = format!("{}-", mc_version);
+                let builds = self
+                    .forge_metadata(&endpoints)
+                    .await?
+                    .remove(mc_version)
+                    .ok_or_else(|| format!("Forge 不支持 {}", mc_version))?;
+                Ok(builds
+                    .iter()
+                    .rev()
+                    .map(|full| CoreBuild {
+                        id: full.strip_prefix(&prefix).unwrap_or(full).to_string(),
+                        stable: true,
+                    })
+                    .collect())
+            }
+            CoreSource::NeoForge => Ok(self
+                .neoforge_versions(&endpoints)
+                .await?
+                .into_iter()
+                .rev()
+                .filter(|v| neoforge_mc_version(v).as_deref() == Some(mc_version))
+                .map(|id| CoreBuild { stable: !id.contains('-'), id })
+                .collect()),
+        }
+    }
+
+    /// 解析下载地址与摘要。未指定构建时选择最新的稳定构建
+    pub async fn resolve(
+        &self,
+        core_type: &str,
+        mc_version: &str,
+        build: Option<&str>,
+    ) -> Result<CoreDownload, String> {
+        let (source, endpoints) = self.source(core_type)?;
+        let core_key = CoreType::normalize_to_api_core_key(core_type).unwrap_or_default();
+        let mut download = CoreDownload {
+            core_type: core_key.clone(),
+            mc_version: mc_version.to_string(),
+            build: String::new(),
+            url: String::new(),
+            file_name: String::new(),
+            sha1: None,
+            sha256: None,
+            md5: None,
+        };
+
+        match source {
+            CoreSource::Mojang { .. } => {
+                let manifest = self.mojang_manifest(&endpoints).await?;
+                let version_ref = manifest
+                    .versions
+                    .iter()
+                    .find(|v| v.id == mc_version)
+                    .ok_or_else(|| format!("Mojang 版本清单中没有 {}", mc_version))?;
+                let version: MojangVersion = self
+                    .get_json(&endpoints.mojang_url(&version_ref.url))
+                    .await?;
+                let server = version
+                    .downloads
+                    .server
+                    .ok_or_else(|| format!("{} 没有提供服务端", mc_version))?;
+                download.build = mc_version.to_string();
+                download.url = endpoints.mojang_url(&server.url);
+                download.file_name = format!("minecraft_server.{}.jar", mc_version);
+                download.sha1 = Some(server.sha1);
+            }
+            CoreSource::PaperMc { base, project } => {
+                let builds: PaperBuilds = self
+                    .get_json(&format!(
+                        "{}/projects/{}/versions/{}/builds",
+                        base, project, mc_version
+                    ))
+                    .await?;
+                let selected = match build {
+                    Some(id) => builds.builds.iter().find(|b| b.build.to_string() == id),
+                    None => builds
+                        .builds
+                        .iter()
+                        .rev()
+                        .find(|b| is_stable_channel(&b.channel))
+                        .or_else(|| builds.builds.last()),
+                }
+                .ok_or_else(|| format!("{} {} 没有可用的构建", core_key, mc_version))?;
+                let file = selected
+                    .downloads
+                    .get("application")
+                    .ok_or_else(|| format!("构建 {} 没有服务端文件", selected.build))?;
+                download.build = selected.build.to_string();
+                download.url = format!(
+                    "{}/projects/{}/versions/{}/builds/{}/downloads/{}",
+                    base, project, mc_version, selected.build, file.name
+                );
+                download.file_name = file.name.clone();
+                download.sha256 = file.sha256.clone();
+            }
+            CoreSource::Purpur => {
+                let build = match build {
+                    Some(id) => id.to_string(),
+                    None => self
+                        .list_builds(core_type, mc_version)
+                        .await?
+                        .into_iter()
+                        .next()
+                        .map(|b| b.id)
+                        .ok_or_else(|| format!("purpur {} 没有可用的构建", mc_version))?,
+                };
+                let info: PurpurBuild = self
+                    .get_json(&format!("{}/{}/{}", endpoints.purpur, mc_version, build))
+                    .await?;
+                download.url = format!("{}/{}/{}/download", endpoints.purpur, mc_version, build);
+                download.file_name = format!("purpur-{}-{}.jar", mc_version, build);
+                download.md5 = info.md5;
+                download.build = build;
+            }
+            CoreSource::Fabric => {
+                let loader = match build {
+                    Some(id) => id.to_string(),
+                    None => latest_stable(self.list_builds(core_type, mc_version).await?)
+                        .ok_or_else(|| format!("Fabric 不支持 {}", mc_version))?,
+                };
+                let installers: Vec<FabricVersion> = self
+                    .get_json(&format!("{}/versions/installer", endpoints.fabric_meta))
+                    .await?;
+                let installer = installers
+                    .iter()
+                    .find(|v| v.stable)
+                    .or_else(|| installers.first())
+                    .ok_or_else(|| "未找到 Fabric 安装器版本".to_string())?;
+                download.url = format!(
+                    "{}/versions/loader/{}/{}/{}/server/jar",
+                    endpoints.fabric_meta, mc_version, loader, installer.version
+                );
+                download.file_name = format!(
+                    "fabric-server-mc.{}-loader.{}-launcher.{}.jar",
+                    mc_version, loader, installer.version
+                );
+                download.build = loader;
+            }
+            CoreSource::Forge | CoreSource::NeoForge | CoreSource::Quilt => {
+                return Err(format!("{} 需要运行安装器，无法直接下载核心", core_key));
+            }
+        }
+        Ok(download)
+    }
+
+    /// 通过下载管理器把核心下载到目录中，校验通过后返回 jar 路径
+    pub async fn download(
+        &self,
+        downloads: &DownloadManager,
+        download: &CoreDownload,
+        target_dir: &Path,
+    ) -> Result<PathBuf, String> {
+        if download.file_name.is_empty()
+            || download.file_name.contains(['/', '\\'])
+            || download.file_name.contains("..")
+        {
+            return Err(format!("无效的核心文件名: {}", download.file_name));
+        }
+        fs::create_dir_all(target_dir).map_err(|e| format!("创建服务器目录失败: {}", e))?;
+        let target = target_dir.join(&download.file_name);
+        let temp = target_dir.join(format!("{}.download", download.file_name));
+
+        let task = downloads
+            .create_internal_task(
+                &download.url,
+                &temp.to_string_lossy(),
+                4,
+                expected_hash(download),
+            )
+            .await;
+        if let Err(e) = downloads.wait_for_task(task).await {
+            let _ = fs::remove_file(&temp);
+            return Err(format!("下载 {} 失败: {}", download.file_name, e));
+        }
+
+        let temp_path = temp.clone();
+        let verified = tauri::async_runtime::spawn_blocking(move || verify_core(&temp_path))
+            .await
+            .map_err(|e| format!("校验文件任务失败: {}", e))?;
+        if let Err(e) = verified {
+            let _ = fs::remove_file(&temp);
+            return Err(format!("{}: {}", download.file_name, e));
+        }
+        fs::rename(&temp, &target)
+            .map_err(|e| format!("保存 {} 失败: {}", download.file_name, e))?;
+        Ok(target)
+    }
+
+    /// 下载核心并创建服务器。`req` 中的运行目录、内存与端口与导入整合包一致
+    pub async fn create_server(
+        &self,
+        downloads: &DownloadManager,
+        req: ImportModpackRequest,
+        core_type: &str,
+        mc_version: &str,
+        build: Option<&str>,
+    ) -> Result<ServerInstance, String> {
+        let core_key = CoreType::normalize_to_api_core_key(core_type).unwrap_or_default();
+        if loader_installer::LOADER_CORE_TYPES.contains(&core_key.as_str()) {
+            return self
+                .create_loader_server(req, &core_key, mc_version, build)
+                .await;
+        }
+        let download = self.resolve(core_type, mc_version, build).await?;
+        let manager = global::server_manager();
+        let (id, server_name, run_dir) = manager.prepare_modpack_run_dir(&req)?;
+
+        let jar = match self.download(downloads, &download, &run_dir).await {
+            Ok(jar) => jar,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&run_dir);
+                return Err(e);
+            }
+        };
+        let core_name = CoreType::from_str(&download.core_type)
+            .map(|core| core.as_str().to_string())
+            .unwrap_or_else(|_| download.core_type.clone());
+        let startup = ModpackStartup {
+            startup_mode: "jar".to_string(),
+            startup_file_path: Some(jar.to_string_lossy().to_string()),
+            custom_command: None,
+            core_type: Some(core_name),
+            core_version: download.build.clone(),
+            mc_version: Some(mc_version.to_string()),
+            jvm_args: Vec::new(),
+        };
+        manager
+            .register_modpack(req, id, server_name, run_dir.clone(), startup)
+            .inspect_err(|_| {
+                let _ = fs::remove_dir_all(&run_dir);
+            })
+    }
+
+    /// 在新的服务器目录中运行加载器安装器，安装输出写入该服务器的日志
+    async fn create_loader_server(
+        &self,
+        mut req: ImportModpackRequest,
+        core_key: &str,
+        mc_version: &str,
+        build: Option<&str>,
+    ) -> Result<ServerInstance, String> {
+        let version = match build.map(str::trim).filter(|v| !v.is_empty()) {
+            Some(version) => version.to_string(),
+            None => latest_stable(self.list_builds(core_key, mc_version).await?)
+                .ok_or_else(|| format!("{} 不支持 {}", core_key, mc_version))?,
+        };
+        let core_name = CoreType::from_str(core_key)
+            .map(|core| core.as_str().to_string())
+            .unwrap_or_else(|_| core_key.to_string());
+        let spec = LoaderSpec {
+            core_type: core_name.clone(),
+            version: version.clone(),
+            mc_version: mc_version.to_string(),
+        };
+        // 安装器本身需要兼容的 Java，未指定时在运行前自动选择
+        req.java_path =
+            java_requirements::select_java_async(&req.java_path, mc_version, &core_name).await?;
+
+        let manager = global::server_manager();
+        let (id, server_name, run_dir) = manager.prepare_modpack_run_dir(&req)?;
+        let log = InstallLog::new(&id, &run_dir);
+        let installed = match fs::create_dir_all(&run_dir)
+            .map_err(|e| format!("无法创建运行目录: {}", e))
+        {
+            Ok(()) => loader_installer::install(&run_dir, &spec, &req.java_path, &log).await,
+            Err(e) => Err(e),
+        };
+        let result = installed.and_then(|installed| {
+            let startup = ModpackStartup {
+                startup_mode: installed.startup_mode,
+                startup_file_path: Some(installed.startup_file.to_string_lossy().to_string()),
+                custom_command: None,
+                core_type: Some(core_name),
+                core_version: version,
+                mc_version: Some(mc_version.to_string()),
+                jvm_args: Vec::new(),
+            };
+            manager.register_modpack(req, id, server_name, run_dir.clone(), startup)
+        });
+        if result.is_err() {
+            log.close();
+            let _ = fs::remove_dir_all(&run_dir);
+        }
+        result
+    }
+}
+
+fn is_stable_channel(channel: &str) -> bool {
+    channel.is_empty()
+        || channel.eq_ignore_ascii_case("default")
+        || channel.eq_ignore_ascii_case("stable")
+}
+
+fn latest_stable(builds: Vec<CoreBuild>) -> Option<String> {
+    builds
+        .iter()
+        .find(|b| b.stable)
+        .or_else(|| builds.first())
+        .map(|b| b.id.clone())
+}
+
+/// 构建接口按时间正序返回版本，实验版本（含 `-`）标为不稳定
+fn newest_first(versions: Vec<String>) -> Vec<CoreVersion> {
+    versions
+        .into_iter()
+        .rev()
+        .map(|id| CoreVersion { stable: !id.contains('-'), id })
+        .collect()
+}
+
+/// 版本号中的数字部分，用于排序（`1.7.10_pre4` 排在 `1.7.10` 之后）
+fn version_key(version: &str) -> Vec<u32> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+/// NeoForge 版本对应的 Minecraft 版本：`21.1.77` 对应 1.21.1，`20.4.237` 对应 1.20.4；
+/// 从 26.1 开始版本号以 Minecraft 版本开头，如 `26.1.0.1-beta` 对应 26.1
+fn neoforge_mc_version(version: &str) -> Option<String> {
+    let numbers = version.split('-').next()?;
+    let parts = numbers
+        .split('.')
+        .map(|part| part.parse::<u32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    match parts.as_slice() {
+        [major, minor, ..] if *major < 25 => Some(if *minor == 0 {
+            format!("1.{}", major)
+        } else {
+            format!("1.{}.{}", major, minor)
+        }),
+        [major, minor, patch, _, ..] if *patch != 0 => {
+            Some(format!("{}.{}.{}", major, minor, patch))
+        }
+        [major, minor, ..] => Some(format!("{}.{}", major, minor)),
+        _ => None,
+    }
+}
+
+/// 下载器校验用的摘要，依次使用 SHA-256、SHA-1、MD5，格式见 [`crate::utils::hash::verify_file`]
+fn expected_hash(download: &CoreDownload) -> Option<String> {
+    if let Some(sha256) = &download.sha256 {
+        Some(format!("sha256:{}", sha256))
+    } else if let Some(sha1) = &download.sha1 {
+        Some(format!("sha1:{}", sha1))
+    } else {
+        download.md5.as_ref().map(|md5| format!("md5:{}", md5))
+    }
+}
+
+/// 确认下载的文件是可以读取的 jar
+fn verify_core(path: &Path) -> Result<(), String> {
+    let file = fs::File::open(path).map_err(|e| format!("打开文件失败: {}", e))?;
+    zip::ZipArchive::new(file).map_err(|e| format!("下载的文件不是有效的 jar: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_http;
+
+    #[tokio::test]
+    async fn resolves_builds_from_mock_sources() {
+        let (base, _) = test_http::routes(vec![
+            (
+                "/paper/projects/paper/versions/1.20.4/builds",
+                r#"{"builds":[
+                    {"build":496,"channel":"default","downloads":{"application":
+                        {"name":"paper-1.20.4-496.jar","sha256":"aa"}}},
+                    {"build":497,"channel":"experimental","downloads":{"application":
+                        {"name":"paper-1.20.4-497.jar","sha256":"bb"}}}]}"#
+                    .to_string(),
+            ),
+            (
+                "/paper/projects/paper",
+                r#"{"versions":["1.20.4","1.20.5-rc1","1.21"]}"#.to_string(),
+            ),
+            (
+                "/purpur/1.21.1/2329",
+                r#"{"build":"2329","md5":"dd","result":"SUCCESS"}"#.to_string(),
+            ),
+            (
+                "/purpur/1.21.1",
+                r#"{"builds":{"all":["2328","2329"],"latest":"2329"}}"#.to_string(),
+            ),
+            (
+                "/mirror/mc/game/version_manifest_v2.json",
+                r#"{"versions":[
+                    {"id":"24w10a","type":"snapshot","url":"https://piston-meta.mojang.com/v1/24w10a.json"},
+                    {"id":"1.20.4","type":"release","url":"https://piston-meta.mojang.com/v1/1.20.4.json"}]}"#
+                    .to_string(),
+            ),
+            (
+                "/mirror/v1/1.20.4.json",
+                r#"{"downloads":{"server":{"url":"https://piston-data.mojang.com/v1/objects/abc/server.jar",
+                    "sha1":"cc"}}}"#
+                    .to_string(),
+            ),
+        ]);
+        let mut endpoints = CoreEndpoints::with_mirror(&format!("{}/mirror/", base));
+        endpoints.papermc = format!("{}/paper", base);
+        endpoints.purpur = format!("{}/purpur", base);
+        let catalog = CoreCatalog::with_endpoints(endpoints).unwrap();
+
+        let versions = catalog.list_versions("Paper").await.unwrap();
+        let ids = versions.iter().map(|v| v.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["1.21", "1.20.5-rc1", "1.20.4"]);
+        assert!(!versions[1].stable);
+
+        let builds = catalog.list_builds("paper", "1.20.4").await.unwrap();
+        assert_eq!(builds[0].id, "497");
+        let latest = catalog.resolve("paper", "1.20.4", None).await.unwrap();
+        assert_eq!(latest.build, "496");
+        assert_eq!(latest.sha256.as_deref(), Some("aa"));
+        assert!(latest
+            .url
+            .ends_with("/builds/496/downloads/paper-1.20.4-496.jar"));
+
+        let releases = catalog.list_versions("vanilla").await.unwrap();
+        assert_eq!(releases.len(), 1);
+        let vanilla = catalog.resolve("vanilla", "1.20.4", None).await.unwrap();
+        assert_eq!(vanilla.url, format!("{}/mirror/v1/objects/abc/server.jar", base));
+        assert_eq!(vanilla.sha1.as_deref(), Some("cc"));
+
+        let purpur = catalog.resolve("purpur", "1.21.1", None).await.unwrap();
+        assert_eq!(purpur.build, "2329");
+        assert_eq!(purpur.md5.as_deref(), Some("dd"));
+        assert_eq!(purpur.url, format!("{}/purpur/1.21.1/2329/download", base));
+
+        assert!(catalog.resolve("spigot", "1.20.4", None).await.is_err());
+    }
+
+    #[test]
+    fn maps_neoforge_versions_to_minecraft() {
+        assert_eq!(neoforge_mc_version("20.4.237").as_deref(), Some("1.20.4"));
+        assert_eq!(neoforge_mc_version("21.0.167").as_deref(), Some("1.21"));
+        assert_eq!(neoforge_mc_version("21.1.77-beta").as_deref(), Some("1.21.1"));
+        assert_eq!(neoforge_mc_version("26.1.0.1-beta").as_deref(), Some("26.1"));
+        assert_eq!(neoforge_mc_version("snapshot"), None);
+        assert!(version_key("1.20.10") > version_key("1.20.4"));
+    }
+}
@@ -0,0 +1,494 @@
+//! 原地升级服务端核心。
+//!
+//! 新核心先下载（或由加载器安装器生成）到 `.sealantern/staging/core/`，再通过回滚事务
+//! 替换服务器目录中的文件，同时更新服务器配置中的核心版本。新版本首次启动可能升级
+//! 世界格式，所以替换前先把世界与配置导出为便携包。随后试启动服务器：启动完成即确认
+//! 升级；启动失败或超时则恢复旧文件与旧配置。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::models::core_catalog::CoreUpgradeResult;
+use crate::models::server::{ServerInstance, ServerStatus};
+use crate::services::core_catalog;
+use crate::services::download_manager::DownloadManager;
+use crate::services::global;
+use crate::services::java_requirements;
+use crate::services::loader_installer::{self, InstallLog, LoaderSpec};
+use crate::services::server_export;
+use crate::services::server_installer::CoreType;
+use crate::services::server_log_pipeline;
+use crate::services::update_rollback::{self, RollbackTransaction};
+
+pub const ROLLBACK_REASON: &str = "core_upgrade";
+const STAGING_DIR: &str = ".sealantern/staging/core";
+/// 试启动等待启动完成的最长时间，大型整合包首次启动较慢
+const READY_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// 安装器生成的文件中由用户维护的部分，已存在时不覆盖
+const USER_FILES: [&str; 1] = ["user_jvm_args.txt"];
+/// 升级前备份在应用数据目录中的位置
+const BACKUP_DIR: &str = "backups";
+/// 服务器根目录下视为配置的文件扩展名
+const CONFIG_EXTENSIONS: [&str; 6] = ["properties", "yml", "yaml", "toml", "json", "txt"];
+/// 服务器根目录下保存配置的目录
+const CONFIG_DIRS: [&str; 3] = ["config", "defaultconfigs", "plugins"];
+
+/// 一个待替换的文件：旧位置、新位置（均相对服务器目录）与暂存文件
+struct Replacement {
+    old: PathBuf,
+    new: PathBuf,
+    staged: PathBuf,
+}
+
+fn find_server(server_id: &str) -> Result<ServerInstance, String> {
+    global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| format!("未找到服务器: {}", server_id))
+}
+
+fn log(server_id: &str, message: &str) {
+    let _ = server_log_pipeline::append_sealantern_log(server_id, message);
+}
+
+/// 升级核心到指定的 Minecraft 版本与构建。未指定 Minecraft 版本时保持当前版本，
+/// 未指定构建时使用最新的稳定构建；Forge、NeoForge、Quilt 必须指定加载器版本
+pub async fn upgrade(
+    downloads: &DownloadManager,
+    server_id: &str,
+    mc_version: Option<&str>,
+    build: Option<&str>,
+    keep_running: bool,
+) -> Result<CoreUpgradeResult, String> {
+    let server = find_server(server_id)?;
+    let manager = global::server_manager();
+    if !matches!(manager.get_server_status(server_id).status, ServerStatus::Stopped) {
+        return Err("请先停止服务器再升级核心".to_string());
+    }
+    let server_path = PathBuf::from(&server.path);
+    if update_rollback::pending(&server_path).is_some() {
+        return Err("服务器还有未确认的更新，请先启动确认或回滚后再升级核心".to_string());
+    }
+
+    let mc_version = mc_version
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .unwrap_or(&server.mc_version)
+        .to_string();
+    let java_path = select_upgrade_java(&server, &mc_version).await?;
+    let staging = server_path.join(STAGING_DIR);
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging).map_err(|e| format!("创建临时目录失败: {}", e))?;
+
+    let staged = stage_core(downloads, &server, &staging, &mc_version, build, &java_path).await;
+    let (replacements, startup_mode, new_startup, core_version) = match staged {
+        Ok(staged) => staged,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    let backup = backup_before_upgrade(&server).await;
+    let backup_path = match backup {
+        Ok(path) => path,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(format!("升级前备份世界与配置失败: {}", e));
+        }
+    };
+    log(
+        server_id,
+        &format!("[Sea Lantern] 已备份世界与配置到 {}", backup_path.display()),
+    );
+
+    let mut updated = server.clone();
+    updated.jar_path = server_path.join(&new_startup).to_string_lossy().to_string();
+    updated.startup_mode = startup_mode;
+    updated.core_version = core_version;
+    updated.mc_version = mc_version;
+    updated.java_path = java_path;
+
+    let applied = apply(&server, &updated, &replacements);
+    let _ = fs::remove_dir_all(&staging);
+    if let Err(e) = applied {
+        let _ = update_rollback::rollback(&server_path);
+        return Err(e);
+    }
+    log(
+        server_id,
+        &format!(
+            "[Sea Lantern] 核心已升级到 {} {}，正在试启动",
+            updated.mc_version, updated.core_version
+        ),
+    );
+
+    let confirmed = trial_start(server_id, &server_path, &updated).await?;
+    if !confirmed {
+        return Err(format!(
+            "升级后启动失败，已回滚到 {} {}；世界可能已被新版本改写，可从 {} 恢复",
+            server.mc_version,
+            server.core_version,
+            backup_path.display()
+        ));
+    }
+
+    let mut running = true;
+    if !keep_running {
+        let id = server_id.to_string();
+        tauri::async_runtime::spawn_blocking(move || global::server_manager().stop_server(&id))
+            .await
+            .map_err(|e| format!("停止服务器任务失败: {}", e))??;
+        running = false;
+    }
+    Ok(CoreUpgradeResult {
+        previous_core_version: server.core_version,
+        previous_mc_version: server.mc_version,
+        core_version: updated.core_version,
+        mc_version: updated.mc_version,
+        jar_path: updated.jar_path,
+        running,
+        backup_path: backup_path.to_string_lossy().to_string(),
+    })
+}
+
+/// 当前 Java 满足目标版本时继续使用，否则改用已安装的兼容 Java
+async fn select_upgrade_java(server: &ServerInstance, mc_version: &str) -> Result<String, String> {
+    match java_requirements::select_java_async(&server.java_path, mc_version, &server.core_type)
+        .await
+    {
+        Ok(java_path) => Ok(java_path),
+        Err(_) => java_requirements::select_java_async("", mc_version, &server.core_type).await,
+    }
+}
+
+/// 世界目录（含 level.dat）、配置目录与根目录下的配置文件，相对服务器目录
+fn backup_entries(server_path: &Path) -> Result<Vec<String>, String> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(server_path)
+        .map_err(|e| format!("读取服务器目录失败: {}", e))?
+        .flatten()
+    {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let keep = if path.is_dir() {
+            CONFIG_DIRS.contains(&name.as_str()) || path.join("level.dat").is_file()
+        } else {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| CONFIG_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        };
+        if keep {
+            entries.push(name);
+        }
+    }
+    entries.sort();
+    Ok(entries)
+}
+
+/// 把世界与配置导出为便携包，保存在应用数据目录的 backups/<服务器 ID>/ 中
+async fn backup_before_upgrade(server: &ServerInstance) -> Result<PathBuf, String> {
+    let include = backup_entries(Path::new(&server.path))?;
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let output = crate::utils::path::get_app_data_dir()
+        .join(BACKUP_DIR)
+        .join(&server.id)
+        .join(format!("{}_{}.zip", ROLLBACK_REASON, created_at));
+    server_export::export(server, "bundle", &output, &include).await?;
+    Ok(output)
+}
+
+/// 在已有服务器的目录中运行加载器安装器并改为直接启动生成的文件，用于以 starter
+/// 方式创建、首次启动时才安装加载器的服务器。未指定版本时使用最新的稳定版本
+pub async fn install_loader(
+    server_id: &str,
+    version: Option<&str>,
+) -> Result<ServerInstance, String> {
+    let server = find_server(server_id)?;
+    let manager = global::server_manager();
+    if !matches!(manager.get_server_status(server_id).status, ServerStatus::Stopped) {
+        return Err("请先停止服务器再安装加载器".to_string());
+    }
+    let core_key = CoreType::normalize_to_api_core_key(&server.core_type).unwrap_or_default();
+    if !loader_installer::LOADER_CORE_TYPES.contains(&core_key.as_str()) && core_key != "fabric" {
+        return Err(format!("{} 不需要安装加载器", server.core_type));
+    }
+    let version = match version.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(version) => version.to_string(),
+        None => global::core_catalog()
+            .list_builds(&core_key, &server.mc_version)
+            .await?
+            .iter()
+            .find(|b| b.stable)
+            .map(|b| b.id.clone())
+            .ok_or_else(|| format!("{} 不支持 {}", server.core_type, server.mc_version))?,
+    };
+
+    let server_path = PathBuf::from(&server.path);
+    let spec = LoaderSpec {
+        core_type: server.core_type.clone(),
+        version: version.clone(),
+        mc_version: server.mc_version.clone(),
+    };
+    let java_path = java_requirements::select_java_async(
+        &server.java_path,
+        &server.mc_version,
+        &server.core_type,
+    )
+    .await?;
+    let log = InstallLog::new(&server.id, &server_path);
+    let installed = loader_installer::install(&server_path, &spec, &java_path, &log).await?;
+
+    let mut updated = server;
+    updated.jar_path = installed.startup_file.to_string_lossy().to_string();
+    updated.startup_mode = installed.startup_mode;
+    updated.core_version = version;
+    updated.java_path = java_path;
+    manager.update_core_info(&updated)?;
+    Ok(updated)
+}
+
+/// 以 starter 方式创建的 Forge、NeoForge、Quilt、Fabric 服务器在首次启动前安装加载器，
+/// 之后直接启动生成的文件；其他核心仍由 Starter 在启动时下载安装器
+pub async fn prepare_starter(server_id: &str) -> Result<(), String> {
+    let server = find_server(server_id)?;
+    if !server.startup_mode.eq_ignore_ascii_case("starter") {
+        return Ok(());
+    }
+    let core_key = CoreType::normalize_to_api_core_key(&server.core_type).unwrap_or_default();
+    if !loader_installer::LOADER_CORE_TYPES.contains(&core_key.as_str()) && core_key != "fabric" {
+        return Ok(());
+    }
+    // 运行中的服务器交给启动流程报告
+    let status = global::server_manager().get_server_status(server_id).status;
+    if !matches!(status, ServerStatus::Stopped) {
+        return Ok(());
+    }
+    log(server_id, "[Sea Lantern] 首次启动，正在安装加载器...");
+    install_loader(server_id, None).await.map(|_| ())
+}
+
+/// 准备新核心，返回要替换的文件、启动方式、新启动文件（相对服务器目录）与核心版本。
+/// 加载器安装器使用为目标版本选择的 `java_path` 运行
+async fn stage_core(
+    downloads: &DownloadManager,
+    server: &ServerInstance,
+    staging: &Path,
+    mc_version: &str,
+    build: Option<&str>,
+    java_path: &str,
+) -> Result<(Vec<Replacement>, String, PathBuf, String), String> {
+    let server_path = Path::new(&server.path);
+    let old_startup = Path::new(&server.jar_path)
+        .strip_prefix(server_path)
+        .map(Path::to_path_buf)
+        .map_err(|_| "启动文件不在服务器目录中，无法自动升级核心".to_string())?;
+    let core_key = CoreType::normalize_to_api_core_key(&server.core_type).unwrap_or_default();
+
+    if core_catalog::SUPPORTED_CORE_TYPES.contains(&core_key.as_str()) {
+        if server.startup_mode != "jar" {
+            return Err(format!(
+                "当前启动方式为 {}，只有直接启动 jar 的服务器可以自动替换核心",
+                server.startup_mode
+            ));
+        }
+        let catalog = global::core_catalog();
+        let download = catalog.resolve(&core_key, mc_version, build).await?;
+        if download.build == server.core_version && mc_version == server.mc_version {
+            return Err(format!("已经是 {} {}", mc_version, download.build));
+        }
+        let staged = catalog.download(downloads, &download, staging).await?;
+        // 新 jar 放在旧 jar 所在的目录
+        let new_startup = old_startup.with_file_name(&download.file_name);
+        return Ok((
+            vec![Replacement {
+                old: old_startup,
+                new: new_startup.clone(),
+                staged,
+            }],
+            "jar".to_string(),
+            new_startup,
+            download.build,
+        ));
+    }
+
+    if !loader_installer::LOADER_CORE_TYPES.contains(&core_key.as_str()) {
+        return Err(format!("暂不支持自动升级 {} 核心", server.core_type));
+    }
+    let version = build
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| format!("升级 {} 需要指定加载器版本", server.core_type))?;
+    if version == server.core_version && mc_version == server.mc_version {
+        return Err(format!("已经是 {} {}", mc_version, version));
+    }
+    let spec = LoaderSpec {
+        core_type: server.core_type.clone(),
+        version: version.to_string(),
+        mc_version: mc_version.to_string(),
+    };
+    let log = InstallLog::new(&server.id, server_path);
+    let installed = loader_installer::install(staging, &spec, java_path, &log).await?;
+    let new_startup = installed
+        .startup_file
+        .strip_prefix(staging)
+        .map(Path::to_path_buf)
+        .map_err(|_| "安装器生成的启动文件不在临时目录中".to_string())?;
+
+    let mut replacements = Vec::new();
+    for relative in collect_files(staging)? {
+        let is_user_file = USER_FILES.iter().any(|f| relative == Path::new(f));
+        if is_user_file && server_path.join(&relative).exists() {
+            continue;
+        }
+        // 旧的启动文件（例如旧版 Forge 的服务端 jar）由新的启动文件替换
+        let old = if relative == new_startup {
+            old_startup.clone()
+        } else {
+            relative.clone()
+        };
+        replacements.push(Replacement {
+            old,
+            staged: staging.join(&relative),
+            new: relative,
+        });
+    }
+    Ok((replacements, installed.startup_mode, new_startup, version.to_string()))
+}
+
+/// 在回滚事务中替换文件并更新服务器配置
+fn apply(
+    server: &ServerInstance,
+    updated: &ServerInstance,
+    replacements: &[Replacement],
+) -> Result<(), String> {
+    let server_path = Path::new(&server.path);
+    let mut tx = RollbackTransaction::begin(server_path, ROLLBACK_REASON)?;
+    tx.record_instance(server)?;
+    for replacement in replacements {
+        tx.replace_file(&replacement.old, &replacement.new, &replacement.staged)?;
+    }
+    global::server_manager().update_core_info(updated)?;
+    tx.commit().map(|_| ())
+}
+
+/// 临时目录中的所有文件，路径相对临时目录
+fn collect_files(root: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let entries = fs::read_dir(&dir).map_err(|e| format!("读取临时目录失败: {}", e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else if let Ok(relative) = path.strip_prefix(root) {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// 启动服务器并等待升级被确认。启动完成返回 true；启动失败或超时时回滚并返回 false
+async fn trial_start(
+    server_id: &str,
+    server_path: &Path,
+    updated: &ServerInstance,
+) -> Result<bool, String> {
+    let id = server_id.to_string();
+    let started =
+        tauri::async_runtime::spawn_blocking(move || global::server_manager().start_server(&id))
+            .await
+            .map_err(|e| format!("启动服务器任务失败: {}", e))?;
+    if let Err(e) = started {
+        update_rollback::rollback(server_path)?;
+        return Err(format!("升级后无法启动服务器，已回滚: {}", e));
+    }
+
+    let deadline = Instant::now() + READY_TIMEOUT;
+    loop {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        // 启动完成时确认升级，启动失败时由日志管线回滚并恢复旧配置，两者都会清除回滚记录
+        if update_rollback::pending(server_path).is_none() {
+            let current = find_server(server_id)?;
+            return Ok(current.jar_path == updated.jar_path
+                && current.core_version == updated.core_version
+                && current.mc_version == updated.mc_version);
+        }
+        if Instant::now() >= deadline {
+            break;
+        }
+    }
+
+    log(server_id, "[Sea Lantern] 升级后启动超时，正在停止服务器并回滚");
+    let id = server_id.to_string();
+    let stopped =
+        tauri::async_runtime::spawn_blocking(move || global::server_manager().stop_server(&id))
+            .await
+            .map_err(|e| format!("停止服务器任务失败: {}", e))
+            .and_then(|stopped| stopped);
+    // 停止失败时也要回滚，否则主动停止的标记会让之后的退出保留新核心
+    let rolled_back = update_rollback::rollback(server_path);
+    stopped?;
+    rolled_back?;
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_nested_staging_files() {
+        let root = std::env::temp_dir().join(format!("sl_core_upgrade_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("libraries/net/forge")).unwrap();
+        fs::write(root.join("run.sh"), "").unwrap();
+        fs::write(root.join("libraries/net/forge/forge.jar"), "").unwrap();
+
+        let files = collect_files(&root).unwrap();
+        assert_eq!(
+            files,
+            vec![PathBuf::from("libraries/net/forge/forge.jar"), PathBuf::from("run.sh")]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn backs_up_worlds_and_config_only() {
+        let root = std::env::temp_dir().join(format!("sl_core_upgrade_{}", uuid::Uuid::new_v4()));
+        for dir in ["world", "world_nether/DIM-1", "config", "plugins", "libraries", "logs"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "world/level.dat",
+            "world_nether/level.dat",
+            "server.properties",
+            "bukkit.yml",
+            "paper-1.20.4-496.jar",
+            "eula.txt",
+        ] {
+            fs::write(root.join(file), "").unwrap();
+        }
+
+        assert_eq!(
+            backup_entries(&root).unwrap(),
+            vec![
+                "bukkit.yml",
+                "config",
+                "eula.txt",
+                "plugins",
+                "server.properties",
+                "world",
+                "world_nether"
+            ]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+//! 服务器崩溃分析。
+//!
+//! 读取最近的服务器日志与最新的崩溃报告，识别常见的崩溃原因。
+//! 断电等异常退出后最常见的是区块加载失败，此时会自动校验区域文件，
+//! 并把与日志坐标吻合的损坏区块单独列出，供前端一键隔离。
+
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+use crate::models::crash::{ChunkLoadError, CrashAnalysis};
+use crate::services::{global, region_checker, server_log_pipeline};
+
+/// 参与分析的最近日志行数
+const MAX_LOG_LINES: usize = 5000;
+/// 同类错误最多记录的条数
+const MAX_CHUNK_ERRORS: usize = 100;
+
+/// 区块加载失败的典型日志（原版、Paper、Forge 等）
+const CHUNK_ERROR_PATTERNS: &str = r"(?i)(couldn't load chunk|failed to (?:load|read) chunk|couldn't read chunk|error (?:loading|reading) chunk|exception (?:loading|reading) chunk|chunk file at \[|corrupt(?:ed)? chunk|chunk load failed|failed to decompress chunk|invalid chunk)";
+
+pub fn analyze_server(server_id: &str) -> Result<CrashAnalysis, String> {
+    let server = global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .ok_or_else(|| "未找到服务器".to_string())?;
+    let server_path = PathBuf::from(&server.path);
+
+    let mut lines =
+        server_log_pipeline::read_logs(&server_path, 0, Some(MAX_LOG_LINES)).unwrap_or_default();
+    let crash_report = latest_crash_report(&server_path);
+    if let Some(report) = &crash_report {
+        if let Ok(content) = std::fs::read_to_string(report) {
+            lines.extend(content.lines().map(str::to_string));
+        }
+    }
+
+    Ok(analyze(server_id, &server_path, &lines, crash_report))
+}
+
+pub fn analyze(
+    server_id: &str,
+    server_path: &Path,
+    lines: &[String],
+    crash_report: Option<PathBuf>,
+) -> CrashAnalysis {
+    let chunk_errors = find_chunk_errors(lines);
+    let mut analysis = CrashAnalysis {
+        server_id: server_id.to_string(),
+        crash_report: crash_report.map(|path| path.to_string_lossy().to_string()),
+        chunk_errors,
+        region_scan: None,
+        matched_issues: Vec::new(),
+        suggestions: Vec::new(),
+    };
+
+    if analysis.chunk_errors.is_empty() {
+        return analysis;
+    }
+
+    match region_checker::scan_world(server_path, None) {
+        Ok(scan) => {
+            analysis.matched_issues = scan
+                .issues
+                .iter()
+                .filter(|issue| {
+                    analysis.chunk_errors.iter().any(|error| {
+                        error.chunk_x.is_some()
+                            && error.chunk_x == issue.chunk_x
+                            && error.chunk_z == issue.chunk_z
+                    })
+                })
+                .cloned()
+                .collect();
+
+            if scan.issues.is_empty() {
+                analysis.suggestions.push(
+                    "日志中存在区块加载错误，但区域文件校验未发现损坏，可能由模组或插件的数据引起"
+                        .to_string(),
+                );
+            } else {
+                analysis.suggestions.push(format!(
+                    "检测到 {} 个损坏的区块数据，建议备份世界后隔离这些区块，服务器会重新生成它们",
+                    scan.issues.len()
+                ));
+            }
+            analysis.region_scan = Some(scan);
+        }
+        Err(e) => analysis
+            .suggestions
+            .push(format!("日志中存在区块加载错误，但无法校验区域文件: {}", e)),
+    }
+
+    analysis
+}
+
+/// 从日志中提取区块加载错误，并尽量解析区块坐标
+pub fn find_chunk_errors(lines: &[String]) -> Vec<ChunkLoadError> {
+    let (Ok(pattern), Ok(bracket), Ok(named)) = (
+        Regex::new(CHUNK_ERROR_PATTERNS),
+        Regex::new(r"\[\s*(-?\d+)\s*,\s*(-?\d+)\s*\]"),
+        Regex::new(r"(?i)\bx\s*[=:]\s*(-?\d+)\s*,\s*z\s*[=:]\s*(-?\d+)"),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut errors: Vec<ChunkLoadError> = Vec::new();
+    for line in lines {
+        if !pattern.is_match(line) {
+            continue;
+        }
+        let line = line.trim().to_string();
+        if errors.iter().any(|error| error.line == line) {
+            continue;
+        }
+
+        let coords = bracket
+            .captures(&line)
+            .or_else(|| named.captures(&line))
+            .and_then(|caps| Some((caps[1].parse().ok()?, caps[2].parse().ok()?)));
+        errors.push(ChunkLoadError {
+            chunk_x: coords.map(|(x, _)| x),
+            chunk_z: coords.map(|(_, z)| z),
+            line,
+        });
+        if errors.len() >= MAX_CHUNK_ERRORS {
+            break;
+        }
+    }
+    errors
+}
+
+fn latest_crash_report(server_path: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(server_path.join("crash-reports"))
+        .ok()?
+        .flatten()
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|name| name.starts_with("crash-") && name.ends_with(".txt"))
+        })
+        .filter_map(|entry| {
+            let modified = entry.metadata().and_then(|meta| meta.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_chunk_errors_extracts_coordinates() {
+        let lines = vec![
+            "[12:00:01] [Server thread/INFO]: Preparing spawn area".to_string(),
+            "[12:00:02] [Server thread/ERROR]: Couldn't load chunk [12, -7]".to_string(),
+            "[12:00:02] [Server thread/ERROR]: Couldn't load chunk [12, -7]".to_string(),
+            "[12:00:03] [Worker/ERROR]: Failed to read chunk ChunkPos{x=3, z=4}".to_string(),
+            "[12:00:04] [Worker/ERROR]: Chunk load failed".to_string(),
+        ];
+
+        let errors = find_chunk_errors(&lines);
+        assert_eq!(errors.len(), 3);
+        assert_eq!((errors[0].chunk_x, errors[0].chunk_z), (Some(12), Some(-7)));
+        assert_eq!((errors[1].chunk_x, errors[1].chunk_z), (Some(3), Some(4)));
+        assert_eq!(errors[2].chunk_x, None);
+    }
+}
@@ -0,0 +1,469 @@
+//! 世界数据包管理（world/datapacks）。
+//!
+//! 数据包可以是 zip 文件或包含 pack.mcmeta 的文件夹。
+//! 服务器停止时，禁用的数据包会被移动到 datapacks_disabled 目录，启用时移回；
+//! 服务器运行时改为返回需要发送的 `datapack enable/disable` 命令，由调用方通过控制台下发。
+
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::models::world::DatapackInfo;
+use crate::services::world_pruner;
+use crate::utils::mc_version;
+use crate::utils::nbt::{self, NbtTag};
+
+const DATAPACKS_DIR: &str = "datapacks";
+/// 离线禁用的数据包存放目录，服务器不会扫描该目录
+const DISABLED_DIR: &str = "datapacks_disabled";
+
+/// 各版本起始的数据包格式，按版本从新到旧排列
+const PACK_FORMATS: &[((u32, u32, u32), i64)] = &[
+    ((1, 21, 7), 81),
+    ((1, 21, 6), 80),
+    ((1, 21, 5), 71),
+    ((1, 21, 4), 61),
+    ((1, 21, 2), 57),
+    ((1, 21, 0), 48),
+    ((1, 20, 5), 41),
+    ((1, 20, 3), 26),
+    ((1, 20, 2), 18),
+    ((1, 20, 0), 15),
+    ((1, 19, 4), 12),
+    ((1, 19, 0), 10),
+    ((1, 18, 2), 9),
+    ((1, 18, 0), 8),
+    ((1, 17, 0), 7),
+    ((1, 16, 2), 6),
+    ((1, 15, 0), 5),
+    ((1, 13, 0), 4),
+];
+/// 表中已知的最新版本，更新的版本改用了新的格式编号规则，不做判断
+const LATEST_KNOWN_VERSION: (u32, u32, u32) = (1, 21, 8);
+
+/// pack.mcmeta 中的关键信息
+struct PackMeta {
+    pack_format: Option<i64>,
+    /// 声明支持的格式范围（含两端）
+    supported: Option<(i64, i64)>,
+    description: String,
+}
+
+/// 服务器版本对应的数据包格式，快照或未知版本返回 None
+pub fn expected_pack_format(mc_version: &str) -> Option<i64> {
+    let version = mc_version::parse(mc_version)?;
+    if version > LATEST_KNOWN_VERSION {
+        return None;
+    }
+    PACK_FORMATS
+        .iter()
+        .find(|(since, _)| version >= *since)
+        .map(|(_, format)| *format)
+}
+
+pub fn list_datapacks(
+    server_path: &Path,
+    world_name: Option<&str>,
+    mc_version: &str,
+) -> Result<Vec<DatapackInfo>, String> {
+    let world_dir = world_pruner::resolve_world_dir(server_path, world_name)?;
+    let level_disabled = read_level_disabled_packs(&world_dir);
+    let expected = expected_pack_format(mc_version);
+
+    let mut packs = Vec::new();
+    for (dir_name, in_datapacks) in [(DATAPACKS_DIR, true), (DISABLED_DIR, false)] {
+        let Ok(entries) = std::fs::read_dir(world_dir.join(dir_name)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let is_zip = path.is_file() && file_name.to_lowercase().ends_with(".zip");
+            if !is_zip && !path.is_dir() {
+                continue;
+            }
+            let pack_id = format!("file/{}", file_name);
+            let enabled = in_datapacks && !level_disabled.contains(&pack_id);
+            packs
+                .push(build_info(&path, file_name, pack_id, is_zip, enabled, mc_version, expected));
+        }
+    }
+
+    packs.sort_by_key(|pack| pack.file_name.to_lowercase());
+    Ok(packs)
+}
+
+fn build_info(
+    path: &Path,
+    file_name: String,
+    pack_id: String,
+    is_zip: bool,
+    enabled: bool,
+    mc_version: &str,
+    expected: Option<i64>,
+) -> DatapackInfo {
+    let file_size = if is_zip {
+        path.metadata().map(|m| m.len()).unwrap_or(0)
+    } else {
+        dir_size(path)
+    };
+
+    let (meta, warning) = match read_pack_meta(path, is_zip) {
+        Ok(meta) => {
+            let warning = expected.and_then(|expected| {
+                let (min, max) = meta.supported.or(meta.pack_format.map(|f| (f, f)))?;
+                if (min..=max).contains(&expected) {
+                    None
+                } else {
+                    Some(format!(
+                        "数据包格式 {} 与服务器版本 {}（格式 {}）不匹配，可能无法正常加载",
+                        format_range(min, max),
+                        mc_version,
+                        expected
+                    ))
+                }
+            });
+            (Some(meta), warning)
+        }
+        Err(e) => (None, Some(e)),
+    };
+
+    DatapackInfo {
+        file_name,
+        pack_id,
+        is_zip,
+        enabled,
+        pack_format: meta.as_ref().and_then(|meta| meta.pack_format),
+        description: meta.map(|meta| meta.description).unwrap_or_default(),
+        file_size,
+        expected_pack_format: expected,
+        warning,
+    }
+}
+
+fn format_range(min: i64, max: i64) -> String {
+    if min == max {
+        min.to_string()
+    } else {
+        format!("{}-{}", min, max)
+    }
+}
+
+fn read_pack_meta(path: &Path, is_zip: bool) -> Result<PackMeta, String> {
+    let content = if is_zip {
+        let file = std::fs::File::open(path).map_err(|e| format!("打开数据包失败: {}", e))?;
+        let mut archive =
+            zip::ZipArchive::new(file).map_err(|e| format!("数据包不是有效的 zip 文件: {}", e))?;
+        let mut entry = archive
+            .by_name("pack.mcmeta")
+            .map_err(|_| "缺少 pack.mcmeta，服务器不会加载该数据包".to_string())?;
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .map_err(|e| format!("读取 pack.mcmeta 失败: {}", e))?;
+        content
+    } else {
+        std::fs::read_to_string(path.join("pack.mcmeta"))
+            .map_err(|_| "缺少 pack.mcmeta，服务器不会加载该数据包".to_string())?
+    };
+
+    parse_pack_meta(&content)
+}
+
+fn parse_pack_meta(content: &str) -> Result<PackMeta, String> {
+    // 部分数据包带有 UTF-8 BOM
+    let value: Value = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("pack.mcmeta 格式错误: {}", e))?;
+    let pack = value
+        .get("pack")
+        .ok_or_else(|| "pack.mcmeta 缺少 pack 字段".to_string())?;
+
+    let pack_format = pack.get("pack_format").and_then(Value::as_i64);
+    let supported = pack
+        .get("supported_formats")
+        .and_then(parse_format_range)
+        .or_else(|| {
+            // 1.21.9 起改用 min_format / max_format，值可以是整数或 [主版本, 次版本]
+            let min = pack.get("min_format").and_then(format_major)?;
+            let max = pack.get("max_format").and_then(format_major)?;
+            Some((min, max))
+        });
+
+    Ok(PackMeta {
+        pack_format: pack_format.or(supported.map(|(min, _)| min)),
+        supported,
+        description: pack
+            .get("description")
+            .map(flatten_text_component)
+            .unwrap_or_default(),
+    })
+}
+
+fn format_major(value: &Value) -> Option<i64> {
+    value
+        .as_i64()
+        .or_else(|| value.as_array()?.first()?.as_i64())
+}
+
+/// supported_formats 支持整数、[最小, 最大] 与 { min_inclusive, max_inclusive } 三种写法
+fn parse_format_range(value: &Value) -> Option<(i64, i64)> {
+    if let Some(format) = value.as_i64() {
+        return Some((format, format));
+    }
+    if let Some([min, max]) = value.as_array().map(Vec::as_slice) {
+        return Some((min.as_i64()?, max.as_i64()?));
+    }
+    Some((value.get("min_inclusive")?.as_i64()?, value.get("max_inclusive")?.as_i64()?))
+}
+
+/// 把 JSON 文本组件展开为纯文本
+fn flatten_text_component(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(flatten_text_component).collect(),
+        Value::Object(map) => {
+            let mut text = map
+                .get("text")
+                .or_else(|| map.get("translate"))
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            if let Some(extra) = map.get("extra") {
+                text.push_str(&flatten_text_component(extra));
+            }
+            text
+        }
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Null => String::new(),
+    }
+}
+
+/// level.dat 中 Data.DataPacks.Disabled 记录的数据包 ID
+fn read_level_disabled_packs(world_dir: &Path) -> Vec<String> {
+    std::fs::read(world_dir.join("level.dat"))
+        .ok()
+        .and_then(|bytes| nbt::parse_gzip(&bytes).ok())
+        .and_then(|(_, root)| {
+            let list = root
+                .get_path(&["Data", "DataPacks", "Disabled"])?
+                .as_list()?;
+            Some(
+                list.iter()
+                    .filter_map(NbtTag::as_str)
+                    .map(str::to_string)
+                    .collect(),
+            )
+        })
+        .unwrap_or_default()
+}
+
+fn validate_pack_name(file_name: &str) -> Result<(), String> {
+    let mut components = Path::new(file_name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(()),
+        _ => Err(format!("数据包名称无效: {}", file_name)),
+    }
+}
+
+/// 定位数据包，返回 (所在路径, 是否位于 datapacks 目录)
+fn locate_pack(world_dir: &Path, file_name: &str) -> Result<(PathBuf, bool), String> {
+    validate_pack_name(file_name)?;
+    let active = world_dir.join(DATAPACKS_DIR).join(file_name);
+    if active.exists() {
+        return Ok((active, true));
+    }
+    let disabled = world_dir.join(DISABLED_DIR).join(file_name);
+    if disabled.exists() {
+        return Ok((disabled, false));
+    }
+    Err(format!("未找到数据包: {}", file_name))
+}
+
+/// 生成控制台使用的数据包 ID 参数（带引号）
+fn quoted_pack_id(file_name: &str) -> String {
+    let escaped = format!("file/{}", file_name)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"");
+    format!("\"{}\"", escaped)
+}
+
+/// 从本地 zip 文件或文件夹安装数据包，同名数据包会被覆盖
+pub fn install_datapack(
+    server_path: &Path,
+    world_name: Option<&str>,
+    source_path: &str,
+    mc_version: &str,
+) -> Result<DatapackInfo, String> {
+    let world_dir = world_pruner::resolve_world_dir(server_path, world_name)?;
+    let source = Path::new(source_path);
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .ok_or_else(|| format!("数据包路径无效: {}", source_path))?;
+    validate_pack_name(&file_name)?;
+
+    let is_zip = source.is_file() && file_name.to_lowercase().ends_with(".zip");
+    if !is_zip && !source.is_dir() {
+        return Err("数据包必须是 zip 文件或包含 pack.mcmeta 的文件夹".to_string());
+    }
+    read_pack_meta(source, is_zip)?;
+
+    let datapacks_dir = world_dir.join(DATAPACKS_DIR);
+    std::fs::create_dir_all(&datapacks_dir).map_err(|e| format!("创建数据包目录失败: {}", e))?;
+    let target = datapacks_dir.join(&file_name);
+    let stale = world_dir.join(DISABLED_DIR).join(&file_name);
+    for existing in [&target, &stale] {
+        if existing.is_dir() {
+            std::fs::remove_dir_all(existing).map_err(|e| format!("移除旧数据包失败: {}", e))?;
+        } else if existing.is_file() {
+            std::fs::remove_file(existing).map_err(|e| format!("移除旧数据包失败: {}", e))?;
+        }
+    }
+
+    if is_zip {
+        std::fs::copy(source, &target).map_err(|e| format!("复制数据包失败: {}", e))?;
+    } else {
+        copy_dir_recursive(source, &target).map_err(|e| format!("复制数据包失败: {}", e))?;
+    }
+
+    let expected = expected_pack_format(mc_version);
+    let pack_id = format!("file/{}", file_name);
+    Ok(build_info(&target, file_name, pack_id, is_zip, true, mc_version, expected))
+}
+
+/// 启用或禁用数据包，返回服务器运行时需要依次发送的控制台命令
+pub fn set_datapack_enabled(
+    server_path: &Path,
+    world_name: Option<&str>,
+    file_name: &str,
+    enabled: bool,
+    server_running: bool,
+) -> Result<Vec<String>, String> {
+    let world_dir = world_pruner::resolve_world_dir(server_path, world_name)?;
+    let (path, in_datapacks) = locate_pack(&world_dir, file_name)?;
+    let pack_id = quoted_pack_id(file_name);
+
+    if enabled {
+        if !in_datapacks {
+            move_pack(&path, &world_dir.join(DATAPACKS_DIR).join(file_name))?;
+        }
+        if server_running {
+            // datapack list 会刷新可用数据包列表，使新移入的数据包能被找到
+            return Ok(vec![
+                "datapack list available".to_string(),
+                format!("datapack enable {}", pack_id),
+            ]);
+        }
+        if read_level_disabled_packs(&world_dir).contains(&format!("file/{}", file_name)) {
+            return Err("该数据包已在存档中被禁用，请在服务器运行时启用".to_string());
+        }
+        return Ok(Vec::new());
+    }
+
+    if server_running {
+        // 运行中的服务器可能仍持有数据包文件，只下发命令，由服务器记录到存档
+        return Ok(if in_datapacks {
+            vec![format!("datapack disable {}", pack_id)]
+        } else {
+            Vec::new()
+        });
+    }
+    if in_datapacks {
+        move_pack(&path, &world_dir.join(DISABLED_DIR).join(file_name))?;
+    }
+    Ok(Vec::new())
+}
+
+/// 删除数据包（移入回收站）。服务器运行时需先发送禁用命令
+pub fn remove_datapack(
+    server_path: &Path,
+    world_name: Option<&str>,
+    file_name: &str,
+) -> Result<(), String> {
+    let world_dir = world_pruner::resolve_world_dir(server_path, world_name)?;
+    let (path, _) = locate_pack(&world_dir, file_name)?;
+    trash::delete(&path).map_err(|e| format!("删除数据包失败: {}", e))
+}
+
+/// 删除前需要在运行中的服务器上执行的命令
+pub fn disable_command(file_name: &str) -> String {
+    format!("datapack disable {}", quoted_pack_id(file_name))
+}
+
+fn move_pack(from: &Path, to: &Path) -> Result<(), String> {
+    if to.exists() {
+        return Err(format!("目标位置已存在同名数据包: {}", to.display()));
+    }
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| format!("创建数据包目录失败: {}", e))?;
+    }
+    std::fs::rename(from, to).map_err(|e| format!("移动数据包失败: {}", e))
+}
+
+fn dir_size(path: &Path) -> u64 {
+    std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| {
+                    let path = entry.path();
+                    if path.is_dir() {
+                        dir_size(&path)
+                    } else {
+                        entry.metadata().map(|m| m.len()).unwrap_or(0)
+                    }
+                })
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+fn copy_dir_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dst)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let src_path = entry.path();
+        let dst_path = dst.join(entry.file_name());
+        if src_path.is_dir() {
+            copy_dir_recursive(&src_path, &dst_path)?;
+        } else {
+            std::fs::copy(&src_path, &dst_path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_pack_format_follows_version_table() {
+        assert_eq!(expected_pack_format("1.20.1"), Some(15));
+        assert_eq!(expected_pack_format("1.20.4"), Some(26));
+        assert_eq!(expected_pack_format("1.21"), Some(48));
+        assert_eq!(expected_pack_format("1.16.5"), Some(6));
+        assert_eq!(expected_pack_format("1.12.2"), None);
+        assert_eq!(expected_pack_format("24w14a"), None);
+    }
+
+    #[test]
+    fn parse_pack_meta_reads_ranges_and_text_components() {
+        let meta = parse_pack_meta(
+            r#"{"pack":{"pack_format":15,"supported_formats":{"min_inclusive":15,"max_inclusive":26},
+                "description":[{"text":"Hello "},{"text":"World","color":"gold"}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(meta.pack_format, Some(15));
+        assert_eq!(meta.supported, Some((15, 26)));
+        assert_eq!(meta.description, "Hello World");
+
+        let meta =
+            parse_pack_meta(r#"{"pack":{"min_format":[88,0],"max_format":90,"description":"x"}}"#)
+                .unwrap();
+        assert_eq!(meta.supported, Some((88, 90)));
+        assert!(parse_pack_meta(r#"{"other":{}}"#).is_err());
+    }
+}
@@ -1,152 +1,239 @@
 use crate::models::download::{TaskProgressResponse, TaskStatus};
-use crate::utils::downloader::MultiThreadDownloader;
+use crate::utils::downloader::{Checkpoint, DownloadStatus, MultiThreadDownloader};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
+/// 未完成任务的保存目录，位于应用数据目录下
+const TASKS_DIR: &str = "download_tasks";
+
 pub struct DownloadManager {
     // 使用 RwLock 保证多线程下对任务 Map 的读写安全
     tasks: Arc<RwLock<HashMap<Uuid, Arc<DownloadTaskState>>>>,
     downloader: Arc<MultiThreadDownloader>,
+    state_dir: PathBuf,
+}
+
+/// 保存到磁盘的任务信息，任务完成、出错或取消后删除
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TaskRecord {
+    url: String,
+    file_path: String,
+    thread_count: usize,
+    #[serde(default)]
+    expected_hash: Option<String>,
+    /// 由后台流程创建并等待结果的任务，不能暂停；重启后不恢复，直接删除临时文件
+    #[serde(default)]
+    internal: bool,
+    /// 最近一次写入文件的进度，尚未开始下载时为空
+    #[serde(default)]
+    checkpoint: Option<Checkpoint>,
 }
 
 struct DownloadTaskState {
-    _url: String,
-    _file_path: String,
-    status_handle: tokio::sync::Mutex<Option<Arc<crate::utils::downloader::DownloadStatus>>>,
+    record: tokio::sync::Mutex<TaskRecord>,
+    status_handle: tokio::sync::Mutex<Option<Arc<DownloadStatus>>>,
     internal_status: RwLock<TaskStatus>,
 }
 
 impl DownloadManager {
     pub fn new() -> Self {
+        Self::with_state_dir(crate::utils::path::get_app_data_dir().join(TASKS_DIR))
+    }
+
+    /// 使用指定的任务目录，并恢复上次退出时未完成的任务（处于暂停状态）。
+    /// 内部任务已经没有等待结果的流程，只清理其临时文件
+    pub fn with_state_dir(state_dir: PathBuf) -> Self {
+        let mut tasks = HashMap::new();
+        if let Ok(entries) = std::fs::read_dir(&state_dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let id = path
+                    .file_stem()
+                    .and_then(|stem| Uuid::parse_str(&stem.to_string_lossy()).ok());
+                let record = std::fs::read_to_string(&path)
+                    .ok()
+                    .and_then(|content| serde_json::from_str::<TaskRecord>(&content).ok());
+                match (id, record) {
+                    (Some(id), Some(record)) if record.internal => {
+                        let _ = std::fs::remove_file(&record.file_path);
+                        remove_record(&state_dir, id);
+                    }
+                    (Some(id), Some(record)) => {
+                        tasks.insert(
+                            id,
+                            Arc::new(DownloadTaskState::new(record, TaskStatus::Paused)),
+                        );
+                    }
+                    _ => {}
+                }
+            }
+        }
+
         Self {
-            tasks: Arc::new(RwLock::new(HashMap::new())),
+            tasks: Arc::new(RwLock::new(tasks)),
             downloader: Arc::new(MultiThreadDownloader::new(
                 crate::utils::downloader::USER_AGENT_EXAMPLE,
             )),
+            state_dir,
         }
     }
 
     /// 创建下载任务
     pub async fn create_task(&self, url: &str, path: &str, thread_count: usize) -> Uuid {
+        self.create_task_with_hash(url, path, thread_count, None)
+            .await
+    }
+
+    /// 创建下载任务，下载完成后校验文件摘要，格式见 [`crate::utils::hash::verify_file`]
+    pub async fn create_task_with_hash(
+        &self,
+        url: &str,
+        path: &str,
+        thread_count: usize,
+        expected_hash: Option<String>,
+    ) -> Uuid {
+        self.insert_task(url, path, thread_count, expected_hash, false)
+            .await
+    }
+
+    /// 创建由调用方用 [`Self::wait_for_task`] 等待的下载任务，不能暂停，摘要格式同上
+    pub async fn create_internal_task(
+        &self,
+        url: &str,
+        path: &str,
+        thread_count: usize,
+        expected_hash: Option<String>,
+    ) -> Uuid {
+        self.insert_task(url, path, thread_count, expected_hash, true)
+            .await
+    }
+
+    async fn insert_task(
+        &self,
+        url: &str,
+        path: &str,
+        thread_count: usize,
+        expected_hash: Option<String>,
+        internal: bool,
+    ) -> Uuid {
         let id = Uuid::new_v4();
-        let state = Arc::new(DownloadTaskState {
-            _url: url.to_string(),
-            _file_path: path.to_string(),
-            status_handle: tokio::sync::Mutex::new(None),
-            internal_status: RwLock::new(TaskStatus::Pending),
-        });
+        let record = TaskRecord {
+            url: url.to_string(),
+            file_path: path.to_string(),
+            thread_count,
+            expected_hash,
+            internal,
+            checkpoint: None,
+        };
+        save_record(&self.state_dir, id, &record);
+        let state = Arc::new(DownloadTaskState::new(record, TaskStatus::Pending));
 
         // 将任务存入管理 Map
         self.tasks.write().await.insert(id, state.clone());
+        self.spawn(id, state);
 
-        let downloader = self.downloader.clone();
-        let url_str = url.to_string();
-        let path_str = path.to_string();
-
-        // 这里的 state 是 Arc，move 进来后可以在后台线程持续更新该任务的具体状态
-        tokio::spawn(async move {
-            match downloader.download(&url_str, &path_str, thread_count).await {
-                Ok(handle) => {
-                    // 1. 关联下载句柄
-                    {
-                        let mut h = state.status_handle.lock().await;
-                        *h = Some(handle);
-                        let mut s = state.internal_status.write().await;
-                        *s = TaskStatus::Downloading;
-                    }
+        id
+    }
 
-                    // 2. 轮询检测是否完成（utils 层的下载器完成后，handle.snapshot().is_finished 会变为 true）
-                    loop {
-                        // 1. 在一个独立的作用域内获取状态句柄，确保锁能及时释放
-                        let status_handle_opt = {
-                            let h = state.status_handle.lock().await;
-                            h.as_ref().cloned() // 克隆 Arc 指针，增加引用计数，释放互斥锁
-                        };
-
-                        let mut is_done = false;
-
-                        // 2. 如果句柄存在，再进行异步 snapshot 调用
-                        if let Some(status_handle) = status_handle_opt {
-                            let snap = status_handle.snapshot().await;
-
-                            // 如果出错，也标记为结束，并更新内部状态
-                            if let Some(err_msg) = snap.error {
-                                let mut s = state.internal_status.write().await;
-                                *s = TaskStatus::Error(err_msg);
-                                break;
-                            }
-
-                            if snap.is_finished {
-                                is_done = true;
-                            }
-                        }
-
-                        if is_done {
-                            let mut s = state.internal_status.write().await;
-                            // 只有在没报错的情况下才标记为 Completed
-                            if let TaskStatus::Downloading = *s {
-                                *s = TaskStatus::Completed;
-                            }
-                            break;
-                        }
-
-                        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                    }
-                }
-                Err(e) => {
-                    let mut s = state.internal_status.write().await;
-                    *s = TaskStatus::Error(e);
-                }
-            }
-        });
+    /// 在后台执行下载，state 是 Arc，可以在后台线程持续更新该任务的具体状态
+    fn spawn(&self, id: Uuid, state: Arc<DownloadTaskState>) {
+        let downloader = self.downloader.clone();
+        let state_dir = self.state_dir.clone();
+        tokio::spawn(async move { run_task(downloader, state_dir, id, state).await });
+    }
 
-        id
+    /// 暂停任务，返回时已收到的数据都已写入文件
+    pub async fn pause_task(&self, id: Uuid) -> Result<(), String> {
+        let state = self.state(id).await?;
+        if state.record.lock().await.internal {
+            return Err("该任务由后台流程使用，不能暂停".to_string());
+        }
+        if !state.is_running().await {
+            return Err("任务不在下载中".to_string());
+        }
+        state.stop().await;
+        Ok(())
     }
 
-    /// 查询进度并尝试清理
-    pub async fn get_progress(&self, id: Uuid) -> Option<TaskProgressResponse> {
-        let tasks = self.tasks.read().await;
-        let state = tasks.get(&id)?;
-
-        let mut status = state.internal_status.read().await.clone();
-        let mut progress = 0.0;
-        let mut is_finished = false;
-        let mut total_size: u64 = 0;
-        let mut downloaded: u64 = 0;
-
-        // 如果在下载中，从 utils 的 Atomic 变量取实时数据
-        if let Some(handle) = &*state.status_handle.lock().await {
-            let snap = handle.snapshot().await;
-            progress = snap.progress_percentage;
-            total_size = snap.total_size;
-            downloaded = snap.downloaded;
-            if snap.is_finished {
-                status = TaskStatus::Completed;
-                is_finished = true;
+    /// 继续已暂停或出错的任务，从上次写入的位置续传
+    pub async fn resume_task(&self, id: Uuid) -> Result<(), String> {
+        let state = self.state(id).await?;
+        {
+            let mut status = state.internal_status.write().await;
+            if !matches!(*status, TaskStatus::Paused | TaskStatus::Error(_)) {
+                return Err("只能继续已暂停或失败的任务".to_string());
             }
+            *status = TaskStatus::Pending;
         }
+        let record = state.record.lock().await.clone();
+        save_record(&self.state_dir, id, &record);
+        self.spawn(id, state);
+        Ok(())
+    }
 
-        if matches!(status, TaskStatus::Completed | TaskStatus::Error(_)) {
-            is_finished = true;
+    /// 取消任务并删除未下载完的文件
+    pub async fn cancel_task(&self, id: Uuid) -> Result<(), String> {
+        let state = self.state(id).await?;
+        state.stop().await;
+        {
+            let mut status = state.internal_status.write().await;
+            if matches!(*status, TaskStatus::Completed) {
+                return Err("任务已完成".to_string());
+            }
+            *status = TaskStatus::Cancelled;
         }
+        remove_record(&self.state_dir, id);
+        let file_path = state.record.lock().await.file_path.clone();
+        let _ = tokio::fs::remove_file(file_path).await;
+        Ok(())
+    }
 
-        let resp = TaskProgressResponse {
-            id,
-            total_size,
-            downloaded,
-            progress,
-            status,
-            is_finished,
-        };
+    async fn state(&self, id: Uuid) -> Result<Arc<DownloadTaskState>, String> {
+        self.tasks
+            .read()
+            .await
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| "Task not found".to_string())
+    }
 
-        Some(resp)
+    /// 查询进度并尝试清理
+    pub async fn get_progress(&self, id: Uuid) -> Option<TaskProgressResponse> {
+        let state = self.tasks.read().await.get(&id)?.clone();
+        Some(state.progress(id).await)
     }
 
-    /// 显式清理任务
+    /// 等待任务结束，下载出错时返回错误信息。任务在等待期间被清理不影响结果；
+    /// 暂停的任务会一直等到继续，暂停期间被移除则不会再继续，返回错误
+    pub async fn wait_for_task(&self, id: Uuid) -> Result<(), String> {
+        let state = self.state(id).await?;
+
+        loop {
+            match &*state.internal_status.read().await {
+                TaskStatus::Completed => return Ok(()),
+                TaskStatus::Error(e) => return Err(e.clone()),
+                TaskStatus::Cancelled => return Err("下载已取消".to_string()),
+                TaskStatus::Paused if !self.tasks.read().await.contains_key(&id) => {
+                    return Err("下载已暂停且任务已被移除".to_string())
+                }
+                TaskStatus::Pending | TaskStatus::Downloading | TaskStatus::Paused => {}
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+        }
+    }
+
+    /// 显式清理任务；已暂停的任务同时删除保存的进度，重启后不再恢复
     pub async fn remove_task(&self, id: Uuid) {
-        self.tasks.write().await.remove(&id);
+        let removed = self.tasks.write().await.remove(&id);
+        if let Some(state) = removed {
+            if matches!(*state.internal_status.read().await, TaskStatus::Paused) {
+                remove_record(&self.state_dir, id);
+            }
+        }
     }
 
     /// 批量获取所有任务进度，并清理已完成的任务
@@ -157,34 +244,11 @@ impl DownloadManager {
         // 1. 读取所有任务状态
         let tasks = self.tasks.read().await;
         for (id, state) in tasks.iter() {
-            let mut status = state.internal_status.read().await.clone();
-            let mut progress = 0.0;
-            let mut total_size: u64 = 0;
-            let mut downloaded: u64 = 0;
-
-            if let Some(handle) = &*state.status_handle.lock().await {
-                let snap = handle.snapshot().await;
-                progress = snap.progress_percentage;
-                total_size = snap.total_size;
-                downloaded = snap.downloaded;
-                if snap.is_finished {
-                    status = TaskStatus::Completed;
-                }
-            }
-
-            let is_finished = matches!(status, TaskStatus::Completed | TaskStatus::Error(_));
-            results.push(TaskProgressResponse {
-                id: *id,
-                total_size,
-                downloaded,
-                progress,
-                status,
-                is_finished,
-            });
-
-            if is_finished {
+            let resp = state.progress(*id).await;
+            if resp.is_finished {
                 to_remove.push(*id);
             }
+            results.push(resp);
         }
         drop(tasks); // 释放读锁
 
@@ -211,6 +275,156 @@ impl DownloadManager {
     }
 }
 
+impl DownloadTaskState {
+    fn new(record: TaskRecord, status: TaskStatus) -> Self {
+        Self {
+            record: tokio::sync::Mutex::new(record),
+            status_handle: tokio::sync::Mutex::new(None),
+            internal_status: RwLock::new(status),
+        }
+    }
+
+    async fn is_running(&self) -> bool {
+        matches!(
+            *self.internal_status.read().await,
+            TaskStatus::Pending | TaskStatus::Downloading
+        )
+    }
+
+    /// 请求停止并等待后台任务退出
+    async fn stop(&self) {
+        while self.is_running().await {
+            if let Some(handle) = &*self.status_handle.lock().await {
+                handle.pause();
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        }
+    }
+
+    async fn progress(&self, id: Uuid) -> TaskProgressResponse {
+        let status = self.internal_status.read().await.clone();
+        // 下载中从 utils 的 Atomic 变量取实时数据，其余情况使用保存的进度
+        let (downloaded, total_size) = match &*self.status_handle.lock().await {
+            Some(handle) => {
+                let snap = handle.snapshot().await;
+                (snap.downloaded, snap.total_size)
+            }
+            None => self
+                .record
+                .lock()
+                .await
+                .checkpoint
+                .as_ref()
+                .map_or((0, 0), |checkpoint| (checkpoint.downloaded(), checkpoint.total_size)),
+        };
+        let progress = if total_size > 0 {
+            (downloaded as f64 / total_size as f64) * 100.0
+        } else {
+            0.0
+        };
+        // 完成状态只由后台任务在文件写入并校验后设置
+        let is_finished =
+            matches!(status, TaskStatus::Completed | TaskStatus::Cancelled | TaskStatus::Error(_));
+
+        TaskProgressResponse {
+            id,
+            total_size,
+            downloaded,
+            progress,
+            status,
+            is_finished,
+        }
+    }
+}
+
+/// 执行下载并跟踪状态，下载过程中定期保存进度
+async fn run_task(
+    downloader: Arc<MultiThreadDownloader>,
+    state_dir: PathBuf,
+    id: Uuid,
+    state: Arc<DownloadTaskState>,
+) {
+    let record = state.record.lock().await.clone();
+    let handle = match downloader
+        .start(
+            &record.url,
+            &record.file_path,
+            record.thread_count,
+            record.checkpoint.clone(),
+            record.expected_hash.clone(),
+        )
+        .await
+    {
+        Ok(handle) => handle,
+        Err(e) => {
+            *state.internal_status.write().await = TaskStatus::Error(e);
+            remove_record(&state_dir, id);
+            return;
+        }
+    };
+
+    // 1. 关联下载句柄
+    {
+        let mut h = state.status_handle.lock().await;
+        *h = Some(handle.clone());
+        let mut s = state.internal_status.write().await;
+        *s = TaskStatus::Downloading;
+    }
+
+    // 2. 轮询直到下载器报告结束或暂停，进度变化时写入磁盘
+    loop {
+        let snap = handle.snapshot().await;
+        let next = if let Some(err_msg) = snap.error {
+            Some(TaskStatus::Error(err_msg))
+        } else if snap.is_finished {
+            Some(TaskStatus::Completed)
+        } else if snap.is_paused {
+            Some(TaskStatus::Paused)
+        } else {
+            None
+        };
+
+        {
+            let mut record = state.record.lock().await;
+            let checkpoint = handle.checkpoint();
+            let changed = record.checkpoint.as_ref() != Some(&checkpoint);
+            record.checkpoint = Some(checkpoint);
+            match next {
+                None if changed => save_record(&state_dir, id, &record),
+                None => {}
+                Some(TaskStatus::Paused) => save_record(&state_dir, id, &record),
+                Some(_) => remove_record(&state_dir, id),
+            }
+        }
+
+        if let Some(next) = next {
+            *state.status_handle.lock().await = None;
+            *state.internal_status.write().await = next;
+            break;
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    }
+}
+
+fn record_path(state_dir: &Path, id: Uuid) -> PathBuf {
+    state_dir.join(format!("{}.json", id))
+}
+
+fn save_record(state_dir: &Path, id: Uuid, record: &TaskRecord) {
+    let result = std::fs::create_dir_all(state_dir).and_then(|_| {
+        let content = serde_json::to_vec_pretty(record).map_err(std::io::Error::other)?;
+        std::fs::write(record_path(state_dir, id), content)
+    });
+    if let Err(e) = result {
+        eprintln!("[WARN] Failed to save download task {}: {}", id, e);
+    }
+}
+
+fn remove_record(state_dir: &Path, id: Uuid) {
+    let _ = std::fs::remove_file(record_path(state_dir, id));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -219,27 +433,30 @@ mod tests {
 
     #[tokio::test]
     async fn test_download_manager() {
-        let manager = DownloadManager::new();
+        use crate::utils::test_http;
+
+        let dir = std::env::temp_dir().join(format!("sl_download_manager_{}", Uuid::new_v4()));
+        let body = (0..300 * 1024).map(|i| (i % 89) as u8).collect::<Vec<_>>();
+        let server_body = body.clone();
+        let (base, _) =
+            test_http::serve(move |request| test_http::file(&server_body, request, true));
+        let manager = DownloadManager::with_state_dir(dir.clone());
 
-        let url = "https://files.mcjars.app/mohist/1.12.2/1.12.2-17e3fd09/server.jar";
-        let save_path = "test_manager_output.txt";
+        let url = format!("{}/server.jar", base);
+        let save_path = dir.join("test_manager_output.bin");
+        std::fs::create_dir_all(&dir).unwrap();
 
-        let task_id = manager.create_task(url, save_path, 32).await;
-        println!("任务已创建, ID: {}", task_id);
+        let task_id = manager
+            .create_task(&url, &save_path.to_string_lossy(), 4)
+            .await;
 
         let mut completed = false;
         let mut timeout_counter = 0;
 
         while timeout_counter < 30 {
             if let Some(resp) = manager.get_progress(task_id).await {
-                println!(
-                    "进度: {:.2}% | 状态: {:?} | 是否完成: {} | 已下载：{} | 总大小：{}",
-                    resp.progress, resp.status, resp.is_finished, resp.downloaded, resp.total_size
-                );
-
                 if resp.is_finished {
                     if let TaskStatus::Completed = resp.status {
-                        println!("测试通过：文件下载完成！");
                         completed = true;
                     } else if let TaskStatus::Error(e) = resp.status {
                         panic!("测试失败：下载过程中出现错误: {}", e);
@@ -250,20 +467,134 @@ mod tests {
                 panic!("测试失败：无法获取任务状态");
             }
 
-            tokio::time::sleep(Duration::from_millis(500)).await;
+            tokio::time::sleep(Duration::from_millis(200)).await;
             timeout_counter += 1;
         }
 
         assert!(completed, "测试超时：任务未在规定时间内完成");
+        assert_eq!(std::fs::read(&save_path).unwrap(), body);
+        assert!(!record_path(&dir, task_id).exists());
 
         manager.remove_task(task_id).await;
         let final_check = manager.get_progress(task_id).await;
         assert!(final_check.is_none(), "测试失败：任务在清理后依然存在");
-        println!("任务已成功从管理器中移除。");
 
-        if std::path::Path::new(save_path).exists() {
-            let _ = std::fs::remove_file(save_path);
-            println!("测试残留文件已清理。");
-        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn internal_tasks_verify_hash_and_are_dropped_after_restart() {
+        use crate::utils::test_http;
+        use sha2::{Digest, Sha256};
+
+        let dir = std::env::temp_dir().join(format!("sl_internal_tasks_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let body = b"core jar".to_vec();
+        let server_body = body.clone();
+        let (base, _) =
+            test_http::serve(move |request| test_http::file(&server_body, request, true));
+        let url = format!("{}/core.jar", base);
+
+        // 上次退出时未完成的内部任务只留下临时文件与记录
+        let orphan_id = Uuid::new_v4();
+        let orphan_path = dir.join("core.jar.download");
+        std::fs::write(&orphan_path, b"partial").unwrap();
+        let orphan = TaskRecord {
+            url: url.clone(),
+            file_path: orphan_path.to_string_lossy().to_string(),
+            thread_count: 1,
+            expected_hash: None,
+            internal: true,
+            checkpoint: None,
+        };
+        save_record(&dir, orphan_id, &orphan);
+
+        let manager = DownloadManager::with_state_dir(dir.clone());
+        assert!(manager.get_progress(orphan_id).await.is_none());
+        assert!(!orphan_path.exists());
+        assert!(!record_path(&dir, orphan_id).exists());
+
+        let path = dir.join("verified.bin").to_string_lossy().to_string();
+        let sha256 = format!("sha256:{:x}", Sha256::digest(&body));
+        let task = manager
+            .create_internal_task(&url, &path, 1, Some(sha256))
+            .await;
+        manager.wait_for_task(task).await.unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert!(manager.pause_task(task).await.is_err());
+
+        let task = manager
+            .create_internal_task(&url, &path, 1, Some("md5:00".to_string()))
+            .await;
+        assert!(manager
+            .wait_for_task(task)
+            .await
+            .unwrap_err()
+            .contains("校验失败"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn restores_interrupted_tasks_after_restart() {
+        use crate::utils::downloader::Segment;
+        use crate::utils::test_http;
+        use sha2::{Digest, Sha256};
+
+        let dir = std::env::temp_dir().join(format!("sl_download_tasks_{}", Uuid::new_v4()));
+        let body = (0..100 * 1024).map(|i| (i % 97) as u8).collect::<Vec<_>>();
+        let server_body = body.clone();
+        let (base, _) =
+            test_http::serve(move |request| test_http::file(&server_body, request, true));
+        let url = format!("{}/file.bin", base);
+
+        // 模拟上次退出时写入一半的任务
+        let interrupted = |name: &str| {
+            let id = Uuid::new_v4();
+            let file_path = dir.join(name);
+            let mut partial = body.clone();
+            partial[40 * 1024..].fill(0);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(&file_path, partial).unwrap();
+            let record = TaskRecord {
+                url: url.clone(),
+                file_path: file_path.to_string_lossy().to_string(),
+                thread_count: 4,
+                expected_hash: Some(format!("{:x}", Sha256::digest(&body))),
+                internal: false,
+                checkpoint: Some(Checkpoint {
+                    total_size: body.len() as u64,
+                    segments: vec![Segment {
+                        start: 0,
+                        end: Some(body.len() as u64 - 1),
+                        downloaded: 40 * 1024,
+                    }],
+                    validator: None,
+                }),
+            };
+            save_record(&dir, id, &record);
+            (id, file_path)
+        };
+        let (resumed, resumed_path) = interrupted("resumed.bin");
+        let (cancelled, cancelled_path) = interrupted("cancelled.bin");
+
+        let manager = DownloadManager::with_state_dir(dir.clone());
+        let progress = manager.get_progress(resumed).await.unwrap();
+        assert!(matches!(progress.status, TaskStatus::Paused));
+        assert_eq!(progress.downloaded, 40 * 1024);
+        assert!(!progress.is_finished);
+
+        manager.resume_task(resumed).await.unwrap();
+        manager.wait_for_task(resumed).await.unwrap();
+        assert_eq!(std::fs::read(&resumed_path).unwrap(), body);
+        assert!(!record_path(&dir, resumed).exists());
+        assert!(manager.resume_task(resumed).await.is_err());
+
+        manager.cancel_task(cancelled).await.unwrap();
+        assert!(manager.wait_for_task(cancelled).await.is_err());
+        assert!(!cancelled_path.exists());
+        assert!(!record_path(&dir, cancelled).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
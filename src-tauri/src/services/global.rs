@@ -1,7 +1,11 @@
+use super::core_catalog::CoreCatalog;
 use super::i18n::I18nService;
+use super::java_catalog::JavaCatalog;
 use super::join_manager::JoinManager;
 use super::mcs_plugin_manager::m_PluginManager;
 use super::mod_manager::ModManager;
+use super::plugin_repository::PluginRepository;
+use super::resource_pack_host::ResourcePackHost;
 use super::server_id_manager::ServerIdManager;
 use super::server_manager::ServerManager;
 use super::settings_manager::SettingsManager;
@@ -22,12 +26,26 @@ pub fn i18n_service() -> &'static I18nService {
     INSTANCE.get_or_init(I18nService::new)
 }
 
-#[allow(dead_code)]
 pub fn mod_manager() -> &'static ModManager {
     static INSTANCE: OnceLock<ModManager> = OnceLock::new();
     INSTANCE.get_or_init(|| ModManager::new().expect("Failed to initialize ModManager"))
 }
 
+pub fn core_catalog() -> &'static CoreCatalog {
+    static INSTANCE: OnceLock<CoreCatalog> = OnceLock::new();
+    INSTANCE.get_or_init(|| CoreCatalog::new().expect("Failed to initialize CoreCatalog"))
+}
+
+pub fn java_catalog() -> &'static JavaCatalog {
+    static INSTANCE: OnceLock<JavaCatalog> = OnceLock::new();
+    INSTANCE.get_or_init(|| JavaCatalog::new().expect("Failed to initialize JavaCatalog"))
+}
+
+pub fn plugin_repository() -> &'static PluginRepository {
+    static INSTANCE: OnceLock<PluginRepository> = OnceLock::new();
+    INSTANCE.get_or_init(|| PluginRepository::new().expect("Failed to initialize PluginRepository"))
+}
+
 #[allow(dead_code)]
 pub fn join_manager() -> &'static JoinManager {
     static INSTANCE: OnceLock<JoinManager> = OnceLock::new();
@@ -44,3 +62,8 @@ pub fn m_plugin_manager() -> &'static m_PluginManager {
     static INSTANCE: OnceLock<m_PluginManager> = OnceLock::new();
     INSTANCE.get_or_init(m_PluginManager::new)
 }
+
+pub fn resource_pack_host() -> &'static ResourcePackHost {
+    static INSTANCE: OnceLock<ResourcePackHost> = OnceLock::new();
+    INSTANCE.get_or_init(ResourcePackHost::new)
+}
@@ -424,6 +424,39 @@ impl I18nService {
             "server.set_exists_failed".to_string(),
             "设置 server.exists 失败: {0}".to_string(),
         );
+        map.insert(
+            "server.create_config_table_failed".to_string(),
+            "创建 server.config 表失败: {0}".to_string(),
+        );
+        map.insert(
+            "server.create_config_read_failed".to_string(),
+            "创建 server.config.read 失败: {0}".to_string(),
+        );
+        map.insert(
+            "server.set_config_read_failed".to_string(),
+            "设置 server.config.read 失败: {0}".to_string(),
+        );
+        map.insert(
+            "server.create_config_get_failed".to_string(),
+            "创建 server.config.get 失败: {0}".to_string(),
+        );
+        map.insert(
+            "server.set_config_get_failed".to_string(),
+            "设置 server.config.get 失败: {0}".to_string(),
+        );
+        map.insert(
+            "server.create_config_set_failed".to_string(),
+            "创建 server.config.set 失败: {0}".to_string(),
+        );
+        map.insert(
+            "server.set_config_set_failed".to_string(),
+            "设置 server.config.set 失败: {0}".to_string(),
+        );
+        map.insert(
+            "server.set_config_failed".to_string(),
+            "设置 server.config 失败: {0}".to_string(),
+        );
+        map.insert("server.config_failed".to_string(), "配置文件操作失败: {0}".to_string());
         map.insert(
             "server.create_logs_table_failed".to_string(),
             "创建 server.logs 表失败: {0}".to_string(),
@@ -544,6 +577,201 @@ impl I18nService {
         );
         map.insert("system.set_system_failed".to_string(), "设置 sl.system 失败: {0}".to_string());
 
+        // server.properties 配置项说明
+        map.insert(
+            "server_properties.accepts-transfers".to_string(),
+            "允许通过转移数据包从其他服务器转入的玩家加入".to_string(),
+        );
+        map.insert(
+            "server_properties.allow-flight".to_string(),
+            "允许飞行（关闭时悬空过久的玩家会被踢出）".to_string(),
+        );
+        map.insert("server_properties.allow-nether".to_string(), "允许进入下界".to_string());
+        map.insert(
+            "server_properties.broadcast-console-to-ops".to_string(),
+            "将控制台命令输出发送给在线管理员".to_string(),
+        );
+        map.insert(
+            "server_properties.broadcast-rcon-to-ops".to_string(),
+            "将 RCON 命令输出发送给在线管理员".to_string(),
+        );
+        map.insert(
+            "server_properties.bug-report-link".to_string(),
+            "断开连接界面中显示的问题反馈链接".to_string(),
+        );
+        map.insert("server_properties.difficulty".to_string(), "游戏难度".to_string());
+        map.insert(
+            "server_properties.enable-command-block".to_string(),
+            "启用命令方块".to_string(),
+        );
+        map.insert(
+            "server_properties.enable-jmx-monitoring".to_string(),
+            "启用 JMX 性能监控".to_string(),
+        );
+        map.insert("server_properties.enable-query".to_string(), "启用 Query 协议".to_string());
+        map.insert("server_properties.enable-rcon".to_string(), "启用 RCON 远程控制".to_string());
+        map.insert(
+            "server_properties.enable-status".to_string(),
+            "在服务器列表中显示在线状态".to_string(),
+        );
+        map.insert(
+            "server_properties.enforce-secure-profile".to_string(),
+            "要求玩家使用 Mojang 签名的聊天密钥".to_string(),
+        );
+        map.insert(
+            "server_properties.enforce-whitelist".to_string(),
+            "强制白名单（重载白名单时踢出不在名单中的玩家）".to_string(),
+        );
+        map.insert(
+            "server_properties.entity-broadcast-range-percentage".to_string(),
+            "实体同步距离百分比".to_string(),
+        );
+        map.insert(
+            "server_properties.force-gamemode".to_string(),
+            "玩家加入时强制切换为默认游戏模式".to_string(),
+        );
+        map.insert(
+            "server_properties.function-permission-level".to_string(),
+            "函数的默认权限等级".to_string(),
+        );
+        map.insert("server_properties.gamemode".to_string(), "默认游戏模式".to_string());
+        map.insert(
+            "server_properties.generate-structures".to_string(),
+            "生成结构（村庄、要塞等）".to_string(),
+        );
+        map.insert(
+            "server_properties.generator-settings".to_string(),
+            "自定义世界生成设置".to_string(),
+        );
+        map.insert("server_properties.hardcore".to_string(), "极限模式".to_string());
+        map.insert(
+            "server_properties.hide-online-players".to_string(),
+            "在服务器列表中隐藏在线玩家".to_string(),
+        );
+        map.insert(
+            "server_properties.initial-disabled-packs".to_string(),
+            "创建世界时默认禁用的数据包".to_string(),
+        );
+        map.insert(
+            "server_properties.initial-enabled-packs".to_string(),
+            "创建世界时默认启用的数据包".to_string(),
+        );
+        map.insert("server_properties.level-name".to_string(), "世界名称".to_string());
+        map.insert("server_properties.level-seed".to_string(), "世界种子".to_string());
+        map.insert("server_properties.level-type".to_string(), "世界类型".to_string());
+        map.insert("server_properties.log-ips".to_string(), "在日志中记录玩家 IP".to_string());
+        map.insert("server_properties.max-build-height".to_string(), "最大建筑高度".to_string());
+        map.insert(
+            "server_properties.max-chained-neighbor-updates".to_string(),
+            "连锁方块更新上限，负数表示不限制".to_string(),
+        );
+        map.insert("server_properties.max-players".to_string(), "最大玩家数".to_string());
+        map.insert(
+            "server_properties.max-tick-time".to_string(),
+            "单个 tick 的最长时间（毫秒），超时会关闭服务器，-1 为禁用".to_string(),
+        );
+        map.insert(
+            "server_properties.max-world-size".to_string(),
+            "世界边界的最大半径".to_string(),
+        );
+        map.insert("server_properties.motd".to_string(), "服务器描述（MOTD）".to_string());
+        map.insert(
+            "server_properties.network-compression-threshold".to_string(),
+            "网络压缩阈值（字节），-1 为禁用压缩".to_string(),
+        );
+        map.insert("server_properties.online-mode".to_string(), "正版验证".to_string());
+        map.insert(
+            "server_properties.op-permission-level".to_string(),
+            "管理员的默认权限等级".to_string(),
+        );
+        map.insert(
+            "server_properties.pause-when-empty-seconds".to_string(),
+            "无玩家在线多少秒后暂停服务器，0 或负数为禁用".to_string(),
+        );
+        map.insert(
+            "server_properties.player-idle-timeout".to_string(),
+            "玩家挂机多少分钟后踢出，0 为禁用".to_string(),
+        );
+        map.insert(
+            "server_properties.prevent-proxy-connections".to_string(),
+            "阻止通过代理或 VPN 连接的玩家".to_string(),
+        );
+        map.insert("server_properties.previews-chat".to_string(), "启用聊天预览".to_string());
+        map.insert("server_properties.pvp".to_string(), "允许PVP".to_string());
+        map.insert("server_properties.query.port".to_string(), "Query 协议端口".to_string());
+        map.insert(
+            "server_properties.rate-limit".to_string(),
+            "每秒允许的最大数据包数量，0 为不限制".to_string(),
+        );
+        map.insert("server_properties.rcon.password".to_string(), "RCON 密码".to_string());
+        map.insert("server_properties.rcon.port".to_string(), "RCON 端口".to_string());
+        map.insert(
+            "server_properties.region-file-compression".to_string(),
+            "区域文件的区块压缩算法".to_string(),
+        );
+        map.insert(
+            "server_properties.require-resource-pack".to_string(),
+            "强制玩家接受服务器资源包".to_string(),
+        );
+        map.insert(
+            "server_properties.resource-pack".to_string(),
+            "服务器资源包下载地址".to_string(),
+        );
+        map.insert(
+            "server_properties.resource-pack-id".to_string(),
+            "服务器资源包 UUID".to_string(),
+        );
+        map.insert(
+            "server_properties.resource-pack-prompt".to_string(),
+            "资源包提示信息".to_string(),
+        );
+        map.insert(
+            "server_properties.resource-pack-sha1".to_string(),
+            "服务器资源包的 SHA-1 校验值".to_string(),
+        );
+        map.insert(
+            "server_properties.server-ip".to_string(),
+            "服务器绑定IP，留空表示所有".to_string(),
+        );
+        map.insert("server_properties.server-port".to_string(), "服务器端口".to_string());
+        map.insert("server_properties.simulation-distance".to_string(), "模拟距离".to_string());
+        map.insert(
+            "server_properties.snooper-enabled".to_string(),
+            "向 Mojang 发送统计数据".to_string(),
+        );
+        map.insert("server_properties.spawn-animals".to_string(), "生成动物".to_string());
+        map.insert("server_properties.spawn-monsters".to_string(), "生成怪物".to_string());
+        map.insert("server_properties.spawn-npcs".to_string(), "生成NPC".to_string());
+        map.insert("server_properties.spawn-protection".to_string(), "出生点保护半径".to_string());
+        map.insert("server_properties.sync-chunk-writes".to_string(), "同步区块写入".to_string());
+        map.insert(
+            "server_properties.text-filtering-config".to_string(),
+            "聊天文本过滤配置".to_string(),
+        );
+        map.insert(
+            "server_properties.use-native-transport".to_string(),
+            "在 Linux 上使用优化的网络传输".to_string(),
+        );
+        map.insert("server_properties.view-distance".to_string(), "视距".to_string());
+        map.insert("server_properties.white-list".to_string(), "启用白名单".to_string());
+        map.insert(
+            "server_properties.error.boolean".to_string(),
+            "必须是 true 或 false".to_string(),
+        );
+        map.insert("server_properties.error.integer".to_string(), "必须是整数".to_string());
+        map.insert(
+            "server_properties.error.range".to_string(),
+            "必须在 {min} 到 {max} 之间".to_string(),
+        );
+        map.insert(
+            "server_properties.error.enum".to_string(),
+            "必须是以下值之一: {options}".to_string(),
+        );
+        map.insert(
+            "server_properties.error.sha1".to_string(),
+            "必须为空或 40 位十六进制 SHA-1".to_string(),
+        );
+
         map
     }
 
@@ -805,6 +1033,42 @@ impl I18nService {
             "server.set_exists_failed".to_string(),
             "Failed to set server.exists: {0}".to_string(),
         );
+        map.insert(
+            "server.create_config_table_failed".to_string(),
+            "Failed to create server.config table: {0}".to_string(),
+        );
+        map.insert(
+            "server.create_config_read_failed".to_string(),
+            "Failed to create server.config.read: {0}".to_string(),
+        );
+        map.insert(
+            "server.set_config_read_failed".to_string(),
+            "Failed to set server.config.read: {0}".to_string(),
+        );
+        map.insert(
+            "server.create_config_get_failed".to_string(),
+            "Failed to create server.config.get: {0}".to_string(),
+        );
+        map.insert(
+            "server.set_config_get_failed".to_string(),
+            "Failed to set server.config.get: {0}".to_string(),
+        );
+        map.insert(
+            "server.create_config_set_failed".to_string(),
+            "Failed to create server.config.set: {0}".to_string(),
+        );
+        map.insert(
+            "server.set_config_set_failed".to_string(),
+            "Failed to set server.config.set: {0}".to_string(),
+        );
+        map.insert(
+            "server.set_config_failed".to_string(),
+            "Failed to set server.config: {0}".to_string(),
+        );
+        map.insert(
+            "server.config_failed".to_string(),
+            "Config file operation failed: {0}".to_string(),
+        );
         map.insert(
             "server.create_logs_table_failed".to_string(),
             "Failed to create server.logs table: {0}".to_string(),
@@ -970,6 +1234,230 @@ impl I18nService {
             "Failed to set sl.system: {0}".to_string(),
         );
 
+        // server.properties 配置项说明
+        map.insert(
+            "server_properties.accepts-transfers".to_string(),
+            "Accept players transferred from other servers".to_string(),
+        );
+        map.insert(
+            "server_properties.allow-flight".to_string(),
+            "Allow flight (players floating too long are kicked when disabled)".to_string(),
+        );
+        map.insert(
+            "server_properties.allow-nether".to_string(),
+            "Allow players to travel to the Nether".to_string(),
+        );
+        map.insert(
+            "server_properties.broadcast-console-to-ops".to_string(),
+            "Send console command output to online operators".to_string(),
+        );
+        map.insert(
+            "server_properties.broadcast-rcon-to-ops".to_string(),
+            "Send RCON command output to online operators".to_string(),
+        );
+        map.insert(
+            "server_properties.bug-report-link".to_string(),
+            "Bug report link shown on the disconnect screen".to_string(),
+        );
+        map.insert("server_properties.difficulty".to_string(), "Difficulty".to_string());
+        map.insert(
+            "server_properties.enable-command-block".to_string(),
+            "Enable command blocks".to_string(),
+        );
+        map.insert(
+            "server_properties.enable-jmx-monitoring".to_string(),
+            "Expose JMX performance monitoring".to_string(),
+        );
+        map.insert(
+            "server_properties.enable-query".to_string(),
+            "Enable the Query protocol".to_string(),
+        );
+        map.insert(
+            "server_properties.enable-rcon".to_string(),
+            "Enable RCON remote console".to_string(),
+        );
+        map.insert(
+            "server_properties.enable-status".to_string(),
+            "Show the server as online in the server list".to_string(),
+        );
+        map.insert(
+            "server_properties.enforce-secure-profile".to_string(),
+            "Require Mojang-signed chat keys".to_string(),
+        );
+        map.insert(
+            "server_properties.enforce-whitelist".to_string(),
+            "Kick non-whitelisted players when the whitelist reloads".to_string(),
+        );
+        map.insert(
+            "server_properties.entity-broadcast-range-percentage".to_string(),
+            "Entity tracking range percentage".to_string(),
+        );
+        map.insert(
+            "server_properties.force-gamemode".to_string(),
+            "Force the default game mode when players join".to_string(),
+        );
+        map.insert(
+            "server_properties.function-permission-level".to_string(),
+            "Default permission level for functions".to_string(),
+        );
+        map.insert("server_properties.gamemode".to_string(), "Default game mode".to_string());
+        map.insert(
+            "server_properties.generate-structures".to_string(),
+            "Generate structures (villages, strongholds, ...)".to_string(),
+        );
+        map.insert(
+            "server_properties.generator-settings".to_string(),
+            "Custom world generation settings".to_string(),
+        );
+        map.insert("server_properties.hardcore".to_string(), "Hardcore mode".to_string());
+        map.insert(
+            "server_properties.hide-online-players".to_string(),
+            "Hide online players in the server list".to_string(),
+        );
+        map.insert(
+            "server_properties.initial-disabled-packs".to_string(),
+            "Data packs disabled when the world is created".to_string(),
+        );
+        map.insert(
+            "server_properties.initial-enabled-packs".to_string(),
+            "Data packs enabled when the world is created".to_string(),
+        );
+        map.insert("server_properties.level-name".to_string(), "World name".to_string());
+        map.insert("server_properties.level-seed".to_string(), "World seed".to_string());
+        map.insert("server_properties.level-type".to_string(), "World type".to_string());
+        map.insert("server_properties.log-ips".to_string(), "Log player IP addresses".to_string());
+        map.insert(
+            "server_properties.max-build-height".to_string(),
+            "Maximum build height".to_string(),
+        );
+        map.insert(
+            "server_properties.max-chained-neighbor-updates".to_string(),
+            "Limit of chained neighbor updates, negative for no limit".to_string(),
+        );
+        map.insert("server_properties.max-players".to_string(), "Maximum players".to_string());
+        map.insert(
+            "server_properties.max-tick-time".to_string(),
+            "Maximum milliseconds per tick before the watchdog stops the server, -1 to disable"
+                .to_string(),
+        );
+        map.insert(
+            "server_properties.max-world-size".to_string(),
+            "Maximum world border radius".to_string(),
+        );
+        map.insert("server_properties.motd".to_string(), "Server description (MOTD)".to_string());
+        map.insert(
+            "server_properties.network-compression-threshold".to_string(),
+            "Network compression threshold in bytes, -1 to disable".to_string(),
+        );
+        map.insert(
+            "server_properties.online-mode".to_string(),
+            "Online mode (premium account verification)".to_string(),
+        );
+        map.insert(
+            "server_properties.op-permission-level".to_string(),
+            "Default permission level for operators".to_string(),
+        );
+        map.insert(
+            "server_properties.pause-when-empty-seconds".to_string(),
+            "Seconds without players before the server pauses, 0 or negative to disable"
+                .to_string(),
+        );
+        map.insert(
+            "server_properties.player-idle-timeout".to_string(),
+            "Minutes before idle players are kicked, 0 to disable".to_string(),
+        );
+        map.insert(
+            "server_properties.prevent-proxy-connections".to_string(),
+            "Block players connecting through proxies or VPNs".to_string(),
+        );
+        map.insert(
+            "server_properties.previews-chat".to_string(),
+            "Enable chat preview".to_string(),
+        );
+        map.insert("server_properties.pvp".to_string(), "Allow PvP".to_string());
+        map.insert("server_properties.query.port".to_string(), "Query protocol port".to_string());
+        map.insert(
+            "server_properties.rate-limit".to_string(),
+            "Maximum packets per second per player, 0 for no limit".to_string(),
+        );
+        map.insert("server_properties.rcon.password".to_string(), "RCON password".to_string());
+        map.insert("server_properties.rcon.port".to_string(), "RCON port".to_string());
+        map.insert(
+            "server_properties.region-file-compression".to_string(),
+            "Chunk compression algorithm for region files".to_string(),
+        );
+        map.insert(
+            "server_properties.require-resource-pack".to_string(),
+            "Require players to accept the server resource pack".to_string(),
+        );
+        map.insert(
+            "server_properties.resource-pack".to_string(),
+            "Server resource pack URL".to_string(),
+        );
+        map.insert(
+            "server_properties.resource-pack-id".to_string(),
+            "Server resource pack UUID".to_string(),
+        );
+        map.insert(
+            "server_properties.resource-pack-prompt".to_string(),
+            "Resource pack prompt message".to_string(),
+        );
+        map.insert(
+            "server_properties.resource-pack-sha1".to_string(),
+            "SHA-1 checksum of the server resource pack".to_string(),
+        );
+        map.insert(
+            "server_properties.server-ip".to_string(),
+            "Bind IP address, empty for all interfaces".to_string(),
+        );
+        map.insert("server_properties.server-port".to_string(), "Server port".to_string());
+        map.insert(
+            "server_properties.simulation-distance".to_string(),
+            "Simulation distance".to_string(),
+        );
+        map.insert(
+            "server_properties.snooper-enabled".to_string(),
+            "Send usage statistics to Mojang".to_string(),
+        );
+        map.insert("server_properties.spawn-animals".to_string(), "Spawn animals".to_string());
+        map.insert("server_properties.spawn-monsters".to_string(), "Spawn monsters".to_string());
+        map.insert("server_properties.spawn-npcs".to_string(), "Spawn villagers".to_string());
+        map.insert(
+            "server_properties.spawn-protection".to_string(),
+            "Spawn protection radius".to_string(),
+        );
+        map.insert(
+            "server_properties.sync-chunk-writes".to_string(),
+            "Write chunks synchronously".to_string(),
+        );
+        map.insert(
+            "server_properties.text-filtering-config".to_string(),
+            "Chat text filtering configuration".to_string(),
+        );
+        map.insert(
+            "server_properties.use-native-transport".to_string(),
+            "Use optimized native networking on Linux".to_string(),
+        );
+        map.insert("server_properties.view-distance".to_string(), "View distance".to_string());
+        map.insert("server_properties.white-list".to_string(), "Enable whitelist".to_string());
+        map.insert(
+            "server_properties.error.boolean".to_string(),
+            "Must be true or false".to_string(),
+        );
+        map.insert("server_properties.error.integer".to_string(), "Must be an integer".to_string());
+        map.insert(
+            "server_properties.error.range".to_string(),
+            "Must be between {min} and {max}".to_string(),
+        );
+        map.insert(
+            "server_properties.error.enum".to_string(),
+            "Must be one of: {options}".to_string(),
+        );
+        map.insert(
+            "server_properties.error.sha1".to_string(),
+            "Must be empty or a 40-character hexadecimal SHA-1".to_string(),
+        );
+
         map
     }
 }
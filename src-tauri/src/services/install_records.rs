@@ -0,0 +1,34 @@
+//! 服务器目录下 `.sealantern/` 中的安装记录（模组、插件等），每种记录保存为一个 JSON 数组。
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const RECORDS_DIR: &str = ".sealantern";
+
+pub fn records_path(server_path: &Path, file_name: &str) -> PathBuf {
+    server_path.join(RECORDS_DIR).join(file_name)
+}
+
+/// 读取记录，文件不存在或无法解析时返回空列表
+pub fn load<T: DeserializeOwned>(server_path: &Path, file_name: &str) -> Vec<T> {
+    fs::read_to_string(records_path(server_path, file_name))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save<T: Serialize>(
+    server_path: &Path,
+    file_name: &str,
+    records: &[T],
+) -> Result<(), String> {
+    let path = records_path(server_path, file_name);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let content =
+        serde_json::to_string_pretty(records).map_err(|e| format!("序列化安装记录失败: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("保存安装记录失败: {}", e))
+}
@@ -0,0 +1,713 @@
+//! Java 发行版目录。
+//!
+//! 从各发行版的接口查询当前系统（Windows/Linux/macOS，glibc 或 musl）可用的 Java 包与
+//! 校验值：Temurin 使用 Adoptium 兼容接口，下载地址可以改写到 TUNA 等镜像；Zulu 使用
+//! Azul 元数据接口；Liberica 使用 BellSoft 接口；GraalVM 使用 Oracle 的 latest 地址与
+//! 同名 `.sha256` 文件。查询结果缓存在软件目录中，离线时使用上次的结果。
+
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::models::java_catalog::{JavaDistribution, JavaPackage};
+use crate::services::global;
+
+const CACHE_FILE: &str = "java_catalog_cache.json";
+/// 缓存在这段时间内直接使用，不再请求接口
+const CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// 发行版 ID 与名称
+const DISTRIBUTIONS: [(&str, &str); 4] = [
+    ("temurin", "Eclipse Temurin"),
+    ("zulu", "Azul Zulu"),
+    ("liberica", "BellSoft Liberica"),
+    ("graalvm", "Oracle GraalVM"),
+];
+/// 没有版本列表接口的发行版提供的 LTS 版本；Temurin 接口不可用时也使用这个列表
+const LTS_VERSIONS: [u32; 5] = [25, 21, 17, 11, 8];
+const GRAALVM_VERSIONS: [u32; 3] = [25, 21, 17];
+
+/// 要安装 Java 的系统
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Platform {
+    /// windows / linux / macos
+    pub os: &'static str,
+    /// x64 / aarch64 / x86
+    pub arch: &'static str,
+    pub musl: bool,
+}
+
+impl Platform {
+    pub fn current() -> Self {
+        let os = match std::env::consts::OS {
+            "windows" => "windows",
+            "macos" => "macos",
+            _ => "linux",
+        };
+        let arch = match std::env::consts::ARCH {
+            "aarch64" => "aarch64",
+            "x86" => "x86",
+            _ => "x64",
+        };
+        Platform {
+            os,
+            arch,
+            musl: os == "linux" && detect_musl(),
+        }
+    }
+
+    fn libc(&self) -> &'static str {
+        match (self.os, self.musl) {
+            ("linux", true) => "musl",
+            ("linux", false) => "glibc",
+            _ => "",
+        }
+    }
+
+    fn archive_ext(&self) -> &'static str {
+        if self.os == "windows" {
+            "zip"
+        } else {
+            "tar.gz"
+        }
+    }
+
+    fn cache_key(&self) -> String {
+        format!("{}-{}-{}", self.os, self.arch, self.libc())
+    }
+}
+
+/// Alpine 等发行版使用 musl，动态链接器位于 `/lib/ld-musl-*.so.1`
+fn detect_musl() -> bool {
+    cfg!(target_env = "musl")
+        || fs::read_dir("/lib").is_ok_and(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.file_name().to_string_lossy().starts_with("ld-musl-"))
+        })
+}
+
+/// 各发行版的接口地址
+#[derive(Debug, Clone)]
+pub struct JavaEndpoints {
+    /// Adoptium 兼容接口的根地址（不含 `/v3`）
+    pub adoptium: String,
+    /// Adoptium 镜像的根地址，文件位于 `{mirror}/{major}/{image}/{arch}/{os}/{name}`
+    pub adoptium_mirror: Option<String>,
+    pub zulu: String,
+    pub liberica: String,
+    pub graalvm: String,
+}
+
+impl JavaEndpoints {
+    pub fn official() -> Self {
+        JavaEndpoints {
+            adoptium: "https://api.adoptium.net".to_string(),
+            adoptium_mirror: None,
+            zulu: "https://api.azul.com/metadata/v1/zulu/packages".to_string(),
+            liberica: "https://api.bell-sw.com/v1/liberica/releases".to_string(),
+            graalvm: "https://download.oracle.com/graalvm".to_string(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AdoptiumReleases {
+    available_releases: Vec<u32>,
+    #[serde(default)]
+    available_lts_releases: Vec<u32>,
+}
+
+#[derive(Deserialize)]
+struct AdoptiumAsset {
+    binary: AdoptiumBinary,
+    version: AdoptiumVersion,
+}
+
+#[derive(Deserialize)]
+struct AdoptiumBinary {
+    package: AdoptiumPackage,
+}
+
+#[derive(Deserialize)]
+struct AdoptiumPackage {
+    name: String,
+    link: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    checksum: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AdoptiumVersion {
+    semver: String,
+}
+
+#[derive(Deserialize)]
+struct ZuluPackage {
+    package_uuid: String,
+    name: String,
+    download_url: String,
+    #[serde(default)]
+    java_version: Vec<u32>,
+}
+
+#[derive(Deserialize)]
+struct ZuluPackageDetail {
+    #[serde(default)]
+    sha256_hash: Option<String>,
+    #[serde(default)]
+    size: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LibericaRelease {
+    download_url: String,
+    filename: String,
+    #[serde(default)]
+    size: u64,
+    version: String,
+    #[serde(default)]
+    sha1: Option<String>,
+}
+
+/// 缓存的一次查询结果
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: u64,
+    value: serde_json::Value,
+}
+
+pub struct JavaCatalog {
+    client: Client,
+    /// 固定的接口地址，为空时按设置选择
+    endpoints: Option<JavaEndpoints>,
+    cache_path: PathBuf,
+    cache_lock: Mutex<()>,
+}
+
+impl JavaCatalog {
+    pub fn new() -> Result<Self, String> {
+        let cache_path = crate::utils::path::get_app_data_dir().join(CACHE_FILE);
+        Self::build(None, cache_path)
+    }
+
+    /// 使用指定的接口地址与缓存文件，便于对接本地模拟服务
+    #[cfg(test)]
+    pub fn with_endpoints(endpoints: JavaEndpoints, cache_path: PathBuf) -> Result<Self, String> {
+        Self::build(Some(endpoints), cache_path)
+    }
+
+    fn build(endpoints: Option<JavaEndpoints>, cache_path: PathBuf) -> Result<Self, String> {
+        Ok(JavaCatalog {
+            client: Client::builder()
+                .user_agent(concat!("SeaLantern/", env!("CARGO_PKG_VERSION")))
+                .timeout(Duration::from_secs(20))
+                .build()
+                .map_err(|e| format!("Failed to create HTTP client: {}", e))?,
+            endpoints,
+            cache_path,
+            cache_lock: Mutex::new(()),
+        })
+    }
+
+    fn endpoints(&self) -> JavaEndpoints {
+        if let Some(endpoints) = &self.endpoints {
+            return endpoints.clone();
+        }
+        let settings = global::settings_manager().get();
+        let mut endpoints = JavaEndpoints::official();
+        let api = settings
+            .java_catalog_api_base_url
+            .trim()
+            .trim_end_matches('/');
+        if !api.is_empty() {
+            endpoints.adoptium = api.to_string();
+        }
+        let mirror = settings.java_mirror_base_url.trim().trim_end_matches('/');
+        if !mirror.is_empty() {
+            endpoints.adoptium_mirror = Some(mirror.to_string());
+        }
+        endpoints
+    }
+
+    /// 可安装的发行版与主版本
+    pub async fn distributions(&self) -> Vec<JavaDistribution> {
+        let endpoints = self.endpoints();
+        let url = format!("{}/v3/info/available_releases", endpoints.adoptium);
+        let temurin = self
+            .cached("temurin-releases", || async move {
+                let releases: AdoptiumReleases = self.get_json(&url).await?;
+                let mut majors = releases.available_releases;
+                majors.sort_unstable_by(|a, b| b.cmp(a));
+                Ok((majors, releases.available_lts_releases))
+            })
+            .await
+            .unwrap_or_else(|_| (LTS_VERSIONS.to_vec(), LTS_VERSIONS.to_vec()));
+
+        DISTRIBUTIONS
+            .iter()
+            .map(|(id, name)| {
+                let (major_versions, lts_versions, supports_musl) = match *id {
+                    "temurin" => (temurin.0.clone(), temurin.1.clone(), true),
+                    "graalvm" => (GRAALVM_VERSIONS.to_vec(), GRAALVM_VERSIONS.to_vec(), false),
+                    _ => (LTS_VERSIONS.to_vec(), LTS_VERSIONS.to_vec(), true),
+                };
+                JavaDistribution {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    major_versions,
+                    lts_versions,
+                    supports_musl,
+                }
+            })
+            .collect()
+    }
+
+    /// 当前系统上某个发行版与主版本的可下载包
+    pub async fn packages(
+        &self,
+        distribution: &str,
+        major_version: u32,
+        image_type: &str,
+    ) -> Result<Vec<JavaPackage>, String> {
+        self.packages_for(distribution, major_version, image_type, &Platform::current())
+            .await
+    }
+
+    async fn packages_for(
+        &self,
+        distribution: &str,
+        major_version: u32,
+        image_type: &str,
+        platform: &Platform,
+    ) -> Result<Vec<JavaPackage>, String> {
+        let distribution = distribution.trim().to_ascii_lowercase();
+        let image_type = match image_type.trim().to_ascii_lowercase().as_str() {
+            "" | "jdk" => "jdk",
+            "jre" => "jre",
+            other => return Err(format!("不支持的 Java 包类型: {}", other)),
+        };
+        let endpoints = self.endpoints();
+        let key =
+            format!("{}-{}-{}-{}", distribution, major_version, image_type, platform.cache_key());
+        self.cached(&key, || async move {
+            match distribution.as_str() {
+                "temurin" => {
+                    self.temurin_packages(&endpoints, major_version, image_type, platform)
+                        .await
+                }
+                "zulu" => {
+                    self.zulu_packages(&endpoints, major_version, image_type, platform)
+                        .await
+                }
+                "liberica" => {
+                    self.liberica_packages(&endpoints, major_version, image_type, platform)
+                        .await
+                }
+                "graalvm" => {
+                    self.graalvm_packages(&endpoints, major_version, image_type, platform)
+                        .await
+                }
+                _ => Err(format!("不支持的 Java 发行版: {}", distribution)),
+            }
+        })
+        .await
+    }
+
+    fn package(
+        &self,
+        distribution: &str,
+        major_version: u32,
+        image_type: &str,
+        platform: &Platform,
+    ) -> JavaPackage {
+        JavaPackage {
+            distribution: distribution.to_string(),
+            major_version,
+            version: String::new(),
+            os: platform.os.to_string(),
+            arch: platform.arch.to_string(),
+            libc: platform.libc().to_string(),
+            image_type: image_type.to_string(),
+            file_name: String::new(),
+            url: String::new(),
+            size: 0,
+            checksum: None,
+        }
+    }
+
+    async fn temurin_packages(
+        &self,
+        endpoints: &JavaEndpoints,
+        major_version: u32,
+        image_type: &str,
+        platform: &Platform,
+    ) -> Result<Vec<JavaPackage>, String> {
+        let os = match (platform.os, platform.musl) {
+            ("macos", _) => "mac",
+            ("linux", true) => "alpine-linux",
+            (os, _) => os,
+        };
+        let arch = if platform.arch == "x86" {
+            "x32"
+        } else {
+            platform.arch
+        };
+        let url = format!(
+            "{}/v3/assets/latest/{}/hotspot?architecture={}&image_type={}&os={}&vendor=eclipse",
+            endpoints.adoptium, major_version, arch, image_type, os
+        );
+        let assets: Vec<AdoptiumAsset> = self.get_json(&url).await?;
+        Ok(assets
+            .into_iter()
+            .filter(|asset| asset.binary.package.name.ends_with(platform.archive_ext()))
+            .map(|asset| {
+                let package = asset.binary.package;
+                let url = match &endpoints.adoptium_mirror {
+                    Some(mirror) => format!(
+                        "{}/{}/{}/{}/{}/{}",
+                        mirror, major_version, image_type, arch, os, package.name
+                    ),
+                    None => package.link,
+                };
+                JavaPackage {
+                    version: asset.version.semver,
+                    file_name: package.name,
+                    url,
+                    size: package.size,
+                    checksum: package.checksum,
+                    ..self.package("temurin", major_version, image_type, platform)
+                }
+            })
+            .collect())
+    }
+
+    async fn zulu_packages(
+        &self,
+        endpoints: &JavaEndpoints,
+        major_version: u32,
+        image_type: &str,
+        platform: &Platform,
+    ) -> Result<Vec<JavaPackage>, String> {
+        let os = match platform.os {
+            "linux" => format!("linux_{}", platform.libc()),
+            os => os.to_string(),
+        };
+        let arch = if platform.arch == "x86" {
+            "i686"
+        } else {
+            platform.arch
+        };
+        let url = format!(
+            "{}/?java_version={}&os={}&arch={}&archive_type={}&java_package_type={}\
+             &javafx_bundled=false&crac_supported=false&latest=true&release_status=ga\
+             &availability_types=CA&page=1&page_size=3",
+            endpoints.zulu,
+            major_version,
+            os,
+            arch,
+            platform.archive_ext(),
+            image_type
+        );
+        let found: Vec<ZuluPackage> = self.get_json(&url).await?;
+        let mut packages = Vec::new();
+        for found in found {
+            // 列表接口不含校验值，需要逐个查询详情
+            let detail: ZuluPackageDetail = self
+                .get_json(&format!("{}/{}", endpoints.zulu, found.package_uuid))
+                .await?;
+            let version = found
+                .java_version
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(".");
+            packages.push(JavaPackage {
+                version,
+                file_name: found.name,
+                url: found.download_url,
+                size: detail.size,
+                checksum: detail.sha256_hash,
+                ..self.package("zulu", major_version, image_type, platform)
+            });
+        }
+        Ok(packages)
+    }
+
+    async fn liberica_packages(
+        &self,
+        endpoints: &JavaEndpoints,
+        major_version: u32,
+        image_type: &str,
+        platform: &Platform,
+    ) -> Result<Vec<JavaPackage>, String> {
+        let os = match (platform.os, platform.musl) {
+            ("linux", true) => "linux-musl",
+            (os, _) => os,
+        };
+        let (arch, bitness) = match platform.arch {
+            "aarch64" => ("arm", 64),
+            "x86" => ("x86", 32),
+            _ => ("x86", 64),
+        };
+        let url = format!(
+            "{}?version-feature={}&version-modifier=latest&bitness={}&os={}&arch={}\
+             &package-type={}&bundle-type={}",
+            endpoints.liberica,
+            major_version,
+            bitness,
+            os,
+            arch,
+            platform.archive_ext(),
+            image_type
+        );
+        let releases: Vec<LibericaRelease> = self.get_json(&url).await?;
+        Ok(releases
+            .into_iter()
+            .map(|release| JavaPackage {
+                version: release.version,
+                file_name: release.filename,
+                url: release.download_url,
+                size: release.size,
+                checksum: release
+                    .sha1
+                    .filter(|sha1| !sha1.trim().is_empty())
+                    .map(|sha1| format!("sha1:{}", sha1)),
+                ..self.package("liberica", major_version, image_type, platform)
+            })
+            .collect())
+    }
+
+    /// Oracle GraalVM 只提供 JDK，没有 musl 与 32 位版本
+    async fn graalvm_packages(
+        &self,
+        endpoints: &JavaEndpoints,
+        major_version: u32,
+        image_type: &str,
+        platform: &Platform,
+    ) -> Result<Vec<JavaPackage>, String> {
+        if image_type != "jdk" || platform.musl || platform.arch == "x86" {
+            return Ok(Vec::new());
+        }
+        let file_name = format!(
+            "graalvm-jdk-{}_{}-{}_bin.{}",
+            major_version,
+            platform.os,
+            platform.arch,
+            platform.archive_ext()
+        );
+        let url = format!("{}/{}/latest/{}", endpoints.graalvm, major_version, file_name);
+        let checksum = self.get_text(&format!("{}.sha256", url)).await?;
+        let sha256 = checksum
+            .split_whitespace()
+            .next()
+            .filter(|hash| hash.len() == 64)
+            .ok_or_else(|| format!("GraalVM {} 的校验文件格式不正确", major_version))?;
+        Ok(vec![JavaPackage {
+            version: major_version.to_string(),
+            file_name,
+            url,
+            checksum: Some(sha256.to_ascii_lowercase()),
+            ..self.package("graalvm", major_version, image_type, platform)
+        }])
+    }
+
+    async fn get_text(&self, url: &str) -> Result<String, String> {
+        let resp = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("请求 {} 失败: {}", url, e))?;
+        if !resp.status().is_success() {
+            return Err(format!("请求 {} 失败: {}", url, resp.status()));
+        }
+        resp.text()
+            .await
+            .map_err(|e| format!("读取 {} 的响应失败: {}", url, e))
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, url: &str) -> Result<T, String> {
+        let text = self.get_text(url).await?;
+        serde_json::from_str(&text).map_err(|e| format!("解析 {} 的响应失败: {}", url, e))
+    }
+
+    /// 缓存未过期时直接返回；否则请求接口并更新缓存，请求失败时退回到过期的缓存
+    async fn cached<T, F, Fut>(&self, key: &str, fetch: F) -> Result<T, String>
+    where
+        T: Serialize + DeserializeOwned,
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T, String>>,
+    {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let cached = self.read_cache().remove(key);
+        if let Some(entry) = &cached {
+            if now.saturating_sub(entry.fetched_at) < CACHE_TTL.as_secs() {
+                if let Ok(value) = serde_json::from_value(entry.value.clone()) {
+                    return Ok(value);
+                }
+            }
+        }
+
+        match fetch().await {
+            Ok(value) => {
+                if let Ok(json) = serde_json::to_value(&value) {
+                    self.write_cache(key, CacheEntry { fetched_at: now, value: json });
+                }
+                Ok(value)
+            }
+            Err(e) => cached
+                .and_then(|entry| serde_json::from_value(entry.value).ok())
+                .ok_or(e),
+        }
+    }
+
+    fn read_cache(&self) -> HashMap<String, CacheEntry> {
+        read_cache_file(&self.cache_path)
+    }
+
+    fn write_cache(&self, key: &str, entry: CacheEntry) {
+        let _guard = self.cache_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut cache = read_cache_file(&self.cache_path);
+        cache.insert(key.to_string(), entry);
+        if let Ok(content) = serde_json::to_string(&cache) {
+            let temp = self.cache_path.with_extension("json.tmp");
+            if fs::write(&temp, content).is_ok() {
+                let _ = fs::rename(&temp, &self.cache_path);
+            }
+        }
+    }
+}
+
+fn read_cache_file(path: &Path) -> HashMap<String, CacheEntry> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_http;
+
+    fn endpoints(base: &str) -> JavaEndpoints {
+        JavaEndpoints {
+            adoptium: format!("{}/adoptium", base),
+            adoptium_mirror: Some("https://mirrors.example.com/Adoptium".to_string()),
+            zulu: format!("{}/zulu", base),
+            liberica: format!("{}/liberica", base),
+            graalvm: format!("{}/graalvm", base),
+        }
+    }
+
+    #[tokio::test]
+    async fn queries_vendors_and_falls_back_to_cache() {
+        let (base, _) = test_http::routes(vec![
+            (
+                "/adoptium/v3/assets/latest/17/hotspot?architecture=x64&image_type=jdk&os=linux",
+                r#"[{"binary":{"package":{"name":"OpenJDK17U-jdk_x64_linux_hotspot_17.0.9_9.tar.gz",
+                    "link":"https://github.com/adoptium/x.tar.gz","size":190,"checksum":"aa"}},
+                    "version":{"semver":"17.0.9+9"}}]"#
+                    .to_string(),
+            ),
+            (
+                "/adoptium/v3/info/available_releases",
+                r#"{"available_releases":[8,11,17,21,22],"available_lts_releases":[8,11,17,21]}"#
+                    .to_string(),
+            ),
+            (
+                "/zulu/?java_version=21&os=linux_musl",
+                r#"[{"package_uuid":"u1","name":"zulu21-musl.tar.gz",
+                    "download_url":"https://cdn.azul.com/zulu21-musl.tar.gz","java_version":[21,0,1]}]"#
+                    .to_string(),
+            ),
+            ("/zulu/u1", r#"{"sha256_hash":"bb","size":200}"#.to_string()),
+            ("/graalvm/21/latest/", format!("{}  graalvm.tar.gz", "c".repeat(64))),
+            (
+                "/liberica?version-feature=21",
+                r#"[{"downloadUrl":"https://download.bell-sw.com/liberica21.tar.gz",
+                    "filename":"bellsoft-jdk21.0.5+11-linux-amd64.tar.gz","size":300,
+                    "version":"21.0.5+11","sha1":"dd"}]"#
+                    .to_string(),
+            ),
+        ]);
+        let cache =
+            std::env::temp_dir().join(format!("sl_java_catalog_{}.json", uuid::Uuid::new_v4()));
+        let catalog = JavaCatalog::with_endpoints(endpoints(&base), cache.clone()).unwrap();
+        let glibc = Platform { os: "linux", arch: "x64", musl: false };
+        let musl = Platform { os: "linux", arch: "x64", musl: true };
+
+        let distributions = catalog.distributions().await;
+        assert_eq!(distributions[0].major_versions, vec![22, 21, 17, 11, 8]);
+        assert_eq!(distributions[0].lts_versions, vec![8, 11, 17, 21]);
+
+        let temurin = catalog
+            .packages_for("temurin", 17, "jdk", &glibc)
+            .await
+            .unwrap();
+        assert_eq!(temurin[0].version, "17.0.9+9");
+        assert_eq!(temurin[0].checksum.as_deref(), Some("aa"));
+        assert_eq!(
+            temurin[0].url,
+            "https://mirrors.example.com/Adoptium/17/jdk/x64/linux/OpenJDK17U-jdk_x64_linux_hotspot_17.0.9_9.tar.gz"
+        );
+
+        let zulu = catalog
+            .packages_for("Zulu", 21, "jdk", &musl)
+            .await
+            .unwrap();
+        assert_eq!(zulu[0].libc, "musl");
+        assert_eq!(zulu[0].version, "21.0.1");
+        assert_eq!(zulu[0].checksum.as_deref(), Some("bb"));
+
+        let graalvm = catalog
+            .packages_for("graalvm", 21, "jdk", &glibc)
+            .await
+            .unwrap();
+        assert!(graalvm[0]
+            .url
+            .ends_with("/graalvm/21/latest/graalvm-jdk-21_linux-x64_bin.tar.gz"));
+        assert_eq!(graalvm[0].checksum, Some("c".repeat(64)));
+        assert!(catalog
+            .packages_for("graalvm", 21, "jdk", &musl)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let liberica = catalog
+            .packages_for("liberica", 21, "jdk", &glibc)
+            .await
+            .unwrap();
+        assert_eq!(liberica[0].version, "21.0.5+11");
+        assert_eq!(liberica[0].checksum.as_deref(), Some("sha1:dd"));
+
+        // 接口不可用时使用缓存
+        let offline =
+            JavaCatalog::with_endpoints(endpoints("http://127.0.0.1:9"), cache.clone()).unwrap();
+        let mut stale = read_cache_file(&cache);
+        stale.values_mut().for_each(|entry| entry.fetched_at = 0);
+        fs::write(&cache, serde_json::to_string(&stale).unwrap()).unwrap();
+        let cached = offline
+            .packages_for("temurin", 17, "jdk", &glibc)
+            .await
+            .unwrap();
+        assert_eq!(cached[0].file_name, temurin[0].file_name);
+        assert!(offline
+            .packages_for("temurin", 21, "jdk", &glibc)
+            .await
+            .is_err());
+        let _ = fs::remove_file(&cache);
+    }
+}
@@ -1,10 +1,12 @@
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
-use std::path::Path;
-#[cfg(target_os = "windows")]
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::UNIX_EPOCH;
+
+use crate::services::global;
 
 // 静默执行命令
 #[cfg(target_os = "windows")]
@@ -35,12 +37,27 @@ const ENV_VARS: &[&str] = &["JAVA_HOME", "JDK_HOME", "GRAALVM_HOME"];
 const COMMON_JAVA_DIRS: &[&str] =
     &["/usr/lib/jvm", "/usr/local/lib/jvm", "/Library/Java/JavaVirtualMachines"];
 
+// update-alternatives 的状态文件，Debian 系与 Red Hat 系位置不同
+#[cfg(target_os = "linux")]
+const ALTERNATIVES_FILES: &[&str] =
+    &["/var/lib/dpkg/alternatives/java", "/var/lib/alternatives/java"];
+
+// Flatpak 的 OpenJDK 扩展：<扩展>/<架构>/<分支>/active/files/jvm/<jdk>/bin/java
+#[cfg(target_os = "linux")]
+const FLATPAK_JDK_PREFIX: &str = "org.freedesktop.Sdk.Extension.openjdk";
+
 #[cfg(target_os = "windows")]
 const MAX_SCAN_DEPTH: u32 = 5;
 
 #[cfg(not(target_os = "windows"))]
 const MAX_SCAN_DEPTH: u32 = 4;
 
+const JAVA_BIN: &str = if cfg!(target_os = "windows") {
+    "java.exe"
+} else {
+    "java"
+};
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct JavaInfo {
     pub path: String,
@@ -48,10 +65,34 @@ pub struct JavaInfo {
     pub vendor: String,
     pub is_64bit: bool,
     pub major_version: u32,
+    /// java 可执行文件的修改时间（Unix 秒），与路径一起作为检测缓存的键
+    #[serde(default)]
+    pub modified: u64,
+}
+
+/// JDK 根目录下 `release` 文件中的版本信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct JavaRelease {
+    pub version: String,
+    pub vendor: String,
+    pub arch: String,
 }
 
+/// 检测已安装的 Java，路径与修改时间未变的条目直接复用设置中缓存的结果
 pub fn detect_java_installations() -> Vec<JavaInfo> {
-    let mut results = Vec::new();
+    detect_with_cache(&global::settings_manager().get().cached_java_list)
+}
+
+fn detect_with_cache(cached: &[JavaInfo]) -> Vec<JavaInfo> {
+    // 由 Sea Lantern 安装的运行时从清单读取，不需要启动 java
+    let mut results = crate::services::java_installer::managed_runtimes()
+        .into_iter()
+        .map(|runtime| runtime.info)
+        .collect::<Vec<_>>();
+    let mut seen = results
+        .iter()
+        .map(|info| canonical_path(&info.path).unwrap_or_else(|| info.path.clone()))
+        .collect::<HashSet<_>>();
     let candidate_paths = get_candidate_paths();
 
     #[cfg(target_os = "windows")]
@@ -63,15 +104,20 @@ pub fn detect_java_installations() -> Vec<JavaInfo> {
         paths
     };
 
+    // 符号链接（/usr/bin/java、SDKMAN 的 current 等）解析后去重，同一个 Java 只检查一次
     for path in candidate_paths {
-        if let Some(info) = check_java(&path) {
-            if !results.iter().any(|r: &JavaInfo| r.path == info.path) {
-                results.push(info);
-            }
+        let Some(resolved) = canonical_path(&path) else {
+            continue;
+        };
+        if !seen.insert(resolved.clone()) {
+            continue;
+        }
+        if let Some(info) = probe_java(&resolved, cached) {
+            results.push(info);
         }
     }
 
-    results.sort_by(|a, b| b.major_version.cmp(&a.major_version));
+    results.sort_by_key(|r| std::cmp::Reverse(r.major_version));
     results
 }
 
@@ -81,7 +127,14 @@ pub fn validate_java(path: &str) -> Result<JavaInfo, String> {
 
 fn get_candidate_paths() -> Vec<String> {
     let mut paths = Vec::with_capacity(32);
-    paths.push(String::from("java"));
+    if let Some(path_var) = std::env::var_os("PATH") {
+        for dir in std::env::split_paths(&path_var) {
+            let java = dir.join(JAVA_BIN);
+            if java.is_file() {
+                paths.push(java.to_string_lossy().into_owned());
+            }
+        }
+    }
 
     for env_var in ENV_VARS {
         if let Ok(val) = std::env::var(env_var) {
@@ -131,16 +184,6 @@ fn get_candidate_paths() -> Vec<String> {
         for root in scan_roots {
             deep_scan_recursive(&root, &mut paths, MAX_SCAN_DEPTH);
         }
-
-        if let Some(output) = command_output("where", &["java"]) {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            for line in stdout.lines() {
-                let trimmed = line.trim();
-                if !trimmed.is_empty() {
-                    paths.push(trimmed.to_string());
-                }
-            }
-        }
     }
 
     #[cfg(not(target_os = "windows"))]
@@ -148,38 +191,186 @@ fn get_candidate_paths() -> Vec<String> {
         for dir in COMMON_JAVA_DIRS {
             deep_scan_recursive(Path::new(dir), &mut paths, MAX_SCAN_DEPTH);
         }
+        for root in version_manager_roots() {
+            deep_scan_recursive(&root, &mut paths, MAX_SCAN_DEPTH);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        paths.push(String::from("/etc/alternatives/java"));
+        paths.extend(alternatives_paths());
+        for root in sandbox_roots() {
+            deep_scan_recursive(&root, &mut paths, MAX_SCAN_DEPTH);
+        }
     }
 
     paths
 }
 
+/// SDKMAN、asdf 与 jabba 的安装目录
+#[cfg(not(target_os = "windows"))]
+fn version_manager_roots() -> Vec<PathBuf> {
+    let Some(home) = std::env::var_os("HOME").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    let dir = |env_var: &str, default: &str| {
+        std::env::var_os(env_var)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(default))
+    };
+    vec![
+        dir("SDKMAN_DIR", ".sdkman").join("candidates").join("java"),
+        dir("ASDF_DATA_DIR", ".asdf").join("installs").join("java"),
+        dir("JABBA_HOME", ".jabba").join("jdk"),
+    ]
+}
+
+/// update-alternatives 登记的所有 java，包括当前未选中的
+#[cfg(target_os = "linux")]
+fn alternatives_paths() -> Vec<String> {
+    ALTERNATIVES_FILES
+        .iter()
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .flat_map(|content| {
+            content
+                .lines()
+                .map(str::trim)
+                .filter(|line| line.starts_with('/') && line.ends_with("/bin/java"))
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Flatpak 的 OpenJDK 扩展与 Snap 中的 JDK
+#[cfg(target_os = "linux")]
+fn sandbox_roots() -> Vec<PathBuf> {
+    let mut flatpak_dirs = vec![PathBuf::from("/var/lib/flatpak/runtime")];
+    if let Some(home) = std::env::var_os("HOME") {
+        flatpak_dirs.push(PathBuf::from(home).join(".local/share/flatpak/runtime"));
+    }
+
+    let mut roots = Vec::new();
+    for dir in flatpak_dirs {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !entry
+                .file_name()
+                .to_string_lossy()
+                .starts_with(FLATPAK_JDK_PREFIX)
+            {
+                continue;
+            }
+            // 每个架构与分支下的 active/files/jvm
+            for arch in read_subdirs(&entry.path()) {
+                for branch in read_subdirs(&arch) {
+                    roots.push(branch.join("active").join("files").join("jvm"));
+                }
+            }
+        }
+    }
+
+    if let Ok(entries) = fs::read_dir("/snap") {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_lowercase();
+            if name.contains("jdk") || name.contains("java") {
+                roots.push(entry.path().join("current"));
+            }
+        }
+    }
+    roots
+}
+
+#[cfg(target_os = "linux")]
+fn read_subdirs(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_dir())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 查找目录下的 Java；找到 `bin/java` 的目录即为 Java 根目录，不再深入其 lib 等子目录
 fn deep_scan_recursive(dir: &Path, paths: &mut Vec<String>, depth: u32) {
     if depth == 0 || !dir.is_dir() {
         return;
     }
 
-    let target_name = if cfg!(target_os = "windows") {
-        "java.exe"
-    } else {
-        "java"
-    };
+    let java_exe = dir.join("bin").join(JAVA_BIN);
+    if java_exe.is_file() {
+        paths.push(java_exe.to_string_lossy().into_owned());
+        return;
+    }
 
     if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
             if path.is_dir() {
-                if path.file_name().is_some_and(|n| n == "bin") {
-                    let java_exe = path.join(target_name);
-                    if java_exe.exists() {
-                        paths.push(java_exe.to_string_lossy().into_owned());
-                    }
-                }
                 deep_scan_recursive(&path, paths, depth - 1);
             }
         }
     }
 }
 
+/// 优先使用缓存，其次读取 `release` 文件，都不可用时才启动 java
+fn probe_java(path: &str, cached: &[JavaInfo]) -> Option<JavaInfo> {
+    let modified = modified_secs(Path::new(path));
+    if modified != 0 {
+        if let Some(info) = cached
+            .iter()
+            .find(|info| info.path == path && info.modified == modified)
+        {
+            return Some(info.clone());
+        }
+    }
+    release_info(path, modified).or_else(|| check_java(path))
+}
+
+fn release_info(path: &str, modified: u64) -> Option<JavaInfo> {
+    let java_home = Path::new(path).parent()?.parent()?;
+    let release = read_release(java_home)?;
+    let major_version = parse_major_version(&release.version);
+    if major_version == 0 {
+        return None;
+    }
+    Some(JavaInfo {
+        path: path.to_string(),
+        is_64bit: release.arch.contains("64"),
+        version: release.version,
+        vendor: release.vendor,
+        major_version,
+        modified,
+    })
+}
+
+fn modified_secs(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+fn canonical_path(path: &str) -> Option<String> {
+    let p = fs::canonicalize(path).ok()?;
+    let path_str = p.to_string_lossy();
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(stripped) = path_str.strip_prefix(r"\\?\") {
+            return Some(stripped.to_string());
+        }
+    }
+    Some(path_str.into_owned())
+}
+
 fn check_java(path: &str) -> Option<JavaInfo> {
     let output = command_output(path, &["-version"])?;
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -208,23 +399,11 @@ fn check_java(path: &str) -> Option<JavaInfo> {
     let resolved = if path == "java" {
         resolve_path_from_env(path)?
     } else {
-        let p = fs::canonicalize(path).ok()?;
-        #[cfg(target_os = "windows")]
-        {
-            let path_str = p.to_string_lossy();
-            if let Some(stripped) = path_str.strip_prefix(r"\\?\") {
-                stripped.to_string()
-            } else {
-                path_str.into_owned()
-            }
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            p.to_string_lossy().into_owned()
-        }
+        canonical_path(path)?
     };
 
     Some(JavaInfo {
+        modified: modified_secs(Path::new(&resolved)),
         path: resolved,
         version,
         vendor,
@@ -233,7 +412,23 @@ fn check_java(path: &str) -> Option<JavaInfo> {
     })
 }
 
-fn parse_major_version(version: &str) -> u32 {
+/// 读取 Java 根目录下的 `release` 文件
+pub fn read_release(java_home: &Path) -> Option<JavaRelease> {
+    let content = fs::read_to_string(java_home.join("release")).ok()?;
+    let value = |key: &str| {
+        content.lines().find_map(|line| {
+            let (k, v) = line.split_once('=')?;
+            (k.trim() == key).then(|| v.trim().trim_matches('"').to_string())
+        })
+    };
+    Some(JavaRelease {
+        version: value("JAVA_VERSION")?,
+        vendor: value("IMPLEMENTOR").unwrap_or_else(|| "OpenJDK".to_string()),
+        arch: value("OS_ARCH").unwrap_or_default(),
+    })
+}
+
+pub fn parse_major_version(version: &str) -> u32 {
     let parts: Vec<&str> = version.split('.').collect();
     let first: u32 = parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
     if first == 1 && parts.len() > 1 {
@@ -273,13 +468,7 @@ fn search_reg_recursive(key: &RegKey, results: &mut Vec<String>) {
 }
 
 fn push_java_exe(dir: &str, paths: &mut Vec<String>) {
-    let bin = Path::new(dir)
-        .join("bin")
-        .join(if cfg!(target_os = "windows") {
-            "java.exe"
-        } else {
-            "java"
-        });
+    let bin = Path::new(dir).join("bin").join(JAVA_BIN);
     if bin.exists() {
         paths.push(bin.to_string_lossy().into_owned());
     }
@@ -316,3 +505,51 @@ fn command_output(program: &str, args: &[&str]) -> Option<std::process::Output>
 
     command.output().ok()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_from_release_file_and_reuses_cache() {
+        let root = std::env::temp_dir().join(format!("sl_java_detect_{}", uuid::Uuid::new_v4()));
+        let home = root.join("jdk-21.0.2");
+        fs::create_dir_all(home.join("bin")).unwrap();
+        fs::create_dir_all(home.join("lib").join("bin")).unwrap();
+        fs::write(home.join("bin").join(JAVA_BIN), "").unwrap();
+        fs::write(home.join("lib").join("bin").join(JAVA_BIN), "").unwrap();
+        fs::write(
+            home.join("release"),
+            "IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"21.0.2\"\nOS_ARCH=\"x86_64\"\n",
+        )
+        .unwrap();
+
+        // 找到 Java 根目录后不再深入
+        let mut paths = Vec::new();
+        deep_scan_recursive(&root, &mut paths, MAX_SCAN_DEPTH);
+        assert_eq!(paths.len(), 1);
+
+        // 空文件无法运行，只能来自 release 文件
+        let path = canonical_path(&paths[0]).unwrap();
+        let info = probe_java(&path, &[]).unwrap();
+        assert_eq!(info.major_version, 21);
+        assert_eq!(info.vendor, "Eclipse Adoptium");
+        assert!(info.is_64bit);
+        assert!(info.modified > 0);
+
+        let cached = JavaInfo {
+            version: "cached".to_string(),
+            ..info.clone()
+        };
+        assert_eq!(
+            probe_java(&path, std::slice::from_ref(&cached))
+                .unwrap()
+                .version,
+            "cached"
+        );
+        let stale = JavaInfo { modified: info.modified - 1, ..cached };
+        assert_eq!(probe_java(&path, &[stale]).unwrap().version, "21.0.2");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
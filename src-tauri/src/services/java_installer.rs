@@ -1,16 +1,37 @@
-#[cfg(not(target_os = "windows"))]
+//! Java 运行时的下载与安装。
+//!
+//! 压缩包以流的方式写入 `runtimes/.downloads/<name>.part`，中断或取消后再次安装时通过
+//! HTTP Range 从已下载的位置继续。下载完成后按发行版元数据校验 SHA-256，解压到临时目录
+//! 并写入运行时清单，最后整体改名为 `runtimes/<name>`，不会留下只解压了一半的运行时。
+//! 检测 Java 时直接读取清单，不需要启动 `java -version`。
+
 use flate2::read::GzDecoder;
+use futures::StreamExt;
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::io::Cursor;
-use std::path::Path;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-#[cfg(not(target_os = "windows"))]
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tar::Archive;
 use tauri::{Emitter, Window};
-#[cfg(target_os = "windows")]
+use tokio::io::AsyncWriteExt;
 use zip::ZipArchive;
 
+use crate::services::java_detector::{self, JavaInfo};
+use crate::utils::hash;
+
+/// 运行时清单的文件名，位于运行时根目录
+const MANIFEST_FILE: &str = "sealantern-runtime.json";
+/// 未完成的下载所在的目录（位于 runtimes 下）
+const DOWNLOAD_DIR: &str = ".downloads";
+/// 网络中断时自动续传的次数
+const MAX_ATTEMPTS: u32 = 3;
+const CANCELLED: &str = "用户取消下载";
+
 #[derive(Clone, serde::Serialize)]
 struct DownloadProgress {
     state: String,
@@ -19,209 +40,437 @@ struct DownloadProgress {
     message: String,
 }
 
+/// 安装时记录的运行时信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeManifest {
+    pub name: String,
+    pub vendor: String,
+    pub version: String,
+    pub major_version: u32,
+    pub arch: String,
+    pub os: String,
+    pub source_url: String,
+    /// 下载时校验的摘要，格式见 [`hash::verify_file`]
+    #[serde(default, alias = "sha256")]
+    pub checksum: Option<String>,
+    pub installed_at: u64,
+}
+
+/// 由 Sea Lantern 安装的运行时
+#[derive(Debug, Clone)]
+pub struct ManagedRuntime {
+    pub dir: PathBuf,
+    pub manifest: RuntimeManifest,
+    pub info: JavaInfo,
+}
+
+/// 一次下载尝试失败的原因
+enum Failure {
+    /// 网络中断等可以续传的错误
+    Retry(String),
+    Fatal(String),
+}
+
+pub fn runtimes_dir() -> PathBuf {
+    crate::utils::path::get_app_data_dir().join("runtimes")
+}
+
+/// 列出已安装且带有清单的运行时，主版本高的在前
+pub fn managed_runtimes() -> Vec<ManagedRuntime> {
+    list_runtimes(&runtimes_dir())
+}
+
+fn list_runtimes(root: &Path) -> Vec<ManagedRuntime> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut runtimes = entries
+        .flatten()
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| {
+            let dir = entry.path();
+            let content = fs::read_to_string(dir.join(MANIFEST_FILE)).ok()?;
+            let manifest: RuntimeManifest = serde_json::from_str(&content).ok()?;
+            let java = java_bin(&java_home(&dir));
+            if !java.is_file() {
+                return None;
+            }
+            let info = JavaInfo {
+                path: java.to_string_lossy().to_string(),
+                version: manifest.version.clone(),
+                vendor: manifest.vendor.clone(),
+                is_64bit: manifest.arch.contains("64"),
+                major_version: manifest.major_version,
+                modified: 0,
+            };
+            Some(ManagedRuntime { dir, manifest, info })
+        })
+        .collect::<Vec<_>>();
+    runtimes.sort_by_key(|r| std::cmp::Reverse(r.info.major_version));
+    runtimes
+}
+
+/// macOS 的压缩包中 JDK 位于 `Contents/Home`
+pub fn java_home(dir: &Path) -> PathBuf {
+    let home = dir.join("Contents").join("Home");
+    if home.join("bin").is_dir() {
+        home
+    } else {
+        dir.to_path_buf()
+    }
+}
+
+pub fn java_bin(home: &Path) -> PathBuf {
+    home.join("bin").join(if cfg!(target_os = "windows") {
+        "java.exe"
+    } else {
+        "java"
+    })
+}
+
 pub async fn download_and_install_java<R: tauri::Runtime>(
     url: String,
     version_name: String,
+    checksum: Option<String>,
     window: Window<R>,
     cancel_flag: Arc<AtomicBool>,
 ) -> Result<String, String> {
-    let app_dir = crate::utils::path::get_app_data_dir();
-
-    let runtimes_dir = app_dir.join("runtimes");
-    if !runtimes_dir.exists() {
-        fs::create_dir_all(&runtimes_dir).map_err(|e| format!("无法创建运行时目录：{}", e))?;
+    if version_name.trim().is_empty()
+        || version_name.starts_with('.')
+        || version_name.contains(['/', '\\'])
+    {
+        return Err(format!("无效的运行时名称：{}", version_name));
     }
+    let runtimes_dir = runtimes_dir();
+    let download_dir = runtimes_dir.join(DOWNLOAD_DIR);
+    fs::create_dir_all(&download_dir).map_err(|e| format!("无法创建运行时目录：{}", e))?;
 
     let target_dir = runtimes_dir.join(&version_name);
-    if target_dir.exists() {
-        // Simple check: if java executable exists, assume installed
-        let java_bin = if cfg!(target_os = "windows") {
-            target_dir.join("bin").join("java.exe")
-        } else {
-            target_dir.join("bin").join("java")
-        };
-        if java_bin.exists() {
-            return Ok(java_bin.to_string_lossy().to_string());
-        }
+    let installed = java_bin(&java_home(&target_dir));
+    if installed.exists() {
+        return Ok(installed.to_string_lossy().to_string());
     }
 
-    // 1. Download
-    let _ = window.emit(
-        "java-install-progress",
-        DownloadProgress {
-            state: "downloading".to_string(),
-            progress: 0,
-            total: 0,
-            message: "开始下载...".to_string(),
-        },
-    );
-
-    let client = reqwest::Client::new();
-    let res = client
-        .get(&url)
-        .send()
-        .await
-        .map_err(|e| format!("下载请求失败：{}", e))?;
-
-    let total_size = res.content_length().unwrap_or(0);
-    // Use bytes_stream to process chunks
-    use futures::StreamExt;
-    let mut stream = res.bytes_stream();
-    let mut downloaded: u64 = 0;
-    let mut data = Vec::new();
-    let mut last_emit = std::time::Instant::now();
+    let emit = |state: &str, progress: u64, total: u64, message: String| {
+        let _ = window.emit(
+            "java-install-progress",
+            DownloadProgress {
+                state: state.to_string(),
+                progress,
+                total,
+                message,
+            },
+        );
+    };
 
-    while let Some(chunk) = stream.next().await {
-        if cancel_flag.load(Ordering::Relaxed) {
-            return Err("用户取消下载".to_string());
+    // 1. 下载，已有未完成的下载时从断点继续
+    emit("downloading", 0, 0, "开始下载...".to_string());
+    let part = download_dir.join(format!("{}.part", version_name));
+    let mut last_emit = Instant::now();
+    download_resumable(&url, &part, &cancel_flag, |downloaded, total| {
+        if total > 0 && last_emit.elapsed().as_millis() > 100 {
+            emit(
+                "downloading",
+                downloaded,
+                total,
+                format!("正在下载：{}/{}", bytes_to_mb(downloaded), bytes_to_mb(total)),
+            );
+            last_emit = Instant::now();
         }
-        let chunk = chunk.map_err(|e| format!("下载流错误：{}", e))?;
-        data.extend_from_slice(&chunk);
-        downloaded += chunk.len() as u64;
+    })
+    .await?;
 
-        if total_size > 0 && last_emit.elapsed().as_millis() > 100 {
-            let _ = window.emit(
-                "java-install-progress",
-                DownloadProgress {
-                    state: "downloading".to_string(),
-                    progress: downloaded,
-                    total: total_size,
-                    message: format!(
-                        "正在下载：{}/{}",
-                        bytes_to_mb(downloaded),
-                        bytes_to_mb(total_size)
-                    ),
-                },
-            );
-            last_emit = std::time::Instant::now();
+    // 2. 校验
+    if let Some(expected) = checksum.clone().filter(|s| !s.trim().is_empty()) {
+        emit("verifying", 0, 100, "正在校验...".to_string());
+        let path = part.clone();
+        let verified =
+            tauri::async_runtime::spawn_blocking(move || hash::verify_file(&path, &expected))
+                .await
+                .map_err(|e| format!("校验任务失败：{}", e))?;
+        if let Err(e) = verified {
+            remove_download(&part);
+            return Err(e);
         }
     }
-    // Final progress emit
-    let _ = window.emit(
-        "java-install-progress",
-        DownloadProgress {
-            state: "downloading".to_string(),
-            progress: downloaded,
-            total: total_size,
-            message: "下载完成，准备解压...".to_string(),
-        },
-    );
-
-    if cancel_flag.load(Ordering::Relaxed) {
-        return Err("用户取消下载".to_string());
-    }
 
-    // 2. Extract
-    let _ = window.emit(
-        "java-install-progress",
-        DownloadProgress {
-            state: "extracting".to_string(),
-            progress: 0,
-            total: 100,
-            message: "正在解压...".to_string(),
-        },
-    );
-
-    let temp_dir = runtimes_dir.join(format!("temp_{}", version_name));
-    if temp_dir.exists() {
-        fs::remove_dir_all(&temp_dir).map_err(|e| format!("无法清理临时目录：{}", e))?;
+    // 3. 解压并整体替换
+    emit("extracting", 0, 100, "正在解压...".to_string());
+    let archive = part.clone();
+    let name = version_name.clone();
+    let source = SourceInfo { url, checksum };
+    let root = runtimes_dir.clone();
+    let flag = cancel_flag.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        install_archive(&archive, &root, &name, &source, &flag)
+    })
+    .await
+    .map_err(|e| format!("解压任务失败：{}", e))?;
+    // 取消解压时保留压缩包，下次直接安装
+    if result.is_ok() || !cancel_flag.load(Ordering::Relaxed) {
+        remove_download(&part);
     }
-    fs::create_dir_all(&temp_dir).map_err(|e| format!("无法创建临时目录：{}", e))?;
+    let java = result?;
 
-    // Check file signature (Magic Numbers)
-    #[cfg(target_os = "windows")]
-    {
-        if data.len() > 2 && &data[0..2] == b"PK" {
-            extract_zip(&data, &temp_dir, &cancel_flag)?;
-        } else {
-            return Err("下载的文件不是有效的 ZIP 格式".to_string());
-        }
+    emit("finished", 100, 100, "安装完成".to_string());
+    Ok(java.to_string_lossy().to_string())
+}
+
+/// 下载来源，写入运行时清单
+struct SourceInfo {
+    url: String,
+    checksum: Option<String>,
+}
+
+fn source_file(part: &Path) -> PathBuf {
+    part.with_extension("source")
+}
+
+fn remove_download(part: &Path) {
+    let _ = fs::remove_file(part);
+    let _ = fs::remove_file(source_file(part));
+}
+
+/// 下载到 `part`。`part` 已有内容且来自同一地址时用 Range 续传，中途断开会自动续传
+async fn download_resumable(
+    url: &str,
+    part: &Path,
+    cancel_flag: &AtomicBool,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<(), String> {
+    let source = source_file(part);
+    if fs::read_to_string(&source).ok().as_deref() != Some(url) {
+        let _ = fs::remove_file(part);
+        fs::write(&source, url).map_err(|e| format!("保存下载信息失败：{}", e))?;
     }
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        // Gzip header is usually 1F 8B
-        if data.len() > 2 && data[0] == 0x1f && data[1] == 0x8b {
-            extract_tar_gz(&data, &temp_dir, &cancel_flag)?;
-        } else {
-            return Err("下载的文件不是有效的 tar.gz 格式".to_string());
+    let client = reqwest::Client::new();
+    let mut attempt = 1;
+    loop {
+        match download_once(&client, url, part, cancel_flag, &mut on_progress).await {
+            Ok(()) => return Ok(()),
+            Err(Failure::Retry(_)) if attempt < MAX_ATTEMPTS => {
+                tokio::time::sleep(Duration::from_secs(attempt as u64)).await;
+                attempt += 1;
+            }
+            Err(Failure::Retry(e)) | Err(Failure::Fatal(e)) => return Err(e),
         }
     }
+}
 
-    if cancel_flag.load(Ordering::Relaxed) {
-        // Cleanup
-        let _ = fs::remove_dir_all(&temp_dir);
-        return Err("用户取消安装".to_string());
+async fn download_once(
+    client: &reqwest::Client,
+    url: &str,
+    part: &Path,
+    cancel_flag: &AtomicBool,
+    on_progress: &mut impl FnMut(u64, u64),
+) -> Result<(), Failure> {
+    let offset = fs::metadata(part).map(|m| m.len()).unwrap_or(0);
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(RANGE, format!("bytes={}-", offset));
     }
+    let res = request
+        .send()
+        .await
+        .map_err(|e| Failure::Retry(format!("下载请求失败：{}", e)))?;
 
-    // 3. Move Logic
-    // Find the single inner directory if it exists
-    let install_source = if let Ok(entries) = fs::read_dir(&temp_dir) {
-        let entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-        if entries.len() == 1 && entries[0].path().is_dir() {
-            entries[0].path()
+    let status = res.status();
+    if status == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 {
+        // 上次已经下载完整，内容由校验与解压确认
+        return Ok(());
+    }
+    if !status.is_success() {
+        let message = format!("下载请求失败：{}", status);
+        return Err(if status.is_server_error() {
+            Failure::Retry(message)
         } else {
-            temp_dir.clone()
-        }
-    } else {
-        temp_dir.clone()
-    };
+            Failure::Fatal(message)
+        });
+    }
 
-    // Ensure target dir is clean
-    if target_dir.exists() {
-        fs::remove_dir_all(&target_dir).map_err(|e| format!("清理旧文件失败：{}", e))?;
+    // 服务器不支持 Range 时返回完整内容，需要从头写入
+    let resumed = offset > 0 && status == StatusCode::PARTIAL_CONTENT;
+    let mut file = if resumed {
+        tokio::fs::OpenOptions::new().append(true).open(part).await
+    } else {
+        tokio::fs::File::create(part).await
     }
+    .map_err(|e| Failure::Fatal(format!("无法写入下载文件：{}", e)))?;
+    let mut downloaded = if resumed { offset } else { 0 };
+    let total = res
+        .content_length()
+        .map(|len| len + downloaded)
+        .unwrap_or(0);
 
-    // Rename/Move
-    if let Err(e) = fs::rename(&install_source, &target_dir) {
-        // Fallback: Copy if rename fails (e.g. crossfs, though unlikely here)
-        // For now, return error as simple rename should work in AppData
-        let _ = fs::remove_dir_all(&temp_dir);
-        return Err(format!("移动文件失败：{}", e));
+    let mut stream = res.bytes_stream();
+    let mut interrupted = None;
+    while let Some(chunk) = stream.next().await {
+        if cancel_flag.load(Ordering::Relaxed) {
+            interrupted = Some(Failure::Fatal(CANCELLED.to_string()));
+            break;
+        }
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                interrupted = Some(Failure::Retry(format!("下载流错误：{}", e)));
+                break;
+            }
+        };
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| Failure::Fatal(format!("写入下载文件失败：{}", e)))?;
+        downloaded += chunk.len() as u64;
+        on_progress(downloaded, total);
+    }
+    // 中断时也写回已下载的部分，供续传使用
+    file.flush()
+        .await
+        .map_err(|e| Failure::Fatal(format!("写入下载文件失败：{}", e)))?;
+    if let Some(failure) = interrupted {
+        return Err(failure);
+    }
+    if total > 0 && downloaded < total {
+        return Err(Failure::Retry(format!(
+            "下载不完整：{}/{}",
+            bytes_to_mb(downloaded),
+            bytes_to_mb(total)
+        )));
     }
+    on_progress(downloaded, downloaded.max(total));
+    Ok(())
+}
 
-    if install_source != temp_dir {
-        let _ = fs::remove_dir_all(&temp_dir);
+/// 解压到临时目录、写入清单，再整体改名为 `runtimes/<name>`，返回 java 可执行文件
+fn install_archive(
+    archive: &Path,
+    runtimes_dir: &Path,
+    name: &str,
+    source: &SourceInfo,
+    cancel_flag: &AtomicBool,
+) -> Result<PathBuf, String> {
+    let staging = runtimes_dir.join(format!(".temp_{}", name));
+    if staging.exists() {
+        fs::remove_dir_all(&staging).map_err(|e| format!("无法清理临时目录：{}", e))?;
     }
+    fs::create_dir_all(&staging).map_err(|e| format!("无法创建临时目录：{}", e))?;
+
+    let result = extract_archive(archive, &staging, cancel_flag)
+        .and_then(|_| finish_install(&staging, runtimes_dir, name, source));
+    let _ = fs::remove_dir_all(&staging);
+    result
+}
 
-    // 4. Verify & Permissions
-    let java_bin = if cfg!(target_os = "windows") {
-        target_dir.join("bin").join("java.exe")
+fn finish_install(
+    staging: &Path,
+    runtimes_dir: &Path,
+    name: &str,
+    source: &SourceInfo,
+) -> Result<PathBuf, String> {
+    // 压缩包通常只有一个顶层目录
+    let entries = fs::read_dir(staging)
+        .map_err(|e| format!("读取临时目录失败：{}", e))?
+        .flatten()
+        .collect::<Vec<_>>();
+    let install_source = if entries.len() == 1 && entries[0].path().is_dir() {
+        entries[0].path()
     } else {
-        target_dir.join("bin").join("java")
+        staging.to_path_buf()
     };
 
-    if !java_bin.exists() {
-        return Err(format!("安装失败：未找到可执行文件 {:?}", java_bin));
+    let home = java_home(&install_source);
+    let java = java_bin(&home);
+    if !java.exists() {
+        return Err(format!("安装失败：未找到可执行文件 {:?}", java));
     }
-
     #[cfg(not(target_os = "windows"))]
     {
         use std::os::unix::fs::PermissionsExt;
-        if let Ok(metadata) = fs::metadata(&java_bin) {
+        if let Ok(metadata) = fs::metadata(&java) {
             let mut perms = metadata.permissions();
             perms.set_mode(0o755);
-            let _ = fs::set_permissions(&java_bin, perms);
+            let _ = fs::set_permissions(&java, perms);
         }
     }
 
-    let _ = window.emit(
-        "java-install-progress",
-        DownloadProgress {
-            state: "finished".to_string(),
-            progress: 100,
-            total: 100,
-            message: "安装完成".to_string(),
-        },
-    );
-
-    Ok(java_bin.to_string_lossy().to_string())
+    let manifest = build_manifest(&home, &java, name, source)?;
+    let content = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("序列化运行时清单失败：{}", e))?;
+    fs::write(install_source.join(MANIFEST_FILE), content)
+        .map_err(|e| format!("保存运行时清单失败：{}", e))?;
+
+    let target = runtimes_dir.join(name);
+    let previous = runtimes_dir.join(format!(".old_{}", name));
+    if target.exists() {
+        let _ = fs::remove_dir_all(&previous);
+        fs::rename(&target, &previous).map_err(|e| format!("清理旧文件失败：{}", e))?;
+    }
+    if let Err(e) = fs::rename(&install_source, &target) {
+        if previous.exists() {
+            let _ = fs::rename(&previous, &target);
+        }
+        return Err(format!("移动文件失败：{}", e));
+    }
+    let _ = fs::remove_dir_all(&previous);
+    Ok(java_bin(&java_home(&target)))
+}
+
+/// 版本信息优先取 `release` 文件，没有时运行一次 `java -version`
+fn build_manifest(
+    home: &Path,
+    java: &Path,
+    name: &str,
+    source: &SourceInfo,
+) -> Result<RuntimeManifest, String> {
+    let (vendor, version, arch) = match java_detector::read_release(home) {
+        Some(release) => (release.vendor, release.version, release.arch),
+        None => {
+            let info = java_detector::validate_java(&java.to_string_lossy())?;
+            let arch = if info.is_64bit { "x86_64" } else { "x86" };
+            (info.vendor, info.version, arch.to_string())
+        }
+    };
+    let arch = if arch.is_empty() {
+        std::env::consts::ARCH.to_string()
+    } else {
+        arch
+    };
+    Ok(RuntimeManifest {
+        name: name.to_string(),
+        major_version: java_detector::parse_major_version(&version),
+        vendor,
+        version,
+        arch,
+        os: std::env::consts::OS.to_string(),
+        source_url: source.url.clone(),
+        checksum: source.checksum.clone(),
+        installed_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    })
+}
+
+/// 按文件头判断 ZIP 或 tar.gz 并解压
+fn extract_archive(
+    archive: &Path,
+    target_dir: &Path,
+    cancel_flag: &AtomicBool,
+) -> Result<(), String> {
+    let mut magic = [0u8; 2];
+    fs::File::open(archive)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .map_err(|e| format!("读取下载文件失败：{}", e))?;
+    match magic {
+        [b'P', b'K'] => extract_zip(archive, target_dir, cancel_flag),
+        [0x1f, 0x8b] => extract_tar_gz(archive, target_dir, cancel_flag),
+        _ => Err("下载的文件不是有效的 ZIP 或 tar.gz 格式".to_string()),
+    }
 }
 
-#[cfg(target_os = "windows")]
-fn extract_zip(data: &[u8], target_dir: &Path, cancel_flag: &AtomicBool) -> Result<(), String> {
-    let cursor = Cursor::new(data);
-    let mut archive = ZipArchive::new(cursor).map_err(|e| format!("ZIP 解析失败：{}", e))?;
+fn extract_zip(archive: &Path, target_dir: &Path, cancel_flag: &AtomicBool) -> Result<(), String> {
+    let file = fs::File::open(archive).map_err(|e| format!("读取下载文件失败：{}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("ZIP 解析失败：{}", e))?;
 
     for i in 0..archive.len() {
         if cancel_flag.load(Ordering::Relaxed) {
@@ -230,42 +479,149 @@ fn extract_zip(data: &[u8], target_dir: &Path, cancel_flag: &AtomicBool) -> Resu
         let mut file = archive
             .by_index(i)
             .map_err(|e| format!("读取文件失败：{}", e))?;
-        let outpath = target_dir.join(file.mangled_name());
+        let Some(relative) = file.enclosed_name() else {
+            continue;
+        };
+        let outpath = target_dir.join(relative);
 
-        if file.name().ends_with('/') {
+        if file.is_dir() {
             fs::create_dir_all(&outpath).map_err(|e| format!("创建目录失败：{}", e))?;
         } else {
             if let Some(p) = outpath.parent() {
-                if !p.exists() {
-                    fs::create_dir_all(p).map_err(|e| format!("创建父目录失败：{}", e))?;
-                }
+                fs::create_dir_all(p).map_err(|e| format!("创建父目录失败：{}", e))?;
             }
             let mut outfile =
                 fs::File::create(&outpath).map_err(|e| format!("创建文件失败：{}", e))?;
             std::io::copy(&mut file, &mut outfile).map_err(|e| format!("写入文件失败：{}", e))?;
+            #[cfg(not(target_os = "windows"))]
+            if let Some(mode) = file.unix_mode() {
+                use std::os::unix::fs::PermissionsExt;
+                let _ = fs::set_permissions(&outpath, fs::Permissions::from_mode(mode));
+            }
         }
     }
     Ok(())
 }
 
-#[cfg(not(target_os = "windows"))]
-fn extract_tar_gz(data: &[u8], target_dir: &Path, cancel_flag: &AtomicBool) -> Result<(), String> {
-    let cursor = Cursor::new(data);
-    let tar = GzDecoder::new(cursor);
-    let mut archive = Archive::new(tar);
-
-    // tar archive unpacking is usually one-shot, but we can check if there's a way to iterate.
-    // The `unpack` method is convenient but not cancellable per-entry easily without manual iteration.
-    // For now, check flag before. Note: unpacking fully is usually fast on modern SSDs unless huge.
-    if cancel_flag.load(Ordering::Relaxed) {
-        return Err("用户取消解压".to_string());
+fn extract_tar_gz(
+    archive: &Path,
+    target_dir: &Path,
+    cancel_flag: &AtomicBool,
+) -> Result<(), String> {
+    let file = fs::File::open(archive).map_err(|e| format!("读取下载文件失败：{}", e))?;
+    let mut archive = Archive::new(GzDecoder::new(file));
+    let entries = archive.entries().map_err(|e| format!("解压失败：{}", e))?;
+    for entry in entries {
+        if cancel_flag.load(Ordering::Relaxed) {
+            return Err("用户取消解压".to_string());
+        }
+        let mut entry = entry.map_err(|e| format!("解压失败：{}", e))?;
+        entry
+            .unpack_in(target_dir)
+            .map_err(|e| format!("解压失败：{}", e))?;
     }
-    archive
-        .unpack(target_dir)
-        .map_err(|e| format!("解压失败：{}", e))?;
     Ok(())
 }
 
 fn bytes_to_mb(bytes: u64) -> String {
     format!("{:.2}MB", bytes as f64 / 1024.0 / 1024.0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_http;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}_{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn resumes_partial_download_with_range() {
+        let dir = temp_dir("sl_java_download");
+        let body = (0..64 * 1024).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        let server_body = body.clone();
+        let (base, requests) =
+            test_http::serve(move |request| test_http::file(&server_body, request, true));
+        let url = format!("{}/jdk.tar.gz", base);
+        let ranges = || {
+            requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|request| request.range().map(str::to_string))
+                .collect::<Vec<_>>()
+        };
+        let part = dir.join("jdk.part");
+        fs::write(source_file(&part), &url).unwrap();
+        fs::write(&part, &body[..1000]).unwrap();
+
+        let cancel = AtomicBool::new(false);
+        download_resumable(&url, &part, &cancel, |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(fs::read(&part).unwrap(), body);
+        assert_eq!(ranges(), [Some("1000-".to_string())]);
+
+        // 地址变化时不续传旧文件
+        fs::write(&part, b"stale").unwrap();
+        let other = url.replace("jdk.tar.gz", "other.tar.gz");
+        download_resumable(&other, &part, &cancel, |_, _| {})
+            .await
+            .unwrap();
+        assert_eq!(fs::read(&part).unwrap(), body);
+        assert_eq!(ranges().last(), Some(&None));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn installs_archive_atomically_with_manifest() {
+        let dir = temp_dir("sl_java_install");
+        let archive = dir.join("jdk.tar.gz");
+        {
+            let gz = flate2::write::GzEncoder::new(
+                fs::File::create(&archive).unwrap(),
+                flate2::Compression::fast(),
+            );
+            let mut tar = tar::Builder::new(gz);
+            let release =
+                b"IMPLEMENTOR=\"Eclipse Adoptium\"\nJAVA_VERSION=\"17.0.9\"\nOS_ARCH=\"x86_64\"\n";
+            for (path, data) in [
+                ("jdk-17.0.9+9/release", &release[..]),
+                ("jdk-17.0.9+9/bin/java", &b"#!/bin/sh\n"[..]),
+                ("jdk-17.0.9+9/bin/java.exe", &b"MZ"[..]),
+            ] {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(data.len() as u64);
+                header.set_mode(0o755);
+                header.set_cksum();
+                tar.append_data(&mut header, path, data).unwrap();
+            }
+            tar.into_inner().unwrap().finish().unwrap();
+        }
+
+        let runtimes = dir.join("runtimes");
+        fs::create_dir_all(runtimes.join("temurin-17")).unwrap();
+        fs::write(runtimes.join("temurin-17").join("stale"), "").unwrap();
+        let source = SourceInfo {
+            url: "https://example.com/jdk.tar.gz".to_string(),
+            checksum: Some("abc".to_string()),
+        };
+        let java =
+            install_archive(&archive, &runtimes, "temurin-17", &source, &AtomicBool::new(false))
+                .unwrap();
+        assert_eq!(java, java_bin(&runtimes.join("temurin-17")));
+        assert!(!runtimes.join("temurin-17").join("stale").exists());
+        assert!(!runtimes.join(".temp_temurin-17").exists());
+
+        let listed = list_runtimes(&runtimes);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].info.major_version, 17);
+        assert_eq!(listed[0].info.vendor, "Eclipse Adoptium");
+        assert!(listed[0].info.is_64bit);
+        assert_eq!(listed[0].manifest.checksum.as_deref(), Some("abc"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,371 @@
+//! `runtimes/` 下的 Java 运行时清单：占用空间与使用中的服务器、卸载未使用的运行时、
+//! 批量切换服务器的 Java，以及检查服务器记录的 Java 是否还存在。
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::models::server::ServerInstance;
+use crate::models::settings::PartialSettings;
+use crate::services::global;
+use crate::services::java_detector::{self, JavaInfo};
+use crate::services::java_installer::{self, RuntimeManifest};
+use crate::services::java_requirements::{self, JavaRequirement};
+
+/// 使用某个 Java 的服务器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeServer {
+    pub id: String,
+    pub name: String,
+}
+
+/// `runtimes/` 下的一个运行时
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeUsage {
+    /// 目录名，卸载时使用
+    pub name: String,
+    pub dir: String,
+    /// 旧版本安装的运行时没有清单
+    pub manifest: Option<RuntimeManifest>,
+    /// 目录中找不到可识别的 Java 时为空
+    pub java: Option<JavaInfo>,
+    /// 占用空间（字节）
+    pub size: u64,
+    pub servers: Vec<RuntimeServer>,
+}
+
+/// Java 路径已不存在的服务器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissingJava {
+    pub server_id: String,
+    pub server_name: String,
+    pub java_path: String,
+    /// 最接近原来版本的已安装 Java
+    pub suggestion: Option<JavaInfo>,
+}
+
+pub fn list_runtimes() -> Vec<RuntimeUsage> {
+    inventory(&java_installer::runtimes_dir(), &global::server_manager().get_server_list())
+}
+
+fn inventory(root: &Path, servers: &[ServerInstance]) -> Vec<RuntimeUsage> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let managed = java_installer::managed_runtimes();
+    let mut runtimes = entries
+        .flatten()
+        .filter(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| {
+            let dir = entry.path();
+            let runtime = managed.iter().find(|runtime| runtime.dir == dir);
+            let java = match runtime {
+                Some(runtime) => Some(runtime.info.clone()),
+                None => legacy_java(&dir),
+            };
+            RuntimeUsage {
+                name: entry.file_name().to_string_lossy().to_string(),
+                dir: dir.to_string_lossy().to_string(),
+                manifest: runtime.map(|runtime| runtime.manifest.clone()),
+                java,
+                size: dir_size(&dir),
+                servers: servers
+                    .iter()
+                    .filter(|server| is_inside(&server.java_path, &dir))
+                    .map(|server| RuntimeServer {
+                        id: server.id.clone(),
+                        name: server.name.clone(),
+                    })
+                    .collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+    runtimes.sort_by(|a, b| a.name.cmp(&b.name));
+    runtimes
+}
+
+/// 没有清单的运行时从 `release` 文件读取版本
+fn legacy_java(dir: &Path) -> Option<JavaInfo> {
+    let home = java_installer::java_home(dir);
+    let java = java_installer::java_bin(&home);
+    if !java.is_file() {
+        return None;
+    }
+    let release = java_detector::read_release(&home)?;
+    Some(JavaInfo {
+        path: java.to_string_lossy().to_string(),
+        major_version: java_detector::parse_major_version(&release.version),
+        is_64bit: release.arch.contains("64"),
+        version: release.version,
+        vendor: release.vendor,
+        modified: 0,
+    })
+}
+
+/// 卸载运行时；仍有服务器使用时拒绝
+pub fn uninstall_runtime(name: &str) -> Result<(), String> {
+    let runtime = list_runtimes()
+        .into_iter()
+        .find(|runtime| runtime.name == name)
+        .ok_or_else(|| format!("运行时不存在: {}", name))?;
+    if !runtime.servers.is_empty() {
+        let names = runtime
+            .servers
+            .iter()
+            .map(|server| server.name.as_str())
+            .collect::<Vec<_>>()
+            .join("、");
+        return Err(format!(
+            "运行时 {} 仍被服务器使用：{}，请先为这些服务器切换 Java",
+            name, names
+        ));
+    }
+
+    let dir = Path::new(&runtime.dir);
+    fs::remove_dir_all(dir).map_err(|e| format!("删除运行时失败: {}", e))?;
+
+    // 清理设置中指向该运行时的 Java
+    let settings = global::settings_manager().get();
+    let cached_java_list = settings
+        .cached_java_list
+        .into_iter()
+        .filter(|java| !is_inside(&java.path, dir))
+        .collect();
+    let default_java_path = is_inside(&settings.default_java_path, dir).then(String::new);
+    global::settings_manager().update_partial(PartialSettings {
+        cached_java_list: Some(cached_java_list),
+        default_java_path,
+        ..Default::default()
+    })?;
+    Ok(())
+}
+
+/// 把使用 `from_java_path` 的服务器切换到 `to_java_path`，返回切换的服务器 ID；
+/// 任何一个服务器与目标 Java 不兼容时都不做修改
+pub fn repoint_servers(from_java_path: &str, to_java_path: &str) -> Result<Vec<String>, String> {
+    let known = java_requirements::installed_javas()
+        .into_iter()
+        .find(|java| java.path == to_java_path);
+    let target = match known {
+        Some(java) => java,
+        None => java_detector::validate_java(to_java_path)?,
+    };
+
+    let servers = global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .filter(|server| server.java_path == from_java_path)
+        .collect::<Vec<_>>();
+    if servers.is_empty() {
+        return Err(format!("没有服务器使用 {}", from_java_path));
+    }
+    for server in &servers {
+        if let Some(requirement) =
+            java_requirements::requirement(&server.mc_version, &server.core_type)
+        {
+            java_requirements::check(&requirement, target.major_version, &target.path)
+                .map_err(|e| format!("服务器 {}：{}", server.name, e))?;
+        }
+    }
+
+    let ids = servers
+        .into_iter()
+        .map(|server| server.id)
+        .collect::<Vec<_>>();
+    global::server_manager().update_java_path(&ids, &target.path)?;
+    Ok(ids)
+}
+
+/// Java 路径已不存在的服务器，例如系统升级后旧的 JDK 被移除
+pub fn missing_java() -> Vec<MissingJava> {
+    global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .filter(|server| is_missing(&server.java_path))
+        .map(|server| MissingJava {
+            suggestion: replacement_for(&server),
+            server_id: server.id,
+            server_name: server.name,
+            java_path: server.java_path,
+        })
+        .collect()
+}
+
+/// 路径已不存在；只有命令名（例如 `java`）时由系统在 PATH 中查找，不算缺失
+pub fn is_missing(java_path: &str) -> bool {
+    let path = Path::new(java_path);
+    path.components().count() > 1 && !path.exists()
+}
+
+/// 为 Java 已丢失的服务器推荐替代
+pub fn replacement_for(server: &ServerInstance) -> Option<JavaInfo> {
+    let javas = java_requirements::installed_javas()
+        .into_iter()
+        .filter(|java| Path::new(&java.path).is_file())
+        .collect::<Vec<_>>();
+    // 缓存中可能还保留着已删除的 Java 的版本
+    let cached = global::settings_manager().get().cached_java_list;
+    let previous = cached.iter().find(|java| java.path == server.java_path);
+    let requirement = java_requirements::requirement(&server.mc_version, &server.core_type);
+    closest_java(&javas, &server.java_path, previous, requirement.as_ref())
+}
+
+/// 选择与已丢失的 Java 最接近的替代：主版本相同或最接近，其次同一发行商，同版本优先 64 位
+fn closest_java(
+    javas: &[JavaInfo],
+    missing_path: &str,
+    previous: Option<&JavaInfo>,
+    requirement: Option<&JavaRequirement>,
+) -> Option<JavaInfo> {
+    let major_version = previous
+        .map(|java| java.major_version)
+        .or_else(|| guess_major_version(missing_path))
+        .or_else(|| requirement.map(|requirement| requirement.recommended));
+    let vendor = previous.map(|java| java.vendor.to_lowercase());
+
+    javas
+        .iter()
+        .filter(|java| {
+            requirement.is_none_or(|requirement| requirement.accepts(java.major_version))
+        })
+        .min_by_key(|java| {
+            (
+                major_version.map_or(0, |major| java.major_version.abs_diff(major)),
+                // 距离相同时选择较新的版本
+                major_version.is_some_and(|major| java.major_version < major),
+                vendor
+                    .as_ref()
+                    .is_some_and(|vendor| *vendor != java.vendor.to_lowercase()),
+                !java.is_64bit,
+                std::cmp::Reverse(java.major_version),
+            )
+        })
+        .cloned()
+}
+
+/// 从 `jdk-17.0.2`、`java-1.8.0-openjdk`、`zulu21` 之类的目录名推断主版本
+fn guess_major_version(path: &str) -> Option<u32> {
+    let re =
+        Regex::new(r"(?i)(?:jdk|jre|java|zulu|temurin|graalvm)[^/\\\d]{0,16}(1\.8|\d+)").ok()?;
+    let caps = re.captures(path)?;
+    let major = java_detector::parse_major_version(&caps[1]);
+    (major > 0).then_some(major)
+}
+
+/// `java_path` 是否位于运行时目录中，目录为符号链接时按实际路径比较
+fn is_inside(java_path: &str, dir: &Path) -> bool {
+    if java_path.is_empty() {
+        return false;
+    }
+    let path = Path::new(java_path);
+    if path.starts_with(dir) {
+        return true;
+    }
+    match (fs::canonicalize(path), fs::canonicalize(dir)) {
+        (Ok(path), Ok(dir)) => path.starts_with(dir),
+        _ => false,
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| dir_size(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn java(major_version: u32, vendor: &str) -> JavaInfo {
+        JavaInfo {
+            path: format!("/opt/{}-{}/bin/java", vendor, major_version),
+            version: major_version.to_string(),
+            vendor: vendor.to_string(),
+            is_64bit: true,
+            major_version,
+            modified: 0,
+        }
+    }
+
+    fn server(id: &str, java_path: &str) -> ServerInstance {
+        serde_json::from_value(serde_json::json!({
+            "id": id, "name": id, "core_type": "paper", "core_version": "", "mc_version": "1.21.1",
+            "path": "", "jar_path": "", "java_path": java_path, "max_memory": 2048,
+            "min_memory": 512, "jvm_args": [], "port": 25565, "created_at": 0,
+            "last_started_at": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn lists_runtime_size_and_servers() {
+        let root = std::env::temp_dir().join(format!("sl_runtimes_{}", uuid::Uuid::new_v4()));
+        let used = root.join("jdk-21");
+        fs::create_dir_all(used.join("bin")).unwrap();
+        fs::write(used.join("bin").join("java"), vec![0u8; 100]).unwrap();
+        let release = "JAVA_VERSION=\"21.0.5\"\nOS_ARCH=\"aarch64\"\n";
+        fs::write(used.join("release"), release).unwrap();
+        fs::create_dir_all(root.join("empty")).unwrap();
+        fs::create_dir_all(root.join(".downloads")).unwrap();
+
+        let java_path = used.join("bin").join("java").to_string_lossy().to_string();
+        let servers = [server("a", &java_path), server("b", "/usr/bin/java")];
+        let runtimes = inventory(&root, &servers);
+        assert_eq!(runtimes.len(), 2);
+        let runtime = &runtimes[1];
+        assert_eq!(runtime.name, "jdk-21");
+        assert_eq!(runtime.size, 100 + release.len() as u64);
+        assert_eq!(runtime.java.as_ref().unwrap().major_version, 21);
+        assert_eq!(runtime.servers.len(), 1);
+        assert_eq!(runtime.servers[0].id, "a");
+        assert!(runtimes[0].java.is_none() && runtimes[0].servers.is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn suggests_closest_java_for_missing_path() {
+        assert_eq!(guess_major_version("/usr/lib/jvm/java-1.8.0-openjdk/bin/java"), Some(8));
+        assert_eq!(guess_major_version("C:\\Program Files\\Zulu\\zulu17\\bin\\java.exe"), Some(17));
+        assert_eq!(guess_major_version("/opt/jdk-21.0.2/bin/java"), Some(21));
+        assert_eq!(guess_major_version("/opt/custom/bin/java"), None);
+
+        let javas = [
+            java(8, "Oracle"),
+            java(17, "Zulu"),
+            java(21, "Eclipse Adoptium"),
+            java(21, "Zulu"),
+        ];
+        let pick =
+            |path: &str, previous: Option<&JavaInfo>, requirement: Option<&JavaRequirement>| {
+                closest_java(&javas, path, previous, requirement).map(|java| java.path)
+            };
+        assert_eq!(
+            pick("/usr/lib/jvm/java-17-openjdk/bin/java", None, None).unwrap(),
+            "/opt/Zulu-17/bin/java"
+        );
+        // 同版本时优先原来的发行商
+        let previous = java(21, "zulu");
+        assert_eq!(pick("/gone/bin/java", Some(&previous), None).unwrap(), "/opt/Zulu-21/bin/java");
+        // 缺失的版本选择较新的相邻版本
+        assert_eq!(
+            pick("/opt/jdk-19/bin/java", None, None).unwrap(),
+            "/opt/Eclipse Adoptium-21/bin/java"
+        );
+        // 不满足服务器要求的版本不会被推荐
+        let requirement = java_requirements::requirement("1.21.1", "paper").unwrap();
+        assert_eq!(
+            pick("/opt/jdk-8/bin/java", None, Some(&requirement)).unwrap(),
+            "/opt/Eclipse Adoptium-21/bin/java"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+//! 按 Minecraft 版本与核心类型确定服务器需要的 Java 版本。
+//!
+//! 1.16 及更早需要 Java 8，1.17 需要 16，1.18 到 1.20.4 需要 17，1.20.5 起需要 21，
+//! 26.1 起需要 25；Forge 系核心在 1.12.2 及更早只能使用 Java 8。创建、导入时据此
+//! 选择已安装的 Java，启动时拒绝不兼容的 Java 并说明原因。
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::global;
+use crate::services::java_detector::{self, JavaInfo};
+use crate::services::java_installer;
+use crate::services::server_installer::CoreType;
+use crate::utils::mc_version;
+
+/// 基于 Forge 的核心，旧版本依赖 LaunchWrapper，无法在 Java 9+ 上运行
+const FORGE_FAMILY: [&str; 5] = ["forge", "mohist", "catserver", "spongeforge", "arclight-forge"];
+/// 代理端与基岩版核心的版本号不是 Minecraft 版本，不做检查
+const UNCHECKED_CORES: [&str; 5] = ["velocity", "bungeecord", "lightfall", "travertine", "nukkitx"];
+
+/// 服务器需要的 Java 主版本范围
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JavaRequirement {
+    pub min: u32,
+    /// 为空表示没有上限
+    pub max: Option<u32>,
+    /// 自动选择与提示安装时优先使用的版本
+    pub recommended: u32,
+    /// 要求的来源，例如 "Minecraft 1.20.5 及以上"
+    pub reason: String,
+}
+
+impl JavaRequirement {
+    pub fn accepts(&self, major_version: u32) -> bool {
+        major_version >= self.min && self.max.is_none_or(|max| major_version <= max)
+    }
+
+    /// 例如 "Java 17 及以上"、"Java 8"
+    pub fn describe(&self) -> String {
+        match self.max {
+            Some(max) if max == self.min => format!("Java {}", self.min),
+            Some(max) => format!("Java {} 到 {}", self.min, max),
+            None => format!("Java {} 及以上", self.min),
+        }
+    }
+}
+
+/// 为服务器推荐的 Java
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JavaRecommendation {
+    /// 无法确定版本要求时为空
+    pub requirement: Option<JavaRequirement>,
+    /// 已安装的 Java 中最合适的一个，没有兼容的 Java 时为空，需要安装 `recommended` 版本
+    pub selected: Option<JavaInfo>,
+    /// 所有兼容的已安装 Java
+    pub compatible: Vec<JavaInfo>,
+}
+
+/// Minecraft 版本与核心类型对应的 Java 要求；快照、未知版本与代理端返回 None
+pub fn requirement(mc_version: &str, core_type: &str) -> Option<JavaRequirement> {
+    let core_key = CoreType::normalize_to_api_core_key(core_type).unwrap_or_default();
+    if UNCHECKED_CORES.contains(&core_key.as_str()) {
+        return None;
+    }
+    let version = mc_version::parse(mc_version)?;
+    let (min, recommended, reason) = match version {
+        (1, minor, _) if minor <= 16 => (8, 8, "Minecraft 1.16.5 及更早"),
+        (1, 17, _) => (16, 17, "Minecraft 1.17"),
+        (1, minor, _) if minor <= 19 => (17, 17, "Minecraft 1.18 到 1.20.4"),
+        (1, 20, patch) if patch <= 4 => (17, 17, "Minecraft 1.18 到 1.20.4"),
+        (1, _, _) => (21, 21, "Minecraft 1.20.5 及以上"),
+        _ => (25, 25, "Minecraft 26.1 及以上"),
+    };
+    let mut requirement = JavaRequirement {
+        min,
+        max: None,
+        recommended,
+        reason: reason.to_string(),
+    };
+    if FORGE_FAMILY.contains(&core_key.as_str()) && version <= (1, 12, 2) {
+        requirement.max = Some(8);
+        requirement.reason = format!("{} 1.12.2 及更早", core_type);
+    }
+    Some(requirement)
+}
+
+/// 已知的 Java：设置中缓存的检测结果与 Sea Lantern 安装的运行时
+pub fn installed_javas() -> Vec<JavaInfo> {
+    let mut javas = java_installer::managed_runtimes()
+        .into_iter()
+        .map(|runtime| runtime.info)
+        .collect::<Vec<_>>();
+    for java in global::settings_manager().get().cached_java_list {
+        if !javas.iter().any(|j| j.path == java.path) {
+            javas.push(java);
+        }
+    }
+    javas
+}
+
+/// 选择最合适的 Java：优先推荐版本，其次是满足要求的最低版本，同版本优先 64 位
+pub fn pick_best(javas: &[JavaInfo], requirement: &JavaRequirement) -> Option<JavaInfo> {
+    javas
+        .iter()
+        .filter(|java| requirement.accepts(java.major_version))
+        .min_by_key(|java| {
+            (
+                java.major_version != requirement.recommended,
+                java.major_version,
+                !java.is_64bit,
+            )
+        })
+        .cloned()
+}
+
+pub fn recommend(mc_version: &str, core_type: &str) -> JavaRecommendation {
+    let javas = installed_javas();
+    let Some(requirement) = requirement(mc_version, core_type) else {
+        return JavaRecommendation {
+            requirement: None,
+            selected: None,
+            compatible: javas,
+        };
+    };
+    JavaRecommendation {
+        selected: pick_best(&javas, &requirement),
+        compatible: javas
+            .into_iter()
+            .filter(|java| requirement.accepts(java.major_version))
+            .collect(),
+        requirement: Some(requirement),
+    }
+}
+
+/// 创建或导入服务器时确定 Java 路径：未指定时自动选择，指定的 Java 不兼容时说明原因
+pub fn select_java(java_path: &str, mc_version: &str, core_type: &str) -> Result<String, String> {
+    let Some(requirement) = requirement(mc_version, core_type) else {
+        return Ok(java_path.to_string());
+    };
+    if java_path.trim().is_empty() {
+        return pick_best(&installed_javas(), &requirement)
+            .map(|java| java.path)
+            .ok_or_else(|| {
+                format!(
+                    "{} 需要 {}，没有找到已安装的兼容 Java，请先安装 Java {}",
+                    requirement.reason,
+                    requirement.describe(),
+                    requirement.recommended
+                )
+            });
+    }
+    let major_version = installed_javas()
+        .into_iter()
+        .find(|java| java.path == java_path)
+        .map(|java| java.major_version)
+        .or_else(|| {
+            java_detector::validate_java(java_path)
+                .ok()
+                .map(|java| java.major_version)
+        });
+    // 无法识别的路径留到启动时报错
+    if let Some(major_version) = major_version {
+        check(&requirement, major_version, java_path)?;
+    }
+    Ok(java_path.to_string())
+}
+
+/// 在阻塞线程中执行 [`select_java`]，供运行安装器等异步流程在使用 Java 之前调用
+pub async fn select_java_async(
+    java_path: &str,
+    mc_version: &str,
+    core_type: &str,
+) -> Result<String, String> {
+    let (java_path, mc_version, core_type) =
+        (java_path.to_string(), mc_version.to_string(), core_type.to_string());
+    tauri::async_runtime::spawn_blocking(move || select_java(&java_path, &mc_version, &core_type))
+        .await
+        .map_err(|e| format!("选择 Java 任务失败: {}", e))?
+}
+
+/// 检查 Java 是否满足要求，不满足时给出原因与可用的替代
+pub fn check(
+    requirement: &JavaRequirement,
+    major_version: u32,
+    java_path: &str,
+) -> Result<(), String> {
+    if requirement.accepts(major_version) {
+        return Ok(());
+    }
+    let suggestion = match pick_best(&installed_javas(), requirement) {
+        Some(java) => format!("可以改用已安装的 Java {}（{}）", java.major_version, java.path),
+        None => format!("请安装 Java {} 后在服务器设置中切换", requirement.recommended),
+    };
+    Err(format!(
+        "{} 需要 {}，当前选择的是 Java {}（{}），无法启动。{}",
+        requirement.reason,
+        requirement.describe(),
+        major_version,
+        java_path,
+        suggestion
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn java(major_version: u32, is_64bit: bool) -> JavaInfo {
+        JavaInfo {
+            path: format!("/java{}/{}", major_version, is_64bit),
+            version: major_version.to_string(),
+            vendor: "OpenJDK".to_string(),
+            is_64bit,
+            major_version,
+            modified: 0,
+        }
+    }
+
+    #[test]
+    fn maps_minecraft_versions_to_java() {
+        let min = |mc: &str, core: &str| requirement(mc, core).map(|r| (r.min, r.max));
+        assert_eq!(min("1.12.2", "Paper"), Some((8, None)));
+        assert_eq!(min("1.12.2", "Forge"), Some((8, Some(8))));
+        assert_eq!(min("1.16.5", "forge"), Some((8, None)));
+        assert_eq!(min("1.17.1", "Fabric"), Some((16, None)));
+        assert_eq!(min("1.18", "vanilla"), Some((17, None)));
+        assert_eq!(min("1.20.4", "Neoforge"), Some((17, None)));
+        assert_eq!(min("1.20.5", "Paper"), Some((21, None)));
+        assert_eq!(min("1.21.4", "Paper"), Some((21, None)));
+        assert_eq!(min("26.1", "vanilla"), Some((25, None)));
+        assert_eq!(min("24w14a", "vanilla"), None);
+        assert_eq!(min("3.3.0", "Velocity"), None);
+
+        let forge = requirement("1.12.2", "forge").unwrap();
+        assert_eq!(forge.describe(), "Java 8");
+        assert!(check(&forge, 8, "/java8").is_ok());
+        let modern = requirement("1.20.1", "forge").unwrap();
+        assert_eq!(modern.describe(), "Java 17 及以上");
+
+        let javas = vec![java(8, true), java(21, false), java(21, true), java(25, true)];
+        assert_eq!(pick_best(&javas, &modern).unwrap().major_version, 21);
+        let latest = requirement("1.21", "paper").unwrap();
+        assert_eq!(pick_best(&javas, &latest).unwrap().path, "/java21/true");
+        assert_eq!(pick_best(&javas, &forge).unwrap().major_version, 8);
+        assert!(pick_best(&[java(8, true)], &latest).is_none());
+    }
+}
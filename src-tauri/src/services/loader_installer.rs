@@ -0,0 +1,469 @@
+//! 模组加载器服务端的安装。
+//!
+//! Fabric、Quilt、Forge、NeoForge 下载官方安装器后用所选 Java 以无界面模式在服务器目录中
+//! 运行，输出逐行写入服务器日志；原版从 Mojang 版本清单下载服务端。安装完成后根据生成的
+//! `run.sh`/`run.bat`、`libraries` 目录或启动器 jar 判断启动方式。
+
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+
+use reqwest::Client;
+use serde::Deserialize;
+
+use crate::services::server_installer::CoreType;
+use crate::services::server_log_pipeline::{self, LogSource};
+use crate::utils::hash;
+
+const FABRIC_META: &str = "https://meta.fabricmc.net/v2";
+const FABRIC_INSTALLER_MAVEN: &str = "https://maven.fabricmc.net/net/fabricmc/fabric-installer";
+const QUILT_INSTALLER_MAVEN: &str =
+    "https://maven.quiltmc.org/repository/release/org/quiltmc/quilt-installer";
+const FORGE_MAVEN: &str = "https://maven.minecraftforge.net/net/minecraftforge/forge";
+const NEOFORGE_MAVEN: &str = "https://maven.neoforged.net/releases/net/neoforged";
+const MOJANG_VERSION_MANIFEST: &str =
+    "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json";
+
+/// 需要运行安装器的加载器核心（API 核心键）
+pub const LOADER_CORE_TYPES: [&str; 3] = ["forge", "neoforge", "quilt"];
+/// 安装失败时错误信息中保留的输出行数
+const ERROR_TAIL_LINES: usize = 10;
+
+/// 安装器输出写入的服务器日志。服务器可以尚未注册，日志按目录保存
+#[derive(Debug, Clone)]
+pub struct InstallLog {
+    server_id: String,
+    server_path: PathBuf,
+}
+
+impl InstallLog {
+    pub fn new(server_id: &str, server_path: &Path) -> Self {
+        InstallLog {
+            server_id: server_id.to_string(),
+            server_path: server_path.to_path_buf(),
+        }
+    }
+
+    fn output(&self, line: &str) {
+        let _ = server_log_pipeline::append_log(
+            &self.server_id,
+            &self.server_path,
+            line,
+            LogSource::Server,
+        );
+    }
+
+    fn note(&self, message: &str) {
+        let _ = server_log_pipeline::append_log(
+            &self.server_id,
+            &self.server_path,
+            message,
+            LogSource::SeaLantern,
+        );
+    }
+
+    /// 关闭日志写入，失败后删除服务器目录前需要先释放日志数据库
+    pub fn close(&self) {
+        server_log_pipeline::shutdown_writer(&self.server_id);
+    }
+}
+
+/// 需要安装的加载器
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoaderSpec {
+    /// 核心类型，与 `CoreType::as_str` 一致
+    pub core_type: String,
+    /// 加载器版本，原版为空
+    pub version: String,
+    pub mc_version: String,
+}
+
+/// 安装完成后的启动方式
+#[derive(Debug, Clone)]
+pub struct InstalledLoader {
+    pub startup_mode: String,
+    pub startup_file: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct FabricInstallerVersion {
+    version: String,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    stable: bool,
+}
+
+#[derive(Deserialize)]
+struct MojangManifest {
+    versions: Vec<MojangVersionRef>,
+}
+
+#[derive(Deserialize)]
+struct MojangVersionRef {
+    id: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct MojangVersion {
+    downloads: MojangDownloads,
+}
+
+#[derive(Deserialize)]
+struct MojangDownloads {
+    server: Option<MojangDownload>,
+}
+
+#[derive(Deserialize)]
+struct MojangDownload {
+    url: String,
+    sha1: String,
+}
+
+fn client() -> Result<Client, String> {
+    Client::builder()
+        .user_agent(concat!("SeaLantern/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(client: &Client, url: &str) -> Result<T, String> {
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("请求 {} 失败: {}", url, e))?;
+    if !resp.status().is_success() {
+        return Err(format!("请求 {} 失败: {}", url, resp.status()));
+    }
+    resp.json()
+        .await
+        .map_err(|e| format!("解析 {} 的响应失败: {}", url, e))
+}
+
+async fn download(client: &Client, url: &str, target: &Path) -> Result<(), String> {
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("下载 {} 失败: {}", url, e))?;
+    if !resp.status().is_success() {
+        return Err(format!("下载 {} 失败: {}", url, resp.status()));
+    }
+    let bytes = resp
+        .bytes()
+        .await
+        .map_err(|e| format!("下载 {} 失败: {}", url, e))?;
+    fs::write(target, &bytes).map_err(|e| format!("保存 {} 失败: {}", target.display(), e))
+}
+
+/// 安装加载器服务端，返回启动文件
+pub async fn install(
+    server_dir: &Path,
+    spec: &LoaderSpec,
+    java_path: &str,
+    log: &InstallLog,
+) -> Result<InstalledLoader, String> {
+    let client = client()?;
+    let core_type = CoreType::normalize_to_api_core_key(&spec.core_type).unwrap_or_default();
+    log.note(&format!(
+        "[Sea Lantern] 正在安装 {} {}（Minecraft {}）",
+        spec.core_type, spec.version, spec.mc_version
+    ));
+    let dir = server_dir.to_string_lossy().to_string();
+    match core_type.as_str() {
+        "fabric" => {
+            let installer = fabric_installer_url(&client).await?;
+            let args = vec![
+                "server".to_string(),
+                "-dir".to_string(),
+                dir,
+                "-mcversion".to_string(),
+                spec.mc_version.clone(),
+                "-loader".to_string(),
+                spec.version.clone(),
+                "-downloadMinecraft".to_string(),
+            ];
+            run_installer(&client, server_dir, &installer, java_path, args, log).await?;
+            find_startup(server_dir, &core_type)
+        }
+        "quilt" => {
+            let installer = quilt_installer_url(&client).await?;
+            let args = vec![
+                "install".to_string(),
+                "server".to_string(),
+                spec.mc_version.clone(),
+                spec.version.clone(),
+                format!("--install-dir={}", dir),
+                "--download-server".to_string(),
+            ];
+            run_installer(&client, server_dir, &installer, java_path, args, log).await?;
+            find_startup(server_dir, &core_type)
+        }
+        "forge" | "neoforge" => {
+            let installer = if core_type == "forge" {
+                forge_installer_url(&spec.mc_version, &spec.version)
+            } else {
+                neoforge_installer_url(&spec.mc_version, &spec.version)
+            };
+            let args = vec!["--installServer".to_string(), dir];
+            run_installer(&client, server_dir, &installer, java_path, args, log).await?;
+            find_startup(server_dir, &core_type)
+        }
+        "vanilla" => install_vanilla(&client, server_dir, &spec.mc_version).await,
+        _ => Err(format!("暂不支持自动安装 {} 服务端", spec.core_type)),
+    }
+}
+
+async fn fabric_installer_url(client: &Client) -> Result<String, String> {
+    let installers: Vec<FabricInstallerVersion> =
+        get_json(client, &format!("{}/versions/installer", FABRIC_META)).await?;
+    let installer = installers
+        .iter()
+        .find(|v| v.stable)
+        .or_else(|| installers.first())
+        .ok_or_else(|| "未找到 Fabric 安装器版本".to_string())?;
+    Ok(installer.url.clone().unwrap_or_else(|| {
+        format!("{0}/{1}/fabric-installer-{1}.jar", FABRIC_INSTALLER_MAVEN, installer.version)
+    }))
+}
+
+async fn install_vanilla(
+    client: &Client,
+    server_dir: &Path,
+    mc_version: &str,
+) -> Result<InstalledLoader, String> {
+    let manifest: MojangManifest = get_json(client, MOJANG_VERSION_MANIFEST).await?;
+    let version_ref = manifest
+        .versions
+        .iter()
+        .find(|v| v.id == mc_version)
+        .ok_or_else(|| format!("Mojang 版本清单中没有 {}", mc_version))?;
+    let version: MojangVersion = get_json(client, &version_ref.url).await?;
+    let server = version
+        .downloads
+        .server
+        .ok_or_else(|| format!("{} 没有提供服务端", mc_version))?;
+
+    let target = server_dir.join("server.jar");
+    download(client, &server.url, &target).await?;
+    let actual = hash::sha1_file(&target)?;
+    if !actual.eq_ignore_ascii_case(&server.sha1) {
+        let _ = fs::remove_file(&target);
+        return Err(format!("服务端校验失败，期望 {}，实际 {}", server.sha1, actual));
+    }
+    Ok(InstalledLoader {
+        startup_mode: "jar".to_string(),
+        startup_file: target,
+    })
+}
+
+async fn quilt_installer_url(client: &Client) -> Result<String, String> {
+    let url = format!("{}/maven-metadata.xml", QUILT_INSTALLER_MAVEN);
+    let metadata = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("请求 {} 失败: {}", url, e))?
+        .text()
+        .await
+        .map_err(|e| format!("读取 {} 失败: {}", url, e))?;
+    let version = metadata
+        .split("<release>")
+        .nth(1)
+        .and_then(|rest| rest.split("</release>").next())
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| "未找到 Quilt 安装器版本".to_string())?;
+    Ok(format!("{0}/{1}/quilt-installer-{1}.jar", QUILT_INSTALLER_MAVEN, version))
+}
+
+fn forge_installer_url(mc_version: &str, version: &str) -> String {
+    let full = format!("{}-{}", mc_version, version);
+    format!("{0}/{1}/forge-{1}-installer.jar", FORGE_MAVEN, full)
+}
+
+/// NeoForge 在 1.20.1 上沿用 Forge 的坐标
+fn neoforge_installer_url(mc_version: &str, version: &str) -> String {
+    if mc_version == "1.20.1" {
+        let full = format!("1.20.1-{}", version.trim_start_matches("1.20.1-"));
+        format!("{0}/forge/{1}/forge-{1}-installer.jar", NEOFORGE_MAVEN, full)
+    } else {
+        format!("{0}/neoforge/{1}/neoforge-{1}-installer.jar", NEOFORGE_MAVEN, version)
+    }
+}
+
+/// 下载安装器并在服务器目录中运行，输出逐行写入日志，完成后删除安装器
+async fn run_installer(
+    client: &Client,
+    server_dir: &Path,
+    installer_url: &str,
+    java_path: &str,
+    args: Vec<String>,
+    log: &InstallLog,
+) -> Result<(), String> {
+    if java_path.trim().is_empty() {
+        return Err("运行加载器安装器需要 Java，请先选择 Java 路径".to_string());
+    }
+    let installer = server_dir.join("sealantern-loader-installer.jar");
+    log.note(&format!("[Sea Lantern] 正在下载安装器: {}", installer_url));
+    download(client, installer_url, &installer).await?;
+
+    let java = java_path.to_string();
+    let dir = server_dir.to_path_buf();
+    let jar = installer.clone();
+    let task_log = log.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let mut cmd = Command::new(&java);
+        cmd.arg("-jar")
+            .arg(&jar)
+            .args(&args)
+            .current_dir(&dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(target_os = "windows")]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("启动加载器安装器失败: {}", e))?;
+        let tail = Arc::new(Mutex::new(VecDeque::with_capacity(ERROR_TAIL_LINES)));
+        let stderr = child.stderr.take().map(|stderr| {
+            let (log, tail) = (task_log.clone(), tail.clone());
+            std::thread::spawn(move || stream_output(stderr, &log, &tail))
+        });
+        if let Some(stdout) = child.stdout.take() {
+            stream_output(stdout, &task_log, &tail);
+        }
+        if let Some(handle) = stderr {
+            let _ = handle.join();
+        }
+        let status = child
+            .wait()
+            .map_err(|e| format!("等待加载器安装器失败: {}", e))?;
+        let tail = tail.lock().unwrap_or_else(|e| e.into_inner());
+        Ok::<_, String>((status, tail.iter().cloned().collect::<Vec<_>>().join("\n")))
+    })
+    .await
+    .map_err(|e| format!("加载器安装任务失败: {}", e));
+
+    let _ = fs::remove_file(&installer);
+    let _ = fs::remove_file(server_dir.join("sealantern-loader-installer.jar.log"));
+    let (status, tail) = result??;
+    if !status.success() {
+        log.note(&format!("[Sea Lantern] 加载器安装失败（{}）", status));
+        return Err(format!("加载器安装失败（{}）:\n{}", status, tail));
+    }
+    log.note("[Sea Lantern] 加载器安装完成");
+    Ok(())
+}
+
+/// 逐行转发安装器输出，同时保留最后几行用于错误信息
+fn stream_output<R: Read>(reader: R, log: &InstallLog, tail: &Mutex<VecDeque<String>>) {
+    let mut reader = BufReader::new(reader);
+    let mut buffer = Vec::new();
+    loop {
+        buffer.clear();
+        match reader.read_until(b'\n', &mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {
+                let line = String::from_utf8_lossy(&buffer);
+                let line = line.trim_end_matches(['\r', '\n']);
+                if line.trim().is_empty() {
+                    continue;
+                }
+                log.output(line);
+                let mut tail = tail.lock().unwrap_or_else(|e| e.into_inner());
+                if tail.len() == ERROR_TAIL_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line.to_string());
+            }
+        }
+    }
+}
+
+/// 查找安装器生成的启动文件。新版 Forge/NeoForge 生成启动脚本、`user_jvm_args.txt` 与
+/// `libraries` 目录，旧版以及 Fabric、Quilt 生成服务端 jar
+pub fn find_startup(server_dir: &Path, core_type: &str) -> Result<InstalledLoader, String> {
+    let script = if cfg!(target_os = "windows") {
+        ("bat", "run.bat")
+    } else {
+        ("sh", "run.sh")
+    };
+    let script_layout =
+        server_dir.join("libraries").is_dir() || server_dir.join("user_jvm_args.txt").is_file();
+    if server_dir.join(script.1).is_file() && script_layout {
+        return Ok(InstalledLoader {
+            startup_mode: script.0.to_string(),
+            startup_file: server_dir.join(script.1),
+        });
+    }
+
+    let entries = fs::read_dir(server_dir).map_err(|e| format!("读取服务器目录失败: {}", e))?;
+    let mut jars = entries
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| {
+            let lower = name.to_ascii_lowercase();
+            lower.ends_with(".jar")
+                && lower.starts_with(core_type)
+                && !lower.contains("installer")
+                && !lower.contains("shim")
+        })
+        .collect::<Vec<_>>();
+    // 同时存在 universal 与普通 jar 时优先普通 jar；Fabric/Quilt 优先启动器
+    jars.sort_by_key(|name| {
+        (!name.contains("server-launch"), name.contains("universal"), name.len())
+    });
+    let jar = jars
+        .first()
+        .ok_or_else(|| format!("安装完成但未找到 {} 的启动文件", core_type))?;
+    Ok(InstalledLoader {
+        startup_mode: "jar".to_string(),
+        startup_file: server_dir.join(jar),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_script_and_launcher_layouts() {
+        let dir = std::env::temp_dir().join(format!("sl_loader_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("fabric-server-launch.jar"), "").unwrap();
+        fs::write(dir.join("fabric-server-mc.1.20.1-loader.0.15.jar"), "").unwrap();
+        let installed = find_startup(&dir, "fabric").unwrap();
+        assert_eq!(installed.startup_mode, "jar");
+        assert!(installed.startup_file.ends_with("fabric-server-launch.jar"));
+
+        // 只有脚本而没有参数文件与 libraries 时不是安装器生成的布局
+        let script = if cfg!(target_os = "windows") {
+            "run.bat"
+        } else {
+            "run.sh"
+        };
+        fs::write(dir.join(script), "").unwrap();
+        fs::write(dir.join("forge-1.20.1-47.2.0-shim.jar"), "").unwrap();
+        assert!(find_startup(&dir, "forge").is_err());
+
+        fs::create_dir_all(dir.join("libraries")).unwrap();
+        let installed = find_startup(&dir, "forge").unwrap();
+        assert!(installed.startup_file.ends_with(script));
+        assert_ne!(installed.startup_mode, "jar");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
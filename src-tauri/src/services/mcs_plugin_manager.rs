@@ -1,10 +1,136 @@
 use crate::models::mcs_plugin::*;
+use crate::services::server_installer::CoreType;
+use crate::utils::mc_version;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::io::Read;
 use std::path::Path;
 use tokio::io::AsyncWriteExt;
 use zip::ZipArchive;
 
+/// paper-plugin.yml 中与 plugin.yml 不同的部分。其余字段单独按 m_PluginConfig 解析：
+/// flatten 会先把值缓存为通用类型，`api-version: 1.20` 因此变成 1.2
+#[derive(Deserialize)]
+struct PaperPluginDescriptor {
+    #[serde(default)]
+    dependencies: PaperDependencies,
+}
+
+#[derive(Deserialize, Default)]
+struct PaperDependencies {
+    #[serde(default)]
+    server: BTreeMap<String, PaperDependency>,
+}
+
+#[derive(Deserialize)]
+struct PaperDependency {
+    /// BEFORE：依赖先于本插件加载；AFTER：本插件先加载；OMIT：不影响顺序
+    #[serde(default)]
+    load: Option<String>,
+    #[serde(default = "default_true")]
+    required: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct VelocityPluginDescriptor {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    authors: Vec<String>,
+    #[serde(default)]
+    main: Option<String>,
+    #[serde(default)]
+    dependencies: Vec<VelocityDependency>,
+}
+
+#[derive(Deserialize)]
+struct VelocityDependency {
+    id: String,
+    #[serde(default)]
+    optional: bool,
+}
+
+/// 按服务端类型决定读取插件描述文件的顺序
+fn descriptor_order(core_type: &str) -> &'static [&'static str] {
+    let core_key = CoreType::normalize_to_api_core_key(core_type).unwrap_or_default();
+    match core_key.as_str() {
+        "velocity" => &["velocity-plugin.json"],
+        "bungeecord" | "lightfall" | "travertine" => &["bungee.yml", "plugin.yml"],
+        _ => &["paper-plugin.yml", "plugin.yml", "bungee.yml", "velocity-plugin.json"],
+    }
+}
+
+fn parse_descriptor(name: &str, content: &str) -> Result<(String, m_PluginConfig), String> {
+    match name {
+        "paper-plugin.yml" => {
+            let descriptor: PaperPluginDescriptor = serde_yaml::from_str(content)
+                .map_err(|e| format!("Failed to parse config file: {}", e))?;
+            let mut config: m_PluginConfig = serde_yaml::from_str(content)
+                .map_err(|e| format!("Failed to parse config file: {}", e))?;
+            for (dependency, options) in descriptor.dependencies.server {
+                let load_after = options
+                    .load
+                    .as_deref()
+                    .is_some_and(|load| load.eq_ignore_ascii_case("AFTER"));
+                // 后加载的必需依赖同时出现在 depend 与 loadbefore 中，依赖检查只要求它存在
+                if load_after {
+                    config.loadbefore.push(dependency.clone());
+                }
+                if options.required {
+                    config.depend.push(dependency);
+                } else if !load_after {
+                    config.softdepend.push(dependency);
+                }
+            }
+            Ok(("paper".to_string(), config))
+        }
+        "velocity-plugin.json" => {
+            let descriptor: VelocityPluginDescriptor = serde_json::from_str(content)
+                .map_err(|e| format!("Failed to parse config file: {}", e))?;
+            let (soft, hard): (Vec<_>, Vec<_>) = descriptor
+                .dependencies
+                .into_iter()
+                .partition(|d| d.optional);
+            Ok((
+                "velocity".to_string(),
+                m_PluginConfig {
+                    name: Some(descriptor.name.unwrap_or_else(|| descriptor.id.clone())),
+                    version: descriptor.version,
+                    description: descriptor.description,
+                    author: None,
+                    authors: Some(descriptor.authors),
+                    main: descriptor.main,
+                    depend: hard.into_iter().map(|d| d.id).collect(),
+                    softdepend: soft.into_iter().map(|d| d.id).collect(),
+                    loadbefore: Vec::new(),
+                    provides: vec![descriptor.id],
+                    api_version: None,
+                },
+            ))
+        }
+        _ => {
+            let config: m_PluginConfig = serde_yaml::from_str(content)
+                .map_err(|e| format!("Failed to parse config file: {}", e))?;
+            let platform = if name == "bungee.yml" {
+                "bungee"
+            } else {
+                "bukkit"
+            };
+            Ok((platform.to_string(), config))
+        }
+    }
+}
+
 #[allow(non_camel_case_types)]
 pub struct m_PluginManager {
     // Add any necessary fields here
@@ -17,7 +143,11 @@ impl m_PluginManager {
         }
     }
 
-    pub async fn m_get_plugins(&self, server_path: &str) -> Result<Vec<m_PluginInfo>, String> {
+    pub async fn m_get_plugins(
+        &self,
+        server_path: &str,
+        core_type: &str,
+    ) -> Result<Vec<m_PluginInfo>, String> {
         let plugins_dir = Path::new(server_path).join("plugins");
 
         if !plugins_dir.exists() {
@@ -49,8 +179,8 @@ impl m_PluginManager {
                         file_name.replace(".disabled", "")
                     };
 
-                    match self.m_parse_plugin_jar(&path) {
-                        Ok(plugin_config) => {
+                    match self.m_parse_plugin_jar(&path, core_type) {
+                        Ok((platform, plugin_config)) => {
                             // 使用插件在配置中定义的名称来查找配置文件夹
                             let plugin_name = plugin_config
                                 .name
@@ -97,6 +227,12 @@ impl m_PluginManager {
                                     .unwrap_or_else(|| "Unknown".to_string()),
                                 has_config_folder,
                                 config_files,
+                                platform,
+                                depend: plugin_config.depend,
+                                softdepend: plugin_config.softdepend,
+                                loadbefore: plugin_config.loadbefore,
+                                provides: plugin_config.provides,
+                                api_version: plugin_config.api_version,
                             });
                         }
                         Err(_) => {
@@ -110,6 +246,16 @@ impl m_PluginManager {
         Ok(plugins)
     }
 
+    pub async fn m_check_plugin_dependencies(
+        &self,
+        server_path: &str,
+        core_type: &str,
+        mc_version: &str,
+    ) -> Result<m_PluginDependencyReport, String> {
+        let plugins = self.m_get_plugins(server_path, core_type).await?;
+        Ok(m_build_dependency_report(plugins, mc_version))
+    }
+
     pub fn m_get_plugin_config_files(
         &self,
         server_path: &str,
@@ -215,30 +361,29 @@ impl m_PluginManager {
         Ok(())
     }
 
-    fn m_parse_plugin_jar(&self, jar_path: &Path) -> Result<m_PluginConfig, String> {
+    pub fn m_parse_plugin_jar(
+        &self,
+        jar_path: &Path,
+        core_type: &str,
+    ) -> Result<(String, m_PluginConfig), String> {
         let file =
             fs::File::open(jar_path).map_err(|e| format!("Failed to open plugin jar: {}", e))?;
         let mut zip =
             ZipArchive::new(file).map_err(|e| format!("Failed to read plugin jar: {}", e))?;
 
-        // Try to find plugin.yml or bungee.yml
-        for i in 0..zip.len() {
-            let mut file = zip
-                .by_index(i)
-                .map_err(|e| format!("Failed to read zip entry: {}", e))?;
-
-            if file.name() == "plugin.yml" || file.name() == "bungee.yml" {
-                let mut content = String::new();
-                file.read_to_string(&mut content)
-                    .map_err(|e| format!("Failed to read config file: {}", e))?;
-
-                let config: m_PluginConfig = serde_yaml::from_str(&content)
-                    .map_err(|e| format!("Failed to parse config file: {}", e))?;
-                return Ok(config);
-            }
+        // 同一个 jar 可能同时带有多个平台的描述文件
+        for name in descriptor_order(core_type) {
+            let mut file = match zip.by_name(name) {
+                Ok(file) => file,
+                Err(_) => continue,
+            };
+            let mut content = String::new();
+            file.read_to_string(&mut content)
+                .map_err(|e| format!("Failed to read config file: {}", e))?;
+            return parse_descriptor(name, &content);
         }
 
-        Err("No plugin.yml or bungee.yml found in jar".to_string())
+        Err("No plugin descriptor found in jar".to_string())
     }
 
     fn m_scan_plugin_config_files(&self, plugin_dir: &Path) -> Vec<m_PluginConfigFile> {
@@ -283,3 +428,310 @@ impl m_PluginManager {
         config_files
     }
 }
+
+fn dependency_issue(
+    severity: &str,
+    kind: &str,
+    plugin: &m_PluginInfo,
+    related: Vec<String>,
+    message: String,
+) -> m_PluginDependencyIssue {
+    m_PluginDependencyIssue {
+        severity: severity.to_string(),
+        kind: kind.to_string(),
+        file_name: plugin.file_name.clone(),
+        plugin: plugin.name.clone(),
+        related,
+        message,
+    }
+}
+
+/// 强连通分量中包含多个插件的组，即依赖循环
+fn find_cycles(names: &[String], edges: &[&m_PluginDependencyEdge]) -> Vec<Vec<String>> {
+    let mut adjacency: HashMap<&str, Vec<&str>> = HashMap::new();
+    for edge in edges {
+        adjacency
+            .entry(edge.from.as_str())
+            .or_default()
+            .push(edge.to.as_str());
+    }
+    let reachable = |start: &str| {
+        let mut seen = std::collections::HashSet::new();
+        let mut stack = vec![start];
+        while let Some(node) = stack.pop() {
+            for next in adjacency.get(node).into_iter().flatten() {
+                if seen.insert(*next) {
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    };
+    let reach = names
+        .iter()
+        .map(|name| (name.as_str(), reachable(name)))
+        .collect::<HashMap<_, _>>();
+
+    let mut assigned = std::collections::HashSet::new();
+    let mut cycles = Vec::new();
+    for name in names {
+        if assigned.contains(name.as_str()) || !reach[name.as_str()].contains(name.as_str()) {
+            continue;
+        }
+        let mut members = names
+            .iter()
+            .filter(|other| {
+                reach[name.as_str()].contains(other.as_str())
+                    && reach[other.as_str()].contains(name.as_str())
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        members.sort();
+        for member in &members {
+            assigned.insert(member.clone());
+        }
+        cycles.push(members);
+    }
+    cycles
+}
+
+/// 根据插件的 depend / softdepend / loadbefore 建立加载顺序图并检查问题：
+/// 缺少必需前置、前置被禁用、依赖循环，以及 api-version 高于服务器版本
+pub fn m_build_dependency_report(
+    plugins: Vec<m_PluginInfo>,
+    mc_version: &str,
+) -> m_PluginDependencyReport {
+    let key = |value: &str| value.to_ascii_lowercase();
+    let enabled = plugins.iter().filter(|p| p.enabled).collect::<Vec<_>>();
+
+    // 插件名与 provides 都可以满足依赖，名称不区分大小写
+    let mut providers: HashMap<String, &m_PluginInfo> = HashMap::new();
+    for plugin in &enabled {
+        providers.insert(key(&plugin.name), plugin);
+    }
+    for plugin in &enabled {
+        for alias in &plugin.provides {
+            providers.entry(key(alias)).or_insert(plugin);
+        }
+    }
+    let mut disabled: HashMap<String, &m_PluginInfo> = HashMap::new();
+    for plugin in plugins.iter().filter(|p| !p.enabled) {
+        for name in std::iter::once(&plugin.name).chain(plugin.provides.iter()) {
+            disabled.entry(key(name)).or_insert(plugin);
+        }
+    }
+
+    let mut edges = Vec::new();
+    let mut issues = Vec::new();
+    let mut push_edge = |from: &str, to: &str, kind: &str| {
+        if from != to {
+            edges.push(m_PluginDependencyEdge {
+                from: from.to_string(),
+                to: to.to_string(),
+                kind: kind.to_string(),
+            });
+        }
+    };
+
+    for plugin in &enabled {
+        for dependency in &plugin.depend {
+            if let Some(target) = providers.get(&key(dependency)) {
+                // 同时在 loadbefore 中的依赖在本插件之后加载，由下面的 loadbefore 建边
+                if !plugin.loadbefore.iter().any(|l| key(l) == key(dependency)) {
+                    push_edge(&plugin.name, &target.name, "depend");
+                }
+            } else if let Some(target) = disabled.get(&key(dependency)) {
+                issues.push(dependency_issue(
+                    "error",
+                    "disabled_dependency",
+                    plugin,
+                    vec![target.name.clone()],
+                    format!(
+                        "{} 依赖的 {} 已被禁用（{}），启用它或同时禁用 {}",
+                        plugin.name, dependency, target.file_name, plugin.name
+                    ),
+                ));
+            } else {
+                issues.push(dependency_issue(
+                    "error",
+                    "missing_dependency",
+                    plugin,
+                    vec![dependency.clone()],
+                    format!("{} 缺少必需的前置插件 {}", plugin.name, dependency),
+                ));
+            }
+        }
+        for dependency in &plugin.softdepend {
+            if let Some(target) = providers.get(&key(dependency)) {
+                push_edge(&plugin.name, &target.name, "softdepend");
+            }
+        }
+        // loadbefore 表示目标插件在本插件之后加载
+        for target in &plugin.loadbefore {
+            if let Some(target) = providers.get(&key(target)) {
+                push_edge(&target.name, &plugin.name, "loadbefore");
+            }
+        }
+
+        let checks_api = matches!(plugin.platform.as_str(), "bukkit" | "paper")
+            && mc_version::parse(mc_version).is_some();
+        if let (true, Some(api_version)) = (checks_api, &plugin.api_version) {
+            if mc_version::compare(api_version, mc_version) == std::cmp::Ordering::Greater {
+                issues.push(dependency_issue(
+                    "error",
+                    "api_version_too_new",
+                    plugin,
+                    Vec::new(),
+                    format!(
+                        "{} 需要 API 版本 {}，高于服务器的 {}，服务端会拒绝加载",
+                        plugin.name, api_version, mc_version
+                    ),
+                ));
+            }
+        }
+    }
+
+    // 必需依赖构成的循环会导致插件无法加载，只涉及可选依赖的循环由服务端自行打破
+    let names = enabled.iter().map(|p| p.name.clone()).collect::<Vec<_>>();
+    let hard_edges = edges
+        .iter()
+        .filter(|e| e.kind == "depend")
+        .collect::<Vec<_>>();
+    let all_edges = edges.iter().collect::<Vec<_>>();
+    let hard_cycles = find_cycles(&names, &hard_edges);
+    for cycle in find_cycles(&names, &all_edges) {
+        let is_hard = hard_cycles
+            .iter()
+            .any(|hard| hard.iter().all(|name| cycle.contains(name)));
+        let Some(plugin) = enabled.iter().find(|p| p.name == cycle[0]) else {
+            continue;
+        };
+        let (severity, description) = if is_hard {
+            ("error", "必需依赖")
+        } else {
+            ("warning", "加载顺序")
+        };
+        issues.push(dependency_issue(
+            severity,
+            "dependency_cycle",
+            plugin,
+            cycle.clone(),
+            format!("{}存在循环：{}", description, cycle.join(" → ")),
+        ));
+    }
+
+    let has_errors = issues.iter().any(|i| i.severity == "error");
+    m_PluginDependencyReport { plugins, edges, issues, has_errors }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin(name: &str, enabled: bool, depend: &[&str], soft: &[&str]) -> m_PluginInfo {
+        m_PluginInfo {
+            m_id: name.to_string(),
+            name: name.to_string(),
+            version: "1.0".to_string(),
+            description: String::new(),
+            author: String::new(),
+            file_name: format!("{}.jar", name),
+            file_size: 0,
+            enabled,
+            main_class: String::new(),
+            has_config_folder: false,
+            config_files: Vec::new(),
+            platform: "bukkit".to_string(),
+            depend: depend.iter().map(|s| s.to_string()).collect(),
+            softdepend: soft.iter().map(|s| s.to_string()).collect(),
+            loadbefore: Vec::new(),
+            provides: Vec::new(),
+            api_version: None,
+        }
+    }
+
+    #[test]
+    fn parses_descriptors_and_reports_graph_problems() {
+        let (platform, paper) = parse_descriptor(
+            "paper-plugin.yml",
+            "name: Shop\nversion: '2'\nmain: a.Shop\napi-version: '1.21'\n\
+             dependencies:\n  server:\n    Vault:\n      load: BEFORE\n    \
+             Maps:\n      required: false\n    Late:\n      load: AFTER\n    \
+             Hook:\n      load: AFTER\n      required: false\n",
+        )
+        .unwrap();
+        assert_eq!(platform, "paper");
+        assert_eq!(paper.depend, vec!["Late", "Vault"]);
+        assert_eq!(paper.softdepend, vec!["Maps"]);
+        assert_eq!(paper.loadbefore, vec!["Hook", "Late"]);
+        assert_eq!(paper.api_version.as_deref(), Some("1.21"));
+
+        for version in ["1.20", "1.21"] {
+            let (_, unquoted) = parse_descriptor(
+                "paper-plugin.yml",
+                &format!("name: Map\nmain: a.Map\napi-version: {}\n", version),
+            )
+            .unwrap();
+            assert_eq!(unquoted.api_version.as_deref(), Some(version));
+            let (_, bukkit) = parse_descriptor(
+                "plugin.yml",
+                &format!("name: Map\nmain: a.Map\napi-version: {}\n", version),
+            )
+            .unwrap();
+            assert_eq!(bukkit.api_version.as_deref(), Some(version));
+        }
+
+        let (_, bungee) =
+            parse_descriptor("bungee.yml", "name: Chat\nmain: a.Chat\ndepends: LuckPerms\n")
+                .unwrap();
+        assert_eq!(bungee.depend, vec!["LuckPerms"]);
+
+        let (_, velocity) = parse_descriptor(
+            "velocity-plugin.json",
+            r#"{"id":"proxy-chat","main":"a.B","dependencies":[{"id":"luckperms","optional":true}]}"#,
+        )
+        .unwrap();
+        assert_eq!(velocity.softdepend, vec!["luckperms"]);
+        assert_eq!(velocity.provides, vec!["proxy-chat"]);
+
+        let mut shop = plugin("Shop", true, &["Vault", "Economy"], &[]);
+        shop.api_version = Some("1.21".to_string());
+        let report = m_build_dependency_report(
+            vec![
+                shop,
+                plugin("Vault", false, &[], &[]),
+                plugin("A", true, &["B"], &[]),
+                plugin("B", true, &["A"], &[]),
+                plugin("C", true, &[], &["D"]),
+                plugin("D", true, &[], &["C"]),
+            ],
+            "1.20.4",
+        );
+        let kinds = report
+            .issues
+            .iter()
+            .map(|i| (i.kind.as_str(), i.severity.as_str(), i.plugin.as_str()))
+            .collect::<Vec<_>>();
+        assert!(kinds.contains(&("disabled_dependency", "error", "Shop")));
+        assert!(kinds.contains(&("missing_dependency", "error", "Shop")));
+        assert!(kinds.contains(&("api_version_too_new", "error", "Shop")));
+        assert!(kinds.contains(&("dependency_cycle", "error", "A")));
+        assert!(kinds.contains(&("dependency_cycle", "warning", "C")));
+        assert_eq!(report.issues.len(), 5);
+        assert!(report.has_errors);
+
+        // 后加载的必需依赖只检查是否存在，不构成循环
+        let mut shop = plugin("Shop", true, &["Late"], &[]);
+        shop.loadbefore = vec!["Late".to_string()];
+        let report = m_build_dependency_report(vec![shop.clone()], "1.20.4");
+        assert_eq!(report.issues[0].kind, "missing_dependency");
+        let report =
+            m_build_dependency_report(vec![shop, plugin("Late", true, &[], &[])], "1.20.4");
+        assert!(report.issues.is_empty());
+        assert_eq!(report.edges.len(), 1);
+        assert_eq!(
+            (report.edges[0].from.as_str(), report.edges[0].kind.as_str()),
+            ("Late", "loadbefore")
+        );
+    }
+}
@@ -1,19 +1,45 @@
 pub mod async_loader;
+pub mod config_document;
+pub mod config_document_json5;
+pub mod config_document_yaml;
+pub mod config_history;
 pub mod config_parser;
+pub mod content_updates;
+pub mod core_catalog;
+pub mod core_upgrade;
+pub mod crash_analyzer;
+pub mod datapack_manager;
 pub mod download_manager;
 pub mod global;
 pub mod i18n;
+pub mod install_records;
+pub mod java_catalog;
 pub mod java_detector;
 pub mod java_installer;
+pub mod java_inventory;
+pub mod java_requirements;
 pub mod join_manager;
+pub mod loader_installer;
 pub mod mcs_plugin_manager;
 pub mod mod_manager;
+pub mod mod_metadata;
+pub mod mod_preflight;
+pub mod modpack_import;
 #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
 pub mod panic_report;
 pub mod player_manager;
+pub mod plugin_repository;
+pub mod region_checker;
+pub mod region_file;
+pub mod resource_pack_host;
+pub mod server_export;
 pub mod server_id_manager;
 pub mod server_installer;
 pub mod server_log_pipeline;
 pub mod server_manager;
+pub mod server_properties_schema;
+pub mod server_readiness;
 pub mod settings_manager;
 pub mod starter_installer_links;
+pub mod update_rollback;
+pub mod world_pruner;
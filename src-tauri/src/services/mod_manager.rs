@@ -1,156 +1,487 @@
+//! Modrinth 模组搜索、依赖解析与安装。
+//!
+//! 安装的模组记录在服务器目录的 `.sealantern/mods.json` 中，保存项目与版本 ID，
+//! 以便之后检查更新或移除。
+
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::Path;
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-#[allow(dead_code)]
-pub struct ModInfo {
-    pub id: String,
-    pub name: String,
-    pub summary: String,
-    pub download_url: String,
-    pub file_name: String,
-    pub source: String, // "modrinth" or "curseforge"
-}
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::server_mod::{
+    ModInstallRecord, ModSearchPage, ModSearchQuery, ModVersion, ModVersionFile,
+};
+use crate::services::download_manager::DownloadManager;
+use crate::services::global;
+use crate::services::install_records;
+use crate::services::mod_metadata;
+
+pub const DEFAULT_MODRINTH_API: &str = "https://api.modrinth.com/v2";
+const RECORDS_FILE: &str = "mods.json";
+/// 搜索单页最多返回的数量（Modrinth 的上限）
+const MAX_SEARCH_LIMIT: u32 = 100;
 
-#[allow(dead_code)]
 pub struct ModManager {
     client: Client,
+    /// 固定的 API 地址，为空时读取设置
+    base_url: Option<String>,
 }
 
 impl ModManager {
-    #[allow(dead_code)]
     pub fn new() -> Result<Self, String> {
         Ok(ModManager {
             client: Client::builder()
-                .user_agent("SeaLantern/0.5.0 (contact@manus.im)")
+                .user_agent(concat!("SeaLantern/", env!("CARGO_PKG_VERSION")))
                 .build()
                 .map_err(|e| format!("Failed to create HTTP client: {}", e))?,
+            base_url: None,
         })
     }
 
-    #[allow(dead_code)]
-    pub async fn search_modrinth(
+    /// 使用指定的 API 地址（本地模拟服务）
+    #[cfg(test)]
+    pub fn with_base_url(base_url: &str) -> Result<Self, String> {
+        let mut manager = Self::new()?;
+        manager.base_url = Some(base_url.to_string());
+        Ok(manager)
+    }
+
+    fn api_url(&self, path: &str) -> String {
+        let base = match &self.base_url {
+            Some(base) => base.clone(),
+            None => {
+                let configured = global::settings_manager().get().modrinth_api_base_url;
+                if configured.trim().is_empty() {
+                    DEFAULT_MODRINTH_API.to_string()
+                } else {
+                    configured
+                }
+            }
+        };
+        format!("{}/{}", base.trim().trim_end_matches('/'), path)
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(
         &self,
-        query: &str,
-        game_version: &str,
-        loader: &str,
-    ) -> Result<Vec<ModInfo>, String> {
-        let facets = format!(
-            "[[\"versions:{}\"],[\"categories:{}\"],[\"project_type:mod\"]]",
-            game_version,
-            loader.to_lowercase()
-        );
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, String> {
         let resp = self
             .client
-            .get("https://api.modrinth.com/v2/search")
-            .query(&[("query", query), ("facets", facets.as_str())])
+            .get(self.api_url(path))
+            .query(query)
             .send()
             .await
-            .map_err(|e| e.to_string())?;
-        let data: ModrinthSearchResponse = resp.json().await.map_err(|e| e.to_string())?;
-
-        let mut results = Vec::new();
-        for hit in data.hits {
-            // 获取最新版本以获取下载链接
-            if let Ok(version) = self
-                .get_latest_modrinth_version(&hit.project_id, game_version, loader)
-                .await
-            {
-                results.push(ModInfo {
-                    id: hit.project_id,
-                    name: hit.title,
-                    summary: hit.description,
-                    download_url: version.url,
-                    file_name: version.filename,
-                    source: "modrinth".to_string(),
-                });
-            }
+            .map_err(|e| format!("请求 Modrinth 失败: {}", e))?;
+        if !resp.status().is_success() {
+            return Err(format!("Modrinth 返回错误 {}: {}", resp.status(), path));
         }
-        Ok(results)
+        resp.json()
+            .await
+            .map_err(|e| format!("解析 Modrinth 响应失败: {}", e))
     }
 
-    #[allow(dead_code)]
-    async fn get_latest_modrinth_version(
+    async fn post_json<T: serde::de::DeserializeOwned>(
         &self,
-        project_id: &str,
-        game_version: &str,
-        loader: &str,
-    ) -> Result<ModrinthVersionFile, String> {
-        let url = format!(
-            "https://api.modrinth.com/v2/project/{}/version?loaders=[\"{}\"]&game_versions=[\"{}\"]",
-            project_id, loader.to_lowercase(), game_version
-        );
-
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<T, String> {
         let resp = self
             .client
-            .get(&url)
+            .post(self.api_url(path))
+            .json(body)
             .send()
             .await
-            .map_err(|e| e.to_string())?;
-        let versions: Vec<ModrinthVersion> = resp.json().await.map_err(|e| e.to_string())?;
-
-        if let Some(version) = versions.first() {
-            if let Some(file) = version
-                .files
-                .iter()
-                .find(|f| f.primary)
-                .or(version.files.first())
-            {
-                return Ok(ModrinthVersionFile {
-                    url: file.url.clone(),
-                    filename: file.filename.clone(),
-                });
+            .map_err(|e| format!("请求 Modrinth 失败: {}", e))?;
+        if !resp.status().is_success() {
+            return Err(format!("Modrinth 返回错误 {}: {}", resp.status(), path));
+        }
+        resp.json()
+            .await
+            .map_err(|e| format!("解析 Modrinth 响应失败: {}", e))
+    }
+
+    /// 分页搜索。筛选条件转换为 Modrinth 的 facets，同一组内为“或”，组之间为“且”
+    pub async fn search(&self, query: &ModSearchQuery) -> Result<ModSearchPage, String> {
+        let project_type = query.project_type.as_deref().unwrap_or("mod");
+        let mut facets = vec![vec![format!("project_type:{}", project_type)]];
+        if let Some(version) = query.game_version.as_deref().filter(|v| !v.is_empty()) {
+            facets.push(vec![format!("versions:{}", version)]);
+        }
+        if let Some(loader) = query.loader.as_deref().filter(|l| !l.is_empty()) {
+            let loader = loader.to_ascii_lowercase();
+            let mut group = vec![format!("categories:{}", loader)];
+            // Quilt 服务端同样可以加载 Fabric 模组
+            if loader == "quilt" {
+                group.push("categories:fabric".to_string());
             }
+            facets.push(group);
+        }
+
+        let limit = query.limit.clamp(1, MAX_SEARCH_LIMIT);
+        let mut params = vec![
+            ("query", query.query.clone()),
+            ("facets", serde_json::to_string(&facets).unwrap_or_default()),
+            ("offset", query.offset.to_string()),
+            ("limit", limit.to_string()),
+        ];
+        if let Some(index) = query.index.as_deref().filter(|i| !i.is_empty()) {
+            params.push(("index", index.to_string()));
         }
-        Err("No compatible version found".to_string())
+        self.get_json("search", &params).await
     }
 
-    #[allow(dead_code)]
-    pub async fn download_mod(&self, download_url: &str, target_path: &Path) -> Result<(), String> {
-        let resp = self
-            .client
-            .get(download_url)
-            .send()
+    /// 项目的版本列表（最新的在前），可按游戏版本与加载器过滤
+    pub async fn get_project_versions(
+        &self,
+        project_id: &str,
+        game_version: Option<&str>,
+        loaders: &[&str],
+    ) -> Result<Vec<ModVersion>, String> {
+        let mut params = Vec::new();
+        if !loaders.is_empty() {
+            params.push(("loaders", serde_json::to_string(loaders).unwrap_or_default()));
+        }
+        if let Some(version) = game_version.filter(|v| !v.is_empty()) {
+            params.push(("game_versions", serde_json::to_string(&[version]).unwrap_or_default()));
+        }
+        self.get_json(&format!("project/{}/version", project_id), &params)
             .await
-            .map_err(|e| e.to_string())?;
-        let bytes = resp.bytes().await.map_err(|e| e.to_string())?;
+    }
+
+    pub async fn get_version(&self, version_id: &str) -> Result<ModVersion, String> {
+        self.get_json(&format!("version/{}", version_id), &[]).await
+    }
+
+    /// 按文件摘要批量查询对应的版本，返回摘要到版本的映射，未识别的文件不在其中
+    pub async fn versions_from_hashes(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+    ) -> Result<HashMap<String, ModVersion>, String> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let body = serde_json::json!({ "hashes": hashes, "algorithm": algorithm });
+        self.post_json("version_files", &body).await
+    }
+
+    /// 按文件摘要批量查询适用于指定加载器与游戏版本的最新版本
+    pub async fn latest_versions_from_hashes(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+        loaders: &[&str],
+        game_version: &str,
+    ) -> Result<HashMap<String, ModVersion>, String> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let body = serde_json::json!({
+            "hashes": hashes,
+            "algorithm": algorithm,
+            "loaders": loaders,
+            "game_versions": [game_version],
+        });
+        self.post_json("version_files/update", &body).await
+    }
+
+    /// 兼容的最新版本，优先正式版
+    pub async fn latest_version(
+        &self,
+        project_id: &str,
+        game_version: &str,
+        loaders: &[&str],
+    ) -> Result<ModVersion, String> {
+        let versions = self
+            .get_project_versions(project_id, Some(game_version), loaders)
+            .await?;
+        let release = versions.iter().position(|v| v.version_type == "release");
+        versions
+            .into_iter()
+            .nth(release.unwrap_or(0))
+            .ok_or_else(|| {
+                format!(
+                    "{} 没有适用于 Minecraft {} ({}) 的版本",
+                    project_id,
+                    game_version,
+                    loaders.join("/")
+                )
+            })
+    }
+
+    /// 从指定版本开始，递归解析必需的前置。返回的列表第一个为 `root`，
+    /// `installed` 中的项目视为已满足，不再安装
+    pub async fn resolve_dependencies(
+        &self,
+        root: ModVersion,
+        game_version: &str,
+        loaders: &[&str],
+        installed: &HashSet<String>,
+    ) -> Result<Vec<ModVersion>, String> {
+        let mut seen = HashSet::from([root.project_id.clone()]);
+        let mut queue = VecDeque::from([root]);
+        let mut plan = Vec::new();
+
+        while let Some(version) = queue.pop_front() {
+            for dep in &version.dependencies {
+                let project = dep.project_id.as_deref();
+                match dep.dependency_type.as_str() {
+                    "incompatible" => {
+                        if let Some(project) =
+                            project.filter(|p| installed.contains(*p) || seen.contains(*p))
+                        {
+                            return Err(format!(
+                                "{} 与 {} 不兼容，无法一同安装",
+                                version.project_id, project
+                            ));
+                        }
+                    }
+                    "required" => {
+                        if project.is_some_and(|p| installed.contains(p) || seen.contains(p)) {
+                            continue;
+                        }
+                        let resolved = match (&dep.version_id, project) {
+                            (Some(version_id), _) => self.get_version(version_id).await?,
+                            (None, Some(project)) => {
+                                self.latest_version(project, game_version, loaders).await?
+                            }
+                            // 只有文件名的依赖无法从 Modrinth 获取
+                            (None, None) => continue,
+                        };
+                        if seen.insert(resolved.project_id.clone())
+                            && !installed.contains(&resolved.project_id)
+                        {
+                            queue.push_back(resolved);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            plan.push(version);
+        }
+        Ok(plan)
+    }
+
+    /// 安装项目（及其前置）到服务器的 mods 目录，返回本次安装的记录
+    pub async fn install(
+        &self,
+        downloads: &DownloadManager,
+        server_path: &Path,
+        game_version: &str,
+        loaders: &[&str],
+        project_id: &str,
+        version_id: Option<&str>,
+    ) -> Result<Vec<ModInstallRecord>, String> {
+        if loaders.is_empty() {
+            return Err("当前服务端核心不支持加载模组".to_string());
+        }
+
+        let root = match version_id {
+            Some(version_id) => self.get_version(version_id).await?,
+            None => {
+                self.latest_version(project_id, game_version, loaders)
+                    .await?
+            }
+        };
+        let root_project = root.project_id.clone();
+        let installed = load_records(server_path)
+            .into_iter()
+            .map(|r| r.project_id)
+            .filter(|p| *p != root_project)
+            .collect::<HashSet<_>>();
+        let plan = self
+            .resolve_dependencies(root, game_version, loaders, &installed)
+            .await?;
+
+        let mods_dir = mod_metadata::mods_dir(server_path);
+        fs::create_dir_all(&mods_dir).map_err(|e| format!("创建 mods 目录失败: {}", e))?;
 
-        if let Some(parent) = target_path.parent() {
-            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        let mut added = Vec::new();
+        for version in plan {
+            let file = version
+                .primary_file()
+                .ok_or_else(|| format!("{} 没有可下载的文件", version.version_number))?
+                .clone();
+            validate_file_name(&file.filename)?;
+            download_verified(downloads, &file, &mods_dir.join(&file.filename)).await?;
+
+            let record = ModInstallRecord {
+                project_id: version.project_id.clone(),
+                version_id: version.id.clone(),
+                version_number: version.version_number.clone(),
+                file_name: file.filename.clone(),
+                sha1: file.hashes.sha1.clone(),
+                sha512: file.hashes.sha512.clone(),
+                source: "modrinth".to_string(),
+                required_by: (version.project_id != root_project).then(|| root_project.clone()),
+                installed_at: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs(),
+            };
+            upsert_record(server_path, record.clone())?;
+            added.push(record);
         }
+        Ok(added)
+    }
+}
+
+pub fn validate_file_name(file_name: &str) -> Result<(), String> {
+    if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name.contains("..") {
+        return Err(format!("无效的模组文件名: {}", file_name));
+    }
+    Ok(())
+}
 
-        fs::write(target_path, bytes).map_err(|e| e.to_string())?;
-        Ok(())
+/// 通过下载管理器下载到临时文件，下载器校验摘要后再移动到目标位置
+pub async fn download_verified(
+    downloads: &DownloadManager,
+    file: &ModVersionFile,
+    target: &Path,
+) -> Result<(), String> {
+    let temp = target.with_file_name(format!("{}.download", file.filename));
+    let threads = if file.size > 8 * 1024 * 1024 { 4 } else { 1 };
+    let task = downloads
+        .create_internal_task(
+            &file.url,
+            &temp.to_string_lossy(),
+            threads,
+            expected_hash(&file.hashes),
+        )
+        .await;
+    if let Err(e) = downloads.wait_for_task(task).await {
+        let _ = fs::remove_file(&temp);
+        return Err(format!("下载 {} 失败: {}", file.filename, e));
     }
+
+    fs::rename(&temp, target).map_err(|e| format!("保存 {} 失败: {}", file.filename, e))
+}
+
+/// 优先使用 SHA-512 校验，没有时退回 SHA-1，格式见 [`crate::utils::hash::verify_file`]
+fn expected_hash(hashes: &crate::models::server_mod::ModFileHashes) -> Option<String> {
+    hashes
+        .sha512
+        .as_ref()
+        .map(|sha512| format!("sha512:{}", sha512))
+        .or_else(|| hashes.sha1.as_ref().map(|sha1| format!("sha1:{}", sha1)))
 }
 
-#[derive(Deserialize)]
-struct ModrinthSearchResponse {
-    hits: Vec<ModrinthHit>,
+pub fn records_path(server_path: &Path) -> PathBuf {
+    install_records::records_path(server_path, RECORDS_FILE)
 }
 
-#[derive(Deserialize)]
-struct ModrinthHit {
-    project_id: String,
-    title: String,
-    description: String,
+pub fn load_records(server_path: &Path) -> Vec<ModInstallRecord> {
+    install_records::load(server_path, RECORDS_FILE)
 }
 
-#[derive(Deserialize)]
-struct ModrinthVersion {
-    files: Vec<ModrinthFile>,
+pub fn save_records(server_path: &Path, records: &[ModInstallRecord]) -> Result<(), String> {
+    install_records::save(server_path, RECORDS_FILE, records)
 }
 
-#[derive(Deserialize)]
-struct ModrinthFile {
-    url: String,
-    filename: String,
-    primary: bool,
+/// 写入记录。同一项目的旧版本文件名不同时删除旧文件
+fn upsert_record(server_path: &Path, record: ModInstallRecord) -> Result<(), String> {
+    let mut records = load_records(server_path);
+    if let Some(old) = records.iter().find(|r| r.project_id == record.project_id) {
+        if old.file_name != record.file_name {
+            let mods_dir = mod_metadata::mods_dir(server_path);
+            let _ = fs::remove_file(mods_dir.join(&old.file_name));
+            let _ = fs::remove_file(mods_dir.join(format!("{}.disabled", old.file_name)));
+        }
+    }
+    records.retain(|r| r.project_id != record.project_id);
+    records.push(record);
+    save_records(server_path, &records)
+}
+
+/// 删除模组文件（包括已禁用的）以及对应的安装记录
+pub fn remove_mod(server_path: &Path, file_name: &str) -> Result<(), String> {
+    let base_name = file_name.trim_end_matches(".disabled");
+    validate_file_name(base_name)?;
+
+    let mods_dir = mod_metadata::mods_dir(server_path);
+    let mut removed = false;
+    for path in [mods_dir.join(base_name), mods_dir.join(format!("{}.disabled", base_name))] {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("删除 {} 失败: {}", base_name, e))?;
+            removed = true;
+        }
+    }
+    if !removed {
+        return Err(format!("模组文件不存在: {}", base_name));
+    }
+
+    let mut records = load_records(server_path);
+    let before = records.len();
+    records.retain(|r| r.file_name != base_name);
+    if records.len() != before {
+        save_records(server_path, &records)?;
+    }
+    Ok(())
 }
 
-struct ModrinthVersionFile {
-    url: String,
-    filename: String,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_http;
+
+    fn version_json(id: &str, project: &str, deps: &str) -> String {
+        format!(
+            r#"{{"id":"{id}","project_id":"{project}","version_number":"1.0","version_type":"release",
+                "files":[{{"url":"http://localhost/{id}.jar","filename":"{id}.jar","primary":true,
+                "hashes":{{"sha1":"00"}}}}],"dependencies":{deps}}}"#
+        )
+    }
+
+    #[tokio::test]
+    async fn search_and_resolve_against_mock_api() {
+        let (base, requests) = test_http::routes(vec![
+            (
+                "/search",
+                r#"{"hits":[{"project_id":"AANobbMI","slug":"sodium","title":"Sodium","description":"fast"}],
+                    "offset":20,"limit":10,"total_hits":31}"#
+                    .to_string(),
+            ),
+            (
+                "/project/fabric-api/version",
+                format!("[{}]", version_json("api1", "fabric-api", "[]")),
+            ),
+            ("/version/lib1", version_json("lib1", "lib", "[]")),
+        ]);
+        let manager = ModManager::with_base_url(&base).unwrap();
+
+        let page = manager
+            .search(&ModSearchQuery {
+                query: "sodium".to_string(),
+                game_version: Some("1.20.1".to_string()),
+                loader: Some("quilt".to_string()),
+                project_type: None,
+                index: None,
+                offset: 20,
+                limit: 10,
+            })
+            .await
+            .unwrap();
+        assert_eq!(page.total_hits, 31);
+        assert_eq!(page.hits[0].slug, "sodium");
+        let search = requests.lock().unwrap()[0].target.clone();
+        assert!(search.contains("offset=20") && search.contains("limit=10"));
+        assert!(search.contains("categories%3Afabric"));
+
+        let root: ModVersion = serde_json::from_str(&version_json(
+            "root1",
+            "create",
+            r#"[{"project_id":"fabric-api","dependency_type":"required"},
+                {"version_id":"lib1","project_id":"lib","dependency_type":"required"},
+                {"project_id":"jei","dependency_type":"optional"}]"#,
+        ))
+        .unwrap();
+        let plan = manager
+            .resolve_dependencies(root, "1.20.1", &["fabric"], &HashSet::new())
+            .await
+            .unwrap();
+        let ids = plan.iter().map(|v| v.id.as_str()).collect::<Vec<_>>();
+        assert_eq!(ids, vec!["root1", "api1", "lib1"]);
+    }
 }
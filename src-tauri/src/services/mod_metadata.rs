@@ -0,0 +1,611 @@
+//! 读取 mods 目录中模组 jar 的元数据。
+//!
+//! 支持 `fabric.mod.json`、`quilt.mod.json`、`META-INF/neoforge.mods.toml`、
+//! `META-INF/mods.toml` 与旧版 Forge 的 `mcmod.info`。同一个 jar 带有多份元数据时，
+//! 按上述顺序取第一份作为主要信息，`loaders` 中列出全部支持的加载器。
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::models::server_mod::{InstalledMod, ModDependency};
+use crate::services::config_document::{ConfigDocument, ConfigFormat};
+
+const DISABLED_SUFFIX: &str = ".disabled";
+
+/// 单份元数据解析出的信息
+struct ModMetadata {
+    loader: &'static str,
+    mod_id: String,
+    name: String,
+    version: String,
+    description: String,
+    authors: Vec<String>,
+    mc_version_range: Option<String>,
+    loader_version_range: Option<String>,
+    dependencies: Vec<ModDependency>,
+    environment: &'static str,
+    provides: Vec<String>,
+}
+
+pub fn mods_dir(server_path: &Path) -> std::path::PathBuf {
+    server_path.join("mods")
+}
+
+fn is_mod_file(file_name: &str) -> bool {
+    let lower = file_name.to_ascii_lowercase();
+    lower.ends_with(".jar") || lower.ends_with(".jar.disabled")
+}
+
+/// 扫描 mods 目录（不递归），按文件名排序
+pub fn scan_mods(server_path: &Path) -> Result<Vec<InstalledMod>, String> {
+    let dir = mods_dir(server_path);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&dir).map_err(|e| format!("读取 mods 目录失败: {}", e))?;
+    let mut mods = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if !path.is_file() || !is_mod_file(&file_name) {
+            continue;
+        }
+        mods.push(read_installed_mod(&path, &file_name));
+    }
+    mods.sort_by_key(|m| m.file_name.to_lowercase());
+    Ok(mods)
+}
+
+fn read_installed_mod(path: &Path, file_name: &str) -> InstalledMod {
+    let enabled = !file_name.ends_with(DISABLED_SUFFIX);
+    let base_name = file_name.trim_end_matches(DISABLED_SUFFIX).to_string();
+    let file_size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+    let stem = base_name
+        .rsplit_once('.')
+        .map(|(stem, _)| stem.to_string())
+        .unwrap_or_else(|| base_name.clone());
+
+    let mut installed = InstalledMod {
+        file_name: base_name,
+        file_size,
+        enabled,
+        loaders: Vec::new(),
+        mod_id: stem.clone(),
+        name: stem,
+        version: String::new(),
+        description: String::new(),
+        authors: Vec::new(),
+        mc_version_range: None,
+        loader_version_range: None,
+        dependencies: Vec::new(),
+        environment: "both".to_string(),
+        provides: Vec::new(),
+        error: None,
+    };
+
+    match read_jar_metadata(path) {
+        Ok(metadata) => {
+            installed.loaders = metadata.iter().map(|m| m.loader.to_string()).collect();
+            installed.loaders.dedup();
+            if let Some(primary) = metadata.into_iter().next() {
+                installed.mod_id = primary.mod_id;
+                installed.name = primary.name;
+                installed.version = primary.version;
+                installed.description = primary.description;
+                installed.authors = primary.authors;
+                installed.mc_version_range = primary.mc_version_range;
+                installed.loader_version_range = primary.loader_version_range;
+                installed.dependencies = primary.dependencies;
+                installed.environment = primary.environment.to_string();
+                installed.provides = primary.provides;
+            }
+        }
+        Err(e) => installed.error = Some(e),
+    }
+    installed
+}
+
+fn read_entry<R: Read + std::io::Seek>(archive: &mut ZipArchive<R>, name: &str) -> Option<String> {
+    let mut file = archive.by_name(name).ok()?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).ok()?;
+    Some(
+        String::from_utf8_lossy(&bytes)
+            .trim_start_matches('\u{feff}')
+            .to_string(),
+    )
+}
+
+fn parse_json(text: &str) -> Result<Value, String> {
+    // 部分模组的元数据带有注释或尾随逗号，按 JSON5 宽松解析
+    Ok(ConfigDocument::parse(ConfigFormat::Json5, text)?.to_value())
+}
+
+fn read_jar_metadata(path: &Path) -> Result<Vec<ModMetadata>, String> {
+    let file = fs::File::open(path).map_err(|e| format!("打开文件失败: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法读取 jar: {}", e))?;
+    let manifest_version = read_entry(&mut archive, "META-INF/MANIFEST.MF")
+        .and_then(|manifest| manifest_value(&manifest, "Implementation-Version"));
+
+    let mut metadata = Vec::new();
+    if let Some(text) = read_entry(&mut archive, "fabric.mod.json") {
+        metadata.push(parse_fabric(&parse_json(&text)?));
+    }
+    if let Some(text) = read_entry(&mut archive, "quilt.mod.json") {
+        metadata.push(parse_quilt(&parse_json(&text)?));
+    }
+    for (entry, loader) in
+        [("META-INF/neoforge.mods.toml", "neoforge"), ("META-INF/mods.toml", "forge")]
+    {
+        if let Some(text) = read_entry(&mut archive, entry) {
+            let value = ConfigDocument::parse(ConfigFormat::Toml, &text)?.to_value();
+            if let Some(parsed) = parse_mods_toml(&value, loader, manifest_version.as_deref()) {
+                metadata.push(parsed);
+            }
+        }
+    }
+    if let Some(text) = read_entry(&mut archive, "mcmod.info") {
+        if let Some(parsed) = parse_mcmod_info(&parse_json(&text)?, manifest_version.as_deref()) {
+            metadata.push(parsed);
+        }
+    }
+    Ok(metadata)
+}
+
+fn manifest_value(manifest: &str, key: &str) -> Option<String> {
+    manifest.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        (name.trim() == key).then(|| value.trim().to_string())
+    })
+}
+
+fn str_field(value: &Value, key: &str) -> String {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// 字符串或字符串数组，数组按“任一满足”合并
+fn version_spec(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Array(items) => {
+            let parts = items.iter().filter_map(Value::as_str).collect::<Vec<_>>();
+            (!parts.is_empty()).then(|| parts.join(" || "))
+        }
+        Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+fn dependency(
+    mod_id: &str,
+    version_range: Option<String>,
+    kind: &str,
+    side: &str,
+) -> ModDependency {
+    ModDependency {
+        mod_id: mod_id.to_string(),
+        version_range,
+        kind: kind.to_string(),
+        side: side.to_string(),
+    }
+}
+
+fn find_range(dependencies: &[ModDependency], ids: &[&str]) -> Option<String> {
+    dependencies
+        .iter()
+        .find(|dep| ids.contains(&dep.mod_id.as_str()) && dep.kind == "required")
+        .and_then(|dep| dep.version_range.clone())
+}
+
+fn parse_fabric(value: &Value) -> ModMetadata {
+    let mut dependencies = Vec::new();
+    for (field, kind) in [
+        ("depends", "required"),
+        ("recommends", "optional"),
+        ("suggests", "optional"),
+        ("breaks", "incompatible"),
+        ("conflicts", "incompatible"),
+    ] {
+        if let Some(map) = value.get(field).and_then(Value::as_object) {
+            for (id, range) in map {
+                dependencies.push(dependency(id, version_spec(range), kind, "both"));
+            }
+        }
+    }
+
+    let authors = value
+        .get("authors")
+        .and_then(Value::as_array)
+        .map(|authors| {
+            authors
+                .iter()
+                .filter_map(|author| match author {
+                    Value::String(name) => Some(name.clone()),
+                    other => other
+                        .get("name")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let mod_id = str_field(value, "id");
+    let name = Some(str_field(value, "name"))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| mod_id.clone());
+    ModMetadata {
+        loader: "fabric",
+        name,
+        version: str_field(value, "version"),
+        description: str_field(value, "description"),
+        authors,
+        mc_version_range: find_range(&dependencies, &["minecraft"]),
+        loader_version_range: find_range(&dependencies, &["fabricloader"]),
+        environment: match value.get("environment").and_then(Value::as_str) {
+            Some("client") => "client",
+            Some("server") => "server",
+            _ => "both",
+        },
+        provides: value
+            .get("provides")
+            .and_then(Value::as_array)
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        dependencies,
+        mod_id,
+    }
+}
+
+fn parse_quilt(value: &Value) -> ModMetadata {
+    let loader = value.get("quilt_loader").cloned().unwrap_or(Value::Null);
+    let mut dependencies = Vec::new();
+    for (field, kind) in [("depends", "required"), ("breaks", "incompatible")] {
+        for entry in loader
+            .get(field)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            match entry {
+                Value::String(id) => dependencies.push(dependency(id, None, kind, "both")),
+                Value::Object(_) => {
+                    let optional = entry
+                        .get("optional")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false);
+                    let kind = if kind == "required" && optional {
+                        "optional"
+                    } else {
+                        kind
+                    };
+                    let range = entry.get("versions").and_then(version_spec);
+                    dependencies.push(dependency(&str_field(entry, "id"), range, kind, "both"));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let metadata = loader.get("metadata").cloned().unwrap_or(Value::Null);
+    let mod_id = str_field(&loader, "id");
+    let name = Some(str_field(&metadata, "name"))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| mod_id.clone());
+    ModMetadata {
+        loader: "quilt",
+        name,
+        version: str_field(&loader, "version"),
+        description: str_field(&metadata, "description"),
+        authors: metadata
+            .get("contributors")
+            .and_then(Value::as_object)
+            .map(|contributors| contributors.keys().cloned().collect())
+            .unwrap_or_default(),
+        mc_version_range: find_range(&dependencies, &["minecraft"]),
+        loader_version_range: find_range(&dependencies, &["quilt_loader"]),
+        environment: match value
+            .get("minecraft")
+            .and_then(|minecraft| minecraft.get("environment"))
+            .and_then(Value::as_str)
+        {
+            Some("client") => "client",
+            Some("dedicated_server") => "server",
+            _ => "both",
+        },
+        provides: loader
+            .get("provides")
+            .and_then(Value::as_array)
+            .map(|ids| {
+                ids.iter()
+                    .filter_map(|id| match id {
+                        Value::String(id) => Some(id.clone()),
+                        other => other.get("id").and_then(Value::as_str).map(str::to_string),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        dependencies,
+        mod_id,
+    }
+}
+
+/// `${file.jarVersion}` 之类的占位符由构建时替换，未替换时取 MANIFEST 中的版本
+fn resolve_version(version: String, manifest_version: Option<&str>) -> String {
+    if version.contains("${") {
+        manifest_version.map(str::to_string).unwrap_or(version)
+    } else {
+        version
+    }
+}
+
+fn parse_mods_toml(
+    value: &Value,
+    loader: &'static str,
+    manifest_version: Option<&str>,
+) -> Option<ModMetadata> {
+    let mods = value.get("mods")?.as_array()?;
+    let primary = mods.first()?;
+    let own_ids = mods
+        .iter()
+        .map(|m| str_field(m, "modId"))
+        .collect::<Vec<_>>();
+
+    let mut dependencies = Vec::new();
+    for mod_id in &own_ids {
+        let entries = value
+            .get("dependencies")
+            .and_then(|deps| deps.get(mod_id))
+            .and_then(Value::as_array);
+        for entry in entries.into_iter().flatten() {
+            let id = str_field(entry, "modId");
+            if own_ids.contains(&id) || dependencies.iter().any(|d: &ModDependency| d.mod_id == id)
+            {
+                continue;
+            }
+            // NeoForge 使用 type，旧版 Forge 使用 mandatory
+            let kind = match entry.get("type").and_then(Value::as_str) {
+                Some(kind) => match kind.to_ascii_lowercase().as_str() {
+                    "required" => "required",
+                    "incompatible" => "incompatible",
+                    _ => "optional",
+                },
+                None => {
+                    if entry
+                        .get("mandatory")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(true)
+                    {
+                        "required"
+                    } else {
+                        "optional"
+                    }
+                }
+            };
+            let side = match entry.get("side").and_then(Value::as_str) {
+                Some(side) if side.eq_ignore_ascii_case("client") => "client",
+                Some(side) if side.eq_ignore_ascii_case("server") => "server",
+                _ => "both",
+            };
+            let range = entry
+                .get("versionRange")
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            dependencies.push(dependency(&id, range, kind, side));
+        }
+    }
+
+    // 旧版 NeoForge（1.20.4 及以前）仍使用 mods.toml，通过依赖区分
+    let loader = if dependencies.iter().any(|d| d.mod_id == "neoforge") {
+        "neoforge"
+    } else {
+        loader
+    };
+    let mod_id = str_field(primary, "modId");
+    let name = Some(str_field(primary, "displayName"))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| mod_id.clone());
+    Some(ModMetadata {
+        loader,
+        name,
+        version: resolve_version(str_field(primary, "version"), manifest_version),
+        description: str_field(primary, "description").trim().to_string(),
+        authors: match primary.get("authors") {
+            Some(Value::String(authors)) => vec![authors.clone()],
+            Some(Value::Array(authors)) => authors
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect(),
+            _ => Vec::new(),
+        },
+        mc_version_range: find_range(&dependencies, &["minecraft"]),
+        loader_version_range: find_range(&dependencies, &["forge", "neoforge"]).or_else(|| {
+            value
+                .get("loaderVersion")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        }),
+        environment: if value.get("clientSideOnly").and_then(Value::as_bool) == Some(true) {
+            "client"
+        } else {
+            "both"
+        },
+        provides: own_ids.into_iter().skip(1).collect(),
+        dependencies,
+        mod_id,
+    })
+}
+
+fn parse_mcmod_info(value: &Value, manifest_version: Option<&str>) -> Option<ModMetadata> {
+    let list = match value {
+        Value::Array(list) => list,
+        other => other.get("modList")?.as_array()?,
+    };
+    let primary = list.first()?;
+
+    let mut dependencies = Vec::new();
+    for (field, kind) in [("requiredMods", "required"), ("dependencies", "optional")] {
+        for entry in primary
+            .get(field)
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let Some(entry) = entry.as_str() else {
+                continue;
+            };
+            let (id, range) = match entry.split_once('@') {
+                Some((id, range)) => (id, Some(range.to_string())),
+                None => (entry, None),
+            };
+            if !dependencies.iter().any(|d: &ModDependency| d.mod_id == id) {
+                dependencies.push(dependency(id, range, kind, "both"));
+            }
+        }
+    }
+
+    let mod_id = str_field(primary, "modid");
+    let name = Some(str_field(primary, "name"))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| mod_id.clone());
+    let mc_version = str_field(primary, "mcversion");
+    Some(ModMetadata {
+        loader: "forge",
+        name,
+        version: resolve_version(str_field(primary, "version"), manifest_version),
+        description: str_field(primary, "description"),
+        authors: primary
+            .get("authorList")
+            .or_else(|| primary.get("authors"))
+            .and_then(Value::as_array)
+            .map(|authors| {
+                authors
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default(),
+        mc_version_range: (!mc_version.is_empty() && !mc_version.contains("${"))
+            .then_some(mc_version),
+        loader_version_range: find_range(&dependencies, &["Forge", "forge"]),
+        environment: "both",
+        provides: list.iter().skip(1).map(|m| str_field(m, "modid")).collect(),
+        dependencies,
+        mod_id,
+    })
+}
+
+/// 通过追加或去掉 `.disabled` 后缀启用/禁用模组
+pub fn set_mod_enabled(server_path: &Path, file_name: &str, enabled: bool) -> Result<(), String> {
+    let base_name = file_name.trim_end_matches(DISABLED_SUFFIX);
+    if base_name.is_empty() || base_name.contains(['/', '\\']) || base_name.contains("..") {
+        return Err(format!("无效的模组文件名: {}", file_name));
+    }
+
+    let dir = mods_dir(server_path);
+    let enabled_path = dir.join(base_name);
+    let disabled_path = dir.join(format!("{}{}", base_name, DISABLED_SUFFIX));
+    let (from, to) = if enabled {
+        (disabled_path, enabled_path)
+    } else {
+        (enabled_path, disabled_path)
+    };
+
+    if to.exists() {
+        return Ok(());
+    }
+    if !from.exists() {
+        return Err(format!("模组文件不存在: {}", base_name));
+    }
+    fs::rename(&from, &to).map_err(|e| format!("切换模组状态失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_jar(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        for (name, content) in entries {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn scan_reads_fabric_and_forge_metadata() {
+        let server = std::env::temp_dir().join(format!("sl_mods_{}", uuid::Uuid::new_v4()));
+        let mods = server.join("mods");
+        fs::create_dir_all(&mods).unwrap();
+
+        write_jar(
+            &mods.join("sodium.jar"),
+            &[(
+                "fabric.mod.json",
+                r#"{"id": "sodium", "version": "0.5.8", "name": "Sodium", "environment": "client",
+                    "authors": ["JellySquid", {"name": "IMS"}],
+                    "depends": {"minecraft": ">=1.20.1", "fabricloader": ">=0.15.0"},
+                    "breaks": {"optifabric": "*"}}"#,
+            )],
+        );
+        write_jar(
+            &mods.join("jei.jar.disabled"),
+            &[
+                ("META-INF/MANIFEST.MF", "Manifest-Version: 1.0\nImplementation-Version: 15.3.0\n"),
+                (
+                    "META-INF/mods.toml",
+                    "modLoader = \"javafml\"\nloaderVersion = \"[47,)\"\n\n[[mods]]\nmodId = \"jei\"\n\
+                     version = \"${file.jarVersion}\"\ndisplayName = \"Just Enough Items\"\n\n\
+                     [[dependencies.jei]]\nmodId = \"forge\"\nmandatory = true\nversionRange = \"[47.1,)\"\n\
+                     side = \"BOTH\"\n\n[[dependencies.jei]]\nmodId = \"minecraft\"\nmandatory = true\n\
+                     versionRange = \"[1.20.1,1.20.2)\"\nside = \"BOTH\"\n",
+                ),
+            ],
+        );
+
+        let installed = scan_mods(&server).unwrap();
+        assert_eq!(installed.len(), 2);
+
+        let jei = &installed[0];
+        assert_eq!(jei.file_name, "jei.jar");
+        assert!(!jei.enabled);
+        assert_eq!(jei.loaders, vec!["forge"]);
+        assert_eq!(jei.version, "15.3.0");
+        assert_eq!(jei.mc_version_range.as_deref(), Some("[1.20.1,1.20.2)"));
+        assert_eq!(jei.loader_version_range.as_deref(), Some("[47.1,)"));
+
+        let sodium = &installed[1];
+        assert_eq!(sodium.loaders, vec!["fabric"]);
+        assert_eq!(sodium.environment, "client");
+        assert_eq!(sodium.authors, vec!["JellySquid", "IMS"]);
+        assert_eq!(sodium.mc_version_range.as_deref(), Some(">=1.20.1"));
+        assert!(sodium
+            .dependencies
+            .iter()
+            .any(|d| d.mod_id == "optifabric" && d.kind == "incompatible"));
+
+        set_mod_enabled(&server, "jei.jar", true).unwrap();
+        assert!(mods.join("jei.jar").exists());
+
+        fs::remove_dir_all(&server).unwrap();
+    }
+}
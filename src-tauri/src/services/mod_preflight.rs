@@ -0,0 +1,330 @@
+//! 启动前的模组检查。
+//!
+//! 根据 mods 目录中已启用模组的元数据，找出常见的开服崩溃原因：缺少前置、
+//! 同一模组装了多个版本、加载器与服务端核心不符、Minecraft 版本不匹配，
+//! 以及仅客户端模组。
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::models::server_mod::{InstalledMod, ModPreflightIssue, ModPreflightReport};
+use crate::services::mod_metadata;
+use crate::services::server_installer::CoreType;
+use crate::utils::mc_version;
+
+/// 由加载器或游戏本身提供、不对应 mods 目录中文件的依赖 ID
+const BUILTIN_IDS: &[&str] = &[
+    "minecraft",
+    "java",
+    "fabricloader",
+    "fabric-loader",
+    "quilt_loader",
+    "forge",
+    "neoforge",
+    "javafml",
+    "lowcodefml",
+    "mcp",
+    "fml",
+];
+
+/// 服务端核心可加载的模组类型，非模组核心返回空
+pub fn server_loaders(core_type: &str, mc_version: &str) -> Vec<&'static str> {
+    let core_key = CoreType::normalize_to_api_core_key(core_type).unwrap_or_default();
+    match core_key.as_str() {
+        "fabric" | "arclight-fabric" | "banner" => vec!["fabric"],
+        "quilt" => vec!["quilt", "fabric"],
+        "forge" | "arclight-forge" | "mohist" | "catserver" | "spongeforge" => vec!["forge"],
+        // NeoForge 在 1.20.1 上仍是 Forge 的分支，可以加载 Forge 模组
+        "neoforge" | "arclight-neoforge" | "youer" if mc_version.trim() == "1.20.1" => {
+            vec!["neoforge", "forge"]
+        }
+        "neoforge" | "arclight-neoforge" | "youer" => vec!["neoforge"],
+        _ => Vec::new(),
+    }
+}
+
+fn issue(
+    severity: &str,
+    kind: &str,
+    installed: &InstalledMod,
+    related: Option<&str>,
+    message: String,
+) -> ModPreflightIssue {
+    ModPreflightIssue {
+        severity: severity.to_string(),
+        kind: kind.to_string(),
+        file_name: installed.file_name.clone(),
+        mod_id: installed.mod_id.clone(),
+        related: related.map(str::to_string),
+        message,
+    }
+}
+
+/// 检查服务器的 mods 目录。非模组服务端返回空报告
+pub fn check(
+    server_path: &Path,
+    core_type: &str,
+    mc_version: &str,
+) -> Result<ModPreflightReport, String> {
+    let loaders = server_loaders(core_type, mc_version);
+    let mut report = ModPreflightReport {
+        loaders: loaders.iter().map(|l| l.to_string()).collect(),
+        mc_version: mc_version.to_string(),
+        checked_mods: 0,
+        issues: Vec::new(),
+        has_errors: false,
+    };
+    if loaders.is_empty() {
+        return Ok(report);
+    }
+
+    let mods = mod_metadata::scan_mods(server_path)?
+        .into_iter()
+        .filter(|m| m.enabled)
+        .collect::<Vec<_>>();
+    report.checked_mods = mods.len();
+    report.issues = check_mods(&mods, &loaders, mc_version);
+    report.has_errors = report.issues.iter().any(|i| i.severity == "error");
+    Ok(report)
+}
+
+fn check_mods(mods: &[InstalledMod], loaders: &[&str], mc_version: &str) -> Vec<ModPreflightIssue> {
+    let mut issues = Vec::new();
+    let check_mc = mc_version::parse(mc_version).is_some();
+
+    // 只有元数据可读、且能被当前加载器加载的模组才会参与依赖检查
+    let mut loadable = Vec::new();
+    for installed in mods {
+        if let Some(error) = &installed.error {
+            issues.push(issue(
+                "warning",
+                "unreadable",
+                installed,
+                None,
+                format!("{} 无法读取: {}", installed.file_name, error),
+            ));
+            continue;
+        }
+        if installed.loaders.is_empty() {
+            // 没有模组元数据，可能是依赖库，不做判断
+            continue;
+        }
+        if !installed
+            .loaders
+            .iter()
+            .any(|l| loaders.contains(&l.as_str()))
+        {
+            issues.push(issue(
+                "error",
+                "wrong_loader",
+                installed,
+                None,
+                format!(
+                    "{} 是 {} 模组，当前服务端只能加载 {} 模组",
+                    installed.name,
+                    installed.loaders.join("/"),
+                    loaders.join("/")
+                ),
+            ));
+            continue;
+        }
+        if let Some(range) = installed.mc_version_range.as_deref().filter(|_| check_mc) {
+            if !mc_version::matches_range(mc_version, range) {
+                issues.push(issue(
+                    "error",
+                    "mc_version_mismatch",
+                    installed,
+                    Some("minecraft"),
+                    format!(
+                        "{} 需要 Minecraft {}，当前服务端为 {}",
+                        installed.name, range, mc_version
+                    ),
+                ));
+            }
+        }
+        if installed.environment == "client" {
+            // Fabric/Quilt 加载器在专用服务器上会跳过仅客户端模组，其他加载器会因此崩溃
+            let issue = if loaders.contains(&"fabric") {
+                issue(
+                    "warning",
+                    "client_only",
+                    installed,
+                    None,
+                    format!("{} 是仅客户端模组，服务器不会加载它，可以禁用或删除", installed.name),
+                )
+            } else {
+                issue(
+                    "error",
+                    "client_only",
+                    installed,
+                    None,
+                    format!(
+                        "{} 是仅客户端模组，专用服务器加载时会崩溃，请禁用或删除",
+                        installed.name
+                    ),
+                )
+            };
+            issues.push(issue);
+        }
+        loadable.push(installed);
+    }
+
+    let mut by_id: HashMap<&str, Vec<&InstalledMod>> = HashMap::new();
+    for installed in &loadable {
+        by_id.entry(&installed.mod_id).or_default().push(installed);
+    }
+    for installed in &loadable {
+        let same_id = &by_id[installed.mod_id.as_str()];
+        if same_id.len() > 1 {
+            let others = same_id
+                .iter()
+                .filter(|m| m.file_name != installed.file_name)
+                .map(|m| m.file_name.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            issues.push(issue(
+                "error",
+                "duplicate_mod",
+                installed,
+                Some(&installed.mod_id),
+                format!(
+                    "{} 与 {} 是同一个模组（{}），只能保留一个",
+                    installed.file_name, others, installed.mod_id
+                ),
+            ));
+        }
+    }
+
+    // 依赖 ID 到提供者版本号；provides 中的 ID 没有可靠版本，记为空
+    let mut provided: HashMap<&str, Option<&str>> = HashMap::new();
+    for installed in &loadable {
+        provided.insert(&installed.mod_id, Some(&installed.version));
+        for id in &installed.provides {
+            provided.entry(id.as_str()).or_insert(None);
+        }
+    }
+    let builtin = BUILTIN_IDS.iter().copied().collect::<HashSet<_>>();
+
+    for installed in &loadable {
+        for dep in &installed.dependencies {
+            let dep_id = dep.mod_id.as_str();
+            if builtin.contains(dep_id.to_ascii_lowercase().as_str()) || dep.side == "client" {
+                continue;
+            }
+            let range = dep.version_range.as_deref().unwrap_or("*");
+            match (dep.kind.as_str(), provided.get(dep_id)) {
+                ("required", None) => issues.push(issue(
+                    "error",
+                    "missing_dependency",
+                    installed,
+                    Some(dep_id),
+                    format!("{} 缺少前置模组 {} ({})", installed.name, dep_id, range),
+                )),
+                ("required", Some(Some(version)))
+                    if !version.is_empty()
+                        && !version.contains("${")
+                        && !mc_version::matches_range(version, range) =>
+                {
+                    issues.push(issue(
+                        "warning",
+                        "dependency_version_mismatch",
+                        installed,
+                        Some(dep_id),
+                        format!(
+                            "{} 需要 {} {}，已安装的版本为 {}",
+                            installed.name, dep_id, range, version
+                        ),
+                    ))
+                }
+                ("incompatible", Some(version))
+                    if version.is_none_or(|v| mc_version::matches_range(v, range)) =>
+                {
+                    issues.push(issue(
+                        "error",
+                        "incompatible_mod",
+                        installed,
+                        Some(dep_id),
+                        format!("{} 与 {} 不兼容", installed.name, dep_id),
+                    ))
+                }
+                _ => {}
+            }
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::server_mod::ModDependency;
+
+    fn fabric_mod(id: &str, file_name: &str, deps: &[(&str, &str)]) -> InstalledMod {
+        InstalledMod {
+            file_name: file_name.to_string(),
+            file_size: 0,
+            enabled: true,
+            loaders: vec!["fabric".to_string()],
+            mod_id: id.to_string(),
+            name: id.to_string(),
+            version: "1.0.0".to_string(),
+            description: String::new(),
+            authors: Vec::new(),
+            mc_version_range: Some(">=1.20 <1.21".to_string()),
+            loader_version_range: None,
+            dependencies: deps
+                .iter()
+                .map(|(id, range)| ModDependency {
+                    mod_id: id.to_string(),
+                    version_range: Some(range.to_string()),
+                    kind: "required".to_string(),
+                    side: "both".to_string(),
+                })
+                .collect(),
+            environment: "both".to_string(),
+            provides: Vec::new(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn reports_common_boot_failures() {
+        let mut forge_mod = fabric_mod("jei", "jei.jar", &[]);
+        forge_mod.loaders = vec!["forge".to_string()];
+        let mut client_mod = fabric_mod("sodium", "sodium.jar", &[]);
+        client_mod.environment = "client".to_string();
+
+        let mods = vec![
+            fabric_mod("lithium", "lithium-0.11.jar", &[("minecraft", "1.20.x")]),
+            fabric_mod("lithium", "lithium-0.12.jar", &[]),
+            fabric_mod("create", "create.jar", &[("fabric-api", ">=0.90"), ("lithium", "*")]),
+            forge_mod,
+            client_mod,
+        ];
+        let issues = check_mods(&mods, &server_loaders("Fabric", "1.20.1"), "1.20.1");
+        let kinds = issues
+            .iter()
+            .map(|i| (i.kind.as_str(), i.mod_id.as_str()))
+            .collect::<Vec<_>>();
+
+        assert!(kinds.contains(&("wrong_loader", "jei")));
+        assert!(kinds.contains(&("client_only", "sodium")));
+        assert!(issues
+            .iter()
+            .any(|i| i.kind == "client_only" && i.severity == "warning"));
+        assert!(kinds.contains(&("duplicate_mod", "lithium")));
+        assert!(kinds.contains(&("missing_dependency", "create")));
+        assert!(!kinds.iter().any(|(kind, _)| *kind == "mc_version_mismatch"));
+
+        let issues = check_mods(&mods[..1], &["fabric"], "1.21.1");
+        assert_eq!(issues[0].kind, "mc_version_mismatch");
+
+        // Forge 会尝试加载仅客户端模组并崩溃
+        let mut client_mod = mods[4].clone();
+        client_mod.loaders = vec!["forge".to_string()];
+        let issues = check_mods(&[client_mod], &server_loaders("Forge", "1.20.1"), "1.20.1");
+        assert_eq!(issues[0].kind, "client_only");
+        assert_eq!(issues[0].severity, "error");
+    }
+}
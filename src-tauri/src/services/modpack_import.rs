@@ -0,0 +1,682 @@
+//! Modrinth（.mrpack）与 CurseForge 整合包的导入。
+//!
+//! 这两种整合包只包含清单与覆盖文件：按清单下载服务端需要的文件并校验摘要，依次解压
+//! `overrides`、`server-overrides`，再根据清单中的加载器安装对应服务端。核心类型与
+//! Minecraft 版本直接取自清单，导入向导不再需要用户选择。
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use futures::StreamExt;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use zip::ZipArchive;
+
+use crate::models::server::{ImportModpackRequest, ModpackInfo, ServerInstance};
+use crate::models::server_mod::{ModFileHashes, ModVersionFile};
+use crate::services::download_manager::DownloadManager;
+use crate::services::global;
+use crate::services::java_requirements;
+use crate::services::loader_installer::{self, InstallLog, LoaderSpec};
+use crate::services::mod_manager;
+use crate::services::server_installer::CoreType;
+use crate::services::server_manager::ModpackStartup;
+
+const MODRINTH_INDEX: &str = "modrinth.index.json";
+const CURSEFORGE_MANIFEST: &str = "manifest.json";
+pub const DEFAULT_CURSEFORGE_API: &str = "https://api.curseforge.com";
+const CURSEFORGE_CDN: &str = "https://edge.forgecdn.net/files";
+/// 导入后保存整合包信息的位置，相对服务器目录
+pub const PACK_INFO_FILE: &str = ".sealantern/modpack.json";
+const PARALLEL_DOWNLOADS: usize = 4;
+
+/// CurseForge 的内容分类，只有模组需要放到服务端
+const CURSEFORGE_CLASS_MODS: u32 = 6;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModrinthIndex {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    version_id: String,
+    #[serde(default)]
+    files: Vec<ModrinthFile>,
+    #[serde(default)]
+    dependencies: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModrinthFile {
+    path: String,
+    #[serde(default)]
+    hashes: ModFileHashes,
+    #[serde(default)]
+    env: Option<ModrinthEnv>,
+    #[serde(default)]
+    downloads: Vec<String>,
+    #[serde(default)]
+    file_size: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ModrinthEnv {
+    #[serde(default)]
+    server: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeManifest {
+    #[serde(default)]
+    manifest_type: String,
+    minecraft: CurseForgeMinecraft,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    files: Vec<CurseForgeFileRef>,
+    #[serde(default)]
+    overrides: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeMinecraft {
+    version: String,
+    #[serde(default)]
+    mod_loaders: Vec<CurseForgeLoader>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CurseForgeLoader {
+    id: String,
+    #[serde(default)]
+    primary: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeFileRef {
+    #[serde(rename = "projectID")]
+    project_id: u64,
+    #[serde(rename = "fileID")]
+    file_id: u64,
+    #[serde(default = "default_true")]
+    required: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+struct CurseForgeResponse<T> {
+    data: T,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeFile {
+    id: u64,
+    mod_id: u64,
+    file_name: String,
+    #[serde(default)]
+    download_url: Option<String>,
+    #[serde(default)]
+    file_length: u64,
+    #[serde(default)]
+    hashes: Vec<CurseForgeHash>,
+    #[serde(default)]
+    game_versions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct CurseForgeHash {
+    value: String,
+    algo: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CurseForgeMod {
+    id: u64,
+    #[serde(default)]
+    class_id: Option<u32>,
+}
+
+#[derive(Debug, Clone)]
+enum PackManifest {
+    Modrinth(ModrinthIndex),
+    CurseForge(CurseForgeManifest),
+}
+
+/// 已解析的整合包
+#[derive(Debug, Clone)]
+pub struct ParsedPack {
+    pub info: ModpackInfo,
+    manifest: PackManifest,
+}
+
+/// 需要下载到服务器目录的文件
+struct PackDownload {
+    path: String,
+    urls: Vec<String>,
+    size: u64,
+    hashes: ModFileHashes,
+}
+
+#[derive(Serialize)]
+struct PackInfoRecord<'a> {
+    #[serde(flatten)]
+    info: &'a ModpackInfo,
+    source_path: String,
+}
+
+/// 读取压缩包中的整合包清单。不是 Modrinth 或 CurseForge 整合包时返回 None
+pub fn read_pack(archive_path: &Path) -> Result<Option<ParsedPack>, String> {
+    if !archive_path.is_file() {
+        return Ok(None);
+    }
+    let lower = archive_path.to_string_lossy().to_ascii_lowercase();
+    if !lower.ends_with(".mrpack") && !lower.ends_with(".zip") {
+        return Ok(None);
+    }
+    let file = File::open(archive_path).map_err(|e| format!("无法打开整合包: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析整合包: {}", e))?;
+
+    if let Some(content) = read_entry(&mut archive, MODRINTH_INDEX)? {
+        let index: ModrinthIndex = serde_json::from_str(&content)
+            .map_err(|e| format!("解析 {} 失败: {}", MODRINTH_INDEX, e))?;
+        let info = modrinth_info(&index)?;
+        return Ok(Some(ParsedPack {
+            info,
+            manifest: PackManifest::Modrinth(index),
+        }));
+    }
+
+    if let Some(content) = read_entry(&mut archive, CURSEFORGE_MANIFEST)? {
+        // 服务端整合包中也可能有同名文件，只识别 CurseForge 的整合包清单
+        let Ok(manifest) = serde_json::from_str::<CurseForgeManifest>(&content) else {
+            return Ok(None);
+        };
+        if manifest.manifest_type != "minecraftModpack" {
+            return Ok(None);
+        }
+        let info = curseforge_info(&manifest)?;
+        return Ok(Some(ParsedPack {
+            info,
+            manifest: PackManifest::CurseForge(manifest),
+        }));
+    }
+    Ok(None)
+}
+
+pub fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> Result<Option<String>, String> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("读取 {} 失败: {}", name, e)),
+    };
+    let mut content = String::new();
+    entry
+        .read_to_string(&mut content)
+        .map_err(|e| format!("读取 {} 失败: {}", name, e))?;
+    Ok(Some(content))
+}
+
+fn modrinth_info(index: &ModrinthIndex) -> Result<ModpackInfo, String> {
+    let mc_version = index
+        .dependencies
+        .get("minecraft")
+        .cloned()
+        .ok_or_else(|| "整合包未声明 Minecraft 版本".to_string())?;
+    let (core_type, loader_version) = [
+        ("neoforge", CoreType::Neoforge),
+        ("forge", CoreType::Forge),
+        ("quilt-loader", CoreType::Quilt),
+        ("fabric-loader", CoreType::Fabric),
+    ]
+    .into_iter()
+    .find_map(|(key, core)| {
+        index
+            .dependencies
+            .get(key)
+            .map(|version| (core.as_str().to_string(), version.clone()))
+    })
+    .unwrap_or_else(|| (CoreType::Vanilla.as_str().to_string(), String::new()));
+
+    Ok(ModpackInfo {
+        format: "modrinth".to_string(),
+        name: index.name.clone(),
+        version: index.version_id.clone(),
+        mc_version,
+        core_type,
+        loader_version,
+        file_count: index.files.iter().filter(|f| is_server_file(f)).count(),
+    })
+}
+
+fn curseforge_info(manifest: &CurseForgeManifest) -> Result<ModpackInfo, String> {
+    let loader = manifest
+        .minecraft
+        .mod_loaders
+        .iter()
+        .find(|l| l.primary)
+        .or_else(|| manifest.minecraft.mod_loaders.first());
+    let (core_type, loader_version) = match loader {
+        Some(loader) => parse_curseforge_loader(&loader.id)?,
+        None => (CoreType::Vanilla.as_str().to_string(), String::new()),
+    };
+
+    Ok(ModpackInfo {
+        format: "curseforge".to_string(),
+        name: manifest.name.clone(),
+        version: manifest.version.clone(),
+        mc_version: manifest.minecraft.version.clone(),
+        core_type,
+        loader_version,
+        file_count: manifest.files.iter().filter(|f| f.required).count(),
+    })
+}
+
+/// CurseForge 的加载器写作 `forge-47.2.0`、`neoforge-20.4.80` 这样的形式
+fn parse_curseforge_loader(id: &str) -> Result<(String, String), String> {
+    let (name, version) = id
+        .split_once('-')
+        .ok_or_else(|| format!("无法识别的加载器: {}", id))?;
+    let core = match name.to_ascii_lowercase().as_str() {
+        "forge" => CoreType::Forge,
+        "neoforge" => CoreType::Neoforge,
+        "fabric" => CoreType::Fabric,
+        "quilt" => CoreType::Quilt,
+        _ => return Err(format!("暂不支持的加载器: {}", id)),
+    };
+    Ok((core.as_str().to_string(), version.to_string()))
+}
+
+fn is_server_file(file: &ModrinthFile) -> bool {
+    file.env
+        .as_ref()
+        .is_none_or(|env| env.server != "unsupported")
+}
+
+/// 清单中的路径只能指向服务器目录内部
+pub fn safe_relative_path(path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(path);
+    if path.is_empty()
+        || !relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(format!("整合包包含非法路径: {}", path));
+    }
+    Ok(relative.to_path_buf())
+}
+
+fn curseforge_client() -> Result<(Client, String), String> {
+    let settings = global::settings_manager().get();
+    let mut headers = reqwest::header::HeaderMap::new();
+    let key = settings.curseforge_api_key.trim();
+    if !key.is_empty() {
+        let value = reqwest::header::HeaderValue::from_str(key)
+            .map_err(|_| "CurseForge API 密钥包含非法字符".to_string())?;
+        headers.insert("x-api-key", value);
+    }
+    let client = Client::builder()
+        .user_agent(concat!("SeaLantern/", env!("CARGO_PKG_VERSION")))
+        .default_headers(headers)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let base = if settings.curseforge_api_base_url.trim().is_empty() {
+        DEFAULT_CURSEFORGE_API.to_string()
+    } else {
+        settings.curseforge_api_base_url.trim().to_string()
+    };
+    Ok((client, base.trim_end_matches('/').to_string()))
+}
+
+async fn curseforge_post<T: serde::de::DeserializeOwned>(
+    client: &Client,
+    base: &str,
+    path: &str,
+    body: serde_json::Value,
+) -> Result<T, String> {
+    let resp = client
+        .post(format!("{}/{}", base, path))
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("请求 CurseForge 失败: {}", e))?;
+    if !resp.status().is_success() {
+        return Err(format!("CurseForge 返回错误 {}: {}", resp.status(), path));
+    }
+    resp.json::<CurseForgeResponse<T>>()
+        .await
+        .map(|r| r.data)
+        .map_err(|e| format!("解析 CurseForge 响应失败: {}", e))
+}
+
+/// 查询 CurseForge 文件信息，跳过仅客户端的文件与非模组内容
+async fn curseforge_downloads(manifest: &CurseForgeManifest) -> Result<Vec<PackDownload>, String> {
+    let refs = manifest
+        .files
+        .iter()
+        .filter(|f| f.required)
+        .collect::<Vec<_>>();
+    if refs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (client, base) = curseforge_client()?;
+    let file_ids = refs.iter().map(|f| f.file_id).collect::<Vec<_>>();
+    let mod_ids = refs.iter().map(|f| f.project_id).collect::<Vec<_>>();
+    let files: Vec<CurseForgeFile> = curseforge_post(
+        &client,
+        &base,
+        "v1/mods/files",
+        serde_json::json!({ "fileIds": file_ids }),
+    )
+    .await?;
+    let mods: Vec<CurseForgeMod> =
+        curseforge_post(&client, &base, "v1/mods", serde_json::json!({ "modIds": mod_ids }))
+            .await?;
+    let classes = mods
+        .into_iter()
+        .map(|m| (m.id, m.class_id))
+        .collect::<HashMap<_, _>>();
+
+    let mut downloads = Vec::new();
+    for file in files {
+        let class_id = classes.get(&file.mod_id).copied().flatten();
+        if class_id.is_some_and(|id| id != CURSEFORGE_CLASS_MODS) {
+            continue;
+        }
+        let client_only = file.game_versions.iter().any(|v| v == "Client")
+            && !file.game_versions.iter().any(|v| v == "Server");
+        if client_only {
+            continue;
+        }
+        mod_manager::validate_file_name(&file.file_name)?;
+
+        // 作者禁止第三方分发时 downloadUrl 为空，回退到 CDN 的固定路径
+        let url = file.download_url.clone().unwrap_or_else(|| {
+            format!("{}/{}/{}/{}", CURSEFORGE_CDN, file.id / 1000, file.id % 1000, file.file_name)
+        });
+        let sha1 = file
+            .hashes
+            .iter()
+            .find(|h| h.algo == 1)
+            .map(|h| h.value.clone());
+        downloads.push(PackDownload {
+            path: format!("mods/{}", file.file_name),
+            urls: vec![url],
+            size: file.file_length,
+            hashes: ModFileHashes { sha512: None, sha1 },
+        });
+    }
+    Ok(downloads)
+}
+
+fn modrinth_downloads(index: &ModrinthIndex) -> Vec<PackDownload> {
+    index
+        .files
+        .iter()
+        .filter(|f| is_server_file(f))
+        .map(|f| PackDownload {
+            path: f.path.clone(),
+            urls: f.downloads.clone(),
+            size: f.file_size,
+            hashes: f.hashes.clone(),
+        })
+        .collect()
+}
+
+/// 依次尝试清单中的下载地址，下载后校验摘要
+async fn download_pack_file(
+    downloads: &DownloadManager,
+    run_dir: &Path,
+    file: PackDownload,
+) -> Result<(), String> {
+    let relative = safe_relative_path(&file.path)?;
+    let target = run_dir.join(&relative);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let filename = relative
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let mut last_error = format!("{} 没有可用的下载地址", file.path);
+    for url in &file.urls {
+        let version_file = ModVersionFile {
+            url: url.clone(),
+            filename: filename.clone(),
+            primary: true,
+            size: file.size,
+            hashes: file.hashes.clone(),
+        };
+        match mod_manager::download_verified(downloads, &version_file, &target).await {
+            Ok(()) => return Ok(()),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// 解压压缩包中 `prefix/` 下的内容到服务器目录，已存在的文件会被覆盖
+pub fn extract_overrides(archive_path: &Path, prefix: &str, run_dir: &Path) -> Result<(), String> {
+    let file = File::open(archive_path).map_err(|e| format!("无法打开整合包: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析整合包: {}", e))?;
+    let prefix = format!("{}/", prefix.trim_end_matches('/'));
+
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| format!("读取 ZIP 条目失败: {}", e))?;
+        let Some(relative) = entry.name().strip_prefix(&prefix) else {
+            continue;
+        };
+        if relative.is_empty() {
+            continue;
+        }
+        let relative = safe_relative_path(relative.trim_end_matches('/'))?;
+        let out_path = run_dir.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&out_path).map_err(|e| format!("创建目录失败: {}", e))?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        let mut out = File::create(&out_path).map_err(|e| format!("创建文件失败: {}", e))?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| format!("写入文件失败: {}", e))?;
+    }
+    Ok(())
+}
+
+/// 把整合包安装到空的服务器目录，返回启动方式
+async fn install_pack(
+    downloads: &DownloadManager,
+    archive_path: &Path,
+    pack: &ParsedPack,
+    run_dir: &Path,
+    java_path: &str,
+    log: &InstallLog,
+) -> Result<ModpackStartup, String> {
+    fs::create_dir_all(run_dir).map_err(|e| format!("无法创建运行目录: {}", e))?;
+
+    let (files, overrides) = match &pack.manifest {
+        PackManifest::Modrinth(index) => (
+            modrinth_downloads(index),
+            vec!["overrides".to_string(), "server-overrides".to_string()],
+        ),
+        PackManifest::CurseForge(manifest) => (
+            curseforge_downloads(manifest).await?,
+            vec![manifest
+                .overrides
+                .clone()
+                .unwrap_or_else(|| "overrides".to_string())],
+        ),
+    };
+
+    let results = futures::stream::iter(files)
+        .map(|file| download_pack_file(downloads, run_dir, file))
+        .buffer_unordered(PARALLEL_DOWNLOADS)
+        .collect::<Vec<_>>()
+        .await;
+    if let Some(Err(e)) = results.into_iter().find(|r| r.is_err()) {
+        return Err(e);
+    }
+
+    let archive = archive_path.to_path_buf();
+    let target = run_dir.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || {
+        overrides
+            .iter()
+            .try_for_each(|prefix| extract_overrides(&archive, prefix, &target))
+    })
+    .await
+    .map_err(|e| format!("解压覆盖文件任务失败: {}", e))??;
+
+    let spec = LoaderSpec {
+        core_type: pack.info.core_type.clone(),
+        version: pack.info.loader_version.clone(),
+        mc_version: pack.info.mc_version.clone(),
+    };
+    let installed = loader_installer::install(run_dir, &spec, java_path, log).await?;
+
+    let record = PackInfoRecord {
+        info: &pack.info,
+        source_path: archive_path.to_string_lossy().to_string(),
+    };
+    let info_path = run_dir.join(PACK_INFO_FILE);
+    if let Some(parent) = info_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&record)
+        .map_err(|e| format!("序列化整合包信息失败: {}", e))?;
+    fs::write(&info_path, content).map_err(|e| format!("保存整合包信息失败: {}", e))?;
+
+    Ok(ModpackStartup {
+        startup_mode: installed.startup_mode,
+        startup_file_path: Some(installed.startup_file.to_string_lossy().to_string()),
+        custom_command: None,
+        core_type: Some(pack.info.core_type.clone()),
+        core_version: pack.info.loader_version.clone(),
+        mc_version: Some(pack.info.mc_version.clone()),
+        jvm_args: Vec::new(),
+    })
+}
+
+/// 导入 Modrinth 或 CurseForge 整合包并创建服务器，失败时删除已创建的目录
+pub async fn import(
+    downloads: &DownloadManager,
+    mut req: ImportModpackRequest,
+    pack: ParsedPack,
+) -> Result<ServerInstance, String> {
+    // 安装器本身需要兼容的 Java，未指定时在运行前自动选择
+    req.java_path = java_requirements::select_java_async(
+        &req.java_path,
+        &pack.info.mc_version,
+        &pack.info.core_type,
+    )
+    .await?;
+    let manager = global::server_manager();
+    let (id, server_name, run_dir) = manager.prepare_modpack_run_dir(&req)?;
+    let archive_path = PathBuf::from(&req.modpack_path);
+    let log = InstallLog::new(&id, &run_dir);
+
+    let installed =
+        install_pack(downloads, &archive_path, &pack, &run_dir, &req.java_path, &log).await;
+    let result = match installed {
+        Ok(startup) => manager.register_modpack(req, id, server_name, run_dir.clone(), startup),
+        Err(e) => Err(e),
+    };
+    if result.is_err() {
+        log.close();
+        let _ = fs::remove_dir_all(&run_dir);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_pack(path: &Path, entries: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, content) in entries {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(content.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn reads_manifests_and_extracts_overrides() {
+        let dir = std::env::temp_dir().join(format!("sl_modpack_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mrpack = dir.join("pack.mrpack");
+        write_pack(
+            &mrpack,
+            &[
+                (
+                    MODRINTH_INDEX,
+                    r#"{"formatVersion":1,"game":"minecraft","versionId":"1.2","name":"Demo",
+                    "files":[
+                      {"path":"mods/a.jar","hashes":{"sha1":"x"},"downloads":["https://x/a.jar"]},
+                      {"path":"mods/shader.jar","hashes":{},"env":{"client":"required","server":"unsupported"},"downloads":[]}
+                    ],
+                    "dependencies":{"minecraft":"1.20.1","fabric-loader":"0.15.11"}}"#,
+                ),
+                ("overrides/config/a.toml", "client"),
+                ("overrides/options.txt", "keep"),
+                ("server-overrides/config/a.toml", "server"),
+            ],
+        );
+        let pack = read_pack(&mrpack).unwrap().unwrap();
+        assert_eq!(pack.info.format, "modrinth");
+        assert_eq!(pack.info.core_type, "Fabric");
+        assert_eq!(pack.info.loader_version, "0.15.11");
+        assert_eq!(pack.info.mc_version, "1.20.1");
+        assert_eq!(pack.info.file_count, 1);
+
+        let run_dir = dir.join("server");
+        extract_overrides(&mrpack, "overrides", &run_dir).unwrap();
+        extract_overrides(&mrpack, "server-overrides", &run_dir).unwrap();
+        assert_eq!(fs::read_to_string(run_dir.join("config/a.toml")).unwrap(), "server");
+        assert_eq!(fs::read_to_string(run_dir.join("options.txt")).unwrap(), "keep");
+
+        let cf = dir.join("cf.zip");
+        write_pack(
+            &cf,
+            &[(
+                CURSEFORGE_MANIFEST,
+                r#"{"manifestType":"minecraftModpack","name":"CF","version":"3",
+                "minecraft":{"version":"1.20.1","modLoaders":[{"id":"forge-47.2.0","primary":true}]},
+                "files":[{"projectID":1,"fileID":2,"required":true}],"overrides":"overrides"}"#,
+            )],
+        );
+        let pack = read_pack(&cf).unwrap().unwrap();
+        assert_eq!(pack.info.format, "curseforge");
+        assert_eq!(pack.info.core_type, "Forge");
+        assert_eq!(pack.info.loader_version, "47.2.0");
+
+        let plain = dir.join("server.zip");
+        write_pack(&plain, &[("server.jar", "")]);
+        assert!(read_pack(&plain).unwrap().is_none());
+
+        assert!(safe_relative_path("../evil.jar").is_err());
+        assert!(safe_relative_path("/etc/passwd").is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,843 @@
+//! Hangar 与 Spiget 插件仓库的搜索、安装与更新检查。
+//!
+//! Hangar 覆盖 Paper、Velocity、Waterfall 插件并提供 SHA-256；Spiget 只收录 SpigotMC
+//! 上的 Bukkit 系与 BungeeCord 资源，没有摘要，下载后只检查插件描述文件。
+//! 安装的插件记录在服务器目录的 `.sealantern/plugins.json` 中，保存来源与版本，
+//! 以便之后检查更新。
+
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::plugin_repository::{
+    PluginInstallRecord, PluginSearchHit, PluginSearchPage, PluginSearchQuery, PluginUpdate,
+    PluginVersion,
+};
+use crate::services::content_updates;
+use crate::services::download_manager::DownloadManager;
+use crate::services::global;
+use crate::services::install_records;
+use crate::utils::hash;
+
+pub const DEFAULT_HANGAR_API: &str = "https://hangar.papermc.io/api/v1";
+pub const DEFAULT_SPIGET_API: &str = "https://api.spiget.org/v2";
+const SPIGOT_SITE: &str = "https://www.spigotmc.org";
+const RECORDS_FILE: &str = "plugins.json";
+const DISABLED_SUFFIX: &str = ".disabled";
+const MAX_SEARCH_LIMIT: u32 = 25;
+
+pub const SOURCE_HANGAR: &str = "hangar";
+pub const SOURCE_SPIGET: &str = "spiget";
+
+/// 服务器的插件平台与 Minecraft 版本
+#[derive(Debug, Clone, Copy)]
+pub struct PluginTarget<'a> {
+    /// 服务端核心类型
+    pub core_type: &'a str,
+    pub mc_version: &'a str,
+}
+
+impl PluginTarget<'_> {
+    /// 对应的 Hangar 平台：PAPER / VELOCITY / WATERFALL
+    fn hangar_platform(&self) -> Result<&'static str, String> {
+        let loaders = content_updates::plugin_loaders(self.core_type);
+        if loaders.contains(&"velocity") {
+            Ok("VELOCITY")
+        } else if loaders.contains(&"waterfall") {
+            Ok("WATERFALL")
+        } else if loaders
+            .iter()
+            .any(|l| matches!(*l, "paper" | "spigot" | "bukkit" | "folia"))
+        {
+            Ok("PAPER")
+        } else {
+            Err(format!("{} 服务端不支持加载插件", self.core_type))
+        }
+    }
+
+    fn check_spiget(&self) -> Result<(), String> {
+        match self.hangar_platform()? {
+            "VELOCITY" => Err("Spiget 不提供 Velocity 插件".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Spiget 的 testedVersions 只精确到次版本号，如 `1.20`
+    fn minor_version(&self) -> String {
+        self.mc_version
+            .split('.')
+            .take(2)
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// 仓库未声明版本时视为兼容
+    fn supports(&self, game_versions: &[String]) -> bool {
+        if self.mc_version.is_empty() || game_versions.is_empty() {
+            return true;
+        }
+        let minor = self.minor_version();
+        game_versions
+            .iter()
+            .any(|v| v == self.mc_version || *v == minor)
+    }
+}
+
+#[derive(Deserialize)]
+struct HangarPage<T> {
+    pagination: HangarPagination,
+    result: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct HangarPagination {
+    count: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarProject {
+    name: String,
+    namespace: HangarNamespace,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    avatar_url: Option<String>,
+    #[serde(default)]
+    stats: HangarStats,
+    #[serde(default)]
+    supported_platforms: HashMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct HangarNamespace {
+    owner: String,
+    slug: String,
+}
+
+#[derive(Deserialize, Default)]
+struct HangarStats {
+    #[serde(default)]
+    downloads: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarVersion {
+    name: String,
+    #[serde(default)]
+    created_at: String,
+    #[serde(default)]
+    downloads: HashMap<String, HangarDownload>,
+    #[serde(default)]
+    plugin_dependencies: HashMap<String, Vec<HangarPluginDependency>>,
+    #[serde(default)]
+    platform_dependencies: HashMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarDownload {
+    #[serde(default)]
+    file_info: Option<HangarFileInfo>,
+    #[serde(default)]
+    download_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarFileInfo {
+    name: String,
+    #[serde(default)]
+    size_bytes: u64,
+    #[serde(default)]
+    sha256_hash: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HangarPluginDependency {
+    name: String,
+    #[serde(default)]
+    required: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpigetResource {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    tag: String,
+    #[serde(default)]
+    downloads: u64,
+    #[serde(default)]
+    tested_versions: Vec<String>,
+    #[serde(default)]
+    premium: bool,
+    #[serde(default)]
+    external: bool,
+    #[serde(default)]
+    file: Option<SpigetFile>,
+    #[serde(default)]
+    icon: Option<SpigetIcon>,
+}
+
+#[derive(Deserialize)]
+struct SpigetFile {
+    #[serde(rename = "type", default)]
+    file_type: String,
+}
+
+#[derive(Deserialize)]
+struct SpigetIcon {
+    #[serde(default)]
+    url: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SpigetVersion {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    release_date: i64,
+}
+
+impl SpigetResource {
+    /// 付费资源与外链资源无法通过 Spiget 下载
+    fn installable(&self) -> bool {
+        !self.premium
+            && !self.external
+            && self
+                .file
+                .as_ref()
+                .is_some_and(|f| f.file_type.eq_ignore_ascii_case(".jar"))
+    }
+}
+
+pub struct PluginRepository {
+    client: Client,
+    /// 固定的 API 地址，为空时读取设置
+    hangar_base_url: Option<String>,
+    spiget_base_url: Option<String>,
+}
+
+impl PluginRepository {
+    pub fn new() -> Result<Self, String> {
+        Ok(PluginRepository {
+            client: Client::builder()
+                .user_agent(concat!("SeaLantern/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .map_err(|e| format!("Failed to create HTTP client: {}", e))?,
+            hangar_base_url: None,
+            spiget_base_url: None,
+        })
+    }
+
+    #[cfg(test)]
+    pub fn with_base_urls(hangar: &str, spiget: &str) -> Result<Self, String> {
+        let mut repository = Self::new()?;
+        repository.hangar_base_url = Some(hangar.to_string());
+        repository.spiget_base_url = Some(spiget.to_string());
+        Ok(repository)
+    }
+
+    fn api_base(&self, source: &str) -> Result<String, String> {
+        let (fixed, default) = match source {
+            SOURCE_HANGAR => (&self.hangar_base_url, DEFAULT_HANGAR_API),
+            SOURCE_SPIGET => (&self.spiget_base_url, DEFAULT_SPIGET_API),
+            other => return Err(format!("未知的插件仓库: {}", other)),
+        };
+        let base = match fixed {
+            Some(base) => base.clone(),
+            None => {
+                let settings = global::settings_manager().get();
+                let configured = if source == SOURCE_HANGAR {
+                    settings.hangar_api_base_url
+                } else {
+                    settings.spiget_api_base_url
+                };
+                if configured.trim().is_empty() {
+                    default.to_string()
+                } else {
+                    configured
+                }
+            }
+        };
+        Ok(base.trim().trim_end_matches('/').to_string())
+    }
+
+    /// 拼接 API 地址，每一段都按路径片段编码
+    fn api_url(&self, source: &str, segments: &[&str]) -> Result<Url, String> {
+        let base = self.api_base(source)?;
+        let mut url = Url::parse(&base).map_err(|e| format!("无效的 API 地址 {}: {}", base, e))?;
+        url.path_segments_mut()
+            .map_err(|_| format!("无效的 API 地址: {}", base))?
+            .pop_if_empty()
+            .extend(segments);
+        Ok(url)
+    }
+
+    async fn get_json<T: serde::de::DeserializeOwned>(
+        &self,
+        source: &str,
+        segments: &[&str],
+        query: &[(&str, String)],
+    ) -> Result<T, String> {
+        let url = self.api_url(source, segments)?;
+        let resp = self
+            .client
+            .get(url)
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| format!("请求 {} 失败: {}", source, e))?;
+        if !resp.status().is_success() {
+            return Err(format!("{} 返回错误 {}: {}", source, resp.status(), segments.join("/")));
+        }
+        resp.json()
+            .await
+            .map_err(|e| format!("解析 {} 响应失败: {}", source, e))
+    }
+
+    /// 分页搜索，只返回适用于服务器平台与 Minecraft 版本的插件
+    pub async fn search(
+        &self,
+        target: PluginTarget<'_>,
+        query: &PluginSearchQuery,
+    ) -> Result<PluginSearchPage, String> {
+        let limit = query.limit.clamp(1, MAX_SEARCH_LIMIT);
+        match query.source.as_str() {
+            SOURCE_HANGAR => {
+                let platform = target.hangar_platform()?;
+                let mut params = vec![
+                    ("q", query.query.clone()),
+                    ("limit", limit.to_string()),
+                    ("offset", query.offset.to_string()),
+                    ("platform", platform.to_string()),
+                ];
+                if !target.mc_version.is_empty() {
+                    params.push(("version", target.mc_version.to_string()));
+                }
+                let page: HangarPage<HangarProject> =
+                    self.get_json(SOURCE_HANGAR, &["projects"], &params).await?;
+                let hits = page
+                    .result
+                    .into_iter()
+                    .map(|project| {
+                        let game_versions = project
+                            .supported_platforms
+                            .get(platform)
+                            .cloned()
+                            .unwrap_or_default();
+                        PluginSearchHit {
+                            source: SOURCE_HANGAR.to_string(),
+                            project_id: project.namespace.slug,
+                            name: project.name,
+                            author: project.namespace.owner,
+                            description: project.description.unwrap_or_default(),
+                            downloads: project.stats.downloads,
+                            icon_url: project.avatar_url,
+                            game_versions,
+                            installable: true,
+                        }
+                    })
+                    .collect();
+                Ok(PluginSearchPage {
+                    hits,
+                    offset: query.offset,
+                    limit,
+                    total: Some(page.pagination.count),
+                })
+            }
+            SOURCE_SPIGET => {
+                target.check_spiget()?;
+                let params = [
+                    ("field", "name".to_string()),
+                    ("size", limit.to_string()),
+                    ("page", (query.offset / limit + 1).to_string()),
+                    ("sort", "-downloads".to_string()),
+                ];
+                let resources: Vec<SpigetResource> = if query.query.trim().is_empty() {
+                    self.get_json(SOURCE_SPIGET, &["resources"], &params)
+                        .await?
+                } else {
+                    self.get_json(
+                        SOURCE_SPIGET,
+                        &["search", "resources", query.query.trim()],
+                        &params,
+                    )
+                    .await?
+                };
+                let hits = resources
+                    .into_iter()
+                    .filter(|r| target.supports(&r.tested_versions))
+                    .map(|resource| PluginSearchHit {
+                        source: SOURCE_SPIGET.to_string(),
+                        project_id: resource.id.to_string(),
+                        installable: resource.installable(),
+                        icon_url: resource
+                            .icon
+                            .as_ref()
+                            .filter(|icon| !icon.url.is_empty())
+                            .map(|icon| format!("{}/{}", SPIGOT_SITE, icon.url)),
+                        name: resource.name,
+                        author: String::new(),
+                        description: resource.tag,
+                        downloads: resource.downloads,
+                        game_versions: resource.tested_versions,
+                    })
+                    .collect();
+                Ok(PluginSearchPage {
+                    hits,
+                    offset: query.offset,
+                    limit,
+                    total: None,
+                })
+            }
+            other => Err(format!("未知的插件仓库: {}", other)),
+        }
+    }
+
+    /// 项目中适用于服务器的版本，最新的在前
+    pub async fn get_versions(
+        &self,
+        target: PluginTarget<'_>,
+        source: &str,
+        project_id: &str,
+    ) -> Result<Vec<PluginVersion>, String> {
+        match source {
+            SOURCE_HANGAR => {
+                let platform = target.hangar_platform()?;
+                let mut params = vec![
+                    ("limit", MAX_SEARCH_LIMIT.to_string()),
+                    ("offset", "0".to_string()),
+                    ("platform", platform.to_string()),
+                ];
+                if !target.mc_version.is_empty() {
+                    params.push(("platformVersion", target.mc_version.to_string()));
+                }
+                let page: HangarPage<HangarVersion> = self
+                    .get_json(SOURCE_HANGAR, &["projects", project_id, "versions"], &params)
+                    .await?;
+                Ok(page
+                    .result
+                    .into_iter()
+                    .filter_map(|v| hangar_version(project_id, platform, v))
+                    .filter(|v| target.supports(&v.game_versions))
+                    .collect())
+            }
+            SOURCE_SPIGET => {
+                target.check_spiget()?;
+                let resource = self.spiget_resource(project_id).await?;
+                let versions: Vec<SpigetVersion> = self
+                    .get_json(
+                        SOURCE_SPIGET,
+                        &["resources", project_id, "versions"],
+                        &[
+                            ("size", MAX_SEARCH_LIMIT.to_string()),
+                            ("sort", "-releaseDate".to_string()),
+                        ],
+                    )
+                    .await?;
+                let base = self.api_base(SOURCE_SPIGET)?;
+                Ok(versions
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, version)| {
+                        // 只有最新版本可以通过 /download 直接下载，旧版本使用版本下载接口
+                        let download_url = if index == 0 {
+                            format!("{}/resources/{}/download", base, resource.id)
+                        } else {
+                            format!(
+                                "{}/resources/{}/versions/{}/download",
+                                base, resource.id, version.id
+                            )
+                        };
+                        PluginVersion {
+                            source: SOURCE_SPIGET.to_string(),
+                            project_id: resource.id.to_string(),
+                            version_id: version.id.to_string(),
+                            file_name: spiget_file_name(&resource.name, &version.name),
+                            version_number: version.name,
+                            published: version.release_date,
+                            game_versions: resource.tested_versions.clone(),
+                            size: 0,
+                            sha256: None,
+                            download_url,
+                            dependencies: Vec::new(),
+                        }
+                    })
+                    .collect())
+            }
+            other => Err(format!("未知的插件仓库: {}", other)),
+        }
+    }
+
+    async fn spiget_resource(&self, project_id: &str) -> Result<SpigetResource, String> {
+        self.get_json(SOURCE_SPIGET, &["resources", project_id], &[])
+            .await
+    }
+
+    /// 适用于服务器的最新版本
+    pub async fn latest_version(
+        &self,
+        target: PluginTarget<'_>,
+        source: &str,
+        project_id: &str,
+    ) -> Result<Option<PluginVersion>, String> {
+        Ok(self
+            .get_versions(target, source, project_id)
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    /// 下载插件到 plugins 目录。未指定版本时安装最新的兼容版本，
+    /// 已安装同一项目的旧版本时替换旧文件并保持其启用状态
+    pub async fn install(
+        &self,
+        downloads: &DownloadManager,
+        server_path: &Path,
+        target: PluginTarget<'_>,
+        source: &str,
+        project_id: &str,
+        version_id: Option<&str>,
+    ) -> Result<PluginInstallRecord, String> {
+        if source == SOURCE_SPIGET {
+            let resource = self.spiget_resource(project_id).await?;
+            if !resource.installable() {
+                return Err(format!("{} 是付费或外链资源，无法直接安装", resource.name));
+            }
+            if !target.supports(&resource.tested_versions) {
+                return Err(format!(
+                    "{} 未声明支持 Minecraft {}",
+                    resource.name, target.mc_version
+                ));
+            }
+        }
+
+        let versions = self.get_versions(target, source, project_id).await?;
+        let version = match version_id {
+            Some(id) => versions.into_iter().find(|v| v.version_id == id),
+            None => versions.into_iter().next(),
+        }
+        .ok_or_else(|| format!("{} 没有适用于当前服务器的版本", project_id))?;
+        validate_file_name(&version.file_name)?;
+
+        let plugins_dir = plugins_dir(server_path);
+        fs::create_dir_all(&plugins_dir).map_err(|e| format!("创建 plugins 目录失败: {}", e))?;
+        let temp = plugins_dir.join(format!("{}.download", version.file_name));
+        let known_sha256 = version
+            .sha256
+            .clone()
+            .filter(|sha256| !sha256.trim().is_empty());
+        let task = downloads
+            .create_internal_task(
+                &version.download_url,
+                &temp.to_string_lossy(),
+                1,
+                known_sha256
+                    .as_ref()
+                    .map(|sha256| format!("sha256:{}", sha256)),
+            )
+            .await;
+        if let Err(e) = downloads.wait_for_task(task).await {
+            let _ = fs::remove_file(&temp);
+            return Err(format!("下载 {} 失败: {}", version.file_name, e));
+        }
+
+        let temp_path = temp.clone();
+        let core_type = target.core_type.to_string();
+        let verified = tauri::async_runtime::spawn_blocking(move || {
+            verify_plugin(&temp_path, known_sha256.as_deref(), &core_type)
+        })
+        .await
+        .map_err(|e| format!("校验文件任务失败: {}", e))?;
+        let sha256 = match verified {
+            Ok(sha256) => sha256,
+            Err(e) => {
+                let _ = fs::remove_file(&temp);
+                return Err(format!("{}: {}", version.file_name, e));
+            }
+        };
+
+        let mut records = load_records(server_path);
+        let previous = records
+            .iter()
+            .position(|r| r.source == version.source && r.project_id == version.project_id)
+            .map(|index| records.remove(index));
+        let mut file_name = version.file_name.clone();
+        if let Some(old) = &previous {
+            let disabled = plugins_dir.join(format!("{}{}", old.file_name, DISABLED_SUFFIX));
+            if disabled.exists() {
+                file_name.push_str(DISABLED_SUFFIX);
+            }
+            let _ = fs::remove_file(plugins_dir.join(&old.file_name));
+            let _ = fs::remove_file(disabled);
+        }
+        fs::rename(&temp, plugins_dir.join(&file_name))
+            .map_err(|e| format!("保存 {} 失败: {}", version.file_name, e))?;
+
+        let record = PluginInstallRecord {
+            source: version.source,
+            project_id: version.project_id,
+            version_id: version.version_id,
+            version_number: version.version_number,
+            file_name: version.file_name,
+            sha256,
+            installed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        };
+        records.push(record.clone());
+        save_records(server_path, &records)?;
+        Ok(record)
+    }
+
+    /// 检查从仓库安装的插件是否有更新，文件已被删除的记录会被忽略
+    pub async fn check_updates(
+        &self,
+        server_path: &Path,
+        target: PluginTarget<'_>,
+    ) -> Vec<PluginUpdate> {
+        let plugins_dir = plugins_dir(server_path);
+        let mut updates = Vec::new();
+        for record in load_records(server_path) {
+            let exists = plugins_dir.join(&record.file_name).exists()
+                || plugins_dir
+                    .join(format!("{}{}", record.file_name, DISABLED_SUFFIX))
+                    .exists();
+            if !exists {
+                continue;
+            }
+            let update = match self
+                .latest_version(target, &record.source, &record.project_id)
+                .await
+            {
+                Ok(latest) => PluginUpdate {
+                    update_available: latest
+                        .as_ref()
+                        .is_some_and(|v| v.version_id != record.version_id),
+                    latest_version: latest,
+                    record,
+                    error: None,
+                },
+                Err(e) => PluginUpdate {
+                    record,
+                    latest_version: None,
+                    update_available: false,
+                    error: Some(e),
+                },
+            };
+            updates.push(update);
+        }
+        updates
+    }
+}
+
+fn hangar_version(
+    project_id: &str,
+    platform: &str,
+    version: HangarVersion,
+) -> Option<PluginVersion> {
+    let download = version.downloads.get(platform)?;
+    let file = download.file_info.as_ref()?;
+    // 只有外链的版本没有 downloadUrl
+    let download_url = download.download_url.clone()?;
+    let published = chrono::DateTime::parse_from_rfc3339(&version.created_at)
+        .map(|t| t.timestamp())
+        .unwrap_or_default();
+    Some(PluginVersion {
+        source: SOURCE_HANGAR.to_string(),
+        project_id: project_id.to_string(),
+        version_id: version.name.clone(),
+        version_number: version.name,
+        published,
+        game_versions: version
+            .platform_dependencies
+            .get(platform)
+            .cloned()
+            .unwrap_or_default(),
+        file_name: file.name.clone(),
+        size: file.size_bytes,
+        sha256: file.sha256_hash.clone(),
+        download_url,
+        dependencies: version
+            .plugin_dependencies
+            .get(platform)
+            .map(|deps| {
+                deps.iter()
+                    .filter(|d| d.required)
+                    .map(|d| d.name.clone())
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+/// Spiget 不提供文件名，用资源名与版本号生成
+fn spiget_file_name(resource_name: &str, version: &str) -> String {
+    let sanitize = |s: &str| {
+        s.chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>()
+            .trim_matches(['_', '.'])
+            .to_string()
+    };
+    format!("{}-{}.jar", sanitize(resource_name), sanitize(version))
+}
+
+fn validate_file_name(file_name: &str) -> Result<(), String> {
+    if file_name.is_empty()
+        || file_name.contains(['/', '\\'])
+        || file_name.contains("..")
+        || !file_name.to_ascii_lowercase().ends_with(".jar")
+    {
+        return Err(format!("无效的插件文件名: {}", file_name));
+    }
+    Ok(())
+}
+
+/// 确认文件中有当前平台可识别的插件描述文件，返回文件的 SHA-256。
+/// 有摘要时下载器已经校验过，直接使用该摘要
+fn verify_plugin(path: &Path, expected: Option<&str>, core_type: &str) -> Result<String, String> {
+    let sha256 = match expected {
+        Some(expected) => expected.to_ascii_lowercase(),
+        None => hash::sha256_file(path)?,
+    };
+    global::m_plugin_manager()
+        .m_parse_plugin_jar(path, core_type)
+        .map_err(|e| format!("不是有效的插件: {}", e))?;
+    Ok(sha256)
+}
+
+fn plugins_dir(server_path: &Path) -> PathBuf {
+    server_path.join("plugins")
+}
+
+pub fn load_records(server_path: &Path) -> Vec<PluginInstallRecord> {
+    install_records::load(server_path, RECORDS_FILE)
+}
+
+fn save_records(server_path: &Path, records: &[PluginInstallRecord]) -> Result<(), String> {
+    install_records::save(server_path, RECORDS_FILE, records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_http;
+
+    #[tokio::test]
+    async fn search_and_versions_against_mock_repositories() {
+        let (base, requests) = test_http::routes(vec![
+            (
+                "/hangar/projects/ViaVersion/versions",
+                r#"{"pagination":{"count":2},"result":[
+                    {"name":"5.0.1","createdAt":"2024-06-01T00:00:00Z",
+                     "downloads":{"PAPER":{"fileInfo":{"name":"ViaVersion-5.0.1.jar","sizeBytes":10,
+                       "sha256Hash":"ab"},"downloadUrl":"http://cdn/ViaVersion-5.0.1.jar"}},
+                     "pluginDependencies":{"PAPER":[{"name":"ViaBackwards","required":false}]},
+                     "platformDependencies":{"PAPER":["1.20.4","1.20.6"]}},
+                    {"name":"5.0.0-external","createdAt":"2024-05-01T00:00:00Z",
+                     "downloads":{"PAPER":{"fileInfo":null,"externalUrl":"http://example"}},
+                     "platformDependencies":{"PAPER":["1.20.4"]}}]}"#
+                    .to_string(),
+            ),
+            (
+                "/hangar/projects",
+                r#"{"pagination":{"count":41},"result":[{"name":"ViaVersion",
+                    "namespace":{"owner":"ViaVersion","slug":"ViaVersion"},
+                    "stats":{"downloads":9000},"supportedPlatforms":{"PAPER":["1.20.4"]}}]}"#
+                    .to_string(),
+            ),
+            (
+                "/spiget/search/resources",
+                r#"[{"id":1,"name":"Old","testedVersions":["1.8"],"file":{"type":".jar"}},
+                    {"id":2,"name":"Essentials X","tag":"core","testedVersions":["1.19","1.20"],
+                     "file":{"type":".jar"}},
+                    {"id":3,"name":"Paid","premium":true,"file":{"type":".jar"}}]"#
+                    .to_string(),
+            ),
+        ]);
+        let repository =
+            PluginRepository::with_base_urls(&format!("{base}/hangar"), &format!("{base}/spiget"))
+                .unwrap();
+        let target = PluginTarget { core_type: "paper", mc_version: "1.20.4" };
+
+        let page = repository
+            .search(
+                target,
+                &PluginSearchQuery {
+                    source: SOURCE_HANGAR.to_string(),
+                    query: "via".to_string(),
+                    offset: 20,
+                    limit: 10,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.total, Some(41));
+        assert_eq!(page.hits[0].project_id, "ViaVersion");
+        let search = requests.lock().unwrap()[0].target.clone();
+        assert!(search.contains("platform=PAPER") && search.contains("version=1.20.4"));
+        assert!(search.contains("offset=20") && search.contains("limit=10"));
+
+        let versions = repository
+            .get_versions(target, SOURCE_HANGAR, "ViaVersion")
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].sha256.as_deref(), Some("ab"));
+        assert!(versions[0].dependencies.is_empty());
+
+        let page = repository
+            .search(
+                target,
+                &PluginSearchQuery {
+                    source: SOURCE_SPIGET.to_string(),
+                    query: "essentials x".to_string(),
+                    offset: 0,
+                    limit: 10,
+                },
+            )
+            .await
+            .unwrap();
+        let ids = page
+            .hits
+            .iter()
+            .map(|h| h.project_id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["2", "3"]);
+        assert!(page.hits[0].installable && !page.hits[1].installable);
+        assert!(requests.lock().unwrap()[2]
+            .target
+            .contains("/resources/essentials%20x?"));
+
+        let proxy = PluginTarget { core_type: "velocity", mc_version: "" };
+        assert!(repository
+            .search(
+                proxy,
+                &PluginSearchQuery {
+                    source: SOURCE_SPIGET.to_string(),
+                    query: String::new(),
+                    offset: 0,
+                    limit: 10,
+                },
+            )
+            .await
+            .is_err());
+        assert_eq!(spiget_file_name("Essentials X", "2.20.1"), "Essentials_X-2.20.1.jar");
+    }
+}
@@ -0,0 +1,417 @@
+//! 区域文件完整性校验与损坏区块隔离。
+//!
+//! 校验顺序：文件头 → 扇区偏移/重叠 → 长度字段 → 压缩类型 → 解压 → NBT 解析。
+//! 隔离时把损坏区块的原始扇区复制到服务器目录下的隔离区，再清空其位置项，
+//! 服务器下次加载该区块时会重新生成。隔离区保留原始数据，可手动恢复。
+
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::models::world::{RegionIssue, RegionIssueKind, RegionRepairReport, RegionScanReport};
+use crate::services::region_file::{
+    self, ChunkLocation, RegionFile, COMPRESSION_CUSTOM, COMPRESSION_GZIP, COMPRESSION_LZ4,
+    COMPRESSION_NONE, COMPRESSION_ZLIB, EXTERNAL_FLAG, SECTOR_SIZE,
+};
+use crate::services::world_pruner;
+use crate::utils::nbt;
+
+/// 每个维度下需要校验的区域目录
+const REGION_FOLDERS: [&str; 3] = ["region", "entities", "poi"];
+
+/// 隔离区目录（位于服务器根目录）
+pub const QUARANTINE_DIR: &str = "sealantern_quarantine";
+
+pub fn scan_world(
+    server_path: &Path,
+    world_name: Option<&str>,
+) -> Result<RegionScanReport, String> {
+    let world_dir = world_pruner::resolve_world_dir(server_path, world_name)?;
+    let mut report = RegionScanReport {
+        world_path: world_dir.to_string_lossy().to_string(),
+        regions_scanned: 0,
+        chunks_scanned: 0,
+        chunks_skipped: 0,
+        issues: Vec::new(),
+    };
+
+    for (_, dim_dir, _) in world_pruner::dimension_dirs(&world_dir) {
+        for folder in REGION_FOLDERS {
+            for path in region_file::list_region_files(&dim_dir.join(folder)) {
+                scan_region(&world_dir, &path, &mut report)?;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn relative_region_path(world_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(world_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn scan_region(world_dir: &Path, path: &Path, report: &mut RegionScanReport) -> Result<(), String> {
+    let region = RegionFile::open(path)?;
+    let region_path = relative_region_path(world_dir, path);
+    report.regions_scanned += 1;
+
+    // 服务器会预先创建空的区域文件，属于正常情况
+    if region.file_len() == 0 {
+        return Ok(());
+    }
+    if !region.has_complete_header() {
+        report.issues.push(RegionIssue {
+            region_path,
+            chunk_x: None,
+            chunk_z: None,
+            kind: RegionIssueKind::TruncatedHeader,
+            message: format!("文件长度 {} 字节，不足以容纳区域文件头", region.file_len()),
+        });
+        return Ok(());
+    }
+
+    let total_sectors = (region.file_len() as usize).div_ceil(SECTOR_SIZE);
+    let mut owners: Vec<Option<usize>> = vec![None; total_sectors];
+
+    for loc in region.locations() {
+        report.chunks_scanned += 1;
+        let (chunk_x, chunk_z) = region.chunk_coords(&loc);
+        let issue = |kind: RegionIssueKind, message: String| RegionIssue {
+            region_path: region_path.clone(),
+            chunk_x: Some(chunk_x),
+            chunk_z: Some(chunk_z),
+            kind,
+            message,
+        };
+
+        let start = loc.sector_offset as usize;
+        let end = start + loc.sector_count as usize;
+        if start < 2 || loc.sector_count == 0 || end > total_sectors {
+            report.issues.push(issue(
+                RegionIssueKind::InvalidOffset,
+                format!(
+                    "扇区位置无效: offset={}, count={}, 文件共 {} 个扇区",
+                    loc.sector_offset, loc.sector_count, total_sectors
+                ),
+            ));
+            continue;
+        }
+        if let Some(other) = owners[start..end].iter().flatten().next() {
+            let (other_x, other_z) = region.chunk_coords(&location_with_index(*other));
+            report.issues.push(issue(
+                RegionIssueKind::OverlappingSectors,
+                format!("与区块 [{}, {}] 占用了相同的扇区", other_x, other_z),
+            ));
+            continue;
+        }
+        owners[start..end].fill(Some(loc.index));
+
+        match check_chunk(&region, &loc) {
+            Ok(true) => {}
+            Ok(false) => report.chunks_skipped += 1,
+            Err((kind, message)) => report.issues.push(issue(kind, message)),
+        }
+    }
+
+    Ok(())
+}
+
+/// 仅用于换算坐标的占位位置项
+fn location_with_index(index: usize) -> ChunkLocation {
+    ChunkLocation {
+        index,
+        sector_offset: 0,
+        sector_count: 0,
+        timestamp: 0,
+    }
+}
+
+/// 校验单个区块内容。返回 Ok(false) 表示压缩格式无法在此校验，已跳过
+fn check_chunk(
+    region: &RegionFile,
+    loc: &ChunkLocation,
+) -> Result<bool, (RegionIssueKind, String)> {
+    let sectors = region
+        .raw_sectors(loc)
+        .map_err(|e| (RegionIssueKind::InvalidOffset, e))?;
+    if sectors.len() < 5 {
+        return Err((RegionIssueKind::InvalidLength, "区块头不完整".to_string()));
+    }
+    let length = u32::from_be_bytes([sectors[0], sectors[1], sectors[2], sectors[3]]) as usize;
+    if length == 0 || length + 4 > sectors.len() {
+        return Err((
+            RegionIssueKind::InvalidLength,
+            format!("区块长度 {} 与已分配的 {} 个扇区不符", length, loc.sector_count),
+        ));
+    }
+
+    let compression = sectors[4] & !EXTERNAL_FLAG;
+    if !matches!(
+        compression,
+        COMPRESSION_GZIP
+            | COMPRESSION_ZLIB
+            | COMPRESSION_NONE
+            | COMPRESSION_LZ4
+            | COMPRESSION_CUSTOM
+    ) {
+        return Err((
+            RegionIssueKind::UnknownCompression,
+            format!("未知的区块压缩类型: {}", compression),
+        ));
+    }
+
+    if sectors[4] & EXTERNAL_FLAG != 0 {
+        let (chunk_x, chunk_z) = region.chunk_coords(loc);
+        if !region.external_chunk_path(chunk_x, chunk_z).is_file() {
+            return Err((
+                RegionIssueKind::MissingExternalFile,
+                format!("缺少外部区块文件 c.{}.{}.mcc", chunk_x, chunk_z),
+            ));
+        }
+    }
+
+    if matches!(compression, COMPRESSION_LZ4 | COMPRESSION_CUSTOM) {
+        return Ok(false);
+    }
+
+    let (compression, payload) = region
+        .read_chunk_payload(loc)
+        .map_err(|e| (RegionIssueKind::MissingExternalFile, e))?;
+    let decoded = region_file::decompress_chunk(compression, &payload)
+        .map_err(|e| (RegionIssueKind::DecompressFailed, e))?;
+    nbt::parse(&decoded).map_err(|e| (RegionIssueKind::InvalidNbt, e))?;
+    Ok(true)
+}
+
+/// 将问题区块移入隔离区并从区域文件中移除，返回修复报告（同时写入隔离目录）
+pub fn quarantine_chunks(
+    server_path: &Path,
+    world_name: Option<&str>,
+    issues: &[RegionIssue],
+) -> Result<RegionRepairReport, String> {
+    let world_dir = world_pruner::resolve_world_dir(server_path, world_name)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let world_label = world_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "world".to_string());
+    let quarantine_root = server_path
+        .join(QUARANTINE_DIR)
+        .join(format!("{}_{}", world_label, now));
+
+    let mut report = RegionRepairReport {
+        world_path: world_dir.to_string_lossy().to_string(),
+        quarantine_path: quarantine_root.to_string_lossy().to_string(),
+        created_at: now,
+        quarantined: Vec::new(),
+        failed: Vec::new(),
+    };
+
+    let mut by_region: BTreeMap<&str, Vec<&RegionIssue>> = BTreeMap::new();
+    for issue in issues {
+        by_region
+            .entry(issue.region_path.as_str())
+            .or_default()
+            .push(issue);
+    }
+
+    for (region_path, region_issues) in by_region {
+        let result = resolve_region_path(&world_dir, region_path).and_then(|path| {
+            let target_dir = quarantine_root.join(region_path);
+            if region_issues
+                .iter()
+                .any(|issue| issue.chunk_x.is_none() || issue.chunk_z.is_none())
+            {
+                quarantine_whole_file(&path, &target_dir)?;
+                Ok(region_issues
+                    .iter()
+                    .map(|issue| ((*issue).clone(), None))
+                    .collect::<Vec<_>>())
+            } else {
+                quarantine_region_chunks(&path, &target_dir, &region_issues)
+            }
+        });
+
+        match result {
+            Ok(outcomes) => {
+                for (issue, error) in outcomes {
+                    match error {
+                        None => report.quarantined.push(issue),
+                        Some(error) => report.failed.push((issue, error)),
+                    }
+                }
+            }
+            Err(error) => {
+                for issue in region_issues {
+                    report.failed.push((issue.clone(), error.clone()));
+                }
+            }
+        }
+    }
+
+    if !report.quarantined.is_empty() {
+        let json = serde_json::to_string_pretty(&report)
+            .map_err(|e| format!("序列化修复报告失败: {}", e))?;
+        std::fs::write(quarantine_root.join("repair_report.json"), json)
+            .map_err(|e| format!("写入修复报告失败: {}", e))?;
+    }
+
+    Ok(report)
+}
+
+/// 校验前端传回的相对路径，只允许指向世界目录内的区域文件
+fn resolve_region_path(world_dir: &Path, region_path: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(region_path);
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_)))
+        || region_file::parse_region_coords(relative).is_none()
+    {
+        return Err(format!("区域文件路径无效: {}", region_path));
+    }
+    let path = world_dir.join(relative);
+    if !path.is_file() {
+        return Err(format!("区域文件不存在: {}", region_path));
+    }
+    Ok(path)
+}
+
+fn quarantine_whole_file(path: &Path, target_dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(target_dir).map_err(|e| format!("创建隔离目录失败: {}", e))?;
+    let file_name = path.file_name().ok_or("区域文件名无效")?;
+    let target = target_dir.join(file_name);
+    std::fs::copy(path, &target).map_err(|e| format!("复制区域文件到隔离区失败: {}", e))?;
+    std::fs::remove_file(path).map_err(|e| format!("移除损坏的区域文件失败: {}", e))
+}
+
+fn quarantine_region_chunks(
+    path: &Path,
+    target_dir: &Path,
+    issues: &[&RegionIssue],
+) -> Result<Vec<(RegionIssue, Option<String>)>, String> {
+    let mut region = RegionFile::open(path)?;
+    let locations = region.locations();
+    std::fs::create_dir_all(target_dir).map_err(|e| format!("创建隔离目录失败: {}", e))?;
+
+    let mut outcomes = Vec::with_capacity(issues.len());
+    let mut cleared = Vec::new();
+    // 外部区块文件在位置项清空后再删除，避免写入失败时区域仍指向已删除的数据
+    let mut external_files = Vec::new();
+    for issue in issues {
+        let (Some(chunk_x), Some(chunk_z)) = (issue.chunk_x, issue.chunk_z) else {
+            continue;
+        };
+        let local_x = chunk_x - region.region_x * 32;
+        let local_z = chunk_z - region.region_z * 32;
+        let loc = if (0..32).contains(&local_x) && (0..32).contains(&local_z) {
+            let index = (local_x + local_z * 32) as usize;
+            locations.iter().find(|loc| loc.index == index).copied()
+        } else {
+            None
+        };
+        let Some(loc) = loc else {
+            outcomes.push(((*issue).clone(), Some("区块已不存在于该区域文件".to_string())));
+            continue;
+        };
+        if cleared.contains(&loc.index) {
+            outcomes.push(((*issue).clone(), None));
+            continue;
+        }
+
+        // 扇区位置损坏时没有可保存的数据，直接清空位置项即可
+        if let Ok(sectors) = region.raw_sectors(&loc) {
+            let target = target_dir.join(format!("c.{}.{}.bin", chunk_x, chunk_z));
+            if let Err(e) = std::fs::write(&target, sectors) {
+                outcomes.push(((*issue).clone(), Some(format!("保存区块数据失败: {}", e))));
+                continue;
+            }
+        }
+        if region.is_external(&loc) {
+            let external = region.external_chunk_path(chunk_x, chunk_z);
+            if external.is_file() {
+                let target = target_dir.join(format!("c.{}.{}.mcc", chunk_x, chunk_z));
+                if let Err(e) = std::fs::copy(&external, &target) {
+                    outcomes.push(((*issue).clone(), Some(format!("保存外部区块文件失败: {}", e))));
+                    continue;
+                }
+                external_files.push(external);
+            }
+        }
+
+        cleared.push(loc.index);
+        outcomes.push(((*issue).clone(), None));
+    }
+
+    if !cleared.is_empty() {
+        region.clear_entries(&cleared)?;
+        for path in &external_files {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_region(dir: &Path, chunks: &[(usize, u8, &[u8])]) -> PathBuf {
+        let path = dir.join("r.0.0.mca");
+        let mut data = vec![0u8; SECTOR_SIZE * (2 + chunks.len())];
+        for (i, (index, compression, payload)) in chunks.iter().enumerate() {
+            let slot = 2 + i;
+            data[index * 4 + 2] = slot as u8;
+            data[index * 4 + 3] = 1;
+            let start = slot * SECTOR_SIZE;
+            data[start..start + 4].copy_from_slice(&(payload.len() as u32 + 1).to_be_bytes());
+            data[start + 4] = *compression;
+            data[start + 5..start + 5 + payload.len()].copy_from_slice(payload);
+        }
+        std::fs::write(&path, &data).unwrap();
+        path
+    }
+
+    #[test]
+    fn scan_reports_and_quarantines_corrupted_chunk() {
+        let server_dir =
+            std::env::temp_dir().join(format!("sl_check_test_{}", uuid::Uuid::new_v4()));
+        let region_dir = server_dir.join("world").join("region");
+        std::fs::create_dir_all(&region_dir).unwrap();
+        let empty_compound: &[u8] = &[10, 0, 0, 0];
+        let garbage: &[u8] = &[1, 2, 3, 4, 5];
+        let path = write_region(
+            &region_dir,
+            &[(0, COMPRESSION_NONE, empty_compound), (33, COMPRESSION_ZLIB, garbage)],
+        );
+
+        let report = scan_world(&server_dir, Some("world")).unwrap();
+        assert_eq!(report.chunks_scanned, 2);
+        assert_eq!(report.issues.len(), 1);
+        let issue = &report.issues[0];
+        assert_eq!(issue.kind, RegionIssueKind::DecompressFailed);
+        assert_eq!((issue.chunk_x, issue.chunk_z), (Some(1), Some(1)));
+        assert_eq!(issue.region_path, "region/r.0.0.mca");
+
+        let repair = quarantine_chunks(&server_dir, Some("world"), &report.issues).unwrap();
+        assert_eq!(repair.quarantined.len(), 1);
+        assert!(repair.failed.is_empty());
+        assert!(Path::new(&repair.quarantine_path)
+            .join("region/r.0.0.mca/c.1.1.bin")
+            .is_file());
+
+        let region = RegionFile::open(&path).unwrap();
+        assert_eq!(region.locations().len(), 1);
+        assert!(scan_world(&server_dir, Some("world"))
+            .unwrap()
+            .issues
+            .is_empty());
+
+        let _ = std::fs::remove_dir_all(&server_dir);
+    }
+}
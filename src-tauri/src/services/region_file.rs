@@ -0,0 +1,367 @@
+//! Anvil 区域文件（r.X.Z.mca）读写。
+//!
+//! 文件结构：
+//! - 0x0000..0x1000：1024 个位置项，每项 4 字节（3 字节扇区偏移 + 1 字节扇区数）
+//! - 0x1000..0x2000：1024 个时间戳（秒）
+//! - 之后按 4 KiB 扇区存放区块：4 字节长度 + 1 字节压缩类型 + 压缩数据
+//!
+//! 压缩类型最高位（128）表示数据存放在同目录下的外部文件 c.X.Z.mcc 中。
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+
+use crate::utils::nbt::{self, NbtTag};
+
+pub const SECTOR_SIZE: usize = 4096;
+pub const CHUNKS_PER_REGION: usize = 1024;
+pub const HEADER_SIZE: usize = SECTOR_SIZE * 2;
+
+pub const COMPRESSION_GZIP: u8 = 1;
+pub const COMPRESSION_ZLIB: u8 = 2;
+pub const COMPRESSION_NONE: u8 = 3;
+pub const COMPRESSION_LZ4: u8 = 4;
+pub const COMPRESSION_CUSTOM: u8 = 127;
+pub const EXTERNAL_FLAG: u8 = 128;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkLocation {
+    /// 区域内索引：x + z * 32
+    pub index: usize,
+    pub sector_offset: u32,
+    pub sector_count: u8,
+    pub timestamp: u32,
+}
+
+impl ChunkLocation {
+    pub fn local_x(&self) -> i32 {
+        (self.index % 32) as i32
+    }
+
+    pub fn local_z(&self) -> i32 {
+        (self.index / 32) as i32
+    }
+}
+
+/// 区块的关键元数据，用于裁剪判断
+#[derive(Debug, Clone, Copy)]
+pub struct ChunkSummary {
+    pub chunk_x: i32,
+    pub chunk_z: i32,
+    pub inhabited_time: i64,
+    pub last_update: i64,
+}
+
+pub struct RegionFile {
+    pub path: PathBuf,
+    pub region_x: i32,
+    pub region_z: i32,
+    data: Vec<u8>,
+}
+
+impl RegionFile {
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let (region_x, region_z) = parse_region_coords(path)
+            .ok_or_else(|| format!("不是有效的区域文件名: {}", path.display()))?;
+        let data = std::fs::read(path)
+            .map_err(|e| format!("读取区域文件失败 {}: {}", path.display(), e))?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            region_x,
+            region_z,
+            data,
+        })
+    }
+
+    pub fn file_len(&self) -> u64 {
+        self.data.len() as u64
+    }
+
+    pub fn has_complete_header(&self) -> bool {
+        self.data.len() >= HEADER_SIZE
+    }
+
+    /// 返回所有已分配的区块位置（偏移或扇区数为 0 的项视为空）
+    pub fn locations(&self) -> Vec<ChunkLocation> {
+        if !self.has_complete_header() {
+            return Vec::new();
+        }
+
+        (0..CHUNKS_PER_REGION)
+            .filter_map(|index| {
+                let loc = self.location_at(index);
+                if loc.sector_offset == 0 && loc.sector_count == 0 {
+                    None
+                } else {
+                    Some(loc)
+                }
+            })
+            .collect()
+    }
+
+    fn location_at(&self, index: usize) -> ChunkLocation {
+        let base = index * 4;
+        let entry = &self.data[base..base + 4];
+        let ts_base = SECTOR_SIZE + index * 4;
+        let ts = &self.data[ts_base..ts_base + 4];
+        ChunkLocation {
+            index,
+            sector_offset: u32::from_be_bytes([0, entry[0], entry[1], entry[2]]),
+            sector_count: entry[3],
+            timestamp: u32::from_be_bytes([ts[0], ts[1], ts[2], ts[3]]),
+        }
+    }
+
+    /// 区块在世界中的坐标
+    pub fn chunk_coords(&self, loc: &ChunkLocation) -> (i32, i32) {
+        (self.region_x * 32 + loc.local_x(), self.region_z * 32 + loc.local_z())
+    }
+
+    /// 读取区块的原始扇区（包含 5 字节头）
+    pub fn raw_sectors(&self, loc: &ChunkLocation) -> Result<&[u8], String> {
+        let start = loc.sector_offset as usize * SECTOR_SIZE;
+        let end = start + loc.sector_count as usize * SECTOR_SIZE;
+        if loc.sector_offset < 2 || loc.sector_count == 0 {
+            return Err(format!(
+                "扇区位置无效: offset={}, count={}",
+                loc.sector_offset, loc.sector_count
+            ));
+        }
+        if end > self.data.len() {
+            // 最后一个区块可能没有补齐到整扇区，只要长度字段覆盖的数据存在即可
+            if start + 5 > self.data.len() {
+                return Err(format!("扇区超出文件末尾: offset={}", loc.sector_offset));
+            }
+            return Ok(&self.data[start..]);
+        }
+        Ok(&self.data[start..end])
+    }
+
+    /// 读取区块的压缩类型与压缩数据（已处理外部 .mcc 文件）
+    pub fn read_chunk_payload(&self, loc: &ChunkLocation) -> Result<(u8, Vec<u8>), String> {
+        let sectors = self.raw_sectors(loc)?;
+        if sectors.len() < 5 {
+            return Err("区块头不完整".to_string());
+        }
+        let length = u32::from_be_bytes([sectors[0], sectors[1], sectors[2], sectors[3]]) as usize;
+        if length == 0 {
+            return Err("区块长度为 0".to_string());
+        }
+        if length + 4 > sectors.len() {
+            return Err(format!("区块长度 {} 超出已分配的 {} 个扇区", length, loc.sector_count));
+        }
+        let compression = sectors[4];
+
+        if compression & EXTERNAL_FLAG != 0 {
+            let (chunk_x, chunk_z) = self.chunk_coords(loc);
+            let external = self.external_chunk_path(chunk_x, chunk_z);
+            let payload = std::fs::read(&external)
+                .map_err(|e| format!("读取外部区块文件失败 {}: {}", external.display(), e))?;
+            return Ok((compression & !EXTERNAL_FLAG, payload));
+        }
+
+        Ok((compression, sectors[5..4 + length].to_vec()))
+    }
+
+    pub fn read_chunk_nbt(&self, loc: &ChunkLocation) -> Result<NbtTag, String> {
+        let (compression, payload) = self.read_chunk_payload(loc)?;
+        let decoded = decompress_chunk(compression, &payload)?;
+        nbt::parse(&decoded).map(|(_, root)| root)
+    }
+
+    pub fn read_chunk_summary(&self, loc: &ChunkLocation) -> Result<ChunkSummary, String> {
+        let root = self.read_chunk_nbt(loc)?;
+        // 1.18 之前的区块数据包裹在 Level 标签内
+        let level = root.get("Level").unwrap_or(&root);
+        let (chunk_x, chunk_z) = self.chunk_coords(loc);
+        Ok(ChunkSummary {
+            chunk_x,
+            chunk_z,
+            inhabited_time: level
+                .get("InhabitedTime")
+                .and_then(NbtTag::as_i64)
+                .unwrap_or(0),
+            last_update: level
+                .get("LastUpdate")
+                .and_then(NbtTag::as_i64)
+                .unwrap_or(0),
+        })
+    }
+
+    pub fn external_chunk_path(&self, chunk_x: i32, chunk_z: i32) -> PathBuf {
+        let dir = self.path.parent().unwrap_or_else(|| Path::new("."));
+        dir.join(format!("c.{}.{}.mcc", chunk_x, chunk_z))
+    }
+
+    pub fn is_external(&self, loc: &ChunkLocation) -> bool {
+        self.raw_sectors(loc)
+            .map(|sectors| sectors.len() > 4 && sectors[4] & EXTERNAL_FLAG != 0)
+            .unwrap_or(false)
+    }
+
+    /// 仅保留给定区块并紧凑重写区域文件，返回新文件大小。
+    /// 没有剩余区块时删除文件并返回 0。
+    pub fn write_compacted(&self, keep: &[ChunkLocation]) -> Result<u64, String> {
+        if keep.is_empty() {
+            std::fs::remove_file(&self.path)
+                .map_err(|e| format!("删除区域文件失败 {}: {}", self.path.display(), e))?;
+            return Ok(0);
+        }
+
+        let mut header = vec![0u8; HEADER_SIZE];
+        let mut body = Vec::new();
+        let mut next_sector = 2u32;
+
+        for loc in keep {
+            let sectors = self.raw_sectors(loc)?;
+            let count = sectors.len().div_ceil(SECTOR_SIZE);
+            if count > u8::MAX as usize {
+                return Err(format!("区块扇区数过大: {}", count));
+            }
+
+            let base = loc.index * 4;
+            let offset = next_sector.to_be_bytes();
+            header[base..base + 3].copy_from_slice(&offset[1..4]);
+            header[base + 3] = count as u8;
+            let ts_base = SECTOR_SIZE + loc.index * 4;
+            header[ts_base..ts_base + 4].copy_from_slice(&loc.timestamp.to_be_bytes());
+
+            body.extend_from_slice(sectors);
+            body.resize(body.len() + (count * SECTOR_SIZE - sectors.len()), 0);
+            next_sector += count as u32;
+        }
+
+        header.extend_from_slice(&body);
+        let temp_path = self.path.with_extension("mca.tmp");
+        std::fs::write(&temp_path, &header)
+            .map_err(|e| format!("写入临时区域文件失败 {}: {}", temp_path.display(), e))?;
+        std::fs::rename(&temp_path, &self.path).map_err(|e| {
+            let _ = std::fs::remove_file(&temp_path);
+            format!("替换区域文件失败 {}: {}", self.path.display(), e)
+        })?;
+
+        Ok(header.len() as u64)
+    }
+
+    /// 清空指定区块的位置与时间戳项（不移动其他数据），服务器会在下次加载时重新生成这些区块
+    pub fn clear_entries(&mut self, indices: &[usize]) -> Result<(), String> {
+        if !self.has_complete_header() {
+            return Err(format!("区域文件头不完整: {}", self.path.display()));
+        }
+        for &index in indices.iter().filter(|index| **index < CHUNKS_PER_REGION) {
+            self.data[index * 4..index * 4 + 4].fill(0);
+            let ts_base = SECTOR_SIZE + index * 4;
+            self.data[ts_base..ts_base + 4].fill(0);
+        }
+
+        let temp_path = self.path.with_extension("mca.tmp");
+        std::fs::write(&temp_path, &self.data)
+            .map_err(|e| format!("写入临时区域文件失败 {}: {}", temp_path.display(), e))?;
+        std::fs::rename(&temp_path, &self.path).map_err(|e| {
+            let _ = std::fs::remove_file(&temp_path);
+            format!("替换区域文件失败 {}: {}", self.path.display(), e)
+        })
+    }
+}
+
+pub fn decompress_chunk(compression: u8, payload: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::new();
+    match compression {
+        COMPRESSION_GZIP => {
+            GzDecoder::new(payload)
+                .read_to_end(&mut decoded)
+                .map_err(|e| format!("gzip 解压失败: {}", e))?;
+        }
+        COMPRESSION_ZLIB => {
+            ZlibDecoder::new(payload)
+                .read_to_end(&mut decoded)
+                .map_err(|e| format!("zlib 解压失败: {}", e))?;
+        }
+        COMPRESSION_NONE => decoded.extend_from_slice(payload),
+        COMPRESSION_LZ4 => return Err("暂不支持 LZ4 压缩的区块".to_string()),
+        COMPRESSION_CUSTOM => return Err("暂不支持自定义压缩的区块".to_string()),
+        other => return Err(format!("未知的区块压缩类型: {}", other)),
+    }
+    Ok(decoded)
+}
+
+/// 从 r.X.Z.mca 文件名中解析区域坐标
+pub fn parse_region_coords(path: &Path) -> Option<(i32, i32)> {
+    let name = path.file_name()?.to_str()?;
+    let mut parts = name.split('.');
+    if parts.next()? != "r" {
+        return None;
+    }
+    let x = parts.next()?.parse().ok()?;
+    let z = parts.next()?.parse().ok()?;
+    if parts.next()? != "mca" || parts.next().is_some() {
+        return None;
+    }
+    Some((x, z))
+}
+
+/// 列出目录下所有区域文件，按文件名排序
+pub fn list_region_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && parse_region_coords(path).is_some())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_region_coords_accepts_negative() {
+        assert_eq!(parse_region_coords(Path::new("r.-1.3.mca")), Some((-1, 3)));
+        assert_eq!(parse_region_coords(Path::new("r.0.0.mcr")), None);
+        assert_eq!(parse_region_coords(Path::new("c.0.0.mcc")), None);
+    }
+
+    #[test]
+    fn compacted_region_keeps_selected_chunks() {
+        let dir = std::env::temp_dir().join(format!("sl_region_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("r.0.0.mca");
+
+        // 两个未压缩区块，分别位于第 2、3 扇区
+        let mut data = vec![0u8; HEADER_SIZE + SECTOR_SIZE * 2];
+        for (slot, index) in [(2u8, 0usize), (3u8, 33usize)] {
+            data[index * 4 + 2] = slot;
+            data[index * 4 + 3] = 1;
+            let start = slot as usize * SECTOR_SIZE;
+            // 空 Compound：类型 10、名称长度 0、End
+            let nbt = [10u8, 0, 0, 0];
+            data[start..start + 4].copy_from_slice(&(nbt.len() as u32 + 1).to_be_bytes());
+            data[start + 4] = COMPRESSION_NONE;
+            data[start + 5..start + 5 + nbt.len()].copy_from_slice(&nbt);
+        }
+        std::fs::write(&path, &data).unwrap();
+
+        let region = RegionFile::open(&path).unwrap();
+        let locations = region.locations();
+        assert_eq!(locations.len(), 2);
+        assert_eq!(region.chunk_coords(&locations[1]), (1, 1));
+        assert!(region.read_chunk_nbt(&locations[1]).is_ok());
+
+        let size = region.write_compacted(&locations[1..]).unwrap();
+        assert_eq!(size, (HEADER_SIZE + SECTOR_SIZE) as u64);
+
+        let rewritten = RegionFile::open(&path).unwrap();
+        let kept = rewritten.locations();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].index, 33);
+        assert_eq!(kept[0].sector_offset, 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
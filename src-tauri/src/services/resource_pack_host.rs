@@ -0,0 +1,526 @@
+//! 内置资源包托管。
+//!
+//! 在本机开启一个只读的 HTTP 端点（GET/HEAD /<server_id>/<sha1>/<文件名>），
+//! 并把下载地址、SHA-1 与是否强制写入对应服务器的 server.properties。
+//! 只有路径中的 SHA-1 与当前资源包一致时才返回文件，监听地址可在设置中修改（重启后生效）。
+//! 后台定期检查资源包文件的修改时间与大小，发生变化时自动重新计算 SHA-1 并回写配置。
+//! 托管列表持久化到数据目录，应用重启后自动恢复。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::models::resource_pack::HostedResourcePack;
+use crate::services::{config_history, config_parser, global, server_log_pipeline};
+use crate::utils::hash;
+
+const STATE_FILE: &str = "sea_lantern_resource_packs.json";
+/// 文件变更检查间隔
+const WATCH_INTERVAL: Duration = Duration::from_secs(3);
+/// 请求头读取上限与超时（只限制读取请求头，不限制文件传输）
+const MAX_REQUEST_HEAD: usize = 8192;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct ResourcePackHost {
+    packs: Mutex<HashMap<String, HostedResourcePack>>,
+    /// 上次计算摘要时文件的 (修改时间, 大小)，缺失表示需要重新计算
+    stamps: Mutex<HashMap<String, (Option<SystemTime>, u64)>>,
+    bound_port: tokio::sync::Mutex<Option<u16>>,
+    /// (设置中的公网地址, 实际使用的地址)，设置变化时才重新计算
+    public_host: Mutex<Option<(String, String)>>,
+}
+
+/// 解析出的资源包请求
+#[derive(Debug, PartialEq, Eq)]
+struct PackRequest<'a> {
+    head_only: bool,
+    server_id: &'a str,
+    sha1: &'a str,
+}
+
+impl ResourcePackHost {
+    pub fn new() -> Self {
+        Self {
+            packs: Mutex::new(load_state()),
+            stamps: Mutex::new(HashMap::new()),
+            bound_port: tokio::sync::Mutex::new(None),
+            public_host: Mutex::new(None),
+        }
+    }
+
+    fn packs(&self) -> std::sync::MutexGuard<'_, HashMap<String, HostedResourcePack>> {
+        self.packs.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn stamps(&self) -> std::sync::MutexGuard<'_, HashMap<String, (Option<SystemTime>, u64)>> {
+        self.stamps.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn get(&self, server_id: &str) -> Option<HostedResourcePack> {
+        self.packs().get(server_id).cloned()
+    }
+
+    /// 应用启动时恢复上次的托管；地址与摘要由首次文件检查统一刷新
+    pub fn restore(&'static self) {
+        if self.packs().is_empty() {
+            return;
+        }
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = self.ensure_started().await {
+                eprintln!("[WARN] 恢复资源包托管失败: {}", e);
+            }
+        });
+    }
+
+    pub async fn host(
+        &'static self,
+        server_id: &str,
+        file_path: &str,
+        require: bool,
+    ) -> Result<HostedResourcePack, String> {
+        let server_path = find_server_path(server_id)?;
+        let path = PathBuf::from(file_path);
+        if !path.is_file() {
+            return Err(format!("资源包文件不存在: {}", file_path));
+        }
+        if !file_path.to_lowercase().ends_with(".zip") {
+            return Err("资源包必须是 zip 文件".to_string());
+        }
+
+        let port = self.ensure_started().await?;
+        let stamp = file_stamp(&path);
+        let sha1 = hash_in_background(path.clone()).await?;
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "resources.zip".to_string());
+
+        let pack = HostedResourcePack {
+            server_id: server_id.to_string(),
+            file_path: file_path.to_string(),
+            url: build_url(&self.public_host(), port, server_id, &sha1, &file_name),
+            file_name,
+            sha1,
+            size: stamp.1,
+            require,
+            updated_at: now_secs(),
+        };
+        write_pack_properties(&server_path, Some(&pack))?;
+
+        self.stamps().insert(server_id.to_string(), stamp);
+        self.packs().insert(server_id.to_string(), pack.clone());
+        self.save();
+        Ok(pack)
+    }
+
+    /// 停止托管；clear_properties 为 true 时同时清空 server.properties 中的资源包配置
+    pub fn unhost(&self, server_id: &str, clear_properties: bool) -> Result<(), String> {
+        let removed = self.packs().remove(server_id);
+        self.stamps().remove(server_id);
+        self.save();
+
+        if removed.is_some() && clear_properties {
+            write_pack_properties(&find_server_path(server_id)?, None)?;
+        }
+        Ok(())
+    }
+
+    fn save(&self) {
+        let packs = self.packs().values().cloned().collect::<Vec<_>>();
+        let path = Path::new(&crate::utils::path::get_or_create_app_data_dir()).join(STATE_FILE);
+        if let Ok(json) = serde_json::to_string_pretty(&packs) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+
+    /// 首次调用时绑定端口并启动请求处理与文件检查任务，返回实际监听端口
+    async fn ensure_started(&'static self) -> Result<u16, String> {
+        let mut bound = self.bound_port.lock().await;
+        if let Some(port) = *bound {
+            return Ok(port);
+        }
+
+        let settings = global::settings_manager().get();
+        let port = settings.resource_pack_port;
+        let address = match settings.resource_pack_bind_address.trim() {
+            "" => "0.0.0.0".to_string(),
+            address => address.to_string(),
+        };
+        let listener = TcpListener::bind((address.as_str(), port))
+            .await
+            .map_err(|e| format!("资源包托管地址 {}:{} 绑定失败: {}", address, port, e))?;
+        let port = listener
+            .local_addr()
+            .map(|addr| addr.port())
+            .map_err(|e| format!("读取监听端口失败: {}", e))?;
+
+        tauri::async_runtime::spawn(async move {
+            loop {
+                if let Ok((stream, _)) = listener.accept().await {
+                    tauri::async_runtime::spawn(async move {
+                        let _ = self.serve(stream).await;
+                    });
+                }
+            }
+        });
+        tauri::async_runtime::spawn(async move {
+            loop {
+                tokio::time::sleep(WATCH_INTERVAL).await;
+                self.check_for_changes(port).await;
+            }
+        });
+
+        *bound = Some(port);
+        Ok(port)
+    }
+
+    async fn serve(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        let head = match tokio::time::timeout(REQUEST_TIMEOUT, read_request_head(&mut stream)).await
+        {
+            Ok(Ok(Some(head))) => head,
+            _ => return Ok(()),
+        };
+
+        let head = String::from_utf8_lossy(&head);
+        let request = match parse_request(&head) {
+            Ok(request) => request,
+            Err(status) => return write_status(&mut stream, status).await,
+        };
+        // 旧摘要的地址不再提供文件，避免客户端缓存与服务器配置不一致
+        let pack = self
+            .get(request.server_id)
+            .filter(|pack| pack.sha1.eq_ignore_ascii_case(request.sha1));
+        let Some(pack) = pack else {
+            return write_status(&mut stream, "404 Not Found").await;
+        };
+        let Ok(mut file) = tokio::fs::File::open(&pack.file_path).await else {
+            return write_status(&mut stream, "404 Not Found").await;
+        };
+        let len = file.metadata().await?.len();
+
+        let header = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/zip\r\nContent-Length: {}\r\nContent-Disposition: attachment; filename=\"{}\"\r\nConnection: close\r\n\r\n",
+            len,
+            pack.file_name.replace('"', "")
+        );
+        stream.write_all(header.as_bytes()).await?;
+        if !request.head_only {
+            tokio::io::copy(&mut file, &mut stream).await?;
+        }
+        stream.shutdown().await
+    }
+
+    /// 资源包文件变化（或刚恢复、端口变化）时重新计算摘要并回写配置
+    async fn check_for_changes(&self, port: u16) {
+        let packs = self.packs().values().cloned().collect::<Vec<_>>();
+        if packs.is_empty() {
+            return;
+        }
+        let host = self.public_host();
+        let mut changed = false;
+
+        for pack in packs {
+            let path = PathBuf::from(&pack.file_path);
+            if !path.is_file() {
+                continue;
+            }
+            let stamp = file_stamp(&path);
+            let expected_prefix = build_url(&host, port, &pack.server_id, "", "");
+            let url_stale = !pack.url.starts_with(expected_prefix.trim_end_matches('/'));
+            if self.stamps().get(&pack.server_id) == Some(&stamp) && !url_stale {
+                continue;
+            }
+
+            let sha1 = match hash_in_background(path).await {
+                Ok(sha1) => sha1,
+                Err(e) => {
+                    let _ = server_log_pipeline::append_sealantern_log(
+                        &pack.server_id,
+                        &format!("[Sea Lantern] 资源包摘要计算失败: {}", e),
+                    );
+                    continue;
+                }
+            };
+            // 计算期间可能已被取消托管或换成了其他文件
+            let still_hosted = self
+                .packs()
+                .get(&pack.server_id)
+                .is_some_and(|current| current.file_path == pack.file_path);
+            if !still_hosted {
+                continue;
+            }
+            let hash_changed = sha1 != pack.sha1;
+            let updated = HostedResourcePack {
+                url: build_url(&host, port, &pack.server_id, &sha1, &pack.file_name),
+                sha1,
+                size: stamp.1,
+                updated_at: now_secs(),
+                ..pack
+            };
+
+            let server_path = match find_server_path(&updated.server_id) {
+                Ok(path) => path,
+                Err(_) => continue,
+            };
+            if let Err(e) = write_pack_properties(&server_path, Some(&updated)) {
+                let _ = server_log_pipeline::append_sealantern_log(
+                    &updated.server_id,
+                    &format!("[Sea Lantern] 更新资源包配置失败: {}", e),
+                );
+                continue;
+            }
+            if hash_changed {
+                let _ = server_log_pipeline::append_sealantern_log(
+                    &updated.server_id,
+                    &format!(
+                        "[Sea Lantern] 资源包已变更，新的 SHA-1: {}（重启服务器后生效）",
+                        updated.sha1
+                    ),
+                );
+            }
+
+            self.stamps().insert(updated.server_id.clone(), stamp);
+            self.packs().insert(updated.server_id.clone(), updated);
+            changed = true;
+        }
+
+        if changed {
+            self.save();
+        }
+    }
+
+    /// 玩家访问使用的地址：优先使用设置中的公网地址，其次为本机局域网 IP
+    fn public_host(&self) -> String {
+        let configured = global::settings_manager()
+            .get()
+            .resource_pack_public_host
+            .trim()
+            .to_string();
+        let mut cached = self.public_host.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((key, host)) = cached.as_ref() {
+            if *key == configured {
+                return host.clone();
+            }
+        }
+        let host = if configured.is_empty() {
+            lan_ip()
+        } else {
+            configured.clone()
+        };
+        *cached = Some((configured, host.clone()));
+        host
+    }
+}
+
+fn load_state() -> HashMap<String, HostedResourcePack> {
+    let path = Path::new(&crate::utils::path::get_or_create_app_data_dir()).join(STATE_FILE);
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Vec<HostedResourcePack>>(&content).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|pack| (pack.server_id.clone(), pack))
+        .collect()
+}
+
+fn find_server_path(server_id: &str) -> Result<PathBuf, String> {
+    global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .map(|s| PathBuf::from(s.path))
+        .ok_or_else(|| "未找到服务器".to_string())
+}
+
+fn file_stamp(path: &Path) -> (Option<SystemTime>, u64) {
+    std::fs::metadata(path)
+        .map(|meta| (meta.modified().ok(), meta.len()))
+        .unwrap_or((None, 0))
+}
+
+async fn hash_in_background(path: PathBuf) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || hash::sha1_file(&path))
+        .await
+        .map_err(|e| format!("资源包摘要任务失败: {}", e))?
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn lan_ip() -> String {
+    // UDP connect 只查询路由，不会真正发送数据
+    std::net::UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect("223.5.5.5:80")?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip().to_string())
+        .unwrap_or_else(|_| "127.0.0.1".to_string())
+}
+
+fn build_url(host: &str, port: u16, server_id: &str, sha1: &str, file_name: &str) -> String {
+    let encoded = url::form_urlencoded::byte_serialize(file_name.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20");
+    format!("http://{}:{}/{}/{}/{}", host, port, server_id, sha1, encoded)
+}
+
+/// 解析请求行 `GET /<server_id>/<sha1>/<文件名>`，失败时返回应答状态
+fn parse_request(head: &str) -> Result<PackRequest<'_>, &'static str> {
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let target = request_line.next().unwrap_or_default();
+    if method != "GET" && method != "HEAD" {
+        return Err("405 Method Not Allowed");
+    }
+
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    let mut segments = path.trim_start_matches('/').split('/');
+    match (segments.next(), segments.next()) {
+        (Some(server_id), Some(sha1)) if !server_id.is_empty() && !sha1.is_empty() => {
+            Ok(PackRequest {
+                head_only: method == "HEAD",
+                server_id,
+                sha1,
+            })
+        }
+        _ => Err("404 Not Found"),
+    }
+}
+
+fn write_pack_properties(
+    server_path: &Path,
+    pack: Option<&HostedResourcePack>,
+) -> Result<(), String> {
+    let mut values = HashMap::new();
+    match pack {
+        Some(pack) => {
+            values.insert("resource-pack".to_string(), pack.url.clone());
+            values.insert("resource-pack-sha1".to_string(), pack.sha1.clone());
+            values.insert("require-resource-pack".to_string(), pack.require.to_string());
+        }
+        None => {
+            values.insert("resource-pack".to_string(), String::new());
+            values.insert("resource-pack-sha1".to_string(), String::new());
+            values.insert("require-resource-pack".to_string(), "false".to_string());
+        }
+    }
+    let props_path = server_path.join("server.properties");
+    config_history::snapshot(server_path, &props_path, config_history::ORIGIN_RESOURCE_PACK)?;
+    config_parser::write_properties(&props_path.to_string_lossy(), &values)
+}
+
+/// 读取到空行为止的请求头，连接提前关闭或超出上限时返回 None
+async fn read_request_head(stream: &mut TcpStream) -> std::io::Result<Option<Vec<u8>>> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 || head.len() + read > MAX_REQUEST_HEAD {
+            return Ok(None);
+        }
+        head.extend_from_slice(&buf[..read]);
+    }
+    Ok(Some(head))
+}
+
+async fn write_status(stream: &mut TcpStream, status: &str) -> std::io::Result<()> {
+    let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n", status);
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host_with(pack: HostedResourcePack) -> &'static ResourcePackHost {
+        let mut packs = HashMap::new();
+        packs.insert(pack.server_id.clone(), pack);
+        Box::leak(Box::new(ResourcePackHost {
+            packs: Mutex::new(packs),
+            stamps: Mutex::new(HashMap::new()),
+            bound_port: tokio::sync::Mutex::new(None),
+            public_host: Mutex::new(None),
+        }))
+    }
+
+    async fn request(host: &'static ResourcePackHost, request: &str) -> String {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            host.serve(stream).await.unwrap();
+        });
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[test]
+    fn parses_pack_requests() {
+        assert_eq!(
+            parse_request("GET /srv/abc/pack%20one.zip?x=1 HTTP/1.1\r\nHost: a\r\n\r\n"),
+            Ok(PackRequest {
+                head_only: false,
+                server_id: "srv",
+                sha1: "abc",
+            })
+        );
+        assert!(
+            parse_request("HEAD /srv/abc HTTP/1.1\r\n\r\n")
+                .unwrap()
+                .head_only
+        );
+        assert_eq!(
+            parse_request("POST /srv/abc/a.zip HTTP/1.1\r\n\r\n"),
+            Err("405 Method Not Allowed")
+        );
+        assert_eq!(parse_request("GET /srv HTTP/1.1\r\n\r\n"), Err("404 Not Found"));
+        assert_eq!(parse_request("GET / HTTP/1.1\r\n\r\n"), Err("404 Not Found"));
+    }
+
+    #[tokio::test]
+    async fn serves_only_the_current_hash() {
+        let dir = std::env::temp_dir().join(format!("sl_rp_host_{}", now_secs()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("pack.zip");
+        std::fs::write(&file, b"zip-bytes").unwrap();
+        let host = host_with(HostedResourcePack {
+            server_id: "srv".to_string(),
+            file_path: file.to_string_lossy().to_string(),
+            file_name: "pack.zip".to_string(),
+            sha1: "ABC123".to_string(),
+            size: 9,
+            url: String::new(),
+            require: true,
+            updated_at: 0,
+        });
+
+        let ok = request(host, "GET /srv/abc123/pack.zip HTTP/1.1\r\n\r\n").await;
+        assert!(ok.starts_with("HTTP/1.1 200 OK"));
+        assert!(ok.ends_with("zip-bytes"));
+
+        let head = request(host, "HEAD /srv/abc123/pack.zip HTTP/1.1\r\n\r\n").await;
+        assert!(head.contains("Content-Length: 9"));
+        assert!(!head.ends_with("zip-bytes"));
+
+        let stale = request(host, "GET /srv/000000/pack.zip HTTP/1.1\r\n\r\n").await;
+        assert!(stale.starts_with("HTTP/1.1 404"));
+        let unknown = request(host, "GET /other/abc123/pack.zip HTTP/1.1\r\n\r\n").await;
+        assert!(unknown.starts_with("HTTP/1.1 404"));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,739 @@
+//! 把受管理的服务器导出为便携包、.mrpack 或客户端包。
+//!
+//! 便携包包含服务器信息与用户选择的文件，可以在另一台机器上通过导入整合包恢复。
+//! .mrpack 中能在 Modrinth 按摘要找到的模组只写引用，其余文件放入 overrides。
+//! 客户端包去掉仅服务端的模组、配置与服务器文件，解压到客户端实例目录即可使用。
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::models::server::{
+    ImportModpackRequest, ServerExportResult, ServerInstance, ServerStatus,
+};
+use crate::services::global;
+use crate::services::server_installer::CoreType;
+use crate::services::server_manager::ModpackStartup;
+use crate::services::{mod_metadata, modpack_import};
+use crate::utils::hash;
+
+pub const BUNDLE_MANIFEST: &str = "sealantern-bundle.json";
+const BUNDLE_FILES_DIR: &str = "files";
+const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// 未指定导出内容时 .mrpack 与客户端包包含的目录
+const DEFAULT_PACK_ENTRIES: [&str; 4] = ["mods", "config", "defaultconfigs", "kubejs"];
+/// 任何形式都不导出的内容
+const ALWAYS_EXCLUDED: [&str; 3] = [".sealantern/rollback", ".sealantern/staging", "logs"];
+/// 服务器根目录下只有服务端使用的文件
+const SERVER_ROOT_FILES: [&str; 12] = [
+    "server.properties",
+    "eula.txt",
+    "ops.json",
+    "whitelist.json",
+    "banned-ips.json",
+    "banned-players.json",
+    "usercache.json",
+    "user_jvm_args.txt",
+    "run.sh",
+    "run.bat",
+    "start.sh",
+    "start.bat",
+];
+/// 服务器根目录下只有服务端使用的目录
+const SERVER_ROOT_DIRS: [&str; 6] = [
+    ".sealantern",
+    "libraries",
+    "crash-reports",
+    "plugins",
+    "serverconfig",
+    "versions",
+];
+
+/// 便携包中记录的服务器信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub exported_at: u64,
+    /// 导出时的服务器信息，`path` 为空
+    pub server: ServerInstance,
+    /// 启动文件，相对服务器目录
+    #[serde(default)]
+    pub startup_file: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Bundle,
+    Mrpack,
+    Client,
+}
+
+impl ExportFormat {
+    fn parse(value: &str) -> Result<Self, String> {
+        match value.to_ascii_lowercase().as_str() {
+            "bundle" => Ok(Self::Bundle),
+            "mrpack" => Ok(Self::Mrpack),
+            "client" => Ok(Self::Client),
+            other => Err(format!("未知的导出格式: {}", other)),
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Bundle => "bundle",
+            Self::Mrpack => "mrpack",
+            Self::Client => "client",
+        }
+    }
+}
+
+/// 要写入压缩包的文件
+struct ExportPlan {
+    files: Vec<String>,
+    excluded: Vec<String>,
+    /// mods 目录中启用的模组：相对路径 → 运行环境（both / client / server）
+    mod_environments: HashMap<String, String>,
+    /// 仅服务端模组的 ID，用于识别它们的配置文件
+    server_only_ids: Vec<String>,
+}
+
+/// 已在 Modrinth 找到、只写引用的模组
+struct ReferencedFile {
+    path: String,
+    sha1: String,
+    sha512: String,
+    size: u64,
+    url: String,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn to_relative(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn is_always_excluded(rel: &str) -> bool {
+    rel.ends_with(".download")
+        || rel.ends_with("session.lock")
+        || ALWAYS_EXCLUDED
+            .iter()
+            .any(|prefix| rel == *prefix || rel.starts_with(&format!("{}/", prefix)))
+}
+
+/// 客户端不需要的文件：服务器文件、存档、服务端配置与仅服务端模组的配置
+fn is_server_side_path(server_path: &Path, rel: &str, server_only_ids: &[String]) -> bool {
+    let lower = rel.to_ascii_lowercase();
+    let parts = lower.split('/').collect::<Vec<_>>();
+    let first = parts[0];
+    if parts.len() == 1 {
+        return SERVER_ROOT_FILES.contains(&first) || first.ends_with(".jar");
+    }
+    if SERVER_ROOT_DIRS.contains(&first)
+        || parts.contains(&"serverconfig")
+        || server_path.join(parts[0]).join("level.dat").exists()
+    {
+        return true;
+    }
+    if first == "config" {
+        let name = parts[parts.len() - 1];
+        if ["-server.toml", "_server.toml", "-server.json", "-server.json5"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+        {
+            return true;
+        }
+        let entry = parts[1].split('.').next().unwrap_or_default();
+        return server_only_ids
+            .iter()
+            .any(|id| entry == id || entry.starts_with(&format!("{}-", id)));
+    }
+    false
+}
+
+fn walk(server_path: &Path, rel: &Path, out: &mut Vec<String>) -> Result<(), String> {
+    let path = server_path.join(rel);
+    let metadata =
+        fs::symlink_metadata(&path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?;
+    if metadata.file_type().is_symlink() {
+        return Ok(());
+    }
+    if metadata.is_file() {
+        out.push(to_relative(rel));
+        return Ok(());
+    }
+    let entries =
+        fs::read_dir(&path).map_err(|e| format!("读取目录 {} 失败: {}", path.display(), e))?;
+    for entry in entries.flatten() {
+        walk(server_path, &rel.join(entry.file_name()), out)?;
+    }
+    Ok(())
+}
+
+fn plan_export(
+    server_path: &Path,
+    format: ExportFormat,
+    include: &[String],
+) -> Result<ExportPlan, String> {
+    let installed = mod_metadata::scan_mods(server_path).unwrap_or_default();
+    let mod_environments = installed
+        .iter()
+        .filter(|m| m.enabled)
+        .map(|m| (format!("mods/{}", m.file_name), m.environment.clone()))
+        .collect::<HashMap<_, _>>();
+    let server_only_ids = installed
+        .iter()
+        .filter(|m| m.environment == "server" && !m.mod_id.is_empty())
+        .map(|m| m.mod_id.to_ascii_lowercase())
+        .collect::<Vec<_>>();
+
+    let entries = if !include.is_empty() {
+        include.to_vec()
+    } else if format == ExportFormat::Bundle {
+        fs::read_dir(server_path)
+            .map_err(|e| format!("读取服务器目录失败: {}", e))?
+            .flatten()
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect()
+    } else {
+        DEFAULT_PACK_ENTRIES.iter().map(|e| e.to_string()).collect()
+    };
+
+    let mut candidates = Vec::new();
+    for entry in &entries {
+        let rel = modpack_import::safe_relative_path(entry.trim_matches('/'))?;
+        if server_path.join(&rel).exists() {
+            walk(server_path, &rel, &mut candidates)?;
+        } else if !include.is_empty() {
+            return Err(format!("导出的文件不存在: {}", entry));
+        }
+    }
+    candidates.sort();
+    candidates.dedup();
+
+    let mut plan = ExportPlan {
+        files: Vec::new(),
+        excluded: Vec::new(),
+        mod_environments,
+        server_only_ids,
+    };
+    for rel in candidates {
+        if is_always_excluded(&rel) {
+            continue;
+        }
+        let is_mod_jar = rel.starts_with("mods/") && rel.to_ascii_lowercase().ends_with(".jar");
+        let exclude = match format {
+            ExportFormat::Bundle => false,
+            ExportFormat::Mrpack => rel.ends_with(".disabled"),
+            ExportFormat::Client => {
+                rel.ends_with(".disabled")
+                    || (is_mod_jar
+                        && plan.mod_environments.get(&rel).map(String::as_str) == Some("server"))
+                    || is_server_side_path(server_path, &rel, &plan.server_only_ids)
+            }
+        };
+        if exclude {
+            plan.excluded.push(rel);
+        } else {
+            plan.files.push(rel);
+        }
+    }
+    Ok(plan)
+}
+
+/// Modrinth 清单中的加载器依赖
+fn mrpack_dependencies(
+    server: &ServerInstance,
+    server_path: &Path,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    if server.mc_version.trim().is_empty() || server.mc_version == "unknown" {
+        return Err("未知的 Minecraft 版本，无法导出 .mrpack".to_string());
+    }
+    let mut dependencies = serde_json::Map::new();
+    dependencies.insert("minecraft".to_string(), server.mc_version.clone().into());
+
+    let core_key = CoreType::normalize_to_api_core_key(&server.core_type).unwrap_or_default();
+    let loader_key = match core_key.as_str() {
+        "fabric" | "arclight-fabric" | "banner" => "fabric-loader",
+        "quilt" => "quilt-loader",
+        "forge" | "arclight-forge" | "mohist" | "catserver" | "spongeforge" => "forge",
+        "neoforge" | "arclight-neoforge" | "youer" => "neoforge",
+        _ => return Ok(dependencies),
+    };
+    // 导入的整合包记录了加载器版本，其他服务器使用核心版本
+    let loader_version = Some(server.core_version.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .or_else(|| {
+            fs::read_to_string(server_path.join(modpack_import::PACK_INFO_FILE))
+                .ok()
+                .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+                .and_then(|info| info["loader_version"].as_str().map(str::to_string))
+                .filter(|v| !v.is_empty())
+        })
+        .ok_or_else(|| "未知的加载器版本，无法导出 .mrpack".to_string())?;
+    dependencies.insert(loader_key.to_string(), loader_version.into());
+    Ok(dependencies)
+}
+
+fn env_value(environment: &str) -> serde_json::Value {
+    let (client, server) = match environment {
+        "client" => ("required", "unsupported"),
+        "server" => ("unsupported", "required"),
+        _ => ("required", "required"),
+    };
+    serde_json::json!({ "client": client, "server": server })
+}
+
+/// 在 Modrinth 按 SHA-512 查找模组，找到的只写下载引用
+async fn reference_mods(
+    server_path: &Path,
+    plan: &ExportPlan,
+    warnings: &mut Vec<String>,
+) -> Result<Vec<ReferencedFile>, String> {
+    let jars = plan
+        .files
+        .iter()
+        .filter(|rel| plan.mod_environments.contains_key(*rel))
+        .cloned()
+        .collect::<Vec<_>>();
+    let base = server_path.to_path_buf();
+    let hashed = tauri::async_runtime::spawn_blocking(move || {
+        jars.into_iter()
+            .filter_map(|rel| {
+                let path = base.join(&rel);
+                let size = fs::metadata(&path).ok()?.len();
+                let sha1 = hash::sha1_file(&path).ok()?;
+                let sha512 = hash::sha512_file(&path).ok()?;
+                Some((rel, sha1, sha512, size))
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("计算文件摘要任务失败: {}", e))?;
+    if hashed.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let hashes = hashed.iter().map(|h| h.2.clone()).collect::<Vec<_>>();
+    let versions = match global::mod_manager()
+        .versions_from_hashes(&hashes, "sha512")
+        .await
+    {
+        Ok(versions) => versions,
+        Err(e) => {
+            warnings.push(format!("查询 Modrinth 失败，所有模组将直接打包: {}", e));
+            return Ok(Vec::new());
+        }
+    };
+
+    Ok(hashed
+        .into_iter()
+        .filter_map(|(path, sha1, sha512, size)| {
+            let url = versions
+                .get(&sha512)?
+                .files
+                .iter()
+                .find(|f| f.hashes.sha512.as_deref() == Some(sha512.as_str()))?
+                .url
+                .clone();
+            Some(ReferencedFile { path, sha1, sha512, size, url })
+        })
+        .collect())
+}
+
+fn start_entry(zip: &mut ZipWriter<File>, name: &str) -> Result<(), String> {
+    let options = SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .large_file(true);
+    zip.start_file(name, options)
+        .map_err(|e| format!("写入 {} 失败: {}", name, e))
+}
+
+fn add_bytes(zip: &mut ZipWriter<File>, name: &str, content: &[u8]) -> Result<(), String> {
+    start_entry(zip, name)?;
+    zip.write_all(content)
+        .map_err(|e| format!("写入 {} 失败: {}", name, e))
+}
+
+fn add_file(zip: &mut ZipWriter<File>, name: &str, source: &Path) -> Result<(), String> {
+    start_entry(zip, name)?;
+    let mut file =
+        File::open(source).map_err(|e| format!("读取 {} 失败: {}", source.display(), e))?;
+    std::io::copy(&mut file, zip).map_err(|e| format!("写入 {} 失败: {}", name, e))?;
+    Ok(())
+}
+
+/// 写入压缩包。先写临时文件，完成后再替换目标文件
+fn write_archive(
+    output_path: &Path,
+    write: impl FnOnce(&mut ZipWriter<File>) -> Result<usize, String>,
+) -> Result<usize, String> {
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建导出目录失败: {}", e))?;
+    }
+    let temp = output_path.with_file_name(format!(
+        "{}.part",
+        output_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    ));
+    let result = File::create(&temp)
+        .map_err(|e| format!("创建导出文件失败: {}", e))
+        .and_then(|file| {
+            let mut zip = ZipWriter::new(file);
+            let count = write(&mut zip)?;
+            zip.finish()
+                .map_err(|e| format!("写入导出文件失败: {}", e))?;
+            Ok(count)
+        });
+    match result {
+        Ok(count) => {
+            fs::rename(&temp, output_path).map_err(|e| format!("保存导出文件失败: {}", e))?;
+            Ok(count)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&temp);
+            Err(e)
+        }
+    }
+}
+
+/// 解析符号链接与 `..` 后判断 path 是否位于 dir 中。path 不存在时从最近的已存在上级目录解析
+fn is_inside(path: &Path, dir: &Path) -> bool {
+    let Ok(dir) = dir.canonicalize() else {
+        return false;
+    };
+    let mut existing = path;
+    let mut rest = Vec::new();
+    let mut resolved = loop {
+        if let Ok(resolved) = existing.canonicalize() {
+            break resolved;
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(last)) => {
+                rest.push(last);
+                existing = parent;
+            }
+            // 没有可解析的上级目录（例如空的相对路径），按原样比较
+            _ => return path.starts_with(&dir),
+        }
+    };
+    // 尚不存在的部分不会是符号链接，直接按字面处理 `..`
+    for component in rest.into_iter().rev() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            other => resolved.push(other),
+        }
+    }
+    resolved.starts_with(&dir)
+}
+
+/// 导出服务器。`include` 为相对服务器目录的文件或目录，为空时使用各形式的默认内容
+pub async fn export(
+    server: &ServerInstance,
+    format: &str,
+    output_path: &Path,
+    include: &[String],
+) -> Result<ServerExportResult, String> {
+    let format = ExportFormat::parse(format)?;
+    let server_path = PathBuf::from(&server.path);
+    if !server_path.is_dir() {
+        return Err(format!("服务器目录不存在: {}", server.path));
+    }
+    if is_inside(output_path, &server_path) {
+        return Err("导出文件不能保存在服务器目录中".to_string());
+    }
+    // 便携包包含世界等运行中会被改写的文件
+    if format == ExportFormat::Bundle
+        && global::server_manager()
+            .get_server_status(&server.id)
+            .status
+            != ServerStatus::Stopped
+    {
+        return Err("请先停止服务器再导出便携包".to_string());
+    }
+
+    let plan_path = server_path.clone();
+    let include_list = include.to_vec();
+    let plan = tauri::async_runtime::spawn_blocking(move || {
+        plan_export(&plan_path, format, &include_list)
+    })
+    .await
+    .map_err(|e| format!("整理导出文件任务失败: {}", e))??;
+
+    let mut warnings = Vec::new();
+    let mut referenced = Vec::new();
+    let mut dependencies = serde_json::Map::new();
+    if format == ExportFormat::Mrpack {
+        dependencies = mrpack_dependencies(server, &server_path)?;
+        referenced = reference_mods(&server_path, &plan, &mut warnings).await?;
+    }
+    let referenced_mods = referenced.len();
+
+    let server = server.clone();
+    let output = output_path.to_path_buf();
+    let ExportPlan {
+        files,
+        excluded,
+        mod_environments,
+        server_only_ids,
+    } = plan;
+    let excluded_list = excluded.clone();
+    let file_count = tauri::async_runtime::spawn_blocking(move || {
+        write_archive(&output, |zip| match format {
+            ExportFormat::Bundle => {
+                let startup_file = Path::new(&server.jar_path)
+                    .strip_prefix(&server_path)
+                    .ok()
+                    .map(to_relative);
+                let mut manifest_server = server.clone();
+                manifest_server.path = String::new();
+                manifest_server.jar_path = startup_file.clone().unwrap_or_default();
+                manifest_server.last_started_at = None;
+                let manifest = BundleManifest {
+                    format_version: BUNDLE_FORMAT_VERSION,
+                    exported_at: now_secs(),
+                    server: manifest_server,
+                    startup_file,
+                };
+                let content = serde_json::to_vec_pretty(&manifest)
+                    .map_err(|e| format!("序列化便携包信息失败: {}", e))?;
+                add_bytes(zip, BUNDLE_MANIFEST, &content)?;
+                for rel in &files {
+                    add_file(
+                        zip,
+                        &format!("{}/{}", BUNDLE_FILES_DIR, rel),
+                        &server_path.join(rel),
+                    )?;
+                }
+                Ok(files.len())
+            }
+            ExportFormat::Mrpack => {
+                let index_files = referenced
+                    .iter()
+                    .map(|f| {
+                        serde_json::json!({
+                            "path": f.path,
+                            "hashes": { "sha1": f.sha1, "sha512": f.sha512 },
+                            "env": env_value(
+                                mod_environments.get(&f.path).map(String::as_str).unwrap_or("both"),
+                            ),
+                            "downloads": [f.url],
+                            "fileSize": f.size,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let index = serde_json::json!({
+                    "formatVersion": 1,
+                    "game": "minecraft",
+                    "versionId": chrono::Local::now().format("%Y.%m.%d").to_string(),
+                    "name": server.name,
+                    "files": index_files,
+                    "dependencies": dependencies,
+                });
+                let content = serde_json::to_vec_pretty(&index)
+                    .map_err(|e| format!("序列化 modrinth.index.json 失败: {}", e))?;
+                add_bytes(zip, "modrinth.index.json", &content)?;
+
+                let mut count = 0;
+                for rel in &files {
+                    if referenced.iter().any(|f| &f.path == rel) {
+                        continue;
+                    }
+                    let environment = mod_environments.get(rel).map(String::as_str);
+                    let prefix = if environment == Some("server")
+                        || is_server_side_path(&server_path, rel, &server_only_ids)
+                    {
+                        "server-overrides"
+                    } else if environment == Some("client") {
+                        "client-overrides"
+                    } else {
+                        "overrides"
+                    };
+                    add_file(zip, &format!("{}/{}", prefix, rel), &server_path.join(rel))?;
+                    count += 1;
+                }
+                Ok(count + referenced.len())
+            }
+            ExportFormat::Client => {
+                for rel in &files {
+                    add_file(zip, rel, &server_path.join(rel))?;
+                }
+                Ok(files.len())
+            }
+        })
+    })
+    .await
+    .map_err(|e| format!("导出任务失败: {}", e))??;
+
+    Ok(ServerExportResult {
+        format: format.as_str().to_string(),
+        output_path: output_path.to_string_lossy().to_string(),
+        file_count,
+        referenced_mods,
+        excluded: excluded_list,
+        warnings,
+    })
+}
+
+/// 读取便携包中的服务器信息，不是便携包时返回 None
+pub fn read_bundle(archive_path: &Path) -> Result<Option<BundleManifest>, String> {
+    let is_zip = archive_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    if !archive_path.is_file() || !is_zip {
+        return Ok(None);
+    }
+    let file = File::open(archive_path).map_err(|e| format!("无法打开压缩包: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("无法解析压缩包: {}", e))?;
+    let Some(content) = modpack_import::read_entry(&mut archive, BUNDLE_MANIFEST)? else {
+        return Ok(None);
+    };
+    let manifest: BundleManifest = serde_json::from_str(&content)
+        .map_err(|e| format!("解析 {} 失败: {}", BUNDLE_MANIFEST, e))?;
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err("便携包由更新版本的 Sea Lantern 导出，请先升级".to_string());
+    }
+    Ok(Some(manifest))
+}
+
+/// 从便携包创建服务器。Java 路径与内存使用导入时的设置，其余沿用导出时的信息
+pub fn import_bundle(
+    req: ImportModpackRequest,
+    manifest: BundleManifest,
+) -> Result<ServerInstance, String> {
+    let manager = global::server_manager();
+    let (id, server_name, run_dir) = manager.prepare_modpack_run_dir(&req)?;
+    let archive_path = PathBuf::from(&req.modpack_path);
+
+    let result = fs::create_dir_all(&run_dir)
+        .map_err(|e| format!("无法创建运行目录: {}", e))
+        .and_then(|_| modpack_import::extract_overrides(&archive_path, BUNDLE_FILES_DIR, &run_dir))
+        .and_then(|_| {
+            let startup_file_path = match &manifest.startup_file {
+                Some(rel) => Some(
+                    run_dir
+                        .join(modpack_import::safe_relative_path(rel)?)
+                        .to_string_lossy()
+                        .to_string(),
+                ),
+                None => None,
+            };
+            let server = manifest.server;
+            manager.register_modpack(
+                req,
+                id,
+                server_name,
+                run_dir.clone(),
+                ModpackStartup {
+                    startup_mode: server.startup_mode,
+                    startup_file_path,
+                    custom_command: server.custom_command,
+                    core_type: Some(server.core_type),
+                    core_version: server.core_version,
+                    mc_version: Some(server.mc_version),
+                    jvm_args: server.jvm_args,
+                },
+            )
+        });
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&run_dir);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_inside_server_is_detected_through_dot_dot() {
+        let root = std::env::temp_dir().join(format!("sl_export_{}", uuid::Uuid::new_v4()));
+        let server = root.join("server");
+        fs::create_dir_all(server.join("world")).unwrap();
+
+        assert!(is_inside(&server.join("new/out.zip"), &server));
+        assert!(is_inside(&root.join("other/../server/out.zip"), &server));
+        assert!(is_inside(&server.join("world/../out.zip"), &server));
+        assert!(!is_inside(&server.join("../out.zip"), &server));
+        assert!(!is_inside(&root.join("server-exports/out.zip"), &server));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn client_plan_skips_server_side_files() {
+        let server = std::env::temp_dir().join(format!("sl_export_{}", uuid::Uuid::new_v4()));
+        for dir in ["mods", "config/serverconfig", "config/ledger", "world", "logs"] {
+            fs::create_dir_all(server.join(dir)).unwrap();
+        }
+        for (path, content) in [
+            ("server.properties", ""),
+            ("world/level.dat", ""),
+            ("logs/latest.log", ""),
+            ("config/sodium-options.json", "{}"),
+            ("config/create-server.toml", ""),
+            ("config/serverconfig/a.toml", ""),
+            ("config/ledger/ledger.toml", ""),
+            ("mods/old.jar.disabled", ""),
+        ] {
+            fs::write(server.join(path), content).unwrap();
+        }
+        let fabric_mod = |id: &str, environment: &str| {
+            let path = server.join(format!("mods/{}.jar", id));
+            let mut zip = ZipWriter::new(File::create(path).unwrap());
+            zip.start_file("fabric.mod.json", SimpleFileOptions::default())
+                .unwrap();
+            let json = format!(
+                r#"{{"id": "{}", "version": "1.0", "environment": "{}"}}"#,
+                id, environment
+            );
+            zip.write_all(json.as_bytes()).unwrap();
+            zip.finish().unwrap();
+        };
+        fabric_mod("ledger", "server");
+        fabric_mod("sodium", "client");
+        fabric_mod("lithium", "*");
+
+        let include = ["mods", "config", "world", "logs", "server.properties"]
+            .map(String::from)
+            .to_vec();
+        let plan = plan_export(&server, ExportFormat::Client, &include).unwrap();
+        assert_eq!(
+            plan.files,
+            vec!["config/sodium-options.json", "mods/lithium.jar", "mods/sodium.jar"]
+        );
+        assert!(plan.excluded.contains(&"mods/ledger.jar".to_string()));
+        assert!(plan
+            .excluded
+            .contains(&"config/ledger/ledger.toml".to_string()));
+        assert!(plan.excluded.contains(&"world/level.dat".to_string()));
+
+        let bundle = plan_export(&server, ExportFormat::Bundle, &[]).unwrap();
+        assert!(bundle.files.contains(&"server.properties".to_string()));
+        assert!(bundle.files.contains(&"mods/ledger.jar".to_string()));
+        assert!(!bundle.files.iter().any(|f| f.starts_with("logs/")));
+
+        assert!(plan_export(&server, ExportFormat::Client, &["../x".to_string()]).is_err());
+        fs::remove_dir_all(&server).unwrap();
+    }
+}
@@ -378,11 +378,16 @@ pub fn read_logs(
     Ok(logs)
 }
 
-pub fn spawn_server_output_reader<R>(server_id: String, reader: R)
+/// 在后台线程读取服务器输出，按核心类型识别启动完成，输出结束时线程退出
+pub fn spawn_server_output_reader<R>(
+    server_id: String,
+    core_type: String,
+    reader: R,
+) -> thread::JoinHandle<()>
 where
     R: Read + Send + 'static,
 {
-    std::thread::spawn(move || {
+    thread::spawn(move || {
         let mut buf_reader = BufReader::new(reader);
         let mut buffer = Vec::new();
 
@@ -399,15 +404,16 @@ where
 
                     let _ = append_server_log(&server_id, &line);
 
-                    if line.contains("Done (") && line.contains(")! For help") {
+                    if super::server_readiness::is_ready_line(&core_type, &line) {
                         super::global::server_manager().clear_starting(&server_id);
+                        super::update_rollback::on_server_ready(&server_id);
                         let _ = crate::plugins::api::emit_server_ready(&server_id);
                     }
                 }
                 Err(_) => break,
             }
         }
-    });
+    })
 }
 
 fn emit_server_log_line(server_id: &str, line: &str) {
@@ -6,7 +6,7 @@ use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::models::server::*;
-use crate::services::server_log_pipeline;
+use crate::services::{java_inventory, java_requirements, server_log_pipeline};
 use serde::{Deserialize, Serialize};
 
 const DATA_FILE: &str = "sea_lantern_servers.json";
@@ -84,6 +84,17 @@ impl ManagedConsoleEncoding {
     }
 }
 
+/// 整合包导入后确定的启动方式与核心信息，核心类型与版本为空时自动检测
+pub struct ModpackStartup {
+    pub startup_mode: String,
+    pub startup_file_path: Option<String>,
+    pub custom_command: Option<String>,
+    pub core_type: Option<String>,
+    pub core_version: String,
+    pub mc_version: Option<String>,
+    pub jvm_args: Vec<String>,
+}
+
 pub struct ServerManager {
     pub servers: Mutex<Vec<ServerInstance>>,
     pub processes: Mutex<HashMap<String, Child>>,
@@ -218,6 +229,8 @@ impl ServerManager {
 
     pub fn create_server(&self, req: CreateServerRequest) -> Result<ServerInstance, String> {
         let server_name = validate_server_name(&req.name)?;
+        let java_path =
+            java_requirements::select_java(&req.java_path, &req.mc_version, &req.core_type)?;
         let id = uuid::Uuid::new_v4().to_string();
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -239,7 +252,7 @@ impl ServerManager {
             jar_path: req.jar_path,
             startup_mode: normalize_startup_mode(&req.startup_mode).to_string(),
             custom_command: req.custom_command,
-            java_path: req.java_path,
+            java_path,
             max_memory: req.max_memory,
             min_memory: req.min_memory,
             jvm_args: Vec::new(),
@@ -362,12 +375,11 @@ impl ServerManager {
         Ok(server)
     }
 
-    pub fn import_modpack(&self, req: ImportModpackRequest) -> Result<ServerInstance, String> {
-        let source_path = Path::new(&req.modpack_path);
-        if !source_path.exists() {
-            return Err(format!("整合包路径不存在: {}", req.modpack_path));
-        }
-
+    /// 校验整合包导入请求，生成服务器 ID、名称与尚未创建的运行目录
+    pub fn prepare_modpack_run_dir(
+        &self,
+        req: &ImportModpackRequest,
+    ) -> Result<(String, String, PathBuf), String> {
         let id = uuid::Uuid::new_v4().to_string();
 
         let base_path = req.run_path.trim().to_string();
@@ -388,6 +400,17 @@ impl ServerManager {
             ));
         }
 
+        Ok((id, server_name, run_dir))
+    }
+
+    pub fn import_modpack(&self, req: ImportModpackRequest) -> Result<ServerInstance, String> {
+        let source_path = Path::new(&req.modpack_path);
+        if !source_path.exists() {
+            return Err(format!("整合包路径不存在: {}", req.modpack_path));
+        }
+
+        let (id, server_name, run_dir) = self.prepare_modpack_run_dir(&req)?;
+
         // 判断文件类型并处理
         let source_file_name = source_path
             .file_name()
@@ -454,6 +477,42 @@ impl ServerManager {
             Some(resolve_startup_file_path(source_path, &run_dir, raw_path)?)
         };
 
+        self.register_modpack(
+            req,
+            id,
+            server_name,
+            run_dir,
+            ModpackStartup {
+                startup_mode,
+                startup_file_path,
+                custom_command,
+                core_type: selected_core_type,
+                core_version: String::new(),
+                mc_version: selected_mc_version,
+                jvm_args: Vec::new(),
+            },
+        )
+    }
+
+    /// 为已经准备好文件的运行目录创建服务器实例
+    pub fn register_modpack(
+        &self,
+        req: ImportModpackRequest,
+        id: String,
+        server_name: String,
+        run_dir: PathBuf,
+        startup: ModpackStartup,
+    ) -> Result<ServerInstance, String> {
+        let ModpackStartup {
+            startup_mode,
+            startup_file_path,
+            custom_command,
+            core_type: selected_core_type,
+            core_version,
+            mc_version: selected_mc_version,
+            jvm_args,
+        } = startup;
+
         let startup_path = startup_file_path.clone().unwrap_or_default();
         if startup_mode != "custom" && !Path::new(&startup_path).exists() {
             return Err(format!("启动文件不存在: {}", startup_path));
@@ -525,21 +584,22 @@ impl ServerManager {
         };
         let core_type = selected_core_type.unwrap_or(detected_core_type);
         let mc_version = selected_mc_version.unwrap_or_else(|| "unknown".to_string());
+        let java_path = java_requirements::select_java(&req.java_path, &mc_version, &core_type)?;
 
         let server = ServerInstance {
             id: id.clone(),
             name: server_name,
             core_type,
-            core_version: String::new(),
+            core_version,
             mc_version,
             path: run_dir.to_string_lossy().to_string(),
             jar_path: startup_path,
             startup_mode,
             custom_command,
-            java_path: req.java_path,
+            java_path,
             max_memory: req.max_memory,
             min_memory: req.min_memory,
-            jvm_args: Vec::new(),
+            jvm_args,
             port,
             created_at: now,
             last_started_at: None,
@@ -658,7 +718,57 @@ impl ServerManager {
         Ok(server)
     }
 
+    /// 启动前检查模组，把发现的问题写入控制台日志；开启阻止启动时有错误则返回 Err。
+    /// 调用方确认忽略时，仅客户端模组不计入错误
+    fn run_mod_preflight(
+        &self,
+        server: &ServerInstance,
+        block_on_errors: bool,
+        allow_client_only: bool,
+    ) -> Result<(), String> {
+        let report = match super::mod_preflight::check(
+            std::path::Path::new(&server.path),
+            &server.core_type,
+            &server.mc_version,
+        ) {
+            Ok(report) => report,
+            Err(e) => {
+                println!("模组预检失败: {}", e);
+                return Ok(());
+            }
+        };
+        for issue in &report.issues {
+            let level = if issue.severity == "error" {
+                "错误"
+            } else {
+                "警告"
+            };
+            let _ = server_log_pipeline::append_sealantern_log(
+                &server.id,
+                &format!("[模组检查] {}: {}", level, issue.message),
+            );
+        }
+        if !block_on_errors {
+            return Ok(());
+        }
+        let errors = report
+            .issues
+            .iter()
+            .filter(|i| i.severity == "error")
+            .filter(|i| !(allow_client_only && i.kind == "client_only"))
+            .count();
+        if errors > 0 {
+            return Err(format!("模组检查发现 {} 个错误，已阻止启动", errors));
+        }
+        Ok(())
+    }
+
     pub fn start_server(&self, id: &str) -> Result<(), String> {
+        self.start_server_with(id, false)
+    }
+
+    /// 启动服务器；allow_client_only 为 true 时忽略仅客户端模组检查（用户已确认）
+    pub fn start_server_with(&self, id: &str, allow_client_only: bool) -> Result<(), String> {
         let server = {
             let servers = self.servers.lock().expect("servers lock poisoned");
             servers
@@ -691,6 +801,8 @@ impl ServerManager {
         }
 
         let settings = self.get_app_settings();
+        self.run_mod_preflight(&server, settings.block_start_on_mod_errors, allow_client_only)?;
+
         if settings.auto_accept_eula {
             let eula = std::path::Path::new(&server.path).join("eula.txt");
             let _ = std::fs::write(&eula, "# Auto-accepted by Sea Lantern CPE\neula=true\n");
@@ -824,6 +936,29 @@ impl ServerManager {
             }
         }
 
+        if startup_mode != "custom" && java_inventory::is_missing(&server.java_path) {
+            let suggestion = match java_inventory::replacement_for(&server) {
+                Some(java) => {
+                    format!("可以改用已安装的 Java {}（{}）", java.major_version, java.path)
+                }
+                None => "请在服务器设置中重新选择 Java".to_string(),
+            };
+            return Err(format!(
+                "Java 不存在: {}，可能已被系统升级移除。{}",
+                server.java_path, suggestion
+            ));
+        }
+
+        if startup_mode != "custom" {
+            if let Some(requirement) =
+                java_requirements::requirement(&server.mc_version, &server.core_type)
+            {
+                if let Some(major_version) = detect_java_major_version(&server.java_path) {
+                    java_requirements::check(&requirement, major_version, &server.java_path)?;
+                }
+            }
+        }
+
         let java_path_obj = std::path::Path::new(&server.java_path);
         let java_bin_dir = java_path_obj
             .parent()
@@ -1074,12 +1209,34 @@ impl ServerManager {
         self.save();
         let _ = server_log_pipeline::append_sealantern_log(id, "[Sea Lantern CPE] 服务器启动中...");
 
-        if let Some(stdout) = stdout {
-            server_log_pipeline::spawn_server_output_reader(id.to_string(), stdout);
-        }
-        if let Some(stderr) = stderr {
-            server_log_pipeline::spawn_server_output_reader(id.to_string(), stderr);
-        }
+        let core_type = &server.core_type;
+        let readers: Vec<_> = [
+            stdout.map(|out| {
+                server_log_pipeline::spawn_server_output_reader(
+                    id.to_string(),
+                    core_type.clone(),
+                    out,
+                )
+            }),
+            stderr.map(|err| {
+                server_log_pipeline::spawn_server_output_reader(
+                    id.to_string(),
+                    core_type.clone(),
+                    err,
+                )
+            }),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        // 两路输出都结束才算进程退出，回滚检查每次退出只执行一次
+        let server_id = id.to_string();
+        std::thread::spawn(move || {
+            for reader in readers {
+                let _ = reader.join();
+            }
+            super::update_rollback::on_server_exit(&server_id);
+        });
 
         Ok(())
     }
@@ -1120,6 +1277,7 @@ impl ServerManager {
             return Ok(());
         }
 
+        super::update_rollback::on_stop_requested(id);
         let _ = server_log_pipeline::append_sealantern_log(id, "[Sea Lantern] 正在发送停止命令...");
         let _ = self.send_command(id, "stop");
 
@@ -1278,6 +1436,38 @@ impl ServerManager {
         }
     }
 
+    /// 批量修改服务器使用的 Java，下次启动时生效
+    pub fn update_java_path(&self, ids: &[String], java_path: &str) -> Result<(), String> {
+        let mut servers = self.servers.lock().expect("servers lock poisoned");
+        if let Some(missing) = ids.iter().find(|id| !servers.iter().any(|s| &s.id == *id)) {
+            return Err(format!("未找到服务器: {}", missing));
+        }
+        for server in servers.iter_mut().filter(|s| ids.contains(&s.id)) {
+            server.java_path = java_path.to_string();
+        }
+        drop(servers);
+        self.save();
+        Ok(())
+    }
+
+    /// 更新核心相关的字段（启动文件、启动方式、核心类型与版本），其余配置保持不变
+    pub fn update_core_info(&self, updated: &ServerInstance) -> Result<(), String> {
+        let mut servers = self.servers.lock().expect("servers lock poisoned");
+        let server = servers
+            .iter_mut()
+            .find(|s| s.id == updated.id)
+            .ok_or_else(|| "未找到服务器".to_string())?;
+        server.jar_path = updated.jar_path.clone();
+        server.startup_mode = updated.startup_mode.clone();
+        server.core_type = updated.core_type.clone();
+        server.core_version = updated.core_version.clone();
+        server.mc_version = updated.mc_version.clone();
+        server.java_path = updated.java_path.clone();
+        drop(servers);
+        self.save();
+        Ok(())
+    }
+
     pub fn stop_all_servers(&self) {
         let ids: Vec<String> = self
             .processes
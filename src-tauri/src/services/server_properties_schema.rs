@@ -0,0 +1,432 @@
+//! server.properties 的版本化结构定义与校验。
+//!
+//! 每个配置项记录引入与移除的版本，按服务器的 mc_version 过滤；
+//! 版本未知（快照、自定义核心等）时使用最新版本的结构。
+//! 描述文本通过 I18nService 的 `server_properties.<key>` 键获取。
+
+use std::collections::HashMap;
+
+use crate::models::config::{ConfigEntry, PropertyValidationError};
+use crate::services::global;
+use crate::utils::mc_version;
+
+type Version = (u32, u32, u32);
+
+pub enum PropertyKind {
+    Boolean,
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    /// 只允许列出的值
+    Enum(&'static [&'static str]),
+    /// 列出常用值，但模组可能注册其他值，因此不做限制
+    Suggest(&'static [&'static str]),
+    /// 空字符串或 40 位十六进制 SHA-1
+    Sha1,
+    Text,
+}
+
+pub struct PropertySpec {
+    pub key: &'static str,
+    pub kind: PropertyKind,
+    pub default: &'static str,
+    pub category: &'static str,
+    /// 引入该配置项的版本
+    pub since: Option<Version>,
+    /// 移除该配置项的版本（不含）
+    pub until: Option<Version>,
+}
+
+const fn spec(
+    key: &'static str,
+    kind: PropertyKind,
+    default: &'static str,
+    category: &'static str,
+) -> PropertySpec {
+    PropertySpec {
+        key,
+        kind,
+        default,
+        category,
+        since: None,
+        until: None,
+    }
+}
+
+impl PropertySpec {
+    const fn since(mut self, version: Version) -> Self {
+        self.since = Some(version);
+        self
+    }
+
+    const fn until(mut self, version: Version) -> Self {
+        self.until = Some(version);
+        self
+    }
+
+    fn applies_to(&self, version: Option<Version>) -> bool {
+        match version {
+            Some(version) => {
+                self.since.is_none_or(|since| version >= since)
+                    && self.until.is_none_or(|until| version < until)
+            }
+            None => self.until.is_none(),
+        }
+    }
+
+    pub fn value_type(&self) -> &'static str {
+        match self.kind {
+            PropertyKind::Boolean => "boolean",
+            PropertyKind::Integer { .. } => "number",
+            PropertyKind::Enum(_) | PropertyKind::Suggest(_) => "select",
+            PropertyKind::Sha1 | PropertyKind::Text => "string",
+        }
+    }
+
+    pub fn options(&self) -> Vec<String> {
+        match self.kind {
+            PropertyKind::Enum(options) | PropertyKind::Suggest(options) => {
+                options.iter().map(|option| option.to_string()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn range(&self) -> (Option<i64>, Option<i64>) {
+        match self.kind {
+            PropertyKind::Integer { min, max } => (Some(min), Some(max)),
+            _ => (None, None),
+        }
+    }
+
+    pub fn description(&self) -> String {
+        let key = format!("server_properties.{}", self.key);
+        let text = global::i18n_service().t(&key);
+        if text == key {
+            String::new()
+        } else {
+            text
+        }
+    }
+
+    /// 校验单个值，失败时返回本地化的错误信息
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        let i18n = global::i18n_service();
+        let value = value.trim();
+        let with = |pairs: &[(&str, String)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.clone()))
+                .collect::<HashMap<_, _>>()
+        };
+
+        match self.kind {
+            PropertyKind::Boolean => {
+                if value != "true" && value != "false" {
+                    return Err(i18n.t("server_properties.error.boolean"));
+                }
+            }
+            PropertyKind::Integer { min, max } => {
+                let number = value
+                    .parse::<i64>()
+                    .map_err(|_| i18n.t("server_properties.error.integer"))?;
+                if number < min || number > max {
+                    return Err(i18n.t_with_options(
+                        "server_properties.error.range",
+                        &with(&[("min", min.to_string()), ("max", max.to_string())]),
+                    ));
+                }
+            }
+            PropertyKind::Enum(options) => {
+                // 旧版本允许用数字表示难度和游戏模式
+                let numeric_alias = matches!(self.key, "difficulty" | "gamemode")
+                    && value
+                        .parse::<usize>()
+                        .is_ok_and(|index| index < options.len());
+                if !numeric_alias && !options.contains(&value) {
+                    return Err(i18n.t_with_options(
+                        "server_properties.error.enum",
+                        &with(&[("options", options.join(", "))]),
+                    ));
+                }
+            }
+            PropertyKind::Sha1 => {
+                let valid = value.is_empty()
+                    || (value.len() == 40 && value.chars().all(|c| c.is_ascii_hexdigit()));
+                if !valid {
+                    return Err(i18n.t("server_properties.error.sha1"));
+                }
+            }
+            PropertyKind::Suggest(_) | PropertyKind::Text => {}
+        }
+        Ok(())
+    }
+}
+
+const INT_MAX: i64 = i32::MAX as i64;
+const INT_MIN: i64 = i32::MIN as i64;
+
+const LEVEL_TYPES: &[&str] = &[
+    "minecraft:normal",
+    "minecraft:flat",
+    "minecraft:large_biomes",
+    "minecraft:amplified",
+    "minecraft:single_biome_surface",
+];
+const LEGACY_LEVEL_TYPES: &[&str] = &[
+    "default",
+    "flat",
+    "largeBiomes",
+    "amplified",
+    "buffet",
+    "customized",
+    "default_1_1",
+];
+
+static PROPERTY_SPECS: &[PropertySpec] = &[
+    spec("accepts-transfers", PropertyKind::Boolean, "false", "network").since((1, 20, 5)),
+    spec("allow-flight", PropertyKind::Boolean, "false", "game"),
+    spec("allow-nether", PropertyKind::Boolean, "true", "world"),
+    spec("broadcast-console-to-ops", PropertyKind::Boolean, "true", "admin"),
+    spec("broadcast-rcon-to-ops", PropertyKind::Boolean, "true", "admin"),
+    spec("bug-report-link", PropertyKind::Text, "", "display").since((1, 21, 0)),
+    spec(
+        "difficulty",
+        PropertyKind::Enum(&["peaceful", "easy", "normal", "hard"]),
+        "easy",
+        "game",
+    ),
+    spec("enable-command-block", PropertyKind::Boolean, "false", "game"),
+    spec("enable-jmx-monitoring", PropertyKind::Boolean, "false", "performance").since((1, 16, 0)),
+    spec("enable-query", PropertyKind::Boolean, "false", "network"),
+    spec("enable-rcon", PropertyKind::Boolean, "false", "network"),
+    spec("enable-status", PropertyKind::Boolean, "true", "network").since((1, 16, 0)),
+    spec("enforce-secure-profile", PropertyKind::Boolean, "true", "player").since((1, 19, 0)),
+    spec("enforce-whitelist", PropertyKind::Boolean, "false", "player"),
+    spec(
+        "entity-broadcast-range-percentage",
+        PropertyKind::Integer { min: 10, max: 1000 },
+        "100",
+        "performance",
+    )
+    .since((1, 16, 0)),
+    spec("force-gamemode", PropertyKind::Boolean, "false", "game"),
+    spec(
+        "function-permission-level",
+        PropertyKind::Integer { min: 1, max: 4 },
+        "2",
+        "admin",
+    )
+    .since((1, 14, 4)),
+    spec(
+        "gamemode",
+        PropertyKind::Enum(&["survival", "creative", "adventure", "spectator"]),
+        "survival",
+        "game",
+    ),
+    spec("generate-structures", PropertyKind::Boolean, "true", "world"),
+    spec("generator-settings", PropertyKind::Text, "{}", "world"),
+    spec("hardcore", PropertyKind::Boolean, "false", "game"),
+    spec("hide-online-players", PropertyKind::Boolean, "false", "player").since((1, 18, 0)),
+    spec("initial-disabled-packs", PropertyKind::Text, "", "world").since((1, 19, 3)),
+    spec("initial-enabled-packs", PropertyKind::Text, "vanilla", "world").since((1, 19, 3)),
+    spec("level-name", PropertyKind::Text, "world", "world"),
+    spec("level-seed", PropertyKind::Text, "", "world"),
+    spec("level-type", PropertyKind::Suggest(LEVEL_TYPES), "minecraft:normal", "world")
+        .since((1, 19, 0)),
+    spec("level-type", PropertyKind::Suggest(LEGACY_LEVEL_TYPES), "default", "world")
+        .until((1, 19, 0)),
+    spec("log-ips", PropertyKind::Boolean, "true", "admin").since((1, 20, 2)),
+    spec("max-build-height", PropertyKind::Integer { min: 1, max: 256 }, "256", "world")
+        .until((1, 17, 0)),
+    spec(
+        "max-chained-neighbor-updates",
+        PropertyKind::Integer { min: INT_MIN, max: INT_MAX },
+        "1000000",
+        "performance",
+    )
+    .since((1, 19, 0)),
+    spec("max-players", PropertyKind::Integer { min: 0, max: INT_MAX }, "20", "player"),
+    spec(
+        "max-tick-time",
+        PropertyKind::Integer { min: -1, max: i64::MAX },
+        "60000",
+        "performance",
+    ),
+    spec(
+        "max-world-size",
+        PropertyKind::Integer { min: 1, max: 29_999_984 },
+        "29999984",
+        "world",
+    ),
+    spec("motd", PropertyKind::Text, "A Minecraft Server", "display"),
+    spec(
+        "network-compression-threshold",
+        PropertyKind::Integer { min: -1, max: INT_MAX },
+        "256",
+        "performance",
+    ),
+    spec("online-mode", PropertyKind::Boolean, "true", "player"),
+    spec("op-permission-level", PropertyKind::Integer { min: 0, max: 4 }, "4", "admin"),
+    spec(
+        "pause-when-empty-seconds",
+        PropertyKind::Integer { min: INT_MIN, max: INT_MAX },
+        "60",
+        "performance",
+    )
+    .since((1, 21, 2)),
+    spec(
+        "player-idle-timeout",
+        PropertyKind::Integer { min: 0, max: INT_MAX },
+        "0",
+        "player",
+    ),
+    spec("prevent-proxy-connections", PropertyKind::Boolean, "false", "network"),
+    spec("previews-chat", PropertyKind::Boolean, "false", "display")
+        .since((1, 19, 0))
+        .until((1, 19, 3)),
+    spec("pvp", PropertyKind::Boolean, "true", "game").until((1, 21, 9)),
+    spec("query.port", PropertyKind::Integer { min: 1, max: 65534 }, "25565", "network"),
+    spec("rate-limit", PropertyKind::Integer { min: 0, max: INT_MAX }, "0", "network"),
+    spec("rcon.password", PropertyKind::Text, "", "network"),
+    spec("rcon.port", PropertyKind::Integer { min: 1, max: 65534 }, "25575", "network"),
+    spec(
+        "region-file-compression",
+        PropertyKind::Enum(&["deflate", "lz4", "none"]),
+        "deflate",
+        "performance",
+    )
+    .since((1, 20, 5)),
+    spec("require-resource-pack", PropertyKind::Boolean, "false", "resource").since((1, 17, 0)),
+    spec("resource-pack", PropertyKind::Text, "", "resource"),
+    spec("resource-pack-id", PropertyKind::Text, "", "resource").since((1, 20, 3)),
+    spec("resource-pack-prompt", PropertyKind::Text, "", "resource").since((1, 17, 0)),
+    spec("resource-pack-sha1", PropertyKind::Sha1, "", "resource"),
+    spec("server-ip", PropertyKind::Text, "", "network"),
+    spec("server-port", PropertyKind::Integer { min: 1, max: 65534 }, "25565", "network"),
+    spec(
+        "simulation-distance",
+        PropertyKind::Integer { min: 3, max: 32 },
+        "10",
+        "performance",
+    )
+    .since((1, 18, 0)),
+    spec("snooper-enabled", PropertyKind::Boolean, "true", "other").until((1, 18, 0)),
+    spec("spawn-animals", PropertyKind::Boolean, "true", "world").until((1, 21, 2)),
+    spec("spawn-monsters", PropertyKind::Boolean, "true", "world").until((1, 21, 9)),
+    spec("spawn-npcs", PropertyKind::Boolean, "true", "world").until((1, 21, 2)),
+    spec(
+        "spawn-protection",
+        PropertyKind::Integer { min: 0, max: INT_MAX },
+        "16",
+        "world",
+    ),
+    spec("sync-chunk-writes", PropertyKind::Boolean, "true", "performance").since((1, 16, 0)),
+    spec("text-filtering-config", PropertyKind::Text, "", "other").since((1, 17, 0)),
+    spec("use-native-transport", PropertyKind::Boolean, "true", "network"),
+    spec("view-distance", PropertyKind::Integer { min: 2, max: 32 }, "10", "performance"),
+    spec("white-list", PropertyKind::Boolean, "false", "player"),
+];
+
+/// 返回适用于该版本的配置项结构，按 key 索引
+pub fn schema_for(mc_version: Option<&str>) -> HashMap<&'static str, &'static PropertySpec> {
+    let version = mc_version.and_then(mc_version::parse);
+    PROPERTY_SPECS
+        .iter()
+        .filter(|spec| spec.applies_to(version))
+        .map(|spec| (spec.key, spec))
+        .collect()
+}
+
+/// 构造配置项条目；不在结构中的键（插件或第三方核心添加）按字符串处理
+pub fn build_entry(key: &str, value: &str, spec: Option<&PropertySpec>) -> ConfigEntry {
+    let (min, max) = spec.map(PropertySpec::range).unwrap_or((None, None));
+    ConfigEntry {
+        key: key.to_string(),
+        value: value.to_string(),
+        description: spec.map(PropertySpec::description).unwrap_or_default(),
+        value_type: spec
+            .map(|spec| spec.value_type().to_string())
+            .unwrap_or_else(|| "string".to_string()),
+        default_value: spec
+            .map(|spec| spec.default.to_string())
+            .unwrap_or_default(),
+        category: spec
+            .map(|spec| spec.category.to_string())
+            .unwrap_or_else(|| "other".to_string()),
+        options: spec.map(PropertySpec::options).unwrap_or_default(),
+        min,
+        max,
+    }
+}
+
+/// 完整的配置项结构，值为默认值
+pub fn schema_entries(mc_version: Option<&str>) -> Vec<ConfigEntry> {
+    let mut entries = schema_for(mc_version)
+        .into_values()
+        .map(|spec| build_entry(spec.key, spec.default, Some(spec)))
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.category.cmp(&b.category).then(a.key.cmp(&b.key)));
+    entries
+}
+
+/// 校验待写入的值，返回所有不合法的键
+pub fn validate_values(
+    values: &HashMap<String, String>,
+    mc_version: Option<&str>,
+) -> Vec<PropertyValidationError> {
+    let schema = schema_for(mc_version);
+    let mut errors = values
+        .iter()
+        .filter_map(|(key, value)| {
+            let spec = schema.get(key.as_str())?;
+            spec.validate(value)
+                .err()
+                .map(|message| PropertyValidationError {
+                    key: key.clone(),
+                    value: value.clone(),
+                    message,
+                })
+        })
+        .collect::<Vec<_>>();
+    errors.sort_by(|a, b| a.key.cmp(&b.key));
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn schema_follows_version_ranges() {
+        let legacy = schema_for(Some("1.12.2"));
+        assert!(legacy.contains_key("max-build-height"));
+        assert!(!legacy.contains_key("simulation-distance"));
+        assert_eq!(legacy["level-type"].default, "default");
+
+        let modern = schema_for(Some("1.20.4"));
+        assert!(!modern.contains_key("max-build-height"));
+        assert!(modern.contains_key("simulation-distance"));
+        assert_eq!(modern["level-type"].default, "minecraft:normal");
+
+        let unknown = schema_for(Some("24w14a"));
+        assert!(!unknown.contains_key("snooper-enabled"));
+        assert!(unknown.contains_key("accepts-transfers"));
+    }
+
+    #[test]
+    fn validate_values_reports_each_invalid_key() {
+        let mut values = HashMap::new();
+        values.insert("server-port".to_string(), "70000".to_string());
+        values.insert("difficulty".to_string(), "insane".to_string());
+        values.insert("pvp".to_string(), "yes".to_string());
+        values.insert("gamemode".to_string(), "1".to_string());
+        values.insert("motd".to_string(), "Hello".to_string());
+        values.insert("custom-plugin-key".to_string(), "anything".to_string());
+
+        let errors = validate_values(&values, Some("1.20.1"));
+        let keys = errors.iter().map(|e| e.key.as_str()).collect::<Vec<_>>();
+        assert_eq!(keys, vec!["difficulty", "pvp", "server-port"]);
+    }
+}
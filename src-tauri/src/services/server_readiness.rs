@@ -0,0 +1,36 @@
+//! 根据服务器输出判断启动是否完成。
+//!
+//! Bukkit 系、原版与模组服务端输出 `Done (12.3s)! For help, type "help"`；
+//! Velocity 只输出 `Done (1.23s)!`；BungeeCord 系没有 Done 行，以开始监听端口为准。
+
+use crate::services::server_installer::CoreType;
+
+/// 这一行输出是否表示服务器已启动完成
+pub fn is_ready_line(core_type: &str, line: &str) -> bool {
+    let core_key = CoreType::normalize_to_api_core_key(core_type).unwrap_or_default();
+    match core_key.as_str() {
+        "velocity" => line.contains("Done (") && line.contains(")!"),
+        "bungeecord" | "lightfall" | "travertine" => line.contains("Listening on /"),
+        _ => line.contains("Done (") && line.contains(")! For help"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_ready_lines_per_core_family() {
+        let paper = r#"[12:00:05 INFO]: Done (6.512s)! For help, type "help""#;
+        let velocity = "[12:00:02 INFO]: Done (1.23s)!";
+        let bungee = "12:00:01 [INFO] Listening on /0.0.0.0:25577";
+
+        assert!(is_ready_line("paper", paper));
+        assert!(is_ready_line("vanilla", paper));
+        assert!(!is_ready_line("paper", velocity));
+        assert!(is_ready_line("velocity", velocity));
+        assert!(!is_ready_line("velocity", "[12:00:01 INFO]: Loading plugins..."));
+        assert!(is_ready_line("bungeecord", bungee));
+        assert!(!is_ready_line("bungeecord", velocity));
+    }
+}
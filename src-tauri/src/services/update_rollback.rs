@@ -0,0 +1,387 @@
+//! 更新后的试启动与自动回滚。
+//!
+//! 替换服务器文件前先把旧文件移入 `.sealantern/rollback/<时间戳>/`，并在
+//! `.sealantern/pending_rollback.json` 记录如何撤销。下一次启动完成时确认更新并
+//! 删除备份；如果服务器在此之前退出，则自动恢复旧文件。启动前多次更新会合并为一次回滚。
+//! 更新修改了服务器配置（例如升级核心）时，回滚同时恢复更新前的配置。
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::server::ServerInstance;
+use crate::services::global;
+use crate::services::server_log_pipeline;
+
+const STATE_DIR: &str = ".sealantern";
+const PENDING_FILE: &str = "pending_rollback.json";
+const BACKUP_DIR: &str = "rollback";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreEntry {
+    /// 备份文件，相对服务器目录
+    pub backup: String,
+    /// 恢复到的位置，相对服务器目录
+    pub target: String,
+}
+
+/// 等待试启动确认的更新
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingRollback {
+    /// 发起更新的功能，多次更新以 `, ` 连接
+    pub reason: String,
+    pub created_at: u64,
+    pub backup_dir: String,
+    pub restore: Vec<RestoreEntry>,
+    /// 更新新增的文件，回滚时删除
+    pub remove: Vec<String>,
+    /// 更新前的服务器配置，回滚时恢复其中的核心信息
+    #[serde(default)]
+    pub instance: Option<ServerInstance>,
+}
+
+/// 串行化对回滚记录的读写，避免更新、确认与回滚互相覆盖
+static STATE_LOCK: Mutex<()> = Mutex::new(());
+/// 用户在启动完成前主动停止的服务器，这次退出不视为启动失败
+static STOP_REQUESTED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+fn pending_path(server_path: &Path) -> PathBuf {
+    server_path.join(STATE_DIR).join(PENDING_FILE)
+}
+
+fn to_relative(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub fn pending(server_path: &Path) -> Option<PendingRollback> {
+    fs::read_to_string(pending_path(server_path))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn save_pending(server_path: &Path, plan: &PendingRollback) -> Result<(), String> {
+    let path = pending_path(server_path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let content =
+        serde_json::to_string_pretty(plan).map_err(|e| format!("序列化回滚记录失败: {}", e))?;
+    fs::write(path, content).map_err(|e| format!("保存回滚记录失败: {}", e))
+}
+
+/// 一组可回滚的文件替换。每一步都会立即写入回滚记录，中途失败也能撤销已完成的部分
+pub struct RollbackTransaction {
+    server_path: PathBuf,
+    plan: PendingRollback,
+}
+
+impl RollbackTransaction {
+    pub fn begin(server_path: &Path, reason: &str) -> Result<Self, String> {
+        let _guard = STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let plan = match pending(server_path) {
+            Some(mut plan) => {
+                if !plan.reason.split(", ").any(|r| r == reason) {
+                    plan.reason = format!("{}, {}", plan.reason, reason);
+                }
+                plan
+            }
+            None => {
+                let created_at = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_secs();
+                PendingRollback {
+                    reason: reason.to_string(),
+                    created_at,
+                    backup_dir: format!("{}/{}/{}", STATE_DIR, BACKUP_DIR, created_at),
+                    restore: Vec::new(),
+                    remove: Vec::new(),
+                    instance: None,
+                }
+            }
+        };
+        Ok(Self {
+            server_path: server_path.to_path_buf(),
+            plan,
+        })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let _guard = STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        save_pending(&self.server_path, &self.plan)
+    }
+
+    /// 把现有文件移入备份目录。同一文件只保留第一次的备份，新增的文件不需要备份
+    fn stash(&mut self, target: &str, copy: bool) -> Result<(), String> {
+        let path = self.server_path.join(target);
+        if !path.exists()
+            || self.plan.restore.iter().any(|e| e.target == target)
+            || self.plan.remove.iter().any(|t| t == target)
+        {
+            return Ok(());
+        }
+
+        let file_name = Path::new(target)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let backup = format!("{}/{}_{}", self.plan.backup_dir, self.plan.restore.len(), file_name);
+        let backup_path = self.server_path.join(&backup);
+        if let Some(parent) = backup_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建备份目录失败: {}", e))?;
+        }
+        let result = if copy {
+            fs::copy(&path, &backup_path).map(|_| ())
+        } else {
+            fs::rename(&path, &backup_path)
+        };
+        result.map_err(|e| format!("备份 {} 失败: {}", target, e))?;
+        self.plan
+            .restore
+            .push(RestoreEntry { backup, target: target.to_string() });
+        self.save()
+    }
+
+    /// 保存文件当前内容的副本，之后可以直接修改原文件
+    pub fn backup_file(&mut self, target: &Path) -> Result<(), String> {
+        let target = to_relative(target);
+        if !self.server_path.join(&target).exists() {
+            if !self.plan.remove.contains(&target) {
+                self.plan.remove.push(target);
+                self.save()?;
+            }
+            return Ok(());
+        }
+        self.stash(&target, true)
+    }
+
+    /// 用 `staged` 替换 `old`，新文件放在 `new` 位置（两者可以相同）。路径相对服务器目录
+    pub fn replace_file(&mut self, old: &Path, new: &Path, staged: &Path) -> Result<(), String> {
+        let old = to_relative(old);
+        let new = to_relative(new);
+        self.stash(&old, false)?;
+        if old != new {
+            self.stash(&new, false)?;
+        }
+        // 本次更新中新增过的文件再次被替换时直接删除
+        for target in [&old, &new] {
+            if self.plan.remove.contains(target) {
+                let _ = fs::remove_file(self.server_path.join(target));
+            }
+        }
+
+        let new_path = self.server_path.join(&new);
+        if let Some(parent) = new_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        if fs::rename(staged, &new_path).is_err() {
+            fs::copy(staged, &new_path).map_err(|e| format!("写入 {} 失败: {}", new, e))?;
+            let _ = fs::remove_file(staged);
+        }
+        if !self.plan.remove.contains(&new) {
+            self.plan.remove.push(new);
+        }
+        self.save()
+    }
+
+    /// 记录更新前的服务器配置。多次更新只保留第一次的
+    pub fn record_instance(&mut self, instance: &ServerInstance) -> Result<(), String> {
+        if self.plan.instance.is_none() {
+            self.plan.instance = Some(instance.clone());
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    pub fn commit(self) -> Result<PendingRollback, String> {
+        self.save()?;
+        Ok(self.plan)
+    }
+}
+
+/// 更新通过试启动，删除备份
+pub fn confirm(server_path: &Path) -> Result<(), String> {
+    let _guard = STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(plan) = pending(server_path) else {
+        return Ok(());
+    };
+    let _ = fs::remove_dir_all(server_path.join(&plan.backup_dir));
+    fs::remove_file(pending_path(server_path)).map_err(|e| format!("删除回滚记录失败: {}", e))
+}
+
+/// 恢复更新前的文件。没有待确认的更新时返回 false
+pub fn rollback(server_path: &Path) -> Result<bool, String> {
+    let _guard = STATE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(plan) = pending(server_path) else {
+        return Ok(false);
+    };
+
+    for target in &plan.remove {
+        let path = server_path.join(target);
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("删除 {} 失败: {}", target, e))?;
+        }
+    }
+    for entry in plan.restore.iter().rev() {
+        let target = server_path.join(&entry.target);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+        }
+        fs::rename(server_path.join(&entry.backup), &target)
+            .map_err(|e| format!("恢复 {} 失败: {}", entry.target, e))?;
+    }
+
+    if let Some(instance) = &plan.instance {
+        global::server_manager().update_core_info(instance)?;
+    }
+
+    let _ = fs::remove_dir_all(server_path.join(&plan.backup_dir));
+    fs::remove_file(pending_path(server_path)).map_err(|e| format!("删除回滚记录失败: {}", e))?;
+    Ok(true)
+}
+
+fn server_path(server_id: &str) -> Option<PathBuf> {
+    global::server_manager()
+        .get_server_list()
+        .into_iter()
+        .find(|s| s.id == server_id)
+        .map(|s| PathBuf::from(s.path))
+}
+
+pub fn on_stop_requested(server_id: &str) {
+    let mut stopped = STOP_REQUESTED.lock().unwrap_or_else(|e| e.into_inner());
+    stopped
+        .get_or_insert_with(HashSet::new)
+        .insert(server_id.to_string());
+}
+
+/// 服务器启动完成
+pub fn on_server_ready(server_id: &str) {
+    let Some(path) = server_path(server_id) else {
+        return;
+    };
+    if pending(&path).is_none() {
+        return;
+    }
+    match confirm(&path) {
+        Ok(()) => {
+            let _ = server_log_pipeline::append_sealantern_log(
+                server_id,
+                "[Sea Lantern] 更新后启动成功，已清理更新前的备份",
+            );
+        }
+        Err(e) => println!("确认更新失败: {}", e),
+    }
+}
+
+/// 服务器进程退出，每次退出只调用一次。若此时更新仍未确认，说明启动失败，恢复旧文件
+pub fn on_server_exit(server_id: &str) {
+    let Some(path) = server_path(server_id) else {
+        return;
+    };
+    let message = match handle_exit(server_id, &path) {
+        Ok(false) => return,
+        Ok(true) => "[Sea Lantern] 更新后启动失败，已自动回滚到更新前的文件".to_string(),
+        Err(e) => format!("[Sea Lantern] 更新后启动失败，自动回滚出错: {}", e),
+    };
+    let _ = server_log_pipeline::append_sealantern_log(server_id, &message);
+}
+
+/// 用户主动停止时保留待确认的更新，否则回滚。返回是否执行了回滚
+fn handle_exit(server_id: &str, server_path: &Path) -> Result<bool, String> {
+    let stop_requested = STOP_REQUESTED
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .as_mut()
+        .is_some_and(|stopped| stopped.remove(server_id));
+    if stop_requested {
+        return Ok(false);
+    }
+    rollback(server_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rollback_restores_replaced_and_removes_added_files() {
+        let server = std::env::temp_dir().join(format!("sl_rollback_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(server.join("mods")).unwrap();
+        fs::write(server.join("mods/a-1.0.jar"), "old").unwrap();
+        let staged = server.join("staged.jar");
+        fs::write(&staged, "new").unwrap();
+
+        let mut tx = RollbackTransaction::begin(&server, "mod_update").unwrap();
+        tx.replace_file(Path::new("mods/a-1.0.jar"), Path::new("mods/a-2.0.jar"), &staged)
+            .unwrap();
+        tx.backup_file(Path::new(".sealantern/mods.json")).unwrap();
+        tx.commit().unwrap();
+        fs::write(server.join(".sealantern/mods.json"), "[]").unwrap();
+
+        assert!(!server.join("mods/a-1.0.jar").exists());
+        assert_eq!(fs::read_to_string(server.join("mods/a-2.0.jar")).unwrap(), "new");
+        assert!(pending(&server).is_some());
+
+        assert!(rollback(&server).unwrap());
+        assert_eq!(fs::read_to_string(server.join("mods/a-1.0.jar")).unwrap(), "old");
+        assert!(!server.join("mods/a-2.0.jar").exists());
+        assert!(!server.join(".sealantern/mods.json").exists());
+        assert!(pending(&server).is_none());
+        assert!(!rollback(&server).unwrap());
+
+        fs::remove_dir_all(&server).unwrap();
+    }
+
+    fn updated_server() -> PathBuf {
+        let server = std::env::temp_dir().join(format!("sl_rollback_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(server.join("plugins")).unwrap();
+        fs::write(server.join("plugins/a-1.0.jar"), "old").unwrap();
+        let staged = server.join("staged.jar");
+        fs::write(&staged, "new").unwrap();
+
+        let mut tx = RollbackTransaction::begin(&server, "plugin_update").unwrap();
+        tx.replace_file(Path::new("plugins/a-1.0.jar"), Path::new("plugins/a-2.0.jar"), &staged)
+            .unwrap();
+        tx.commit().unwrap();
+        server
+    }
+
+    #[test]
+    fn exit_after_stop_request_keeps_pending_update() {
+        let server = updated_server();
+        let server_id = uuid::Uuid::new_v4().to_string();
+
+        on_stop_requested(&server_id);
+        assert!(!handle_exit(&server_id, &server).unwrap());
+        assert_eq!(fs::read_to_string(server.join("plugins/a-2.0.jar")).unwrap(), "new");
+        assert!(pending(&server).is_some());
+
+        // 停止请求只对一次退出有效，之后的启动失败仍会回滚
+        assert!(handle_exit(&server_id, &server).unwrap());
+        assert_eq!(fs::read_to_string(server.join("plugins/a-1.0.jar")).unwrap(), "old");
+
+        fs::remove_dir_all(&server).unwrap();
+    }
+
+    #[test]
+    fn exit_after_crash_rolls_back() {
+        let server = updated_server();
+        let server_id = uuid::Uuid::new_v4().to_string();
+
+        assert!(handle_exit(&server_id, &server).unwrap());
+        assert_eq!(fs::read_to_string(server.join("plugins/a-1.0.jar")).unwrap(), "old");
+        assert!(!server.join("plugins/a-2.0.jar").exists());
+        assert!(pending(&server).is_none());
+        assert!(!handle_exit(&server_id, &server).unwrap());
+
+        fs::remove_dir_all(&server).unwrap();
+    }
+}
@@ -0,0 +1,229 @@
+//! 世界区块裁剪：删除几乎无人停留过的区块（例如只是飞过一次），并紧凑重写区域文件。
+//!
+//! 判断依据是区块 NBT 中的 InhabitedTime（玩家在该区块附近累计停留的 tick 数）。
+//! 出生点周围的保护半径内不做裁剪；无法解析的区块一律保留。
+//! 地形区块被删除时，同坐标的实体（entities/）与兴趣点（poi/）数据也一并删除，
+//! 避免服务器重新生成地形后残留旧实体。
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::models::world::{RegionPruneSummary, WorldPruneReport, WorldPruneRequest};
+use crate::services::region_file::{self, ChunkLocation, RegionFile, HEADER_SIZE, SECTOR_SIZE};
+use crate::utils::nbt::{self, NbtTag};
+
+/// 与地形区域文件共享坐标的附属数据目录
+const COMPANION_DIRS: [&str; 2] = ["entities", "poi"];
+
+pub fn resolve_world_dir(server_path: &Path, world_name: Option<&str>) -> Result<PathBuf, String> {
+    let name = match world_name.map(str::trim).filter(|name| !name.is_empty()) {
+        Some(name) => name.to_string(),
+        None => {
+            let props_path = server_path.join("server.properties");
+            crate::services::config_parser::read_properties(&props_path.to_string_lossy())
+                .ok()
+                .and_then(|props| props.get("level-name").cloned())
+                .filter(|name| !name.trim().is_empty())
+                .unwrap_or_else(|| "world".to_string())
+        }
+    };
+
+    if Path::new(&name)
+        .components()
+        .any(|c| !matches!(c, std::path::Component::Normal(_)))
+    {
+        return Err(format!("世界名称无效: {}", name));
+    }
+
+    let world_dir = server_path.join(&name);
+    if !world_dir.is_dir() {
+        return Err(format!("世界目录不存在: {}", world_dir.display()));
+    }
+    Ok(world_dir)
+}
+
+/// 从 level.dat 读取出生点所在区块，读取失败时返回 (0, 0)
+pub fn read_spawn_chunk(world_dir: &Path) -> (i32, i32) {
+    let root = match std::fs::read(world_dir.join("level.dat"))
+        .ok()
+        .and_then(|bytes| nbt::parse_gzip(&bytes).ok())
+    {
+        Some((_, root)) => root,
+        None => return (0, 0),
+    };
+
+    let spawn_x = root.get_path(&["Data", "SpawnX"]).and_then(NbtTag::as_i64);
+    let spawn_z = root.get_path(&["Data", "SpawnZ"]).and_then(NbtTag::as_i64);
+    let (x, z) = match (spawn_x, spawn_z) {
+        (Some(x), Some(z)) => (x, z),
+        _ => {
+            // 新版本将出生点移动到 Data.spawn.pos（整型数组 [x, y, z]）
+            match root
+                .get_path(&["Data", "spawn", "pos"])
+                .and_then(NbtTag::as_int_array)
+            {
+                Some([x, _, z]) => (*x as i64, *z as i64),
+                _ => (0, 0),
+            }
+        }
+    };
+
+    ((x >> 4) as i32, (z >> 4) as i32)
+}
+
+/// 世界内的维度目录：(维度名, 维度根目录, 是否受出生点保护)
+pub fn dimension_dirs(world_dir: &Path) -> Vec<(String, PathBuf, bool)> {
+    let mut dims = vec![("overworld".to_string(), world_dir.to_path_buf(), true)];
+    for (name, sub) in [("the_nether", "DIM-1"), ("the_end", "DIM1")] {
+        let dir = world_dir.join(sub);
+        if dir.join("region").is_dir() {
+            dims.push((name.to_string(), dir, false));
+        }
+    }
+    dims
+}
+
+pub fn prune_world(
+    server_path: &Path,
+    req: &WorldPruneRequest,
+) -> Result<WorldPruneReport, String> {
+    if req.min_inhabited_ticks < 0 {
+        return Err("InhabitedTime 阈值不能为负数".to_string());
+    }
+
+    let world_dir = resolve_world_dir(server_path, req.world_name.as_deref())?;
+    let (spawn_chunk_x, spawn_chunk_z) = read_spawn_chunk(&world_dir);
+    let radius = req.protected_radius as i32;
+
+    let mut report = WorldPruneReport {
+        world_path: world_dir.to_string_lossy().to_string(),
+        dry_run: req.dry_run,
+        spawn_chunk_x,
+        spawn_chunk_z,
+        regions_scanned: 0,
+        chunks_scanned: 0,
+        chunks_pruned: 0,
+        chunks_kept: 0,
+        chunks_unreadable: 0,
+        bytes_before: 0,
+        bytes_after: 0,
+        regions: Vec::new(),
+    };
+
+    for (dimension, dim_dir, protect_spawn) in dimension_dirs(&world_dir) {
+        for region_path in region_file::list_region_files(&dim_dir.join("region")) {
+            let region = RegionFile::open(&region_path)?;
+            let mut summary = RegionPruneSummary {
+                dimension: dimension.clone(),
+                file_name: region_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                chunks_total: 0,
+                chunks_pruned: 0,
+                chunks_unreadable: 0,
+                latest_update_tick: 0,
+                bytes_before: region.file_len(),
+                bytes_after: 0,
+                file_removed: false,
+            };
+
+            let mut keep = Vec::new();
+            let mut pruned = HashSet::new();
+            // 外部区块文件在区域文件重写成功后再删除，避免写入失败时区域仍指向已删除的数据
+            let mut external_files = Vec::new();
+            for loc in region.locations() {
+                summary.chunks_total += 1;
+                let chunk = match region.read_chunk_summary(&loc) {
+                    Ok(chunk) => chunk,
+                    Err(_) => {
+                        summary.chunks_unreadable += 1;
+                        keep.push(loc);
+                        continue;
+                    }
+                };
+                summary.latest_update_tick = summary.latest_update_tick.max(chunk.last_update);
+
+                let protected = protect_spawn
+                    && (chunk.chunk_x - spawn_chunk_x).abs() <= radius
+                    && (chunk.chunk_z - spawn_chunk_z).abs() <= radius;
+                if !protected && chunk.inhabited_time < req.min_inhabited_ticks {
+                    if region.is_external(&loc) {
+                        external_files
+                            .push(region.external_chunk_path(chunk.chunk_x, chunk.chunk_z));
+                    }
+                    pruned.insert(loc.index);
+                } else {
+                    keep.push(loc);
+                }
+            }
+            summary.chunks_pruned = pruned.len() as u32;
+
+            summary.bytes_after = if pruned.is_empty() {
+                summary.bytes_before
+            } else if req.dry_run {
+                estimate_compacted_size(&keep)
+            } else {
+                let bytes_after = region.write_compacted(&keep)?;
+                for path in &external_files {
+                    let _ = std::fs::remove_file(path);
+                }
+                bytes_after
+            };
+            summary.file_removed = keep.is_empty() && !pruned.is_empty();
+
+            for companion in COMPANION_DIRS {
+                let companion_path = dim_dir.join(companion).join(&summary.file_name);
+                if !companion_path.is_file() {
+                    continue;
+                }
+                let (before, after) = prune_companion(&companion_path, &pruned, req.dry_run)?;
+                summary.bytes_before += before;
+                summary.bytes_after += after;
+            }
+
+            report.regions_scanned += 1;
+            report.chunks_scanned += summary.chunks_total;
+            report.chunks_pruned += summary.chunks_pruned;
+            report.chunks_unreadable += summary.chunks_unreadable;
+            report.bytes_before += summary.bytes_before;
+            report.bytes_after += summary.bytes_after;
+            report.regions.push(summary);
+        }
+    }
+
+    report.chunks_kept = report.chunks_scanned - report.chunks_pruned;
+    Ok(report)
+}
+
+/// 删除附属区域文件中与被裁剪地形区块同坐标的数据，返回 (原大小, 新大小)
+fn prune_companion(
+    path: &Path,
+    pruned: &HashSet<usize>,
+    dry_run: bool,
+) -> Result<(u64, u64), String> {
+    let region = RegionFile::open(path)?;
+    let before = region.file_len();
+    let locations = region.locations();
+    let keep = locations
+        .iter()
+        .copied()
+        .filter(|loc| !pruned.contains(&loc.index))
+        .collect::<Vec<_>>();
+
+    if keep.len() == locations.len() {
+        return Ok((before, before));
+    }
+    if dry_run {
+        return Ok((before, estimate_compacted_size(&keep)));
+    }
+    Ok((before, region.write_compacted(&keep)?))
+}
+
+fn estimate_compacted_size(keep: &[ChunkLocation]) -> u64 {
+    if keep.is_empty() {
+        return 0;
+    }
+    let sectors: u64 = keep.iter().map(|loc| loc.sector_count as u64).sum();
+    HEADER_SIZE as u64 + sectors * SECTOR_SIZE as u64
+}
@@ -1,14 +1,21 @@
-use reqwest::Client;
-use std::sync::atomic::{AtomicU64, Ordering};
+use reqwest::header::{ACCEPT_RANGES, CONTENT_LENGTH, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::{Client, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::fs::OpenOptions;
 use tokio::io::{AsyncSeekExt, AsyncWriteExt, BufWriter, SeekFrom};
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
+
+use crate::utils::hash;
 
 ///一个基本的User-agent
 pub const USER_AGENT_EXAMPLE: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/145.0.0.0 Safari/537.36 Edg/145.0.0.0";
 
+/// 每写入这么多数据刷新一次文件并计入进度，保证进度中的数据都已写入磁盘
+const FLUSH_INTERVAL: u64 = 512 * 1024;
+
 /// 实时进度快照
 #[derive(Debug, Clone, serde::Serialize)] // 如果需要返回给前端，可以加 Serialize
 pub struct DownloadSnapshot {
@@ -16,23 +23,117 @@ pub struct DownloadSnapshot {
     pub total_size: u64,
     pub progress_percentage: f64,
     pub is_finished: bool,
+    /// 已按请求暂停，所有分段都已停止写入
+    pub is_paused: bool,
     pub error: Option<String>,
 }
 
+/// 下载分段，`end` 为分段最后一个字节；服务器未返回 Content-Length 时只有一个 `end` 为空的分段
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub start: u64,
+    pub end: Option<u64>,
+    /// 已写入文件的字节数
+    pub downloaded: u64,
+}
+
+/// 可以保存到磁盘的下载进度，暂停或重启后据此续传
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// 未知大小时为 0
+    pub total_size: u64,
+    pub segments: Vec<Segment>,
+    /// 开始下载时服务器返回的强 ETag 或 Last-Modified，续传时作为 If-Range 发送
+    #[serde(default)]
+    pub validator: Option<String>,
+}
+
+impl Checkpoint {
+    /// 按线程数划分分段；服务器不支持 Range 或大小未知时单线程下载
+    fn plan(remote: RemoteFile, thread_count: usize) -> Self {
+        let RemoteFile { total_size, ranged, validator } = remote;
+        let segments = match total_size {
+            Some(total) if ranged && thread_count > 1 => {
+                let chunk = total.div_ceil(thread_count as u64);
+                (0..total)
+                    .step_by(chunk as usize)
+                    .map(|start| Segment {
+                        start,
+                        end: Some((start + chunk).min(total) - 1),
+                        downloaded: 0,
+                    })
+                    .collect()
+            }
+            Some(total) => vec![Segment {
+                start: 0,
+                end: Some(total - 1),
+                downloaded: 0,
+            }],
+            None => vec![Segment { start: 0, end: None, downloaded: 0 }],
+        };
+        Self {
+            total_size: total_size.unwrap_or(0),
+            segments,
+            validator,
+        }
+    }
+
+    pub fn downloaded(&self) -> u64 {
+        self.segments.iter().map(|segment| segment.downloaded).sum()
+    }
+}
+
+/// HEAD 请求得到的文件信息
+struct RemoteFile {
+    total_size: Option<u64>,
+    ranged: bool,
+    validator: Option<String>,
+}
+
+struct SegmentProgress {
+    start: u64,
+    end: Option<u64>,
+    downloaded: AtomicU64,
+}
+
 /// 状态管理
 pub struct DownloadStatus {
-    pub total_size: u64,
+    pub total_size: AtomicU64,
     pub downloaded: AtomicU64,
     // 使用 tokio 的 RwLock 存储错误信息
     pub error_message: RwLock<Option<String>>,
+    segments: Vec<SegmentProgress>,
+    validator: Option<String>,
+    /// 完成后校验的摘要，格式见 [`hash::verify_file`]
+    expected_hash: Option<String>,
+    stop_requested: AtomicBool,
+    stop_notify: Notify,
+    paused: AtomicBool,
+    // 所有分段写入并刷新到文件、校验通过后才置为 true
+    finished: AtomicBool,
 }
 
 impl DownloadStatus {
-    pub fn new(total_size: u64) -> Self {
+    fn new(checkpoint: &Checkpoint, expected_hash: Option<String>) -> Self {
         Self {
-            total_size,
-            downloaded: AtomicU64::new(0),
+            total_size: AtomicU64::new(checkpoint.total_size),
+            downloaded: AtomicU64::new(checkpoint.downloaded()),
             error_message: RwLock::new(None),
+            segments: checkpoint
+                .segments
+                .iter()
+                .map(|segment| SegmentProgress {
+                    start: segment.start,
+                    end: segment.end,
+                    downloaded: AtomicU64::new(segment.downloaded),
+                })
+                .collect(),
+            validator: checkpoint.validator.clone(),
+            expected_hash,
+            stop_requested: AtomicBool::new(false),
+            stop_notify: Notify::new(),
+            paused: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
         }
     }
 
@@ -42,23 +143,53 @@ impl DownloadStatus {
         *lock = Some(msg);
     }
 
+    /// 请求暂停，各分段刷新已收到的数据后停止
+    pub fn pause(&self) {
+        self.stop_requested.store(true, Ordering::SeqCst);
+        self.stop_notify.notify_waiters();
+    }
+
+    /// 当前进度，只包含已写入文件的数据
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            total_size: self.total_size.load(Ordering::SeqCst),
+            segments: self
+                .segments
+                .iter()
+                .map(|segment| Segment {
+                    start: segment.start,
+                    end: segment.end,
+                    downloaded: segment.downloaded.load(Ordering::SeqCst),
+                })
+                .collect(),
+            validator: self.validator.clone(),
+        }
+    }
+
     /// 获取当前快照，用于传递给前端
     pub async fn snapshot(&self) -> DownloadSnapshot {
         let downloaded = self.downloaded.load(Ordering::Relaxed);
+        let total_size = self.total_size.load(Ordering::Relaxed);
         let error = self.error_message.read().await.clone();
 
         DownloadSnapshot {
             downloaded,
-            total_size: self.total_size,
-            progress_percentage: if self.total_size > 0 {
-                (downloaded as f64 / self.total_size as f64) * 100.0
+            total_size,
+            progress_percentage: if total_size > 0 {
+                (downloaded as f64 / total_size as f64) * 100.0
             } else {
                 0.0
             },
-            is_finished: downloaded >= self.total_size || error.is_some(),
+            is_finished: self.finished.load(Ordering::SeqCst) || error.is_some(),
+            is_paused: self.paused.load(Ordering::SeqCst),
             error,
         }
     }
+
+    fn commit(&self, segment: &SegmentProgress, len: u64) {
+        segment.downloaded.fetch_add(len, Ordering::SeqCst);
+        self.downloaded.fetch_add(len, Ordering::Relaxed);
+    }
 }
 
 ///多线程下载
@@ -84,116 +215,290 @@ impl MultiThreadDownloader {
         url: &str,
         output_path: &str,
         thread_count: usize,
+    ) -> Result<Arc<DownloadStatus>, String> {
+        self.start(url, output_path, thread_count, None, None).await
+    }
+
+    /// 开始或继续下载；`checkpoint` 与本地文件、远程文件都对得上时从断点续传，否则重新下载
+    pub async fn start(
+        &self,
+        url: &str,
+        output_path: &str,
+        thread_count: usize,
+        checkpoint: Option<Checkpoint>,
+        expected_hash: Option<String>,
     ) -> Result<Arc<DownloadStatus>, String> {
         if thread_count == 0 {
             return Err("Thread count must be positive".to_string());
         }
-        let res = self
-            .client
-            .head(url)
-            .send()
-            .await
-            .map_err(|e| format!("HEAD 请求失败: {}", e))?;
-
-        let total_size = res
-            .headers()
-            .get(reqwest::header::CONTENT_LENGTH)
-            .and_then(|ct| ct.to_str().ok())
-            .and_then(|ct| ct.parse::<u64>().ok())
-            .ok_or("服务器未返回 Content-Length")?;
 
-        let file = tokio::fs::File::create(output_path)
-            .await
-            .map_err(|e| e.to_string())?;
-        file.set_len(total_size).await.map_err(|e| e.to_string())?;
+        let remote = self.probe(url).await;
+        let resumable = match checkpoint {
+            Some(checkpoint) if Self::can_resume(&checkpoint, &remote, output_path).await => {
+                Some(checkpoint)
+            }
+            _ => None,
+        };
+        let checkpoint = match resumable {
+            Some(checkpoint) => checkpoint,
+            None => {
+                let checkpoint = Checkpoint::plan(remote, thread_count);
+                let file = tokio::fs::File::create(output_path)
+                    .await
+                    .map_err(|e| format!("创建文件失败: {}", e))?;
+                file.set_len(checkpoint.total_size)
+                    .await
+                    .map_err(|e| format!("创建文件失败: {}", e))?;
+                checkpoint
+            }
+        };
 
-        let status = Arc::new(DownloadStatus::new(total_size));
-        let chunk_size = total_size / thread_count as u64;
+        let status = Arc::new(DownloadStatus::new(&checkpoint, expected_hash));
         let client = Arc::new(self.client.clone());
 
         let mut tasks = Vec::new();
-
-        for i in 0..thread_count {
-            let start = i as u64 * chunk_size;
-            let end = if i == thread_count - 1 {
-                total_size - 1
-            } else {
-                start + chunk_size - 1
-            };
-
+        for index in 0..checkpoint.segments.len() {
             let url = url.to_string();
             let path = output_path.to_string();
             let client_ptr = Arc::clone(&client);
             let status_ptr = Arc::clone(&status);
 
-            // 移除 unwrap()，让子任务返回 Result
             tasks.push(tokio::spawn(async move {
-                Self::_worker(client_ptr, url, path, start, end, status_ptr).await
+                Self::_worker(client_ptr, url, path, index, status_ptr).await
             }));
         }
 
-        // 异步监控线程结果
+        // 等所有分段结束后再判断结果，保证报告完成时数据已经全部写入文件
         let status_for_monitor = Arc::clone(&status);
+        let path = output_path.to_string();
         tokio::spawn(async move {
+            let mut completed = true;
+            let mut error = None;
             for task in tasks {
                 match task.await {
-                    Ok(Ok(_)) => {} // 线程执行成功
+                    Ok(Ok(done)) => completed &= done,
+                    // 子任务逻辑错误 (如 TimedOut)
                     Ok(Err(e)) => {
-                        // 子任务逻辑错误 (如 TimedOut)
-                        status_for_monitor.set_error(e.to_string()).await;
+                        error.get_or_insert(e);
                     }
+                    // 线程 Panic 或被取消
                     Err(e) => {
-                        // 线程 Panic 或被取消
-                        status_for_monitor
-                            .set_error(format!("线程崩溃: {}", e))
-                            .await;
+                        error.get_or_insert(format!("线程崩溃: {}", e));
                     }
                 }
             }
+
+            let status = status_for_monitor;
+            if let Some(e) = error {
+                status.set_error(e).await;
+            } else if !completed {
+                status.paused.store(true, Ordering::SeqCst);
+            } else if let Some(expected) = status.expected_hash.clone() {
+                let verified = tokio::task::spawn_blocking(move || {
+                    hash::verify_file(std::path::Path::new(&path), &expected)
+                })
+                .await
+                .map_err(|e| format!("校验文件任务失败: {}", e))
+                .and_then(|result| result);
+                match verified {
+                    Ok(()) => status.finished.store(true, Ordering::SeqCst),
+                    Err(e) => status.set_error(e).await,
+                }
+            } else {
+                status.finished.store(true, Ordering::SeqCst);
+            }
         });
 
         Ok(status)
     }
 
+    /// 查询文件大小、是否支持 Range 与校验器，HEAD 请求失败时按未知大小处理
+    async fn probe(&self, url: &str) -> RemoteFile {
+        let head = match self.client.head(url).send().await {
+            Ok(res) if res.status().is_success() => res,
+            _ => {
+                return RemoteFile {
+                    total_size: None,
+                    ranged: false,
+                    validator: None,
+                }
+            }
+        };
+        let raw_header = |name| {
+            head.headers()
+                .get(name)
+                .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let header = |name| raw_header(name).map(|value| value.to_ascii_lowercase());
+        // If-Range 只接受强 ETag，弱 ETag 时退回 Last-Modified
+        let validator = raw_header(ETAG)
+            .filter(|etag| !etag.starts_with("W/"))
+            .or_else(|| raw_header(LAST_MODIFIED));
+        let total_size = header(CONTENT_LENGTH)
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|size| *size > 0);
+        let ranged = match header(ACCEPT_RANGES) {
+            Some(value) => value.contains("bytes"),
+            // 未声明时请求一个字节确认
+            None if total_size.is_some() => self
+                .client
+                .get(url)
+                .header(RANGE, "bytes=0-0")
+                .send()
+                .await
+                .is_ok_and(|res| res.status() == StatusCode::PARTIAL_CONTENT),
+            None => false,
+        };
+        RemoteFile { total_size, ranged, validator }
+    }
+
+    /// 远程文件的大小或校验器变化时不能续传，需要从头下载
+    async fn can_resume(checkpoint: &Checkpoint, remote: &RemoteFile, output_path: &str) -> bool {
+        if checkpoint.validator.is_some() && checkpoint.validator != remote.validator {
+            return false;
+        }
+        if checkpoint.total_size > 0
+            && remote
+                .total_size
+                .is_some_and(|size| size != checkpoint.total_size)
+        {
+            return false;
+        }
+        let Ok(meta) = tokio::fs::metadata(output_path).await else {
+            return false;
+        };
+        if checkpoint.total_size > 0 {
+            meta.len() == checkpoint.total_size
+        } else {
+            // 未知大小时文件只包含已下载的部分
+            meta.len() >= checkpoint.downloaded()
+        }
+    }
+
+    /// 下载一个分段，暂停时返回 `Ok(false)`
     async fn _worker(
         client: Arc<Client>,
         url: String,
         path: String,
-        start: u64,
-        end: u64,
+        index: usize,
         status: Arc<DownloadStatus>,
-    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-        let range = format!("bytes={}-{}", start, end);
-        // 使用 map_err 包装可能的请求错误
-        let mut response = client.get(url).header("Range", range).send().await?;
+    ) -> Result<bool, String> {
+        let segment = &status.segments[index];
+        let done = segment.downloaded.load(Ordering::SeqCst);
+        let mut offset = segment.start + done;
+        if segment.end.is_some_and(|end| offset > end) {
+            return Ok(true);
+        }
+        if status.stop_requested.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+
+        // 从头下载整个文件时不带 Range，兼容不支持 Range 的服务器
+        let whole_file = status.segments.len() == 1 && offset == 0;
+        let mut request = client.get(&url);
+        if !whole_file {
+            let end = segment.end.map(|end| end.to_string()).unwrap_or_default();
+            request = request.header(RANGE, format!("bytes={}-{}", offset, end));
+            // 文件在两次请求之间变化时服务器返回完整内容，不会拼接出混合的文件
+            if let Some(validator) = &status.validator {
+                request = request.header(IF_RANGE, validator);
+            }
+        }
+        let mut response = request
+            .send()
+            .await
+            .map_err(|e| format!("请求失败: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("服务器返回 {}", response.status()));
+        }
+        if !whole_file && response.status() != StatusCode::PARTIAL_CONTENT {
+            if segment.start > 0 {
+                return Err(if status.validator.is_some() {
+                    "远程文件已变化，请重新下载".to_string()
+                } else {
+                    "服务器不支持断点续传".to_string()
+                });
+            }
+            // 服务器忽略了 Range，从头重新写入
+            segment.downloaded.store(0, Ordering::SeqCst);
+            status.downloaded.fetch_sub(done, Ordering::Relaxed);
+            offset = 0;
+        }
 
-        let file = OpenOptions::new().write(true).open(path).await?;
+        let file = OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .await
+            .map_err(|e| format!("打开文件失败: {}", e))?;
         let mut writer = BufWriter::with_capacity(128 * 1024, file);
-        writer.seek(SeekFrom::Start(start)).await?;
-
-        let mut local_downloaded = 0u64;
-
-        // 移除 chunk 后的 unwrap，使用 ? 自动传播错误
-        while let Some(chunk) = response.chunk().await? {
-            let len = chunk.len() as u64;
-            writer.write_all(&chunk).await?;
-
-            local_downloaded += len;
-            // 降低状态更新频率以减轻原子操作压力
-            if local_downloaded > 512 * 1024 {
-                status
-                    .downloaded
-                    .fetch_add(local_downloaded, Ordering::Relaxed);
-                local_downloaded = 0;
+        writer
+            .seek(SeekFrom::Start(offset))
+            .await
+            .map_err(|e| format!("写入文件失败: {}", e))?;
+
+        let mut pending = 0u64;
+        let mut stopped = false;
+        loop {
+            if status.stop_requested.load(Ordering::SeqCst) {
+                stopped = true;
+                break;
+            }
+            let chunk = tokio::select! {
+                chunk = response.chunk() => chunk.map_err(|e| format!("下载中断: {}", e))?,
+                _ = status.stop_notify.notified() => continue,
+            };
+            let Some(chunk) = chunk else {
+                break;
+            };
+            // 不写入超出分段的数据，避免覆盖下一个分段
+            let len = match segment.end {
+                Some(end) => chunk.len().min((end + 1 - offset - pending) as usize),
+                None => chunk.len(),
+            };
+            writer
+                .write_all(&chunk[..len])
+                .await
+                .map_err(|e| format!("写入文件失败: {}", e))?;
+            pending += len as u64;
+
+            if pending >= FLUSH_INTERVAL {
+                writer
+                    .flush()
+                    .await
+                    .map_err(|e| format!("写入文件失败: {}", e))?;
+                status.commit(segment, pending);
+                offset += pending;
+                pending = 0;
             }
         }
 
-        writer.flush().await?;
-        status
-            .downloaded
-            .fetch_add(local_downloaded, Ordering::Relaxed);
+        writer
+            .flush()
+            .await
+            .map_err(|e| format!("写入文件失败: {}", e))?;
+        status.commit(segment, pending);
+        offset += pending;
 
-        Ok(())
+        if stopped {
+            return Ok(false);
+        }
+        match segment.end {
+            Some(end) if offset <= end => {
+                Err(format!("连接提前结束，分段 {}-{} 未下载完整", segment.start, end))
+            }
+            Some(_) => Ok(true),
+            None => {
+                // 大小未知时以实际收到的数据为准，去掉续传前残留的内容
+                writer
+                    .into_inner()
+                    .set_len(offset)
+                    .await
+                    .map_err(|e| format!("写入文件失败: {}", e))?;
+                status.total_size.store(offset, Ordering::SeqCst);
+                Ok(true)
+            }
+        }
     }
 }
 //
@@ -327,3 +632,182 @@ async fn test_multi_thread_download() -> Result<(), String> {
 //         }
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_http;
+    use sha2::{Digest, Sha256};
+
+    /// 本地文件服务，`ranged` 为 false 时忽略 Range，`sized` 为 false 时不返回 Content-Length
+    fn mock_server(body: Vec<u8>, ranged: bool, sized: bool) -> (String, test_http::Requests) {
+        let (base, requests) = test_http::serve(move |request| {
+            let response = test_http::file(&body, request, ranged);
+            if sized {
+                response
+            } else {
+                response.without_length()
+            }
+        });
+        (format!("{}/file.bin", base), requests)
+    }
+
+    /// 每个 GET 请求的 Range 头，没有时为空字符串
+    fn ranges(requests: &test_http::Requests) -> Vec<String> {
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.method == "GET")
+            .map(|request| request.range().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    async fn wait(status: &DownloadStatus) -> DownloadSnapshot {
+        for _ in 0..200 {
+            let snapshot = status.snapshot().await;
+            if snapshot.is_finished || snapshot.is_paused {
+                return snapshot;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        panic!("下载超时");
+    }
+
+    fn temp_file(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("{}_{}", name, uuid::Uuid::new_v4()))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn resumes_segments_and_verifies_hash() {
+        let body = (0..3 * 1024 * 1024)
+            .map(|i| (i % 251) as u8)
+            .collect::<Vec<_>>();
+        let sha256 = format!("{:x}", Sha256::digest(&body));
+        let (url, requests) = mock_server(body.clone(), true, true);
+        let downloader = MultiThreadDownloader::new(USER_AGENT_EXAMPLE);
+        let path = temp_file("sl_segments");
+
+        let status = downloader
+            .start(&url, &path, 4, None, Some(format!("sha256:{}", sha256)))
+            .await
+            .unwrap();
+        let snapshot = wait(&status).await;
+        assert!(snapshot.error.is_none(), "{:?}", snapshot.error);
+        assert_eq!(snapshot.downloaded, body.len() as u64);
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert_eq!(status.checkpoint().segments.len(), 4);
+        let validator = status.checkpoint().validator;
+        assert!(validator.is_some());
+
+        // 第一个分段下载了一半，第二个分段已完成
+        let half = body.len() / 2;
+        let mut partial = body.clone();
+        partial[half / 2..half].fill(0);
+        std::fs::write(&path, &partial).unwrap();
+        let checkpoint = Checkpoint {
+            total_size: body.len() as u64,
+            segments: vec![
+                Segment {
+                    start: 0,
+                    end: Some(half as u64 - 1),
+                    downloaded: (half / 2) as u64,
+                },
+                Segment {
+                    start: half as u64,
+                    end: Some(body.len() as u64 - 1),
+                    downloaded: half as u64,
+                },
+            ],
+            validator: validator.clone(),
+        };
+        requests.lock().unwrap().clear();
+        let status = downloader
+            .start(&url, &path, 4, Some(checkpoint.clone()), Some(sha256))
+            .await
+            .unwrap();
+        assert!(wait(&status).await.error.is_none());
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert_eq!(ranges(&requests).as_slice(), [format!("{}-{}", half / 2, half - 1)]);
+        let if_range = requests.lock().unwrap().iter().find_map(|request| {
+            (request.method == "GET").then(|| request.header("if-range").map(str::to_string))
+        });
+        assert_eq!(if_range.flatten(), validator);
+
+        // 摘要不符时报告错误而不是完成
+        std::fs::write(&path, &partial).unwrap();
+        let status = downloader
+            .start(&url, &path, 4, Some(checkpoint), Some("sha256:00".to_string()))
+            .await
+            .unwrap();
+        let snapshot = wait(&status).await;
+        assert!(snapshot.error.unwrap().contains("校验失败"));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_single_stream_without_length_or_ranges() {
+        let body = (0..200 * 1024).map(|i| (i % 13) as u8).collect::<Vec<_>>();
+        let (url, requests) = mock_server(body.clone(), false, false);
+        let downloader = MultiThreadDownloader::new(USER_AGENT_EXAMPLE);
+        let path = temp_file("sl_stream");
+
+        let status = downloader.download(&url, &path, 8).await.unwrap();
+        let snapshot = wait(&status).await;
+        assert!(snapshot.error.is_none(), "{:?}", snapshot.error);
+        assert_eq!(snapshot.total_size, body.len() as u64);
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert_eq!(ranges(&requests).as_slice(), [String::new()]);
+
+        // 服务器忽略 Range 时从头重新下载
+        std::fs::write(&path, vec![7u8; 300 * 1024]).unwrap();
+        let checkpoint = Checkpoint {
+            total_size: 0,
+            segments: vec![Segment { start: 0, end: None, downloaded: 1000 }],
+            validator: None,
+        };
+        let status = downloader
+            .start(&url, &path, 8, Some(checkpoint), None)
+            .await
+            .unwrap();
+        assert!(wait(&status).await.error.is_none());
+        assert_eq!(std::fs::read(&path).unwrap(), body);
+        assert_eq!(ranges(&requests)[1], "1000-");
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn restarts_when_remote_file_changes() {
+        let old = (0..1024 * 1024).map(|i| (i % 7) as u8).collect::<Vec<_>>();
+        let new = (0..1024 * 1024).map(|i| (i % 11) as u8).collect::<Vec<_>>();
+        let (old_url, _) = mock_server(old.clone(), true, true);
+        let downloader = MultiThreadDownloader::new(USER_AGENT_EXAMPLE);
+        let path = temp_file("sl_changed");
+
+        let status = downloader
+            .start(&old_url, &path, 2, None, None)
+            .await
+            .unwrap();
+        assert!(wait(&status).await.error.is_none());
+        let mut checkpoint = status.checkpoint();
+        checkpoint.segments[1].downloaded = 0;
+
+        // 同样大小的新文件，ETag 不同时从头下载而不是拼接两个版本
+        let (new_url, requests) = mock_server(new.clone(), true, true);
+        let status = downloader
+            .start(&new_url, &path, 2, Some(checkpoint), None)
+            .await
+            .unwrap();
+        assert!(wait(&status).await.error.is_none());
+        assert_eq!(std::fs::read(&path).unwrap(), new);
+        assert!(ranges(&requests).contains(&format!("0-{}", new.len() / 2 - 1)));
+        assert_ne!(status.checkpoint().validator, None);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
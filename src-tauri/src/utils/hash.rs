@@ -0,0 +1,73 @@
+//! 文件摘要计算，统一输出小写十六进制字符串。
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use md5::Md5;
+use sha1::{Digest, Sha1};
+use sha2::{Sha256, Sha512};
+
+/// 流式计算文件摘要，避免把大文件整体读入内存
+fn digest_file<D: Digest>(path: &Path) -> Result<String, String> {
+    let mut file =
+        File::open(path).map_err(|e| format!("打开文件失败 {}: {}", path.display(), e))?;
+    let mut hasher = D::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("读取文件失败 {}: {}", path.display(), e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+pub fn md5_file(path: &Path) -> Result<String, String> {
+    digest_file::<Md5>(path)
+}
+
+pub fn sha1_file(path: &Path) -> Result<String, String> {
+    digest_file::<Sha1>(path)
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    digest_file::<Sha256>(path)
+}
+
+pub fn sha512_file(path: &Path) -> Result<String, String> {
+    digest_file::<Sha512>(path)
+}
+
+/// 校验文件摘要；`expected` 可以带 `md5:`、`sha1:`、`sha256:`、`sha512:` 前缀，否则按长度判断算法
+pub fn verify_file(path: &Path, expected: &str) -> Result<(), String> {
+    let expected = expected.trim().to_ascii_lowercase();
+    let (algorithm, digest) = match expected.split_once(':') {
+        Some((algorithm, digest)) => (algorithm, digest),
+        None => match expected.len() {
+            32 => ("md5", expected.as_str()),
+            40 => ("sha1", expected.as_str()),
+            64 => ("sha256", expected.as_str()),
+            128 => ("sha512", expected.as_str()),
+            _ => ("", expected.as_str()),
+        },
+    };
+    let actual = match algorithm {
+        "md5" => md5_file(path)?,
+        "sha1" => sha1_file(path)?,
+        "sha256" => sha256_file(path)?,
+        "sha512" => sha512_file(path)?,
+        _ => return Err(format!("无法识别的文件摘要: {}", expected)),
+    };
+    if actual != digest {
+        return Err(format!("文件校验失败：期望 {}，实际 {}", digest, actual));
+    }
+    Ok(())
+}
@@ -0,0 +1,180 @@
+//! Minecraft 版本号解析。
+
+use std::cmp::Ordering;
+
+/// 解析 "1.20.4" / "1.21" 形式的正式版本号，快照与预览版返回 None
+pub fn parse(mc_version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = mc_version.trim().split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    let patch = match parts.next() {
+        Some(patch) => patch.parse().ok()?,
+        None => 0,
+    };
+    Some((major, minor, patch))
+}
+
+/// 比较两个版本号。按 `.` 分段比较，数字段按数值、其他按字符串；缺少的段视为 0。
+/// `-` 之后是预发布标记，低于对应的正式版；`+` 之后的构建信息不参与比较
+pub fn compare(a: &str, b: &str) -> Ordering {
+    fn split(version: &str) -> (&str, Option<&str>) {
+        let version = version.trim();
+        let version = version.split_once('+').map_or(version, |(core, _)| core);
+        match version.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (version, None),
+        }
+    }
+    fn compare_segments(a: &str, b: &str) -> Ordering {
+        let mut left = a.split('.');
+        let mut right = b.split('.');
+        loop {
+            let (l, r) = match (left.next(), right.next()) {
+                (None, None) => return Ordering::Equal,
+                (l, r) => (l.unwrap_or("0"), r.unwrap_or("0")),
+            };
+            let ordering = match (l.parse::<u64>(), r.parse::<u64>()) {
+                (Ok(l), Ok(r)) => l.cmp(&r),
+                _ => l.cmp(r),
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+    }
+
+    let (a_core, a_pre) = split(a);
+    let (b_core, b_pre) = split(b);
+    compare_segments(a_core, b_core).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_segments(a, b),
+    })
+}
+
+/// 判断版本是否满足模组声明的版本范围。
+///
+/// 支持 Forge 使用的 Maven 区间（`[1.20,1.21)`、`[1.20.1]`、多个区间以 `,` 并列）
+/// 以及 Fabric/Quilt 使用的语义化版本谓词（`>=1.20 <1.21`、`~1.20.1`、`^1.0`、
+/// `1.20.x`，`||` 表示任一满足）
+pub fn matches_range(version: &str, range: &str) -> bool {
+    let range = range.trim();
+    if range.is_empty() || range == "*" {
+        return true;
+    }
+    if range.starts_with('[') || range.starts_with('(') {
+        return matches_maven_range(version, range);
+    }
+    range.split("||").any(|alternative| {
+        alternative
+            .split_whitespace()
+            .all(|predicate| matches_predicate(version, predicate))
+    })
+}
+
+fn matches_maven_range(version: &str, range: &str) -> bool {
+    let mut rest = range;
+    while let Some(start) = rest.find(['[', '(']) {
+        let Some(end) = rest[start..].find([']', ')']).map(|end| start + end) else {
+            return false;
+        };
+        let lower_inclusive = rest[start..].starts_with('[');
+        let upper_inclusive = rest[end..].starts_with(']');
+        let body = &rest[start + 1..end];
+        let matched = match body.split_once(',') {
+            None => compare(version, body) == Ordering::Equal,
+            Some((lower, upper)) => {
+                let (lower, upper) = (lower.trim(), upper.trim());
+                let above = lower.is_empty()
+                    || match compare(version, lower) {
+                        Ordering::Greater => true,
+                        Ordering::Equal => lower_inclusive,
+                        Ordering::Less => false,
+                    };
+                let below = upper.is_empty()
+                    || match compare(version, upper) {
+                        Ordering::Less => true,
+                        Ordering::Equal => upper_inclusive,
+                        Ordering::Greater => false,
+                    };
+                above && below
+            }
+        };
+        if matched {
+            return true;
+        }
+        rest = &rest[end + 1..];
+    }
+    false
+}
+
+fn matches_predicate(version: &str, predicate: &str) -> bool {
+    let operators = [">=", "<=", ">", "<", "=", "~", "^"];
+    let (operator, target) = operators
+        .iter()
+        .find_map(|op| predicate.strip_prefix(op).map(|target| (*op, target)))
+        .unwrap_or(("", predicate));
+    // Fabric 用 `>=1.20-` 表示同时接受预发布版本：空的预发布标记低于任何预发布版本
+    let target = target.trim();
+    if target.is_empty() || target == "*" {
+        return true;
+    }
+
+    let ordering = compare(version, target);
+    match operator {
+        ">=" => ordering != Ordering::Less,
+        "<=" => ordering != Ordering::Greater,
+        ">" => ordering == Ordering::Greater,
+        "<" => ordering == Ordering::Less,
+        "~" | "^" => {
+            let segments = target.split(['-', '+']).next().unwrap_or(target);
+            let segments = segments.split('.').collect::<Vec<_>>();
+            // ~ 固定到次版本号，^ 固定到主版本号
+            let fixed = if operator == "~" {
+                segments.len().clamp(1, 2)
+            } else {
+                1
+            };
+            ordering != Ordering::Less && has_prefix(version, &segments[..fixed])
+        }
+        _ => {
+            let segments = target.split('.').collect::<Vec<_>>();
+            match segments.iter().position(|s| matches!(*s, "x" | "X" | "*")) {
+                Some(wildcard) => has_prefix(version, &segments[..wildcard]),
+                None => ordering == Ordering::Equal,
+            }
+        }
+    }
+}
+
+fn has_prefix(version: &str, prefix: &[&str]) -> bool {
+    let core = version.split(['-', '+']).next().unwrap_or(version);
+    let mut segments = core.split('.');
+    prefix.iter().all(|expected| {
+        let actual = segments.next().unwrap_or("0");
+        compare(actual, expected) == Ordering::Equal
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_forge_and_fabric_ranges() {
+        assert!(matches_range("1.20.1", "[1.20.1,1.20.2)"));
+        assert!(!matches_range("1.20.2", "[1.20.1,1.20.2)"));
+        assert!(matches_range("1.12.2", "[1.12.2]"));
+        assert!(matches_range("1.19.2", "[1.18,1.18.2],[1.19,)"));
+        assert!(matches_range("1.20.4", ">=1.20.1 <1.21"));
+        assert!(!matches_range("1.21", ">=1.20.1 <1.21"));
+        assert!(matches_range("1.20.6", "1.20.x"));
+        assert!(matches_range("1.20.4", "~1.20.2"));
+        assert!(!matches_range("1.21.1", "~1.20.2"));
+        assert!(matches_range("1.19.4", "1.20 || 1.19.4"));
+        assert!(matches_range("1.20.5-rc1", ">=1.20.5-"));
+        assert_eq!(compare("1.20.5-rc1", "1.20.5"), Ordering::Less);
+        assert_eq!(compare("0.92.0+1.20.1", "0.92"), Ordering::Equal);
+    }
+}
@@ -1,4 +1,10 @@
 pub mod cli;
 pub mod downloader;
+pub mod hash;
 pub mod logger;
+pub mod mc_version;
+pub mod nbt;
 pub mod path;
+pub mod properties;
+#[cfg(test)]
+pub mod test_http;
@@ -0,0 +1,260 @@
+//! 最小化的 NBT 解码器，仅覆盖 Java 版大端格式（区块数据、level.dat）。
+//! 只做读取，不做写回；写回区块时直接搬运原始扇区字节即可。
+
+use std::collections::HashMap;
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+
+/// 嵌套层级上限，防止损坏数据造成栈溢出
+const MAX_DEPTH: usize = 512;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NbtTag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<NbtTag>),
+    Compound(HashMap<String, NbtTag>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl NbtTag {
+    pub fn get(&self, key: &str) -> Option<&NbtTag> {
+        match self {
+            NbtTag::Compound(map) => map.get(key),
+            _ => None,
+        }
+    }
+
+    /// 按路径逐级取值，例如 `["Data", "SpawnX"]`
+    pub fn get_path(&self, path: &[&str]) -> Option<&NbtTag> {
+        path.iter().try_fold(self, |tag, key| tag.get(key))
+    }
+
+    /// 读取任意整数类型并统一转换为 i64
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            NbtTag::Byte(v) => Some(*v as i64),
+            NbtTag::Short(v) => Some(*v as i64),
+            NbtTag::Int(v) => Some(*v as i64),
+            NbtTag::Long(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            NbtTag::String(v) => Some(v.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[NbtTag]> {
+        match self {
+            NbtTag::List(v) => Some(v.as_slice()),
+            _ => None,
+        }
+    }
+
+    pub fn as_int_array(&self) -> Option<&[i32]> {
+        match self {
+            NbtTag::IntArray(v) => Some(v.as_slice()),
+            _ => None,
+        }
+    }
+}
+
+/// 解析未压缩的 NBT 数据，返回根标签名与根标签
+pub fn parse(bytes: &[u8]) -> Result<(String, NbtTag), String> {
+    let mut reader = NbtReader { data: bytes, pos: 0 };
+    let tag_type = reader.read_u8()?;
+    if tag_type != 10 {
+        return Err(format!("NBT 根标签必须是 Compound，实际为 {}", tag_type));
+    }
+    let name = reader.read_string()?;
+    let root = reader.read_payload(tag_type, 0)?;
+    Ok((name, root))
+}
+
+/// 解析 gzip 压缩的 NBT（level.dat 等文件）
+pub fn parse_gzip(bytes: &[u8]) -> Result<(String, NbtTag), String> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(bytes)
+        .read_to_end(&mut decoded)
+        .map_err(|e| format!("gzip 解压失败: {}", e))?;
+    parse(&decoded)
+}
+
+struct NbtReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> NbtReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| format!("NBT 数据在偏移 {} 处意外结束", self.pos))?;
+        let slice = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_i16(&mut self) -> Result<i16, String> {
+        let b = self.take(2)?;
+        Ok(i16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn read_u16(&mut self) -> Result<u16, String> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn read_i32(&mut self) -> Result<i32, String> {
+        let b = self.take(4)?;
+        Ok(i32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn read_i64(&mut self) -> Result<i64, String> {
+        let b = self.take(8)?;
+        let mut buf = [0u8; 8];
+        buf.copy_from_slice(b);
+        Ok(i64::from_be_bytes(buf))
+    }
+
+    fn read_len(&mut self) -> Result<usize, String> {
+        let len = self.read_i32()?;
+        if len < 0 {
+            return Err(format!("NBT 数组长度为负数: {}", len));
+        }
+        let len = len as usize;
+        // 每个元素至少占 1 字节，超过剩余数据长度必然是损坏数据
+        if len > self.data.len() - self.pos {
+            return Err(format!("NBT 数组长度 {} 超出剩余数据", len));
+        }
+        Ok(len)
+    }
+
+    fn read_string(&mut self) -> Result<String, String> {
+        let len = self.read_u16()? as usize;
+        let bytes = self.take(len)?;
+        // Java 使用 Modified UTF-8，绝大多数内容与标准 UTF-8 一致
+        Ok(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    fn read_payload(&mut self, tag_type: u8, depth: usize) -> Result<NbtTag, String> {
+        if depth > MAX_DEPTH {
+            return Err("NBT 嵌套层级过深".to_string());
+        }
+
+        let tag = match tag_type {
+            1 => NbtTag::Byte(self.read_u8()? as i8),
+            2 => NbtTag::Short(self.read_i16()?),
+            3 => NbtTag::Int(self.read_i32()?),
+            4 => NbtTag::Long(self.read_i64()?),
+            5 => NbtTag::Float(f32::from_bits(self.read_i32()? as u32)),
+            6 => NbtTag::Double(f64::from_bits(self.read_i64()? as u64)),
+            7 => {
+                let len = self.read_len()?;
+                NbtTag::ByteArray(self.take(len)?.iter().map(|b| *b as i8).collect())
+            }
+            8 => NbtTag::String(self.read_string()?),
+            9 => {
+                let element_type = self.read_u8()?;
+                let len = self.read_i32()?.max(0) as usize;
+                if element_type == 0 && len > 0 {
+                    return Err("NBT 列表元素类型为 End 但长度不为 0".to_string());
+                }
+                let mut items = Vec::with_capacity(len.min(4096));
+                for _ in 0..len {
+                    items.push(self.read_payload(element_type, depth + 1)?);
+                }
+                NbtTag::List(items)
+            }
+            10 => {
+                let mut map = HashMap::new();
+                loop {
+                    let child_type = self.read_u8()?;
+                    if child_type == 0 {
+                        break;
+                    }
+                    let name = self.read_string()?;
+                    let value = self.read_payload(child_type, depth + 1)?;
+                    map.insert(name, value);
+                }
+                NbtTag::Compound(map)
+            }
+            11 => {
+                let len = self.read_len()?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(self.read_i32()?);
+                }
+                NbtTag::IntArray(values)
+            }
+            12 => {
+                let len = self.read_len()?;
+                let mut values = Vec::with_capacity(len);
+                for _ in 0..len {
+                    values.push(self.read_i64()?);
+                }
+                NbtTag::LongArray(values)
+            }
+            other => return Err(format!("未知的 NBT 标签类型: {}", other)),
+        };
+
+        Ok(tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named_int(name: &str, value: i32) -> Vec<u8> {
+        let mut out = vec![3u8];
+        out.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        out.extend_from_slice(name.as_bytes());
+        out.extend_from_slice(&value.to_be_bytes());
+        out
+    }
+
+    #[test]
+    fn parse_nested_compound() {
+        // { "": { Data: { SpawnX: 120, SpawnZ: -64 } } }
+        let mut bytes = vec![10u8, 0, 0];
+        bytes.push(10);
+        bytes.extend_from_slice(&4u16.to_be_bytes());
+        bytes.extend_from_slice(b"Data");
+        bytes.extend(named_int("SpawnX", 120));
+        bytes.extend(named_int("SpawnZ", -64));
+        bytes.push(0);
+        bytes.push(0);
+
+        let (name, root) = parse(&bytes).unwrap();
+        assert_eq!(name, "");
+        assert_eq!(root.get_path(&["Data", "SpawnX"]).and_then(NbtTag::as_i64), Some(120));
+        assert_eq!(root.get_path(&["Data", "SpawnZ"]).and_then(NbtTag::as_i64), Some(-64));
+    }
+
+    #[test]
+    fn parse_rejects_truncated_data() {
+        let mut bytes = vec![10u8, 0, 0];
+        bytes.extend(named_int("InhabitedTime", 5));
+        bytes.truncate(bytes.len() - 2);
+        assert!(parse(&bytes).is_err());
+    }
+}
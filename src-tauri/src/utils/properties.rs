@@ -0,0 +1,409 @@
+//! Java `.properties` 文件的无损读写。
+//!
+//! 解析遵循 `java.util.Properties#load` 的规则：`=`、`:` 或空白作为分隔符，
+//! 反斜杠续行，`\uXXXX` 与 `\=`、`\:` 等转义。未修改的行原样保留（注释、顺序、
+//! 换行符、编码），只有被修改或新增的条目才会重新生成。
+//! 写入的值中非 ASCII 字符一律转为 `\uXXXX`，新旧版本服务端都能正确读取。
+
+use std::collections::HashMap;
+
+const UTF8_BOM: &str = "\u{feff}";
+
+/// 文件的原始编码。服务端旧版本按 ISO-8859-1 写入，新版本按 UTF-8 写入
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertiesEncoding {
+    Utf8,
+    Latin1,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    key: String,
+    value: String,
+    /// 逻辑行中值之前的部分（缩进、原始键、分隔符），修改值时沿用
+    prefix: String,
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    /// 原始文本，续行时包含内部的换行符
+    text: String,
+    /// 行尾换行符，文件末尾没有换行时为空
+    ending: String,
+    entry: Option<Entry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PropertiesDocument {
+    lines: Vec<Line>,
+    encoding: PropertiesEncoding,
+    bom: bool,
+}
+
+impl Default for PropertiesDocument {
+    fn default() -> Self {
+        Self {
+            lines: Vec::new(),
+            encoding: PropertiesEncoding::Utf8,
+            bom: false,
+        }
+    }
+}
+
+impl PropertiesDocument {
+    /// 按 UTF-8 解码，失败时按 ISO-8859-1 解码
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::parse(text),
+            Err(_) => {
+                let text = bytes.iter().map(|&b| b as char).collect::<String>();
+                let mut document = Self::parse(&text);
+                document.encoding = PropertiesEncoding::Latin1;
+                document
+            }
+        }
+    }
+
+    pub fn parse(text: &str) -> Self {
+        let (bom, text) = match text.strip_prefix(UTF8_BOM) {
+            Some(rest) => (true, rest),
+            None => (false, text),
+        };
+
+        let physical = split_lines(text);
+        let mut lines = Vec::new();
+        let mut index = 0;
+
+        while index < physical.len() {
+            let (content, ending) = physical[index];
+            index += 1;
+
+            let stripped = trim_leading_whitespace(content);
+            if stripped.is_empty() || stripped.starts_with('#') || stripped.starts_with('!') {
+                lines.push(Line {
+                    text: content.to_string(),
+                    ending: ending.to_string(),
+                    entry: None,
+                });
+                continue;
+            }
+
+            // 拼接续行：行尾有奇数个反斜杠时，下一行（去掉前导空白）属于同一逻辑行
+            let mut text = content.to_string();
+            let mut logical = content.to_string();
+            let mut ending = ending;
+            while ends_with_continuation(&logical) {
+                logical.pop();
+                let Some(&(next, next_ending)) = physical.get(index) else {
+                    break;
+                };
+                index += 1;
+                text.push_str(ending);
+                text.push_str(next);
+                logical.push_str(trim_leading_whitespace(next));
+                ending = next_ending;
+            }
+
+            lines.push(Line {
+                text,
+                ending: ending.to_string(),
+                entry: Some(parse_entry(&logical)),
+            });
+        }
+
+        Self {
+            lines,
+            encoding: PropertiesEncoding::Utf8,
+            bom,
+        }
+    }
+
+    /// 与 `Properties#load` 一致，重复的键以最后一次出现为准
+    #[cfg(test)]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries()
+            .filter(|entry| entry.key == key)
+            .last()
+            .map(|entry| entry.value.as_str())
+    }
+
+    pub fn to_map(&self) -> HashMap<String, String> {
+        self.entries()
+            .map(|entry| (entry.key.clone(), entry.value.clone()))
+            .collect()
+    }
+
+    /// 修改已有的键（所有重复项），不存在时追加到文件末尾
+    pub fn set(&mut self, key: &str, value: &str) {
+        let mut found = false;
+        for line in &mut self.lines {
+            let Some(entry) = line.entry.as_mut() else {
+                continue;
+            };
+            if entry.key != key {
+                continue;
+            }
+            found = true;
+            if entry.value != value {
+                entry.value = value.to_string();
+                line.text = format!("{}{}", entry.prefix, escape(value, false));
+            }
+        }
+        if found {
+            return;
+        }
+
+        let ending = self.default_ending();
+        // 保持文件原本“末尾是否有换行”的风格
+        let new_ending = match self.lines.last_mut() {
+            Some(last) if last.ending.is_empty() => {
+                last.ending = ending.clone();
+                String::new()
+            }
+            _ => ending,
+        };
+        let prefix = format!("{}=", escape(key, true));
+        self.lines.push(Line {
+            text: format!("{}{}", prefix, escape(value, false)),
+            ending: new_ending,
+            entry: Some(Entry {
+                key: key.to_string(),
+                value: value.to_string(),
+                prefix,
+            }),
+        });
+    }
+
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        if self.bom {
+            output.push_str(UTF8_BOM);
+        }
+        for line in &self.lines {
+            output.push_str(&line.text);
+            output.push_str(&line.ending);
+        }
+        output
+    }
+
+    /// 按读取时的编码输出字节
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let text = self.render();
+        match self.encoding {
+            PropertiesEncoding::Utf8 => text.into_bytes(),
+            // 新写入的值已转义为 ASCII，其余字符都来自原文件，必然在 Latin-1 范围内
+            PropertiesEncoding::Latin1 => text.chars().map(|c| c as u32 as u8).collect(),
+        }
+    }
+
+    fn entries(&self) -> impl Iterator<Item = &Entry> {
+        self.lines.iter().filter_map(|line| line.entry.as_ref())
+    }
+
+    fn default_ending(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.ending.as_str())
+            .find(|ending| !ending.is_empty())
+            .unwrap_or("\n")
+            .to_string()
+    }
+}
+
+/// 按 `\n`、`\r\n`、`\r` 切分，保留每行的换行符
+fn split_lines(text: &str) -> Vec<(&str, &str)> {
+    let bytes = text.as_bytes();
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\n' => {
+                lines.push((&text[start..index], &text[index..index + 1]));
+                index += 1;
+                start = index;
+            }
+            b'\r' => {
+                let end = if bytes.get(index + 1) == Some(&b'\n') {
+                    index + 2
+                } else {
+                    index + 1
+                };
+                lines.push((&text[start..index], &text[index..end]));
+                index = end;
+                start = index;
+            }
+            _ => index += 1,
+        }
+    }
+    if start < bytes.len() {
+        lines.push((&text[start..], ""));
+    }
+    lines
+}
+
+fn is_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\x0c')
+}
+
+fn trim_leading_whitespace(text: &str) -> &str {
+    text.trim_start_matches(is_whitespace)
+}
+
+fn ends_with_continuation(text: &str) -> bool {
+    text.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+fn parse_entry(logical: &str) -> Entry {
+    let key_start = logical.len() - trim_leading_whitespace(logical).len();
+    let mut chars = logical[key_start..].char_indices().peekable();
+    let mut key_end = logical.len();
+
+    while let Some((offset, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if c == '=' || c == ':' || is_whitespace(c) {
+            key_end = key_start + offset;
+            break;
+        }
+    }
+
+    // 分隔符：任意空白，之后最多一个 `=` 或 `:`，再跟任意空白
+    let rest = trim_leading_whitespace(&logical[key_end..]);
+    let rest = rest
+        .strip_prefix(['=', ':'])
+        .map(trim_leading_whitespace)
+        .unwrap_or(rest);
+    let value_start = logical.len() - rest.len();
+
+    Entry {
+        key: unescape(&logical[key_start..key_end]),
+        value: unescape(rest),
+        prefix: logical[..value_start].to_string(),
+    }
+}
+
+fn unescape(raw: &str) -> String {
+    let mut units: Vec<u16> = Vec::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    let mut buffer = [0u16; 2];
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            units.extend_from_slice(c.encode_utf16(&mut buffer));
+            continue;
+        }
+        let Some(next) = chars.next() else {
+            break;
+        };
+        let decoded = match next {
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            'f' => '\x0c',
+            'u' => {
+                let hex = chars.clone().take(4).collect::<String>();
+                match u16::from_str_radix(&hex, 16) {
+                    Ok(unit) if hex.len() == 4 => {
+                        // 代理对由前后两个 \uXXXX 组成，最后统一按 UTF-16 解码
+                        units.push(unit);
+                        for _ in 0..4 {
+                            chars.next();
+                        }
+                        continue;
+                    }
+                    _ => 'u',
+                }
+            }
+            other => other,
+        };
+        units.extend_from_slice(decoded.encode_utf16(&mut buffer));
+    }
+
+    String::from_utf16_lossy(&units)
+}
+
+/// 与 `Properties#store` 相同的转义规则；值中只转义开头的空格
+fn escape(text: &str, is_key: bool) -> String {
+    let mut output = String::with_capacity(text.len());
+    for (index, c) in text.chars().enumerate() {
+        match c {
+            '\\' => output.push_str("\\\\"),
+            '\t' => output.push_str("\\t"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\x0c' => output.push_str("\\f"),
+            ' ' if is_key || index == 0 => output.push_str("\\ "),
+            '=' | ':' | '#' | '!' if is_key => {
+                output.push('\\');
+                output.push(c);
+            }
+            c if (c as u32) < 0x20 || (c as u32) > 0x7e => {
+                let mut buffer = [0u16; 2];
+                for unit in c.encode_utf16(&mut buffer) {
+                    output.push_str(&format!("\\u{:04X}", unit));
+                }
+            }
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_follows_java_rules() {
+        let text = "# comment\n! also comment\n  key1 = value1\nkey2:value2\nkey3 value3\n\
+                    a\\=b\\:c=d\nmotd=\\u6B22\\u8FCE \\uD83D\\uDE00\nmulti=one, \\\n    two\n\
+                    empty\ntrail=x  \n";
+        let document = PropertiesDocument::parse(text);
+
+        assert_eq!(document.get("key1"), Some("value1"));
+        assert_eq!(document.get("key2"), Some("value2"));
+        assert_eq!(document.get("key3"), Some("value3"));
+        assert_eq!(document.get("a=b:c"), Some("d"));
+        assert_eq!(document.get("motd"), Some("欢迎 😀"));
+        assert_eq!(document.get("multi"), Some("one, two"));
+        assert_eq!(document.get("empty"), Some(""));
+        assert_eq!(document.get("trail"), Some("x  "));
+        assert_eq!(document.to_map().len(), 8);
+        assert_eq!(document.render(), text);
+    }
+
+    #[test]
+    fn set_rewrites_only_changed_lines() {
+        let text = "#Minecraft server properties\r\nmotd : old\r\nmulti=a\\\r\n  b\r\npvp=true";
+        let mut document = PropertiesDocument::parse(text);
+
+        document.set("pvp", "true");
+        assert_eq!(document.render(), text);
+
+        document.set("motd", "欢迎 a=b");
+        document.set("multi", "c");
+        document.set("zeta key", " v");
+        assert_eq!(
+            document.render(),
+            "#Minecraft server properties\r\nmotd : \\u6B22\\u8FCE a=b\r\nmulti=c\r\npvp=true\r\nzeta\\ key=\\ v"
+        );
+
+        let reparsed = PropertiesDocument::parse(&document.render());
+        assert_eq!(reparsed.get("motd"), Some("欢迎 a=b"));
+        assert_eq!(reparsed.get("zeta key"), Some(" v"));
+    }
+
+    #[test]
+    fn latin1_round_trips_bytes() {
+        let bytes = b"motd=caf\xe9\nlevel-name=world\n".to_vec();
+        let mut document = PropertiesDocument::from_bytes(&bytes);
+
+        assert_eq!(document.encoding, PropertiesEncoding::Latin1);
+        assert_eq!(document.get("motd"), Some("café"));
+        assert_eq!(document.to_bytes(), bytes);
+
+        document.set("level-name", "世界");
+        assert_eq!(document.to_bytes(), b"motd=caf\xe9\nlevel-name=\\u4E16\\u754C\n".to_vec());
+    }
+}
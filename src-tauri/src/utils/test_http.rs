@@ -0,0 +1,169 @@
+//! 测试用的本地 HTTP 服务，代替下载站与各个 API。
+//!
+//! 每个连接只处理一个请求，应答后关闭连接。
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+
+use sha2::{Digest, Sha256};
+
+/// 收到的请求
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub method: String,
+    /// 路径与查询参数
+    pub target: String,
+    /// 请求头，名称统一为小写
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    /// Range 头中 `bytes=` 之后的部分，例如 `100-199`
+    pub fn range(&self) -> Option<&str> {
+        self.header("range")
+            .and_then(|range| range.strip_prefix("bytes="))
+    }
+}
+
+pub struct Response {
+    status: &'static str,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    /// 为 false 时不返回 Content-Length，以关闭连接表示内容结束
+    sized: bool,
+}
+
+impl Response {
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self::with_status("200 OK", body)
+    }
+
+    pub fn not_found() -> Self {
+        Self::with_status("404 Not Found", Vec::new())
+    }
+
+    pub fn with_status(status: &'static str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            sized: true,
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn without_length(mut self) -> Self {
+        self.sized = false;
+        self
+    }
+}
+
+/// 收到的所有请求，按到达顺序排列
+pub type Requests = Arc<Mutex<Vec<Request>>>;
+
+/// 启动本地服务，由 handler 为每个请求生成应答；返回 (`http://127.0.0.1:<端口>`, 收到的请求)。
+/// HEAD 请求只返回应答头
+pub fn serve<F>(handler: F) -> (String, Requests)
+where
+    F: Fn(&Request) -> Response + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Requests::default();
+    let log = requests.clone();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let Some(request) = read_request(&mut BufReader::new(&stream)) else {
+                continue;
+            };
+            log.lock().unwrap().push(request.clone());
+            let response = handler(&request);
+
+            let mut head = format!("HTTP/1.1 {}\r\nConnection: close\r\n", response.status);
+            if response.sized {
+                head += &format!("Content-Length: {}\r\n", response.body.len());
+            }
+            for (name, value) in &response.headers {
+                head += &format!("{}: {}\r\n", name, value);
+            }
+            head += "\r\n";
+            let _ = stream.write_all(head.as_bytes());
+            if request.method != "HEAD" {
+                let _ = stream.write_all(&response.body);
+            }
+        }
+    });
+    (base, requests)
+}
+
+/// 按路径前缀返回固定内容，靠前的路由优先，没有匹配时返回 404
+pub fn routes(routes: Vec<(&'static str, String)>) -> (String, Requests) {
+    serve(move |request| {
+        routes
+            .iter()
+            .find(|(prefix, _)| request.target.starts_with(prefix))
+            .map(|(_, body)| Response::ok(body.as_bytes()))
+            .unwrap_or_else(Response::not_found)
+    })
+}
+
+/// 按请求的 Range 返回文件内容，ETag 为内容的 SHA-256；accept_ranges 为 false 时忽略 Range，
+/// If-Range 与 ETag 不一致时也返回完整内容
+pub fn file(body: &[u8], request: &Request, accept_ranges: bool) -> Response {
+    if !accept_ranges {
+        return Response::ok(body).header("Accept-Ranges", "none");
+    }
+    let etag = format!("\"{:x}\"", Sha256::digest(body));
+    let range = request
+        .range()
+        .filter(|_| request.header("if-range").is_none_or(|tag| tag == etag))
+        .and_then(|range| range.split_once('-'));
+    let Some((start, end)) = range else {
+        return Response::ok(body)
+            .header("Accept-Ranges", "bytes")
+            .header("ETag", &etag);
+    };
+    let start = start.parse::<usize>().unwrap();
+    let end = end.parse::<usize>().map_or(body.len(), |end| end + 1);
+    Response::with_status("206 Partial Content", &body[start..end])
+        .header("Accept-Ranges", "bytes")
+        .header("ETag", &etag)
+        .header("Content-Range", &format!("bytes {}-{}/{}", start, end - 1, body.len()))
+}
+
+fn read_request(reader: &mut impl BufRead) -> Option<Request> {
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).ok()?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let target = parts.next()?.to_string();
+
+    let mut headers = HashMap::new();
+    let mut line = String::new();
+    while reader.read_line(&mut line).is_ok() && !line.trim().is_empty() {
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+        line.clear();
+    }
+    // 读完请求体再应答，避免关闭连接时未读的数据导致连接被重置
+    let length = headers
+        .get("content-length")
+        .and_then(|length| length.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    let body = String::from_utf8_lossy(&body).to_string();
+    Some(Request { method, target, headers, body })
+}
@@ -10,6 +10,20 @@ export interface ConfigEntry {
   value_type: string;
   default_value: string;
   category: string;
+  /** select 类型的可选值 */
+  options: string[];
+  /** number 类型的取值范围 */
+  min: number | null;
+  max: number | null;
+}
+
+/**
+ * server.properties 校验错误
+ */
+export interface PropertyValidationError {
+  key: string;
+  value: string;
+  message: string;
 }
 
 /**
@@ -20,6 +34,22 @@ export interface ServerProperties {
   raw: Record<string, string>;
 }
 
+/**
+ * 配置文件的一次历史修订（写入前的内容）
+ */
+export interface ConfigRevision {
+  id: string;
+  /** 相对服务器目录的路径，使用 / 分隔 */
+  file_path: string;
+  /** 触发写入的来源，例如 config_editor、server_properties、plugin:<id> */
+  origin: string;
+  created_at: number;
+  size: number;
+  /** 为 false 表示写入前文件不存在 */
+  existed: boolean;
+  sha256: string;
+}
+
 /**
  * 配置管理 API
  */
@@ -60,4 +90,67 @@ export const configApi = {
   ): Promise<void> {
     return tauriInvoke("write_config", { serverPath, path, values });
   },
+
+  /**
+   * 获取当前服务器版本对应的 server.properties 配置项定义
+   */
+  async getServerPropertiesSchema(serverPath: string): Promise<ConfigEntry[]> {
+    return tauriInvoke("get_server_properties_schema", { serverPath });
+  },
+
+  /**
+   * 按服务器版本校验 server.properties 的值，返回所有不合法的项
+   */
+  async validateServerProperties(
+    serverPath: string,
+    values: Record<string, string>,
+  ): Promise<PropertyValidationError[]> {
+    return tauriInvoke("validate_server_properties", { serverPath, values });
+  },
+
+  /**
+   * 读取 YAML/TOML/JSON5/properties 配置文件的完整内容
+   */
+  async readConfigDocument(serverPath: string, path: string): Promise<unknown> {
+    return tauriInvoke("read_config_document", { serverPath, path });
+  },
+
+  /**
+   * 按点分路径读取配置值，例如 settings.view-distance
+   */
+  async getConfigValue(serverPath: string, path: string, key: string): Promise<unknown | null> {
+    return tauriInvoke("get_config_value", { serverPath, path, key });
+  },
+
+  /**
+   * 按点分路径修改配置，保留注释与格式；任意一项校验失败时不写入
+   */
+  async setConfigValues(
+    serverPath: string,
+    path: string,
+    values: Record<string, unknown>,
+  ): Promise<void> {
+    return tauriInvoke("set_config_values", { serverPath, path, values });
+  },
+
+  /**
+   * 配置修订列表，最新的在前
+   */
+  async listRevisions(serverId: string, filePath?: string): Promise<ConfigRevision[]> {
+    return tauriInvoke("list_config_revisions", { serverId, filePath });
+  },
+
+  /**
+   * 两个修订之间的统一 diff，toId 为空时与当前文件比较
+   */
+  async diffRevisions(serverId: string, fromId: string, toId?: string): Promise<string> {
+    return tauriInvoke("diff_config_revisions", { serverId, fromId, toId });
+  },
+
+  /**
+   * 恢复到指定修订，恢复前的内容同样会保存为修订
+   */
+  async restoreRevision(serverId: string, revisionId: string): Promise<void> {
+    return tauriInvoke("restore_config_revision", { serverId, revisionId });
+  },
 };
@@ -2,7 +2,13 @@ import { reactive, onUnmounted, computed } from "vue";
 import { tauriInvoke } from "./tauri";
 import { i18n } from "@language";
 
-export type TaskStatus = "Pending" | "Downloading" | "Completed" | { Error: string };
+export type TaskStatus =
+  | "Pending"
+  | "Downloading"
+  | "Paused"
+  | "Completed"
+  | "Cancelled"
+  | { Error: string };
 
 export interface DownloadTaskInfo {
   id: string;
@@ -17,6 +23,8 @@ export interface DownloadOptions {
   url: string;
   savePath: string;
   threadCount?: number;
+  /** 下载完成后校验，例如 sha256:<hex> */
+  expectedHash?: string;
 }
 
 export const downloadApi = {
@@ -28,6 +36,7 @@ export const downloadApi = {
       url: options.url,
       savePath: options.savePath,
       threadCount: options.threadCount || 32,
+      expectedHash: options.expectedHash,
     });
   },
 
@@ -38,6 +47,27 @@ export const downloadApi = {
     return tauriInvoke<DownloadTaskInfo>("poll_task", { idStr: id });
   },
 
+  /**
+   * 暂停下载，已下载的部分保留在磁盘上
+   */
+  async pauseTask(id: string): Promise<void> {
+    return tauriInvoke("pause_download_task", { idStr: id });
+  },
+
+  /**
+   * 继续已暂停或失败的下载，应用重启后未完成的任务也可以继续
+   */
+  async resumeTask(id: string): Promise<void> {
+    return tauriInvoke("resume_download_task", { idStr: id });
+  },
+
+  /**
+   * 取消下载并删除未完成的文件
+   */
+  async cancelTask(id: string): Promise<void> {
+    return tauriInvoke("cancel_download_task", { idStr: id });
+  },
+
   /**
    * 启动并自动轮询
    */
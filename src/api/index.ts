@@ -7,13 +7,69 @@ export { tauriInvoke, tauriInvokeAll, createCachedInvoke } from "@api/tauri";
 export type { InvokeOptions } from "@api/tauri";
 
 export { serverApi } from "@api/server";
-export type { ServerStatusInfo } from "@api/server";
+export type {
+  ServerStatusInfo,
+  ChunkLoadError,
+  CrashAnalysis,
+  ModpackInfo,
+  ServerExportResult,
+  CoreVersion,
+  CoreBuild,
+  CoreUpgradeResult,
+} from "@api/server";
 
 export { javaApi } from "@api/java";
-export type { JavaInfo } from "@api/java";
+export type {
+  JavaInfo,
+  JavaDistribution,
+  JavaPackage,
+  JavaRequirement,
+  JavaRecommendation,
+  RuntimeManifest,
+  RuntimeUsage,
+  MissingJava,
+} from "@api/java";
 
 export { configApi } from "@api/config";
-export type { ConfigEntry, ServerProperties } from "@api/config";
+export type {
+  ConfigEntry,
+  ServerProperties,
+  PropertyValidationError,
+  ConfigRevision,
+} from "@api/config";
+
+export { worldApi } from "@api/world";
+export type {
+  RegionPruneSummary,
+  WorldPruneReport,
+  RegionIssueKind,
+  RegionIssue,
+  RegionScanReport,
+  RegionRepairReport,
+  DatapackInfo,
+} from "@api/world";
+
+export { resourcePackApi } from "@api/resourcePack";
+export type { HostedResourcePack } from "@api/resourcePack";
+
+export { modApi } from "@api/mods";
+export type {
+  ModDependency,
+  InstalledMod,
+  ModPreflightIssue,
+  ModPreflightReport,
+  ModSearchQuery,
+  ModSearchHit,
+  ModSearchPage,
+  ModVersionFile,
+  ModVersionDependency,
+  ModVersion,
+  ModInstallRecord,
+  ContentUpdate,
+  ContentUpdateRequest,
+  RestoreEntry,
+  PendingRollback,
+} from "@api/mods";
 
 export { playerApi } from "@api/player";
 export type { PlayerEntry, BanEntry, OpEntry } from "@api/player";
@@ -9,6 +9,92 @@ export interface JavaInfo {
   vendor: string;
   is_64bit: boolean;
   major_version: number;
+  modified: number;
+}
+
+/**
+ * 可安装的 Java 发行版
+ */
+export interface JavaDistribution {
+  id: string;
+  name: string;
+  major_versions: number[];
+  lts_versions: number[];
+  supports_musl: boolean;
+}
+
+/**
+ * 当前系统上可下载的 Java 包
+ */
+export interface JavaPackage {
+  distribution: string;
+  major_version: number;
+  version: string;
+  os: string;
+  arch: string;
+  libc: string;
+  image_type: string;
+  file_name: string;
+  url: string;
+  size: number;
+  /** 摘要，可以带 sha1: / sha256: 前缀 */
+  checksum: string | null;
+}
+
+/**
+ * 服务器需要的 Java 主版本范围
+ */
+export interface JavaRequirement {
+  min: number;
+  max: number | null;
+  recommended: number;
+  reason: string;
+}
+
+/**
+ * 按 Minecraft 版本推荐的 Java
+ */
+export interface JavaRecommendation {
+  requirement: JavaRequirement | null;
+  selected: JavaInfo | null;
+  compatible: JavaInfo[];
+}
+
+/**
+ * Sea Lantern 安装运行时时记录的清单
+ */
+export interface RuntimeManifest {
+  name: string;
+  vendor: string;
+  version: string;
+  major_version: number;
+  arch: string;
+  os: string;
+  source_url: string;
+  checksum: string | null;
+  installed_at: number;
+}
+
+/**
+ * runtimes 目录下的运行时及使用它的服务器
+ */
+export interface RuntimeUsage {
+  name: string;
+  dir: string;
+  manifest: RuntimeManifest | null;
+  java: JavaInfo | null;
+  size: number;
+  servers: { id: string; name: string }[];
+}
+
+/**
+ * Java 路径已不存在的服务器
+ */
+export interface MissingJava {
+  server_id: string;
+  server_name: string;
+  java_path: string;
+  suggestion: JavaInfo | null;
 }
 
 /**
@@ -30,10 +116,63 @@ export const javaApi = {
   },
 
   /**
-   * 安装 Java
+   * 按 Minecraft 版本与核心类型推荐已安装的 Java
+   */
+  async recommend(mcVersion: string, coreType: string): Promise<JavaRecommendation> {
+    return tauriInvoke("recommend_java", { mcVersion, coreType });
+  },
+
+  /**
+   * 安装 Java，提供摘要时下载完成后校验，例如 sha1:<hex>；不带前缀时按长度判断算法
+   */
+  async installJava(url: string, versionName: string, checksum?: string): Promise<string> {
+    return tauriInvoke("install_java", { url, versionName, checksum });
+  },
+
+  /**
+   * 获取可安装的 Java 发行版
+   */
+  async listDistributions(): Promise<JavaDistribution[]> {
+    return tauriInvoke("list_java_distributions");
+  },
+
+  /**
+   * 获取当前系统上可下载的 Java 包，第一个为推荐的包
+   */
+  async listPackages(
+    distribution: string,
+    majorVersion: number,
+    imageType?: string,
+  ): Promise<JavaPackage[]> {
+    return tauriInvoke("list_java_packages", { distribution, majorVersion, imageType });
+  },
+
+  /**
+   * 获取已安装的运行时、占用空间与使用它们的服务器
+   */
+  async listRuntimes(): Promise<RuntimeUsage[]> {
+    return tauriInvoke("list_java_runtimes");
+  },
+
+  /**
+   * 卸载没有服务器使用的运行时
+   */
+  async uninstallRuntime(name: string): Promise<void> {
+    return tauriInvoke("uninstall_java_runtime", { name });
+  },
+
+  /**
+   * 把使用某个 Java 的服务器全部切换到另一个 Java，返回切换的服务器 ID
+   */
+  async repointServers(fromJavaPath: string, toJavaPath: string): Promise<string[]> {
+    return tauriInvoke("repoint_servers_java", { fromJavaPath, toJavaPath });
+  },
+
+  /**
+   * 获取 Java 路径已不存在的服务器与推荐的替代
    */
-  async installJava(url: string, versionName: string): Promise<string> {
-    return tauriInvoke("install_java", { url, versionName });
+  async findMissing(): Promise<MissingJava[]> {
+    return tauriInvoke("find_missing_java");
   },
 
   /**
@@ -12,6 +12,13 @@ export interface m_PluginInfo {
   main_class: string;
   has_config_folder: boolean;
   config_files: m_PluginConfigFile[];
+  /** 描述文件来源：paper / bukkit / bungee / velocity */
+  platform: string;
+  depend: string[];
+  softdepend: string[];
+  loadbefore: string[];
+  provides: string[];
+  api_version: string | null;
 }
 
 export interface m_PluginConfigFile {
@@ -21,6 +28,112 @@ export interface m_PluginConfigFile {
   file_path: string;
 }
 
+/**
+ * 插件依赖检查发现的问题
+ */
+export interface m_PluginDependencyIssue {
+  /** error / warning */
+  severity: string;
+  /** missing_dependency / disabled_dependency / dependency_cycle / api_version_too_new */
+  kind: string;
+  file_name: string;
+  plugin: string;
+  /** 相关的插件名 */
+  related: string[];
+  message: string;
+}
+
+/**
+ * 依赖图中的一条边，from 在 to 之后加载
+ */
+export interface m_PluginDependencyEdge {
+  from: string;
+  to: string;
+  /** depend / softdepend / loadbefore */
+  kind: string;
+}
+
+export interface m_PluginDependencyReport {
+  plugins: m_PluginInfo[];
+  edges: m_PluginDependencyEdge[];
+  issues: m_PluginDependencyIssue[];
+  has_errors: boolean;
+}
+
+export interface PluginSearchQuery {
+  /** hangar / spiget */
+  source: string;
+  query?: string;
+  offset?: number;
+  limit?: number;
+}
+
+export interface PluginSearchHit {
+  source: string;
+  /** Hangar 为项目 slug，Spiget 为资源 ID */
+  project_id: string;
+  name: string;
+  author: string;
+  description: string;
+  downloads: number;
+  icon_url: string | null;
+  /** 仓库声明支持的 Minecraft 版本 */
+  game_versions: string[];
+  /** 付费或外链资源无法直接安装 */
+  installable: boolean;
+}
+
+export interface PluginSearchPage {
+  hits: PluginSearchHit[];
+  offset: number;
+  limit: number;
+  /** Spiget 不提供总数时为 null */
+  total: number | null;
+}
+
+/**
+ * 插件的一个可下载版本
+ */
+export interface PluginVersion {
+  source: string;
+  project_id: string;
+  /** Hangar 为版本名，Spiget 为版本 ID */
+  version_id: string;
+  version_number: string;
+  /** Unix 时间戳（秒） */
+  published: number;
+  game_versions: string[];
+  file_name: string;
+  size: number;
+  /** 仓库提供的 SHA-256，Spiget 没有 */
+  sha256: string | null;
+  download_url: string;
+  /** 前置插件名称 */
+  dependencies: string[];
+}
+
+/**
+ * 从插件仓库安装的插件
+ */
+export interface PluginInstallRecord {
+  source: string;
+  project_id: string;
+  version_id: string;
+  version_number: string;
+  file_name: string;
+  sha256: string;
+  installed_at: number;
+}
+
+export interface PluginUpdate {
+  record: PluginInstallRecord;
+  /** 适用于当前平台与 Minecraft 版本的最新版本 */
+  latest_version: PluginVersion | null;
+  update_available: boolean;
+  /** 查询失败时的错误信息，不影响其他插件 */
+  error: string | null;
+}
+
 export const m_pluginApi = {
   async m_getPlugins(serverId: string): Promise<m_PluginInfo[]> {
     return tauriInvoke<m_PluginInfo[]>("m_get_plugins", { serverId });
@@ -38,6 +151,10 @@ export const m_pluginApi = {
     });
   },
 
+  async m_checkPluginDependencies(serverId: string): Promise<m_PluginDependencyReport> {
+    return tauriInvoke<m_PluginDependencyReport>("m_check_plugin_dependencies", { serverId });
+  },
+
   async m_togglePlugin(serverId: string, fileName: string, enabled: boolean): Promise<void> {
     return tauriInvoke<void>("m_toggle_plugin", { serverId, fileName, enabled });
   },
@@ -50,6 +167,53 @@ export const m_pluginApi = {
     return tauriInvoke<void>("m_install_plugin", { serverId, fileData, fileName });
   },
 
+  /**
+   * 在 Hangar / Spiget 中搜索适用于该服务器的插件
+   */
+  async m_searchRepositoryPlugins(
+    serverId: string,
+    query: PluginSearchQuery,
+  ): Promise<PluginSearchPage> {
+    return tauriInvoke<PluginSearchPage>("m_search_repository_plugins", { serverId, query });
+  },
+
+  async m_getRepositoryPluginVersions(
+    serverId: string,
+    source: string,
+    projectId: string,
+  ): Promise<PluginVersion[]> {
+    return tauriInvoke<PluginVersion[]>("m_get_repository_plugin_versions", {
+      serverId,
+      source,
+      projectId,
+    });
+  },
+
+  /**
+   * 从插件仓库安装或更新插件，未指定版本时安装最新的兼容版本
+   */
+  async m_installRepositoryPlugin(
+    serverId: string,
+    source: string,
+    projectId: string,
+    versionId?: string,
+  ): Promise<PluginInstallRecord> {
+    return tauriInvoke<PluginInstallRecord>("m_install_repository_plugin", {
+      serverId,
+      source,
+      projectId,
+      versionId,
+    });
+  },
+
+  async m_listRepositoryPluginRecords(serverId: string): Promise<PluginInstallRecord[]> {
+    return tauriInvoke<PluginInstallRecord[]>("m_list_repository_plugin_records", { serverId });
+  },
+
+  async m_checkRepositoryPluginUpdates(serverId: string): Promise<PluginUpdate[]> {
+    return tauriInvoke<PluginUpdate[]>("m_check_repository_plugin_updates", { serverId });
+  },
+
   async m_reloadPlugins(serverId: string): Promise<void> {
     // Send reload command to server
     return tauriInvoke<void>("send_command", { id: serverId, command: "reload" });
@@ -0,0 +1,280 @@
+import { tauriInvoke } from "@api/tauri";
+import type { ServerInstance } from "@type/server";
+
+/**
+ * 模组元数据中声明的依赖
+ */
+export interface ModDependency {
+  mod_id: string;
+  /** 原样保留的版本范围，Fabric 为 >=1.20，Forge 为 [1.20,1.21) */
+  version_range: string | null;
+  /** required / optional / incompatible */
+  kind: string;
+  /** both / client / server */
+  side: string;
+}
+
+/**
+ * mods 目录中的一个模组文件
+ */
+export interface InstalledMod {
+  /** 不含 .disabled 后缀的文件名 */
+  file_name: string;
+  file_size: number;
+  enabled: boolean;
+  loaders: string[];
+  mod_id: string;
+  name: string;
+  version: string;
+  description: string;
+  authors: string[];
+  mc_version_range: string | null;
+  loader_version_range: string | null;
+  dependencies: ModDependency[];
+  /** both / client / server */
+  environment: string;
+  provides: string[];
+  error: string | null;
+}
+
+/**
+ * 启动前模组检查发现的一个问题
+ */
+export interface ModPreflightIssue {
+  /** error / warning */
+  severity: string;
+  /**
+   * unreadable / wrong_loader / mc_version_mismatch / client_only / duplicate_mod /
+   * missing_dependency / dependency_version_mismatch / incompatible_mod
+   */
+  kind: string;
+  file_name: string;
+  mod_id: string;
+  related: string | null;
+  message: string;
+}
+
+export interface ModPreflightReport {
+  loaders: string[];
+  mc_version: string;
+  checked_mods: number;
+  issues: ModPreflightIssue[];
+  has_errors: boolean;
+}
+
+/**
+ * Modrinth 搜索条件
+ */
+export interface ModSearchQuery {
+  query?: string;
+  /** Minecraft 版本，如 1.20.1 */
+  game_version?: string;
+  /** 加载器，如 fabric、forge */
+  loader?: string;
+  /** mod / modpack / plugin 等，默认 mod */
+  project_type?: string;
+  /** relevance / downloads / follows / newest / updated */
+  index?: string;
+  offset?: number;
+  limit?: number;
+}
+
+export interface ModSearchHit {
+  project_id: string;
+  slug: string;
+  title: string;
+  description: string;
+  author: string;
+  downloads: number;
+  icon_url: string | null;
+  categories: string[];
+  versions: string[];
+  latest_version: string | null;
+  /** required / optional / unsupported */
+  client_side: string;
+  server_side: string;
+  date_modified: string;
+}
+
+export interface ModSearchPage {
+  hits: ModSearchHit[];
+  offset: number;
+  limit: number;
+  total_hits: number;
+}
+
+export interface ModVersionFile {
+  url: string;
+  filename: string;
+  primary: boolean;
+  size: number;
+  hashes: { sha512: string | null; sha1: string | null };
+}
+
+export interface ModVersionDependency {
+  version_id: string | null;
+  project_id: string | null;
+  file_name: string | null;
+  /** required / optional / incompatible / embedded */
+  dependency_type: string;
+}
+
+/**
+ * Modrinth 上某个项目的一个版本
+ */
+export interface ModVersion {
+  id: string;
+  project_id: string;
+  name: string;
+  version_number: string;
+  /** release / beta / alpha */
+  version_type: string;
+  game_versions: string[];
+  loaders: string[];
+  date_published: string;
+  files: ModVersionFile[];
+  dependencies: ModVersionDependency[];
+}
+
+/**
+ * 通过 Sea Lantern 安装的模组记录
+ */
+export interface ModInstallRecord {
+  project_id: string;
+  version_id: string;
+  version_number: string;
+  file_name: string;
+  sha1: string | null;
+  sha512: string | null;
+  source: string;
+  /** 作为前置被自动安装时，记录需要它的项目 ID */
+  required_by: string | null;
+  installed_at: number;
+}
+
+/**
+ * mods/plugins 目录中一个文件的更新信息
+ */
+export interface ContentUpdate {
+  /** mod / plugin */
+  kind: string;
+  /** 不含 .disabled 后缀的文件名 */
+  file_name: string;
+  enabled: boolean;
+  sha1: string;
+  sha512: string;
+  /** 未能在 Modrinth 上识别时为 null */
+  project_id: string | null;
+  current_version: ModVersion | null;
+  latest_version: ModVersion | null;
+  /** 比当前版本新的兼容版本，最新的在前 */
+  newer_versions: ModVersion[];
+  update_available: boolean;
+}
+
+/**
+ * 要应用的一项更新
+ */
+export interface ContentUpdateRequest {
+  kind: string;
+  file_name: string;
+  version_id: string;
+}
+
+export interface RestoreEntry {
+  backup: string;
+  target: string;
+}
+
+/**
+ * 等待试启动确认的更新
+ */
+export interface PendingRollback {
+  reason: string;
+  created_at: number;
+  backup_dir: string;
+  restore: RestoreEntry[];
+  remove: string[];
+  instance: ServerInstance | null;
+}
+
+/**
+ * 模组管理 API
+ */
+export const modApi = {
+  async search(query: ModSearchQuery): Promise<ModSearchPage> {
+    return tauriInvoke("search_mods", { query });
+  },
+
+  /**
+   * 项目中适用于该服务器的版本
+   */
+  async getVersions(serverId: string, projectId: string): Promise<ModVersion[]> {
+    return tauriInvoke("get_mod_versions", { serverId, projectId });
+  },
+
+  /**
+   * 安装模组及其必需前置，未指定版本时安装兼容的最新版本
+   */
+  async install(
+    serverId: string,
+    projectId: string,
+    versionId?: string,
+  ): Promise<ModInstallRecord[]> {
+    return tauriInvoke("install_mod", { serverId, projectId, versionId });
+  },
+
+  async listInstallRecords(serverId: string): Promise<ModInstallRecord[]> {
+    return tauriInvoke("list_mod_install_records", { serverId });
+  },
+
+  async remove(serverId: string, fileName: string): Promise<void> {
+    return tauriInvoke("remove_mod", { serverId, fileName });
+  },
+
+  /**
+   * 扫描 mods 目录并读取模组元数据
+   */
+  async listInstalled(serverId: string): Promise<InstalledMod[]> {
+    return tauriInvoke("list_installed_mods", { serverId });
+  },
+
+  /**
+   * 启用或禁用模组（添加或去掉 .disabled 后缀）
+   */
+  async toggle(serverId: string, fileName: string, enabled: boolean): Promise<void> {
+    return tauriInvoke("toggle_mod", { serverId, fileName, enabled });
+  },
+
+  /**
+   * 检查模组依赖、加载器与版本兼容性
+   */
+  async checkPreflight(serverId: string): Promise<ModPreflightReport> {
+    return tauriInvoke("check_mod_preflight", { serverId });
+  },
+
+  /**
+   * 按文件摘要检查 mods 与 plugins 目录的更新
+   */
+  async checkUpdates(serverId: string): Promise<ContentUpdate[]> {
+    return tauriInvoke("check_content_updates", { serverId });
+  },
+
+  /**
+   * 应用更新，下一次启动失败时自动回滚
+   */
+  async applyUpdates(serverId: string, updates: ContentUpdateRequest[]): Promise<PendingRollback> {
+    return tauriInvoke("apply_content_updates", { serverId, updates });
+  },
+
+  async getPendingRollback(serverId: string): Promise<PendingRollback | null> {
+    return tauriInvoke("get_pending_rollback", { serverId });
+  },
+
+  /**
+   * 手动撤销尚未通过试启动的更新
+   */
+  async rollbackPendingUpdate(serverId: string): Promise<boolean> {
+    return tauriInvoke("rollback_pending_update", { serverId });
+  },
+};
@@ -0,0 +1,38 @@
+import { tauriInvoke } from "@api/tauri";
+
+/**
+ * 正在托管的资源包
+ */
+export interface HostedResourcePack {
+  server_id: string;
+  file_path: string;
+  file_name: string;
+  sha1: string;
+  size: number;
+  url: string;
+  require: boolean;
+  updated_at: number;
+}
+
+/**
+ * 资源包托管 API
+ */
+export const resourcePackApi = {
+  /**
+   * 托管资源包并写入 server.properties，require 默认为 true
+   */
+  async host(serverId: string, filePath: string, require?: boolean): Promise<HostedResourcePack> {
+    return tauriInvoke("host_resource_pack", { serverId, filePath, require });
+  },
+
+  /**
+   * 停止托管，clearProperties 默认为 true，同时清空 server.properties 中的资源包配置
+   */
+  async stop(serverId: string, clearProperties?: boolean): Promise<void> {
+    return tauriInvoke("stop_resource_pack_hosting", { serverId, clearProperties });
+  },
+
+  async get(serverId: string): Promise<HostedResourcePack | null> {
+    return tauriInvoke("get_hosted_resource_pack", { serverId });
+  },
+};
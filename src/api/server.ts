@@ -1,5 +1,6 @@
 import { tauriInvoke } from "@api/tauri";
 import type { ServerInstance } from "@type/server";
+import type { RegionIssue, RegionScanReport } from "@api/world";
 import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 
 export interface ServerStatusInfo {
@@ -9,6 +10,85 @@ export interface ServerStatusInfo {
   uptime: number | null;
 }
 
+export interface ChunkLoadError {
+  line: string;
+  chunk_x: number | null;
+  chunk_z: number | null;
+}
+
+export interface CrashAnalysis {
+  server_id: string;
+  crash_report: string | null;
+  chunk_errors: ChunkLoadError[];
+  region_scan: RegionScanReport | null;
+  matched_issues: RegionIssue[];
+  suggestions: string[];
+}
+
+/**
+ * 整合包的基本信息
+ */
+export interface ModpackInfo {
+  /** modrinth 或 curseforge */
+  format: string;
+  name: string;
+  version: string;
+  mc_version: string;
+  core_type: string;
+  loader_version: string;
+  /** 需要下载的服务端文件数 */
+  file_count: number;
+}
+
+/**
+ * 导出服务器的结果
+ */
+export interface ServerExportResult {
+  /** bundle / mrpack / client */
+  format: string;
+  output_path: string;
+  /** 写入压缩包的文件数 */
+  file_count: number;
+  /** 以摘要引用、导入时从 Modrinth 下载的模组数 */
+  referenced_mods: number;
+  /** 被排除的仅服务端或仅客户端文件，相对服务器目录 */
+  excluded: string[];
+  warnings: string[];
+}
+
+/**
+ * 核心可用的 Minecraft 版本（代理端为其自身版本），最新的在前
+ */
+export interface CoreVersion {
+  id: string;
+  /** 正式版为 true，快照、预发布与实验版本为 false */
+  stable: boolean;
+}
+
+/**
+ * 某个版本下的一个构建
+ */
+export interface CoreBuild {
+  /** Paper/Leaves/Purpur 为构建号，Fabric 为加载器版本，原版为 Minecraft 版本 */
+  id: string;
+  stable: boolean;
+}
+
+/**
+ * 核心升级的结果
+ */
+export interface CoreUpgradeResult {
+  previous_core_version: string;
+  previous_mc_version: string;
+  core_version: string;
+  mc_version: string;
+  jar_path: string;
+  /** 试启动成功后服务器是否仍在运行 */
+  running: boolean;
+  /** 升级前世界与配置的便携包备份 */
+  backup_path: string;
+}
+
 export interface ParsedServerCoreInfo {
   coreType: string;
   mainClass: string | null;
@@ -143,6 +223,95 @@ export const serverApi = {
     });
   },
 
+  /**
+   * 读取 Modrinth 或 CurseForge 整合包的清单，其他压缩包返回 null
+   */
+  async inspectModpack(modpackPath: string): Promise<ModpackInfo | null> {
+    return tauriInvoke("inspect_modpack", { modpackPath });
+  },
+
+  /**
+   * 导出为便携包、.mrpack 或客户端包，include 为空时使用各形式的默认内容。
+   * 便携包需要先停止服务器
+   */
+  async exportServer(
+    serverId: string,
+    format: "bundle" | "mrpack" | "client",
+    outputPath: string,
+    include: string[] = [],
+  ): Promise<ServerExportResult> {
+    return tauriInvoke("export_server", { serverId, format, outputPath, include });
+  },
+
+  /**
+   * 可以直接下载或通过安装器安装的核心类型
+   */
+  async listDownloadableCoreTypes(): Promise<string[]> {
+    return tauriInvoke("list_downloadable_core_types");
+  },
+
+  async listCoreVersions(coreType: string): Promise<CoreVersion[]> {
+    return tauriInvoke("list_core_versions", { coreType });
+  },
+
+  async listCoreBuilds(coreType: string, mcVersion: string): Promise<CoreBuild[]> {
+    return tauriInvoke("list_core_builds", { coreType, mcVersion });
+  },
+
+  /**
+   * 下载核心并创建服务器，未指定构建时使用最新的稳定构建
+   */
+  async createFromCore(params: {
+    name: string;
+    coreType: string;
+    mcVersion: string;
+    build?: string;
+    javaPath: string;
+    maxMemory: number;
+    minMemory: number;
+    port: number;
+    onlineMode: boolean;
+    runPath: string;
+  }): Promise<ServerInstance> {
+    return tauriInvoke("create_server_from_core", {
+      name: params.name,
+      coreType: params.coreType,
+      mcVersion: params.mcVersion,
+      build: params.build,
+      javaPath: params.javaPath,
+      maxMemory: params.maxMemory,
+      minMemory: params.minMemory,
+      port: params.port,
+      onlineMode: params.onlineMode,
+      runPath: params.runPath,
+    });
+  },
+
+  /**
+   * 原地升级核心并试启动，启动失败时自动回滚。未指定 Minecraft 版本时保持当前版本，
+   * 未指定构建时使用最新的稳定构建
+   */
+  async upgradeCore(params: {
+    serverId: string;
+    mcVersion?: string;
+    build?: string;
+    keepRunning: boolean;
+  }): Promise<CoreUpgradeResult> {
+    return tauriInvoke("upgrade_server_core", {
+      serverId: params.serverId,
+      mcVersion: params.mcVersion,
+      build: params.build,
+      keepRunning: params.keepRunning,
+    });
+  },
+
+  /**
+   * 在服务器目录中运行加载器安装器并改为直接启动生成的文件，未指定版本时使用最新的稳定版本
+   */
+  async installLoader(serverId: string, loaderVersion?: string): Promise<ServerInstance> {
+    return tauriInvoke("install_server_loader", { serverId, loaderVersion });
+  },
+
   async parseServerCoreType(sourcePath: string): Promise<ParsedServerCoreInfo> {
     const result = await tauriInvoke<ParsedServerCoreInfoRaw>("parse_server_core_type", {
       sourcePath,
@@ -215,8 +384,11 @@ export const serverApi = {
     });
   },
 
-  async start(id: string): Promise<void> {
-    return tauriInvoke("start_server", { id });
+  /**
+   * 启动服务器；开启“模组错误时阻止启动”后，传入 allowClientOnly 可忽略仅客户端模组的错误
+   */
+  async start(id: string, allowClientOnly?: boolean): Promise<void> {
+    return tauriInvoke("start_server", { id, allowClientOnly });
   },
 
   async stop(id: string): Promise<void> {
@@ -252,4 +424,8 @@ export const serverApi = {
   async updateServerName(id: string, name: string): Promise<void> {
     return tauriInvoke("update_server_name", { id, name });
   },
+
+  async analyzeCrash(id: string): Promise<CrashAnalysis> {
+    return tauriInvoke("analyze_server_crash", { id });
+  },
 };
@@ -43,6 +43,16 @@ export interface AppSettings {
   close_action: string;
   last_run_path: string;
   minimal_mode: boolean;
+  resource_pack_port: number;
+  resource_pack_public_host: string;
+  resource_pack_bind_address: string;
+  block_start_on_mod_errors: boolean;
+  modrinth_api_base_url: string;
+  curseforge_api_base_url: string;
+  curseforge_api_key: string;
+  hangar_api_base_url: string;
+  spiget_api_base_url: string;
+  core_mirror_base_url: string;
 }
 
 export interface PartialSettings {
@@ -78,6 +88,16 @@ export interface PartialSettings {
   close_action?: string;
   last_run_path?: string;
   minimal_mode?: boolean;
+  resource_pack_port?: number;
+  resource_pack_public_host?: string;
+  resource_pack_bind_address?: string;
+  block_start_on_mod_errors?: boolean;
+  modrinth_api_base_url?: string;
+  curseforge_api_base_url?: string;
+  curseforge_api_key?: string;
+  hangar_api_base_url?: string;
+  spiget_api_base_url?: string;
+  core_mirror_base_url?: string;
 }
 
 export interface UpdateSettingsResult {
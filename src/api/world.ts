@@ -0,0 +1,162 @@
+import { tauriInvoke } from "@api/tauri";
+
+/**
+ * 单个区域文件的裁剪结果
+ */
+export interface RegionPruneSummary {
+  dimension: string;
+  file_name: string;
+  chunks_total: number;
+  chunks_pruned: number;
+  chunks_unreadable: number;
+  latest_update_tick: number;
+  bytes_before: number;
+  bytes_after: number;
+  file_removed: boolean;
+}
+
+/**
+ * 区块裁剪报告
+ */
+export interface WorldPruneReport {
+  world_path: string;
+  dry_run: boolean;
+  spawn_chunk_x: number;
+  spawn_chunk_z: number;
+  regions_scanned: number;
+  chunks_scanned: number;
+  chunks_pruned: number;
+  chunks_kept: number;
+  chunks_unreadable: number;
+  bytes_before: number;
+  bytes_after: number;
+  regions: RegionPruneSummary[];
+}
+
+export type RegionIssueKind =
+  | "truncated_header"
+  | "invalid_offset"
+  | "overlapping_sectors"
+  | "invalid_length"
+  | "unknown_compression"
+  | "missing_external_file"
+  | "decompress_failed"
+  | "invalid_nbt";
+
+/**
+ * 区域文件校验发现的问题
+ */
+export interface RegionIssue {
+  region_path: string;
+  chunk_x: number | null;
+  chunk_z: number | null;
+  kind: RegionIssueKind;
+  message: string;
+}
+
+/**
+ * 区域文件校验报告
+ */
+export interface RegionScanReport {
+  world_path: string;
+  regions_scanned: number;
+  chunks_scanned: number;
+  chunks_skipped: number;
+  issues: RegionIssue[];
+}
+
+/**
+ * 损坏区块隔离结果，failed 为 [问题, 失败原因]
+ */
+export interface RegionRepairReport {
+  world_path: string;
+  quarantine_path: string;
+  created_at: number;
+  quarantined: RegionIssue[];
+  failed: [RegionIssue, string][];
+}
+
+/**
+ * 世界中的数据包
+ */
+export interface DatapackInfo {
+  file_name: string;
+  pack_id: string;
+  is_zip: boolean;
+  enabled: boolean;
+  pack_format: number | null;
+  description: string;
+  file_size: number;
+  expected_pack_format: number | null;
+  warning: string | null;
+}
+
+/**
+ * 世界管理 API
+ */
+export const worldApi = {
+  /**
+   * 裁剪 InhabitedTime 低于阈值的区块，dryRun 默认开启，只统计不修改
+   */
+  async pruneChunks(params: {
+    serverId: string;
+    worldName?: string;
+    minInhabitedTicks: number;
+    protectedRadius: number;
+    dryRun?: boolean;
+  }): Promise<WorldPruneReport> {
+    return tauriInvoke("prune_world_chunks", {
+      serverId: params.serverId,
+      worldName: params.worldName,
+      minInhabitedTicks: params.minInhabitedTicks,
+      protectedRadius: params.protectedRadius,
+      dryRun: params.dryRun,
+    });
+  },
+
+  /**
+   * 校验世界内所有区域文件的完整性
+   */
+  async scanRegions(serverId: string, worldName?: string): Promise<RegionScanReport> {
+    return tauriInvoke("scan_world_regions", { serverId, worldName });
+  },
+
+  /**
+   * 将损坏区块移入隔离区，服务器下次加载时重新生成
+   */
+  async quarantineChunks(
+    serverId: string,
+    issues: RegionIssue[],
+    worldName?: string,
+  ): Promise<RegionRepairReport> {
+    return tauriInvoke("quarantine_region_chunks", { serverId, worldName, issues });
+  },
+
+  async listDatapacks(serverId: string, worldName?: string): Promise<DatapackInfo[]> {
+    return tauriInvoke("list_datapacks", { serverId, worldName });
+  },
+
+  /**
+   * 安装本地 zip 或文件夹数据包，服务器运行时立即启用
+   */
+  async installDatapack(
+    serverId: string,
+    sourcePath: string,
+    worldName?: string,
+  ): Promise<DatapackInfo> {
+    return tauriInvoke("install_datapack", { serverId, worldName, sourcePath });
+  },
+
+  async setDatapackEnabled(
+    serverId: string,
+    fileName: string,
+    enabled: boolean,
+    worldName?: string,
+  ): Promise<void> {
+    return tauriInvoke("set_datapack_enabled", { serverId, worldName, fileName, enabled });
+  },
+
+  async removeDatapack(serverId: string, fileName: string, worldName?: string): Promise<void> {
+    return tauriInvoke("remove_datapack", { serverId, worldName, fileName });
+  },
+};
@@ -70,6 +70,16 @@ const defaultSettings: AppSettings = {
   close_action: "ask",
   last_run_path: "",
   minimal_mode: false,
+  resource_pack_port: 25580,
+  resource_pack_public_host: "",
+  resource_pack_bind_address: "0.0.0.0",
+  block_start_on_mod_errors: false,
+  modrinth_api_base_url: "",
+  curseforge_api_base_url: "",
+  curseforge_api_key: "",
+  hangar_api_base_url: "",
+  spiget_api_base_url: "",
+  core_mirror_base_url: "",
 };
 
 export interface SettingsUpdateEvent {